@@ -2,7 +2,20 @@
 //
 // Command-line tool to serve the Yew web application
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use punch_card_core::deck::Deck;
+use punch_card_core::format::{CardFormat, ColumnLoss};
+use punch_card_core::hollerith::{
+    CasePolicy, Charset, DecodeOptions, HollerithCode, chart_as_table_string, encode_string_strict,
+    parse_notation_line,
+};
+use punch_card_core::ibm1130::{
+    ListingOptions, Severity, deck_to_listing, example, examples, validate_source_deck,
+};
+use punch_card_core::punch_card::{CardType, PunchCard, UnsupportedCharPolicy};
+use punch_card_core::render::svg::SvgOptions;
+use punch_card_core::stats::DeckStats;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(name = "punch-card")]
@@ -11,11 +24,453 @@ struct Args {
     /// Port to serve the application on
     #[arg(short, long, default_value_t = 9267)]
     port: u16,
+
+    /// Punch text even if some characters cannot be punched, and export
+    /// cards even if the format cannot hold some punches (both are left
+    /// blank)
+    #[arg(long, global = true)]
+    force: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Punch a text card and write it as an SVG image
+    Svg {
+        /// Text to punch (max 80 characters)
+        text: String,
+        /// Output file
+        #[arg(short, long, default_value = "card.svg")]
+        output: PathBuf,
+    },
+    /// Punch one text card per argument and print deck statistics as JSON
+    Stats {
+        /// Text of each card (max 80 characters)
+        #[arg(required = true)]
+        cards: Vec<String>,
+    },
+    /// Punch a text card and print its share string
+    Share {
+        /// Text to punch (max 80 characters)
+        text: String,
+    },
+    /// Print the text of a card from its share string
+    Decode {
+        /// Share string of the card
+        share: String,
+        /// Character set to read the punches in
+        #[arg(long, value_enum, default_value_t = CharsetArg::Ibm029)]
+        charset: CharsetArg,
+        /// Letter case of the output
+        #[arg(long, value_enum, default_value_t = CaseArg::Preserve)]
+        case: CaseArg,
+    },
+    /// Punch a card from "col N: 12-3-8" lines and write it as an SVG image
+    ///
+    /// Blank lines and lines starting with '#' are ignored.
+    Notation {
+        /// File of notation lines
+        input: PathBuf,
+        /// Output file
+        #[arg(short, long, default_value = "card.svg")]
+        output: PathBuf,
+    },
+    /// Punch a text card and write it in a card file format
+    ///
+    /// Refuses if the format would lose any punches (printing can be
+    /// restored by interpreting the card, so losing it is allowed).
+    Export {
+        /// Text to punch (max 80 characters)
+        text: String,
+        /// File format to write
+        #[arg(long, value_enum, default_value_t = FormatArg::Ibm1130Binary108)]
+        format: FormatArg,
+        /// Output file
+        #[arg(short, long, default_value = "card.bin")]
+        output: PathBuf,
+    },
+    /// Punch a text card and print a hex dump of it in a card file format
+    Hexdump {
+        /// Text to punch (max 80 characters)
+        text: String,
+        /// File format to dump
+        #[arg(long, value_enum, default_value_t = FormatArg::Ebcdic80)]
+        format: FormatArg,
+    },
+    /// Check an IBM 1130 assembler source deck and print a report
+    ///
+    /// The deck is a text file with one card per line. Exits with status 1
+    /// if any card has errors.
+    Validate {
+        /// Source deck file
+        input: PathBuf,
+        /// Print the diagnostics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a deck as an 1132 printer listing
+    ///
+    /// The deck is a text file with one card per line. Characters the 1132
+    /// cannot print come out blank.
+    Listing {
+        /// Deck file
+        input: PathBuf,
+        /// Number each line with its card's position in the deck
+        #[arg(long)]
+        numbered: bool,
+        /// Read column 1 as carriage control (1 new page, 0 double space,
+        /// - triple space, + overprint)
+        #[arg(long)]
+        carriage_control: bool,
+        /// Lines per page, including the title; 0 for no page breaks
+        #[arg(long, default_value_t = 0)]
+        lines_per_page: usize,
+        /// Title to print at the top of each page
+        #[arg(long)]
+        title: Option<String>,
+    },
+    /// List the built-in example cards, or print one
+    ///
+    /// Text cards print as text, binary cards as hex words.
+    Examples {
+        /// Example to print; lists them all if omitted
+        id: Option<String>,
+    },
+    /// Print the code chart of a character set
+    Chart {
+        /// Character set to chart
+        #[arg(value_enum, default_value_t = CharsetArg::Ibm029)]
+        charset: CharsetArg,
+    },
+}
+
+/// Character sets selectable on the command line
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum CharsetArg {
+    Ibm029,
+    Ibm029Extended,
+    Ibm026Commercial,
+    Ibm026Fortran,
+    AnsiX326,
+}
+
+/// Card file formats selectable on the command line
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum FormatArg {
+    Ibm1130Binary108,
+    FullBinary120,
+    ColumnBinary160,
+    Ebcdic80,
+    Bcdic80,
+    LegacyByte80,
+    AsciiRecord,
+}
+
+impl From<FormatArg> for CardFormat {
+    fn from(arg: FormatArg) -> Self {
+        match arg {
+            FormatArg::Ibm1130Binary108 => CardFormat::Ibm1130Binary108,
+            FormatArg::FullBinary120 => CardFormat::FullBinary120,
+            FormatArg::ColumnBinary160 => CardFormat::ColumnBinary160,
+            FormatArg::Ebcdic80 => CardFormat::Ebcdic80,
+            FormatArg::Bcdic80 => CardFormat::Bcdic80,
+            FormatArg::LegacyByte80 => CardFormat::LegacyByte80,
+            FormatArg::AsciiRecord => CardFormat::AsciiRecord,
+        }
+    }
+}
+
+/// Case policies selectable on the command line
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum CaseArg {
+    Preserve,
+    Upper,
+    Lower,
+}
+
+impl From<CaseArg> for CasePolicy {
+    fn from(arg: CaseArg) -> Self {
+        match arg {
+            CaseArg::Preserve => CasePolicy::Preserve,
+            CaseArg::Upper => CasePolicy::Upper,
+            CaseArg::Lower => CasePolicy::Lower,
+        }
+    }
+}
+
+impl From<CharsetArg> for Charset {
+    fn from(arg: CharsetArg) -> Self {
+        match arg {
+            CharsetArg::Ibm029 => Charset::Ibm029,
+            CharsetArg::Ibm029Extended => Charset::Ibm029Extended,
+            CharsetArg::Ibm026Commercial => Charset::Ibm026Commercial,
+            CharsetArg::Ibm026Fortran => Charset::Ibm026Fortran,
+            CharsetArg::AnsiX326 => Charset::AnsiX326,
+        }
+    }
+}
+
+/// Punch a binary card from notation lines, or list every bad line
+fn card_from_notation(source: &str) -> Result<PunchCard, Vec<String>> {
+    let mut card = PunchCard::new(CardType::Binary);
+    let mut errors = Vec::new();
+    for (number, line) in source.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        match parse_notation_line(line) {
+            Ok((column, punches)) => card
+                .set_column_hollerith(column, punches)
+                .expect("parsed columns are within 80"),
+            Err(e) => errors.push(format!("line {}: {}", number + 1, e)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(card)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Exit with a list of unpunchable characters unless `--force` was given
+fn check_encodable<'a>(texts: impl IntoIterator<Item = &'a String>, force: bool) {
+    if force {
+        return;
+    }
+    let mut failed = false;
+    for text in texts {
+        if let Err(issues) = encode_string_strict(text) {
+            for issue in issues {
+                eprintln!("{:?}: {}", text, issue);
+            }
+            failed = true;
+        }
+    }
+    if failed {
+        eprintln!("Refusing to punch; use --force to leave these columns blank");
+        std::process::exit(1);
+    }
 }
 
 fn main() {
     let args = Args::parse();
 
+    match &args.command {
+        Some(Command::Svg { text, .. })
+        | Some(Command::Share { text })
+        | Some(Command::Export { text, .. })
+        | Some(Command::Hexdump { text, .. }) => check_encodable([text], args.force),
+        Some(Command::Stats { cards }) => check_encodable(cards, args.force),
+        Some(Command::Decode { .. })
+        | Some(Command::Notation { .. })
+        | Some(Command::Validate { .. })
+        | Some(Command::Listing { .. })
+        | Some(Command::Examples { .. })
+        | Some(Command::Chart { .. })
+        | None => {}
+    }
+
+    match args.command {
+        Some(Command::Svg { text, output }) => {
+            let card = PunchCard::from_text(&text);
+            let svg = card.to_svg(&SvgOptions::default());
+            if let Err(e) = std::fs::write(&output, svg) {
+                eprintln!("Failed to write {}: {}", output.display(), e);
+                std::process::exit(1);
+            }
+            println!("Wrote {}", output.display());
+            return;
+        }
+        Some(Command::Stats { cards }) => {
+            let deck: Deck = cards
+                .iter()
+                .map(|text| PunchCard::from_text(text))
+                .collect();
+            let stats = DeckStats::from_deck(&deck);
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&stats).expect("stats serialize to JSON")
+            );
+            return;
+        }
+        Some(Command::Share { text }) => {
+            println!("{}", PunchCard::from_text(&text).to_share_string());
+            return;
+        }
+        Some(Command::Decode {
+            share,
+            charset,
+            case,
+        }) => {
+            let card = match PunchCard::from_share_string(&share) {
+                Ok(card) => card,
+                Err(e) => {
+                    eprintln!("Invalid share string: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let options = DecodeOptions {
+                case: case.into(),
+                ..DecodeOptions::default()
+            };
+            let charset = Charset::from(charset);
+            let (text, report) = card
+                .to_text_with(&charset, &options)
+                .expect("a replacement character is set");
+            println!("{}", text.trim_end());
+            if !report.is_clean() {
+                eprintln!("{}", report);
+            }
+            return;
+        }
+        Some(Command::Notation { input, output }) => {
+            let source = match std::fs::read_to_string(&input) {
+                Ok(source) => source,
+                Err(e) => {
+                    eprintln!("Failed to read {}: {}", input.display(), e);
+                    std::process::exit(1);
+                }
+            };
+            let card = match card_from_notation(&source) {
+                Ok(card) => card,
+                Err(errors) => {
+                    for error in errors {
+                        eprintln!("{}: {}", input.display(), error);
+                    }
+                    std::process::exit(1);
+                }
+            };
+            if let Err(e) = std::fs::write(&output, card.to_svg(&SvgOptions::default())) {
+                eprintln!("Failed to write {}: {}", output.display(), e);
+                std::process::exit(1);
+            }
+            println!("Wrote {}", output.display());
+            return;
+        }
+        Some(Command::Export {
+            text,
+            format,
+            output,
+        }) => {
+            let format = CardFormat::from(format);
+            let mut card = PunchCard::from_text(&text);
+            let audit = card.audit_export(format);
+            if audit.loses_punches() {
+                eprintln!("{}", audit);
+                if !args.force {
+                    eprintln!("Refusing to write a lossy file; use --force to write it anyway");
+                    std::process::exit(1);
+                }
+                for column in audit.columns(ColumnLoss::Unrepresentable) {
+                    card.set_column_hollerith(column, HollerithCode::empty())
+                        .expect("audited columns are on the card");
+                }
+            }
+            let data = card
+                .serialize(format)
+                .expect("unrepresentable columns were blanked");
+            if let Err(e) = std::fs::write(&output, data) {
+                eprintln!("Failed to write {}: {}", output.display(), e);
+                std::process::exit(1);
+            }
+            println!("Wrote {}", output.display());
+            return;
+        }
+        Some(Command::Hexdump { text, format }) => {
+            match PunchCard::from_text(&text).hexdump(format.into()) {
+                Ok(dump) => print!("{}", dump),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Some(Command::Validate { input, json }) => {
+            let deck = match std::fs::read_to_string(&input)
+                .map_err(|e| e.to_string())
+                .and_then(|text| Deck::from_ascii_file(&text, UnsupportedCharPolicy::Error))
+            {
+                Ok(deck) => deck,
+                Err(e) => {
+                    eprintln!("Failed to read {}: {}", input.display(), e);
+                    std::process::exit(1);
+                }
+            };
+            let report = validate_source_deck(&deck);
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).expect("diagnostics serialize to JSON")
+                );
+            } else {
+                for diagnostic in &report.diagnostics {
+                    println!("{}: {}", input.display(), diagnostic);
+                }
+                println!(
+                    "{} error(s), {} warning(s)",
+                    report.count(Severity::Error),
+                    report.count(Severity::Warning)
+                );
+            }
+            if report.has_errors() {
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Listing {
+            input,
+            numbered,
+            carriage_control,
+            lines_per_page,
+            title,
+        }) => {
+            let deck = match std::fs::read_to_string(&input)
+                .map_err(|e| e.to_string())
+                .and_then(|text| Deck::from_ascii_file(&text, UnsupportedCharPolicy::Error))
+            {
+                Ok(deck) => deck,
+                Err(e) => {
+                    eprintln!("Failed to read {}: {}", input.display(), e);
+                    std::process::exit(1);
+                }
+            };
+            let options = ListingOptions {
+                numbered,
+                carriage_control,
+                lines_per_page,
+                header: title,
+                ..ListingOptions::default()
+            };
+            print!("{}", deck_to_listing(&deck, &options));
+            return;
+        }
+        Some(Command::Examples { id: None }) => {
+            for example in examples() {
+                println!("{:<10} {}", example.id, example.title);
+                println!("{:<10} {}", "", example.description);
+            }
+            return;
+        }
+        Some(Command::Examples { id: Some(id) }) => {
+            let Some(example) = example(&id) else {
+                let ids: Vec<&str> = examples().iter().map(|example| example.id).collect();
+                eprintln!("Unknown example {:?}; try one of {}", id, ids.join(", "));
+                std::process::exit(1);
+            };
+            println!("{}", example.card().to_text_or_hex().trim_end());
+            return;
+        }
+        Some(Command::Chart { charset }) => {
+            print!("{}", chart_as_table_string(charset.into()));
+            return;
+        }
+        None => {}
+    }
+
     println!("IBM 1130 Punch Card Simulator");
     println!("Serving on port: {}", args.port);
     println!("Coming soon: HTTP server implementation");
@@ -0,0 +1,52 @@
+// Encode/decode micro-benchmark
+//
+// Times the 029 table lookups on an 80-column card image. Run with
+// `cargo bench -p punch-card-core --bench encode`. Each operation is timed
+// over several samples and the fastest and median are printed, so noise
+// from a busy machine shows up as a gap between the two. To compare with
+// older code, copy this file into a checkout of it and run both.
+
+use punch_card_core::hollerith::{decode_string, encode_string};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const CARD: &str =
+    "      LD   L  VALUE  LOAD THE VALUE, 12.5% = (A+B)*C/2 -> ?             00010020";
+const SAMPLES: usize = 15;
+const ITERATIONS: u32 = 20_000;
+
+/// Time `SAMPLES` runs of `ITERATIONS` calls, sorted fastest first
+fn sample(mut run: impl FnMut()) -> Vec<Duration> {
+    let mut times: Vec<Duration> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..ITERATIONS {
+                run();
+            }
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    times
+}
+
+fn main() {
+    let encode = sample(|| {
+        black_box(encode_string(black_box(CARD)));
+    });
+    let codes = encode_string(CARD);
+    let decode = sample(|| {
+        black_box(decode_string(black_box(&codes)));
+    });
+
+    let per_card = |elapsed: Duration| elapsed.as_nanos() as f64 / f64::from(ITERATIONS);
+    for (name, times) in [("encode", encode), ("decode", decode)] {
+        println!(
+            "{}: {:.0} ns/card fastest, {:.0} ns/card median of {} samples",
+            name,
+            per_card(times[0]),
+            per_card(times[SAMPLES / 2]),
+            SAMPLES
+        );
+    }
+}
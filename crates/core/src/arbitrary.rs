@@ -0,0 +1,201 @@
+// Arbitrary Module
+//
+// proptest strategies for fuzzing code that consumes punch cards. Enabled by
+// the `proptest` feature (and always in this crate's own tests).
+//
+// Generated values are always valid:
+// - HollerithCode: rows drawn from 12, 11, 0-9, sorted and without duplicates
+// - Column: the printed character, if any, is what the punches decode to
+// - PunchCard: 80 columns; binary cards have no printed characters
+
+use crate::hollerith::{HollerithCode, char_to_hollerith, hollerith_to_char};
+use crate::punch_card::{CardType, Column, PunchCard};
+use proptest::prelude::*;
+
+/// Valid punch rows, top of card to bottom
+const ROWS: [u8; 12] = [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+/// Every character with an 029 punch pattern
+pub fn supported_chars() -> Vec<char> {
+    (' '..='~')
+        .chain(['¢', '¬'])
+        .filter(|&c| char_to_hollerith(c).is_some())
+        .collect()
+}
+
+/// Strategy for a single character with an 029 punch pattern
+pub fn supported_char() -> impl Strategy<Value = char> {
+    prop::sample::select(supported_chars())
+}
+
+impl Arbitrary for HollerithCode {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        prop::sample::subsequence(ROWS.to_vec(), 0..=ROWS.len())
+            .prop_map(HollerithCode::new)
+            .boxed()
+    }
+}
+
+impl Arbitrary for Column {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (any::<HollerithCode>(), any::<bool>())
+            .prop_map(|(punches, printed)| {
+                let printed_char = if printed {
+                    hollerith_to_char(&punches)
+                } else {
+                    None
+                };
+                Column {
+                    punches,
+                    printed_char,
+                }
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for PunchCard {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let card_type = prop_oneof![Just(CardType::Text), Just(CardType::Binary)];
+        (card_type, prop::collection::vec(any::<Column>(), 80))
+            .prop_map(|(card_type, columns)| {
+                let mut card = PunchCard::new(card_type);
+                for (index, column) in columns.into_iter().enumerate() {
+                    let dest = card.get_column_mut(index).expect("80 columns");
+                    dest.punches = column.punches;
+                    if card_type == CardType::Text {
+                        dest.printed_char = column.printed_char;
+                    }
+                }
+                card
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ebcdic::{ebcdic_to_hollerith, hollerith_to_ebcdic};
+    use crate::format::CardFormat;
+    use crate::hollerith::{decode_string, encode_string, is_valid_row};
+
+    /// Characters whose punches survive a trip through EBCDIC
+    fn ebcdic_char() -> impl Strategy<Value = char> {
+        let chars: Vec<char> = supported_chars()
+            .into_iter()
+            .filter(|&c| {
+                let code = char_to_hollerith(c).unwrap();
+                hollerith_to_ebcdic(&code).ok().map(ebcdic_to_hollerith) == Some(code)
+            })
+            .collect();
+        prop::sample::select(chars)
+    }
+
+    #[test]
+    fn test_supported_chars() {
+        let chars = supported_chars();
+        assert!(chars.contains(&'A') && chars.contains(&'¬') && chars.contains(&' '));
+        assert!(!chars.contains(&'a'));
+    }
+
+    proptest! {
+        #[test]
+        fn test_generated_codes_are_valid(code in any::<HollerithCode>()) {
+            prop_assert!(code.rows.iter().all(|&row| is_valid_row(row)));
+            prop_assert!(code.rows.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+
+        #[test]
+        fn test_generated_cards_are_coherent(card in any::<PunchCard>()) {
+            prop_assert_eq!(card.columns().len(), 80);
+            for column in card.columns() {
+                match (card.card_type(), column.printed_char) {
+                    (CardType::Binary, printed) => prop_assert_eq!(printed, None),
+                    (CardType::Text, Some(c)) => prop_assert_eq!(Some(c), hollerith_to_char(&column.punches)),
+                    (CardType::Text, None) => {}
+                }
+            }
+        }
+
+        #[test]
+        fn test_binary_roundtrip(card in any::<PunchCard>()) {
+            let loaded = PunchCard::from_binary(&card.to_binary());
+            for (a, b) in card.columns()[..72].iter().zip(loaded.columns()) {
+                prop_assert!(a.punches_eq(b));
+            }
+            prop_assert!(loaded.columns()[72..].iter().all(Column::is_blank));
+
+            let full = card.serialize(CardFormat::FullBinary120).unwrap();
+            let parsed = PunchCard::parse(&full, CardFormat::FullBinary120).unwrap();
+            prop_assert!(parsed.punches_eq(&card));
+        }
+
+        #[test]
+        fn test_ebcdic_roundtrip(text in prop::collection::vec(ebcdic_char(), 80)) {
+            let card = PunchCard::from_text(&text.iter().collect::<String>());
+            prop_assert!(PunchCard::from_ebcdic(&card.to_ebcdic().unwrap()).punches_eq(&card));
+        }
+
+        #[test]
+        fn test_array_roundtrip(arr in any::<[bool; 12]>()) {
+            let code = HollerithCode::from_array(arr);
+            prop_assert_eq!(code.as_array(), arr);
+            prop_assert_eq!(HollerithCode::new(code.rows.clone()), code);
+        }
+
+        #[test]
+        #[cfg(feature = "serde")]
+        fn test_deserialized_codes_are_canonical(rows in prop::collection::vec(any::<u8>(), 0..16)) {
+            use std::collections::hash_map::DefaultHasher;
+            use std::hash::{Hash, Hasher};
+
+            let json = serde_json::json!({ "rows": rows });
+            match serde_json::from_value::<HollerithCode>(json) {
+                Ok(code) => {
+                    prop_assert!(rows.iter().all(|&row| is_valid_row(row)));
+                    prop_assert!(code.rows.windows(2).all(|pair| pair[0] < pair[1]));
+                    // Any order of the same rows gives an equal, equally
+                    // hashed code
+                    let mut reversed = rows.clone();
+                    reversed.reverse();
+                    let other = HollerithCode::new(reversed);
+                    prop_assert_eq!(&code, &other);
+                    let hash = |code: &HollerithCode| {
+                        let mut hasher = DefaultHasher::new();
+                        code.hash(&mut hasher);
+                        hasher.finish()
+                    };
+                    prop_assert_eq!(hash(&code), hash(&other));
+                    prop_assert_eq!(HollerithCode::from_array(code.as_array()), code);
+                }
+                Err(_) => prop_assert!(rows.iter().any(|&row| !is_valid_row(row))),
+            }
+        }
+
+        #[test]
+        fn test_encode_decode_inverse(text in prop::collection::vec(supported_char(), 0..100)) {
+            let text: String = text.into_iter().collect();
+            prop_assert_eq!(decode_string(&encode_string(&text)), text);
+        }
+
+        #[test]
+        fn test_decode_encode_inverse(codes in prop::collection::vec(any::<HollerithCode>(), 0..100)) {
+            // Decodable patterns map back to the same holes
+            let codes: Vec<HollerithCode> = codes
+                .into_iter()
+                .filter(|code| hollerith_to_char(code).is_some())
+                .collect();
+            prop_assert_eq!(encode_string(&decode_string(&codes)), codes);
+        }
+    }
+}
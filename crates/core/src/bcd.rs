@@ -0,0 +1,222 @@
+// BCD Interchange Code Module
+//
+// Maps Hollerith punch patterns to the 6-bit BCD interchange code (BCDIC)
+// used by the IBM 1401 and 7090 before EBCDIC. Bits are B A 8 4 2 1: the
+// zone bits come from the zone punch (12 = BA, 11 = B, 0 = A) and the
+// numeric bits from the digit punch, with 8-2 through 8-7 adding to 8.
+//
+// Every code has an 029 character except:
+// - 0o72 (12-0): the 1401's "?" or plus zero
+// - 0o52 (11-0): the 1401's "!" or minus zero
+// - 0o20 (A zone alone): has no card code and is punched blank
+//
+// Conversions to and from EBCDIC go through the card code, so the two
+// byte codes can never disagree about a column's punches.
+
+use crate::ebcdic::{ebcdic_to_hollerith, hollerith_to_ebcdic};
+use crate::hollerith::HollerithCode;
+use std::fmt;
+
+/// Columns that could not be written as BCD
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BcdicExportError {
+    /// Zero-based columns whose punches have no BCD code
+    pub columns: Vec<usize>,
+}
+
+impl fmt::Display for BcdicExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| (column + 1).to_string())
+            .collect();
+        write!(f, "No BCD code for column(s) {}", columns.join(", "))
+    }
+}
+
+impl std::error::Error for BcdicExportError {}
+
+/// The B zone bit (11 punch; 12 punch with A)
+const ZONE_B: u8 = 0b10_0000;
+/// The A zone bit (0 punch; 12 punch with B)
+const ZONE_A: u8 = 0b01_0000;
+/// The numeric bits for a 0 digit (the 8-2 code)
+const DIGIT_ZERO: u8 = 0b00_1010;
+
+/// Convert a Hollerith pattern to a 6-bit BCD code
+///
+/// The 8-2 punch reads the same as 0, and 12-8-2 and 11-8-2 the same as
+/// 12-0 and 11-0, as on the 1401. Returns None for patterns with no BCD
+/// code, such as two digit punches other than 8 with 2-7.
+pub fn hollerith_to_bcdic(code: &HollerithCode) -> Option<u8> {
+    let zones: Vec<u8> = code
+        .rows
+        .iter()
+        .copied()
+        .filter(|row| matches!(row, 0 | 11 | 12))
+        .collect();
+    let digits: Vec<u8> = code
+        .rows
+        .iter()
+        .copied()
+        .filter(|row| (1..=9).contains(row))
+        .collect();
+    let digit = match digits[..] {
+        [] => 0,
+        [d] => d,
+        [d, 8] if (2..=7).contains(&d) => 8 + d,
+        _ => return None,
+    };
+    match zones[..] {
+        [] => Some(digit),
+        [12] => Some(ZONE_B | ZONE_A | digit),
+        [11] => Some(ZONE_B | digit),
+        [0] if digit == 0 => Some(DIGIT_ZERO),
+        [0] => Some(ZONE_A | digit),
+        [0, 12] if digit == 0 => Some(ZONE_B | ZONE_A | DIGIT_ZERO),
+        [0, 11] if digit == 0 => Some(ZONE_B | DIGIT_ZERO),
+        _ => None,
+    }
+}
+
+/// Convert a 6-bit BCD code to a Hollerith pattern
+///
+/// The top two bits are ignored. A-zone-only (0o20) has no card code and
+/// is punched blank; every other code round trips through
+/// `hollerith_to_bcdic`.
+pub fn bcdic_to_hollerith(code: u8) -> HollerithCode {
+    let digit = code & 0b1111;
+    let zone: &[u8] = match code & (ZONE_B | ZONE_A) {
+        0 => &[],
+        ZONE_B => &[11],
+        ZONE_A => &[0],
+        _ => &[12],
+    };
+    let mut rows = zone.to_vec();
+    match digit {
+        0 if code & (ZONE_B | ZONE_A) == ZONE_A => rows.clear(),
+        0 => {}
+        1..=9 => rows.push(digit),
+        // The 0 punch doubles as the A zone, so A with 8-2 is 0-8-2
+        10 if rows == [0] => rows.extend([8, 2]),
+        10 => rows.push(0),
+        _ => rows.extend([8, digit - 8]),
+    }
+    HollerithCode::new(rows)
+}
+
+/// Convert a 6-bit BCD code to the EBCDIC byte with the same punches
+///
+/// The A-zone-only code (0o20) is punched blank, so it becomes 0x40.
+pub fn bcdic_to_ebcdic(code: u8) -> Option<u8> {
+    hollerith_to_ebcdic(&bcdic_to_hollerith(code)).ok()
+}
+
+/// Convert an EBCDIC byte to the 6-bit BCD code with the same punches
+pub fn ebcdic_to_bcdic(byte: u8) -> Option<u8> {
+    hollerith_to_bcdic(&ebcdic_to_hollerith(byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hollerith::char_to_hollerith;
+    use crate::punch_card::PunchCard;
+
+    /// An IBM 1401 Autocoder card as 80 six-bit BCD codes
+    const FIXTURE_1401: &[u8; 80] = include_bytes!("../tests/fixtures/bcd_1401_record.bin");
+
+    fn bcd(c: char) -> Option<u8> {
+        hollerith_to_bcdic(&char_to_hollerith(c).unwrap())
+    }
+
+    #[test]
+    fn test_standard_codes() {
+        assert_eq!(bcd(' '), Some(0o00));
+        assert_eq!(bcd('1'), Some(0o01));
+        assert_eq!(bcd('9'), Some(0o11));
+        assert_eq!(bcd('0'), Some(0o12));
+        assert_eq!(bcd('A'), Some(0o61));
+        assert_eq!(bcd('J'), Some(0o41));
+        assert_eq!(bcd('/'), Some(0o21));
+        assert_eq!(bcd('S'), Some(0o22));
+        assert_eq!(bcd('&'), Some(0o60));
+        assert_eq!(bcd('-'), Some(0o40));
+        // 8-3, 12-8-3, 11-8-3, 0-8-3
+        assert_eq!(bcd('#'), Some(0o13));
+        assert_eq!(bcd('.'), Some(0o73));
+        assert_eq!(bcd('$'), Some(0o53));
+        assert_eq!(bcd(','), Some(0o33));
+    }
+
+    #[test]
+    fn test_roundtrip_all_codes() {
+        for code in 0..64u8 {
+            let punches = bcdic_to_hollerith(code);
+            if code == ZONE_A {
+                assert!(punches.rows.is_empty());
+                continue;
+            }
+            assert_eq!(hollerith_to_bcdic(&punches), Some(code), "code {:o}", code);
+        }
+        assert_eq!(bcdic_to_hollerith(0o72).rows, vec![0, 12]);
+        assert_eq!(bcdic_to_hollerith(0o32).rows, vec![0, 2, 8]);
+        assert_eq!(bcdic_to_hollerith(0o77).rows, vec![7, 8, 12]);
+        assert_eq!(bcdic_to_hollerith(0o100 | 0o61), bcdic_to_hollerith(0o61));
+    }
+
+    #[test]
+    fn test_alternate_and_invalid_patterns() {
+        assert_eq!(
+            hollerith_to_bcdic(&HollerithCode::new(vec![8, 2])),
+            Some(0o12)
+        );
+        assert_eq!(
+            hollerith_to_bcdic(&HollerithCode::new(vec![12, 8, 2])),
+            Some(0o72)
+        );
+        assert_eq!(hollerith_to_bcdic(&HollerithCode::new(vec![1, 2])), None);
+        assert_eq!(hollerith_to_bcdic(&HollerithCode::new(vec![8, 1])), None);
+        assert_eq!(
+            hollerith_to_bcdic(&HollerithCode::new(vec![12, 11, 1])),
+            None
+        );
+        assert_eq!(
+            hollerith_to_bcdic(&HollerithCode::new(vec![12, 0, 1])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_1401_record_fixture() {
+        let card = PunchCard::from_bcdic(FIXTURE_1401);
+        assert_eq!(
+            card.to_text_trimmed(),
+            "00010     MCW  CUST+12,PRINT+45         1401 PAYROLL RUN. A-Z/JOB"
+        );
+        assert_eq!(card.columns()[0].printed_char, Some('0'));
+        assert_eq!(card.to_bcdic().unwrap(), FIXTURE_1401);
+    }
+
+    #[test]
+    fn test_ebcdic_conversions_share_punches() {
+        for code in 0..64u8 {
+            let punches = bcdic_to_hollerith(code);
+            let byte = bcdic_to_ebcdic(code).unwrap();
+            assert_eq!(ebcdic_to_hollerith(byte), punches, "code {:o}", code);
+            if code != ZONE_A {
+                assert_eq!(ebcdic_to_bcdic(byte), Some(code), "code {:o}", code);
+            }
+        }
+        assert_eq!(bcdic_to_ebcdic(0o61), Some(0xC1));
+        assert_eq!(ebcdic_to_bcdic(0x81), None);
+
+        let ebcdic = PunchCard::from_bcdic(FIXTURE_1401).to_ebcdic().unwrap();
+        let converted: Vec<u8> = ebcdic
+            .iter()
+            .map(|&b| ebcdic_to_bcdic(b).unwrap())
+            .collect();
+        assert_eq!(converted, FIXTURE_1401);
+    }
+}
@@ -0,0 +1,138 @@
+// Bit Packing Module
+//
+// The 12-bits-per-column packing shared by the binary card formats (the
+// 108-byte IBM 1130 image and the 120-byte full-card image).
+//
+// Bit order, defined once here:
+//
+// - Each column contributes 12 bits, one per row position in card order
+//   [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9] (as in `HollerithCode::as_array`).
+// - Row position r of column c (both 0-based) is bit n = c * 12 + r of the
+//   stream.
+// - Bit n of the stream is bit n % 8 of byte n / 8, LSB first.
+//
+// So row 12 of column 1 is bit 0 of byte 0, and every 3 bytes hold exactly
+// 2 columns.
+
+use crate::hollerith::{BitOrder, HollerithCode};
+
+/// Number of bits each column occupies in the stream
+pub const BITS_PER_COLUMN: usize = 12;
+
+/// Number of bytes needed to pack `n_cols` columns
+pub fn packed_len(n_cols: usize) -> usize {
+    (n_cols * BITS_PER_COLUMN).div_ceil(8)
+}
+
+/// Convert a punch pattern to 12 bits (bit 0 = row 12 ... bit 11 = row 9)
+pub fn code_to_bits(code: &HollerithCode) -> u16 {
+    code.to_u16(BitOrder::Row9High)
+}
+
+/// Convert 12 bits (bit 0 = row 12 ... bit 11 = row 9) to a punch pattern
+///
+/// Bits 12-15 are ignored.
+pub fn code_from_bits(bits: u16) -> HollerithCode {
+    HollerithCode::from_u16(bits, BitOrder::Row9High)
+}
+
+/// Pack columns into a new byte vector of `packed_len(codes.len())` bytes
+pub fn pack_columns(codes: &[HollerithCode]) -> Vec<u8> {
+    let mut data = vec![0u8; packed_len(codes.len())];
+    pack_into(codes, &mut data);
+    data
+}
+
+/// Pack columns into `out` without allocating
+///
+/// `out` is zero-filled first. Panics if it is shorter than `packed_len`
+/// of the number of columns.
+pub fn pack_into<'a>(codes: impl IntoIterator<Item = &'a HollerithCode>, out: &mut [u8]) {
+    out.fill(0);
+    for (col_idx, code) in codes.into_iter().enumerate() {
+        for (row_idx, punched) in code.as_array().into_iter().enumerate() {
+            if punched {
+                let bit_idx = col_idx * BITS_PER_COLUMN + row_idx;
+                out[bit_idx / 8] |= 1 << (bit_idx % 8);
+            }
+        }
+    }
+}
+
+/// Unpack the column at `col_idx` (0-based)
+///
+/// Bits past the end of `data` read as unpunched.
+pub fn unpack_column(data: &[u8], col_idx: usize) -> HollerithCode {
+    let mut punches = [false; 12];
+    for (row_idx, punch) in punches.iter_mut().enumerate() {
+        let bit_idx = col_idx * BITS_PER_COLUMN + row_idx;
+        *punch = data
+            .get(bit_idx / 8)
+            .is_some_and(|byte| byte & (1 << (bit_idx % 8)) != 0);
+    }
+    HollerithCode::from_array(punches)
+}
+
+/// Unpack `n_cols` columns (see `unpack_column`)
+pub fn unpack_columns(data: &[u8], n_cols: usize) -> Vec<HollerithCode> {
+    (0..n_cols)
+        .map(|col_idx| unpack_column(data, col_idx))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_bit_order() {
+        // Column 1 row 12 is the first bit; column 2 row 9 is the 24th
+        let codes = [HollerithCode::new(vec![12]), HollerithCode::new(vec![9])];
+        assert_eq!(pack_columns(&codes), vec![0x01, 0x00, 0x80]);
+        assert_eq!(packed_len(72), 108);
+        assert_eq!(packed_len(80), 120);
+        assert_eq!(packed_len(1), 2);
+    }
+
+    #[test]
+    fn test_code_bits() {
+        // A = 12-1: row positions 0 and 3
+        let a = HollerithCode::new(vec![12, 1]);
+        assert_eq!(code_to_bits(&a), 0b1001);
+        assert_eq!(code_from_bits(0b1001), a);
+        assert_eq!(code_from_bits(0xF000), HollerithCode::empty());
+    }
+
+    #[test]
+    fn test_unpack_short_data() {
+        assert_eq!(
+            unpack_column(&[0xFF], 0).rows,
+            vec![0, 1, 2, 3, 4, 5, 11, 12]
+        );
+        assert_eq!(unpack_columns(&[], 3), vec![HollerithCode::empty(); 3]);
+    }
+
+    proptest! {
+        #[test]
+        fn test_pack_unpack_inverse(patterns in prop::collection::vec(0u16..0x1000, 0..100)) {
+            let codes: Vec<HollerithCode> = patterns.iter().map(|&bits| code_from_bits(bits)).collect();
+            let data = pack_columns(&codes);
+            prop_assert_eq!(data.len(), packed_len(codes.len()));
+            prop_assert_eq!(unpack_columns(&data, codes.len()), codes);
+        }
+
+        #[test]
+        fn test_unpack_pack_inverse(data in prop::collection::vec(any::<u8>(), 0..40)) {
+            // Whole groups of 3 bytes hold whole columns, so every bit survives
+            let data = &data[..data.len() / 3 * 3];
+            let n_cols = data.len() * 8 / BITS_PER_COLUMN;
+            prop_assert_eq!(pack_columns(&unpack_columns(data, n_cols)), data.to_vec());
+        }
+
+        #[test]
+        fn test_code_bits_inverse(bits in 0u16..0x1000) {
+            prop_assert_eq!(code_to_bits(&code_from_bits(bits)), bits);
+        }
+    }
+}
@@ -0,0 +1,261 @@
+// System/3 96-Column Card Module
+//
+// IBM System/3 cards hold 96 columns in three tiers of 32. Each column has
+// six round-hole punch positions, B, A, 8, 4, 2, 1, forming a 6-bit BCD code.
+//
+// The character code follows the 029 Hollerith code it replaced: zone 12
+// becomes B+A, zone 11 becomes B, zone 0 becomes A, and the numeric punches
+// are binary coded (8-4-2-1), so Hollerith 8-5 becomes 8+4+1.
+//
+// Binary format: 72 bytes, packed as in `sixbit` (B of column 1 is the high
+// bit of byte 0).
+
+use crate::sixbit::{CodeTable, SixBitColumns};
+
+/// Number of columns on a 96-column card
+pub const CARD96_COLUMNS: usize = 96;
+/// Number of columns in each of the three tiers
+pub const CARD96_TIER_COLUMNS: usize = 32;
+/// Size of the packed binary format in bytes
+pub const CARD96_BINARY_LEN: usize = SixBitColumns::<CARD96_COLUMNS>::BINARY_LEN;
+
+/// B zone punch position
+pub const PUNCH_B: u8 = 0b100000;
+/// A zone punch position
+pub const PUNCH_A: u8 = 0b010000;
+/// 8 numeric punch position
+pub const PUNCH_8: u8 = 0b001000;
+/// 4 numeric punch position
+pub const PUNCH_4: u8 = 0b000100;
+/// 2 numeric punch position
+pub const PUNCH_2: u8 = 0b000010;
+/// 1 numeric punch position
+pub const PUNCH_1: u8 = 0b000001;
+
+/// Character to punch code table (blank is the empty code 0)
+pub(crate) const CODE_TABLE: CodeTable = CodeTable(&[
+    ('1', 0b000001),  // 1
+    ('2', 0b000010),  // 2
+    ('3', 0b000011),  // 2 1
+    ('4', 0b000100),  // 4
+    ('5', 0b000101),  // 4 1
+    ('6', 0b000110),  // 4 2
+    ('7', 0b000111),  // 4 2 1
+    ('8', 0b001000),  // 8
+    ('9', 0b001001),  // 8 1
+    (':', 0b001010),  // 8 2
+    ('#', 0b001011),  // 8 2 1
+    ('@', 0b001100),  // 8 4
+    ('\'', 0b001101), // 8 4 1
+    ('=', 0b001110),  // 8 4 2
+    ('"', 0b001111),  // 8 4 2 1
+    ('0', 0b010000),  // A
+    ('/', 0b010001),  // A 1
+    ('S', 0b010010),  // A 2
+    ('T', 0b010011),  // A 2 1
+    ('U', 0b010100),  // A 4
+    ('V', 0b010101),  // A 4 1
+    ('W', 0b010110),  // A 4 2
+    ('X', 0b010111),  // A 4 2 1
+    ('Y', 0b011000),  // A 8
+    ('Z', 0b011001),  // A 8 1
+    (',', 0b011011),  // A 8 2 1
+    ('%', 0b011100),  // A 8 4
+    ('_', 0b011101),  // A 8 4 1
+    ('>', 0b011110),  // A 8 4 2
+    ('?', 0b011111),  // A 8 4 2 1
+    ('-', 0b100000),  // B
+    ('J', 0b100001),  // B 1
+    ('K', 0b100010),  // B 2
+    ('L', 0b100011),  // B 2 1
+    ('M', 0b100100),  // B 4
+    ('N', 0b100101),  // B 4 1
+    ('O', 0b100110),  // B 4 2
+    ('P', 0b100111),  // B 4 2 1
+    ('Q', 0b101000),  // B 8
+    ('R', 0b101001),  // B 8 1
+    ('!', 0b101010),  // B 8 2
+    ('$', 0b101011),  // B 8 2 1
+    ('*', 0b101100),  // B 8 4
+    (')', 0b101101),  // B 8 4 1
+    (';', 0b101110),  // B 8 4 2
+    ('¬', 0b101111),  // B 8 4 2 1
+    ('&', 0b110000),  // B A
+    ('A', 0b110001),  // B A 1
+    ('B', 0b110010),  // B A 2
+    ('C', 0b110011),  // B A 2 1
+    ('D', 0b110100),  // B A 4
+    ('E', 0b110101),  // B A 4 1
+    ('F', 0b110110),  // B A 4 2
+    ('G', 0b110111),  // B A 4 2 1
+    ('H', 0b111000),  // B A 8
+    ('I', 0b111001),  // B A 8 1
+    ('.', 0b111011),  // B A 8 2 1
+    ('<', 0b111100),  // B A 8 4
+    ('(', 0b111101),  // B A 8 4 1
+    ('+', 0b111110),  // B A 8 4 2
+    ('|', 0b111111),  // B A 8 4 2 1
+]);
+
+/// Convert a character to its 96-column punch code
+///
+/// Lowercase letters are treated as uppercase. Returns None for characters
+/// with no code.
+pub fn char_to_card96(c: char) -> Option<u8> {
+    CODE_TABLE.encode(c)
+}
+
+/// Convert a 96-column punch code to its character
+///
+/// Returns None for unassigned codes and values wider than 6 bits.
+pub fn card96_to_char(code: u8) -> Option<char> {
+    CODE_TABLE.decode(code)
+}
+
+/// A 96-column System/3 card
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Card96 {
+    /// Punch code for each column (low 6 bits: B A 8 4 2 1)
+    columns: SixBitColumns<CARD96_COLUMNS>,
+}
+
+impl Card96 {
+    /// Create a blank card
+    pub fn new() -> Self {
+        Card96::default()
+    }
+
+    /// Create a card from text (max 96 characters)
+    ///
+    /// Fails on text that is too long or contains characters with no code.
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        SixBitColumns::from_text(CODE_TABLE, text).map(|columns| Card96 { columns })
+    }
+
+    /// Decode the card as text, using '?' for unassigned codes
+    ///
+    /// Trailing blanks are kept, so the result is always 96 characters.
+    pub fn to_text(&self) -> String {
+        self.columns.to_text(CODE_TABLE)
+    }
+
+    /// Get the punch code for a column (0-based)
+    pub fn get(&self, index: usize) -> Option<u8> {
+        self.columns.get(index)
+    }
+
+    /// Set the punch code for a column (0-based)
+    pub fn set(&mut self, index: usize, code: u8) -> Result<(), &'static str> {
+        self.columns.set(index, code)
+    }
+
+    /// Set a column to a character's punch code (0-based)
+    pub fn set_char(&mut self, index: usize, c: char) -> Result<(), &'static str> {
+        let code = char_to_card96(c).ok_or("Character has no punch code")?;
+        self.set(index, code)
+    }
+
+    /// Get all column codes
+    pub fn columns(&self) -> &[u8] {
+        self.columns.codes()
+    }
+
+    /// Get the columns of one tier (0 = top, 2 = bottom)
+    pub fn tier(&self, tier: usize) -> Option<&[u8]> {
+        self.columns.tier(tier, CARD96_TIER_COLUMNS)
+    }
+
+    /// Columns whose code has no character assigned (0-based)
+    pub fn invalid_columns(&self) -> Vec<usize> {
+        (0..CARD96_COLUMNS)
+            .filter(|&i| card96_to_char(self.columns()[i]).is_none())
+            .collect()
+    }
+
+    /// Pack the card into the 72-byte binary format
+    pub fn to_binary(&self) -> Vec<u8> {
+        self.columns.to_binary()
+    }
+
+    /// Unpack a card from the 72-byte binary format
+    pub fn from_binary(data: &[u8]) -> Result<Self, String> {
+        SixBitColumns::from_binary(data).map(|columns| Card96 { columns })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full_charset() -> String {
+        CODE_TABLE.chars()
+    }
+
+    #[test]
+    fn test_known_codes() {
+        assert_eq!(char_to_card96('A'), Some(PUNCH_B | PUNCH_A | PUNCH_1));
+        assert_eq!(char_to_card96('J'), Some(PUNCH_B | PUNCH_1));
+        assert_eq!(char_to_card96('S'), Some(PUNCH_A | PUNCH_2));
+        assert_eq!(char_to_card96('0'), Some(PUNCH_A));
+        assert_eq!(char_to_card96('9'), Some(PUNCH_8 | PUNCH_1));
+        assert_eq!(char_to_card96('\''), Some(PUNCH_8 | PUNCH_4 | PUNCH_1));
+        assert_eq!(char_to_card96('a'), char_to_card96('A'));
+        assert_eq!(char_to_card96('~'), None);
+        assert_eq!(card96_to_char(0b011010), None); // A 8 2 is unassigned
+    }
+
+    #[test]
+    fn test_text_roundtrip_full_charset() {
+        let text = full_charset();
+        assert_eq!(text.chars().count(), 62);
+        let card = Card96::from_text(&text).unwrap();
+        assert_eq!(card.to_text().trim_end(), text.trim_end());
+        assert_eq!(card.to_text().chars().count(), CARD96_COLUMNS);
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let card = Card96::from_text(&full_charset()).unwrap();
+        let data = card.to_binary();
+        assert_eq!(data.len(), 72);
+        assert_eq!(Card96::from_binary(&data).unwrap(), card);
+        assert!(Card96::from_binary(&data[..71]).is_err());
+    }
+
+    #[test]
+    fn test_binary_bit_order() {
+        let mut card = Card96::new();
+        card.set(0, PUNCH_B).unwrap();
+        card.set(95, PUNCH_1).unwrap();
+        let data = card.to_binary();
+        assert_eq!(data[0], 0x80);
+        assert_eq!(data[71], 0x01);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(Card96::from_text(&"A".repeat(96)).is_ok());
+        assert!(Card96::from_text(&"A".repeat(97)).is_err());
+        assert_eq!(
+            Card96::from_text("AB~").unwrap_err(),
+            "Column 3: character '~' has no punch code"
+        );
+
+        let mut card = Card96::new();
+        assert!(card.set(96, PUNCH_A).is_err());
+        assert!(card.set(0, 0x40).is_err());
+        card.set(5, PUNCH_A | PUNCH_8 | PUNCH_2).unwrap();
+        assert_eq!(card.invalid_columns(), vec![5]);
+        assert_eq!(card.to_text().chars().nth(5), Some('?'));
+    }
+
+    #[test]
+    fn test_tiers() {
+        let mut card = Card96::new();
+        card.set_char(32, 'X').unwrap();
+        assert_eq!(card.tier(0).unwrap().len(), 32);
+        assert_eq!(card.tier(1).unwrap()[0], char_to_card96('X').unwrap());
+        assert!(card.tier(2).is_some());
+        assert!(card.tier(3).is_none());
+    }
+}
@@ -0,0 +1,243 @@
+// Consistency Module
+//
+// The character sets and the EBCDIC card code are separate tables, so
+// nothing but a check keeps them agreeing. Four mappings are cross-checked:
+// character to punches and back in every character set, and EBCDIC byte to
+// punches and back. Where both a character and a byte exist, the character's
+// punches must be the byte's punches.
+
+use crate::ebcdic::{char_to_ebcdic, ebcdic_to_char, ebcdic_to_hollerith, hollerith_to_ebcdic};
+use crate::hollerith::{BitOrder, CharacterSet, Charset, HollerithCode};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A disagreement between two mappings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// A set decodes punches to a character it encodes differently
+    DecodeEncode {
+        /// Name of the character set
+        set: String,
+        /// The punches decoded
+        punches: HollerithCode,
+        /// The character they decode to
+        decoded: char,
+        /// What the set punches for that character
+        encoded: Option<HollerithCode>,
+    },
+    /// A set encodes a character to punches it decodes differently
+    EncodeDecode {
+        /// Name of the character set
+        set: String,
+        /// The character encoded
+        c: char,
+        /// Its punches
+        punches: HollerithCode,
+        /// What the set reads the punches as
+        decoded: Option<char>,
+    },
+    /// An EBCDIC byte's punches convert back to another byte
+    EbcdicRoundTrip {
+        /// The byte
+        byte: u8,
+        /// Its punches
+        punches: HollerithCode,
+        /// The byte the punches convert back to
+        back: Option<u8>,
+    },
+    /// An EBCDIC byte and its character have different punches
+    CharPunches {
+        /// The byte
+        byte: u8,
+        /// The byte's character
+        c: char,
+        /// The byte's punches
+        byte_punches: HollerithCode,
+        /// The character's punches in the extended 029 set
+        char_punches: Option<HollerithCode>,
+        /// The byte `char_to_ebcdic` gives for the character
+        char_byte: Option<u8>,
+    },
+}
+
+/// Show optional punches in card notation, or "none"
+fn notation(punches: &Option<HollerithCode>) -> String {
+    punches
+        .as_ref()
+        .map_or_else(|| "none".to_string(), HollerithCode::to_notation)
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Inconsistency::DecodeEncode {
+                set,
+                punches,
+                decoded,
+                encoded,
+            } => write!(
+                f,
+                "{}: {} decodes to {:?}, which encodes to {}",
+                set,
+                punches.to_notation(),
+                decoded,
+                notation(encoded)
+            ),
+            Inconsistency::EncodeDecode {
+                set,
+                c,
+                punches,
+                decoded,
+            } => write!(
+                f,
+                "{}: {:?} encodes to {}, which decodes to {:?}",
+                set,
+                c,
+                punches.to_notation(),
+                decoded
+            ),
+            Inconsistency::EbcdicRoundTrip {
+                byte,
+                punches,
+                back,
+            } => write!(
+                f,
+                "EBCDIC 0x{:02X} punches {}, which convert back to {:02X?}",
+                byte,
+                punches.to_notation(),
+                back
+            ),
+            Inconsistency::CharPunches {
+                byte,
+                c,
+                byte_punches,
+                char_punches,
+                char_byte,
+            } => write!(
+                f,
+                "EBCDIC 0x{:02X} is {:?} punched {}, but {:?} punches {} and converts to {:02X?}",
+                byte,
+                c,
+                byte_punches.to_notation(),
+                c,
+                notation(char_punches),
+                char_byte
+            ),
+        }
+    }
+}
+
+/// Check every built-in character set and the EBCDIC card code
+///
+/// Returns every disagreement found; an empty list means the tables agree.
+pub fn consistency_check() -> Vec<Inconsistency> {
+    let mut found: Vec<Inconsistency> = Charset::ALL
+        .iter()
+        .flat_map(|charset| check_character_set(&format!("{:?}", charset), charset))
+        .collect();
+
+    for byte in 0..=255u8 {
+        let punches = ebcdic_to_hollerith(byte);
+        let back = hollerith_to_ebcdic(&punches).ok();
+        if back != Some(byte) {
+            found.push(Inconsistency::EbcdicRoundTrip {
+                byte,
+                punches: punches.clone(),
+                back,
+            });
+        }
+        if let Some(c) = ebcdic_to_char(byte) {
+            let char_punches = Charset::Ibm029Extended.encode(c);
+            let char_byte = char_to_ebcdic(c);
+            if char_punches.as_ref() != Some(&punches) || char_byte != Some(byte) {
+                found.push(Inconsistency::CharPunches {
+                    byte,
+                    c,
+                    byte_punches: punches,
+                    char_punches,
+                    char_byte,
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Check that a character set decodes what it encodes and vice versa
+///
+/// Every punch pattern and every character the set decodes to, plus all
+/// of ASCII, is tried. `name` labels the set in the results.
+pub fn check_character_set(name: &str, set: &dyn CharacterSet) -> Vec<Inconsistency> {
+    let mut found = Vec::new();
+    let mut chars: BTreeSet<char> = (0..=0x7Fu8).map(char::from).collect();
+    for value in 0..=0x0FFF {
+        let punches = HollerithCode::from_u16(value, BitOrder::Row12High);
+        let Some(decoded) = set.decode(&punches) else {
+            continue;
+        };
+        chars.insert(decoded);
+        let encoded = set.encode(decoded);
+        if encoded.as_ref() != Some(&punches) {
+            found.push(Inconsistency::DecodeEncode {
+                set: name.to_string(),
+                punches,
+                decoded,
+                encoded,
+            });
+        }
+    }
+    for c in chars {
+        let Some(punches) = set.encode(c) else {
+            continue;
+        };
+        let decoded = set.decode(&punches);
+        if decoded != Some(c) {
+            found.push(Inconsistency::EncodeDecode {
+                set: name.to_string(),
+                c,
+                punches,
+                decoded,
+            });
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_agree() {
+        let found = consistency_check();
+        let report: Vec<String> = found.iter().map(Inconsistency::to_string).collect();
+        assert!(found.is_empty(), "{:#?}", report);
+    }
+
+    /// A set that reads 12-1 as 'A' but punches 'A' as 12-2
+    struct Drifted;
+
+    impl CharacterSet for Drifted {
+        fn encode(&self, c: char) -> Option<HollerithCode> {
+            (c == 'A').then(|| HollerithCode::new(vec![12, 2]))
+        }
+
+        fn decode(&self, code: &HollerithCode) -> Option<char> {
+            (code.rows == [1, 12]).then_some('A')
+        }
+    }
+
+    #[test]
+    fn test_drift_is_reported() {
+        let found = check_character_set("drifted", &Drifted);
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            found[0].to_string(),
+            "drifted: 12-1 decodes to 'A', which encodes to 12-2"
+        );
+        assert_eq!(
+            found[1].to_string(),
+            "drifted: 'A' encodes to 12-2, which decodes to None"
+        );
+    }
+}
@@ -0,0 +1,351 @@
+// CSV Module
+//
+// Spreadsheet-friendly export/import of the punch matrix.
+//
+// Card layout (one row per column):
+//
+//   col,12,11,0,1,2,3,4,5,6,7,8,9,char
+//   1,1,0,0,1,0,0,0,0,0,0,0,0,A
+//
+// Deck layout adds a leading 1-based "card" index:
+//
+//   card,col,12,11,0,1,2,3,4,5,6,7,8,9,char
+//
+// - Punch cells are 0 or 1, in top-to-bottom row order
+// - "char" is the decoded character (empty if the pattern has none); it is
+//   informational only and ignored on import
+// - Missing columns load as blank, so only punched columns need rows
+// - Imported cards are text cards printed from their punches
+
+use crate::deck::Deck;
+use crate::hollerith::{HollerithCode, hollerith_to_char};
+use crate::punch_card::{CardType, Column, PunchCard};
+
+/// Row order of the punch cells (top of card to bottom)
+const ROW_ORDER: [u8; 12] = [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+const CARD_HEADER: &str = "col,12,11,0,1,2,3,4,5,6,7,8,9,char";
+const DECK_HEADER: &str = "card,col,12,11,0,1,2,3,4,5,6,7,8,9,char";
+
+impl PunchCard {
+    /// Export the punch matrix as CSV, one row per column
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(CARD_HEADER);
+        out.push('\n');
+        for (idx, column) in self.columns().iter().enumerate() {
+            out.push_str(&format!("{},{}\n", idx + 1, column_cells(column)));
+        }
+        out
+    }
+
+    /// Import a card from CSV in the `to_csv` layout
+    ///
+    /// Errors name the 1-based line of the first bad record.
+    pub fn from_csv(csv: &str) -> Result<PunchCard, String> {
+        let mut card = PunchCard::new(CardType::Text);
+        let mut seen = [false; 80];
+        for (line_no, fields) in records(csv, CARD_HEADER)? {
+            let (col, column) =
+                parse_column(&fields).map_err(|e| format!("Line {}: {}", line_no, e))?;
+            place_column(&mut card, &mut seen, col, column)
+                .map_err(|e| format!("Line {}: {}", line_no, e))?;
+        }
+        Ok(card)
+    }
+}
+
+impl Deck {
+    /// Export every card's punch matrix as CSV with a leading card index
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(DECK_HEADER);
+        out.push('\n');
+        for (card_idx, card) in self.iter().enumerate() {
+            for (idx, column) in card.columns().iter().enumerate() {
+                out.push_str(&format!(
+                    "{},{},{}\n",
+                    card_idx + 1,
+                    idx + 1,
+                    column_cells(column)
+                ));
+            }
+        }
+        out
+    }
+
+    /// Import a deck from CSV in the `Deck::to_csv` layout
+    ///
+    /// Card numbers start at 1 and may only stay the same or advance by one,
+    /// so rows for a card must be grouped together.
+    pub fn from_csv(csv: &str) -> Result<Deck, String> {
+        let mut deck = Deck::new();
+        let mut seen = [false; 80];
+        for (line_no, fields) in records(csv, DECK_HEADER)? {
+            let at_line = |e: String| format!("Line {}: {}", line_no, e);
+            let card_no = parse_index(fields[0], "card").map_err(at_line)?;
+            if card_no == deck.len() + 1 {
+                deck.push(PunchCard::new(CardType::Text));
+                seen = [false; 80];
+            } else if card_no != deck.len() {
+                let expected = if deck.is_empty() {
+                    "1".to_string()
+                } else {
+                    format!("{} or {}", deck.len(), deck.len() + 1)
+                };
+                return Err(at_line(format!(
+                    "card {} out of sequence (expected {})",
+                    card_no, expected
+                )));
+            }
+            let (col, column) = parse_column(&fields[1..]).map_err(at_line)?;
+            let card = deck.get_mut(card_no - 1).expect("card was just checked");
+            place_column(card, &mut seen, col, column).map_err(at_line)?;
+        }
+        Ok(deck)
+    }
+}
+
+/// The punch cells and decoded character for one column
+fn column_cells(column: &Column) -> String {
+    let mut cells: Vec<&str> = ROW_ORDER
+        .iter()
+        .map(|&row| {
+            if column.punches.is_punched(row) {
+                "1"
+            } else {
+                "0"
+            }
+        })
+        .collect();
+    let ch = hollerith_to_char(&column.punches)
+        .map(quote_field)
+        .unwrap_or_default();
+    cells.push(&ch);
+    cells.join(",")
+}
+
+/// Quote a character cell if it contains a separator or quote
+fn quote_field(ch: char) -> String {
+    match ch {
+        '"' => "\"\"\"\"".to_string(),
+        ',' => "\",\"".to_string(),
+        _ => ch.to_string(),
+    }
+}
+
+/// Split the non-empty lines after the header into fields, with 1-based line numbers
+fn records<'a>(csv: &'a str, header: &str) -> Result<Vec<(usize, Vec<&'a str>)>, String> {
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim_end_matches('\r')))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    match lines.next() {
+        Some((_, line)) if line.trim() == header => {}
+        Some((line_no, line)) => {
+            return Err(format!(
+                "Line {}: expected header \"{}\", got \"{}\"",
+                line_no, header, line
+            ));
+        }
+        None => return Err("CSV is empty (missing header)".to_string()),
+    }
+
+    let width = header.split(',').count();
+    lines
+        .map(|(line_no, line)| {
+            let fields = split_fields(line);
+            if fields.len() == width {
+                Ok((line_no, fields))
+            } else {
+                Err(format!(
+                    "Line {}: expected {} fields, got {}",
+                    line_no,
+                    width,
+                    fields.len()
+                ))
+            }
+        })
+        .collect()
+}
+
+/// Split a record on commas, keeping a quoted final field intact
+///
+/// Only the trailing "char" cell can be quoted, so a quote ends the record.
+fn split_fields(line: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut rest = line;
+    loop {
+        if rest.starts_with('"') {
+            fields.push(rest);
+            return fields;
+        }
+        match rest.split_once(',') {
+            Some((field, tail)) => {
+                fields.push(field);
+                rest = tail;
+            }
+            None => {
+                fields.push(rest);
+                return fields;
+            }
+        }
+    }
+}
+
+/// Parse `col, 12, 11, 0-9, char` fields into a 1-based column number and column
+fn parse_column(fields: &[&str]) -> Result<(usize, Column), String> {
+    let col = parse_index(fields[0], "col")?;
+    if col > 80 {
+        return Err(format!("column {} is out of range (1-80)", col));
+    }
+
+    let mut rows = Vec::new();
+    for (&row, cell) in ROW_ORDER.iter().zip(&fields[1..13]) {
+        match cell.trim() {
+            "0" | "" => {}
+            "1" => rows.push(row),
+            other => {
+                return Err(format!(
+                    "row {} cell must be 0 or 1, got \"{}\"",
+                    row, other
+                ));
+            }
+        }
+    }
+
+    let punches = HollerithCode::new(rows);
+    let printed_char = if punches.rows.is_empty() {
+        None
+    } else {
+        hollerith_to_char(&punches)
+    };
+    Ok((
+        col,
+        Column {
+            punches,
+            printed_char,
+        },
+    ))
+}
+
+fn parse_index(field: &str, name: &str) -> Result<usize, String> {
+    field
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n >= 1)
+        .ok_or_else(|| format!("{} must be a positive number, got \"{}\"", name, field))
+}
+
+fn place_column(
+    card: &mut PunchCard,
+    seen: &mut [bool; 80],
+    col: usize,
+    column: Column,
+) -> Result<(), String> {
+    if std::mem::replace(&mut seen[col - 1], true) {
+        return Err(format!("column {} appears more than once", col));
+    }
+    *card.get_column_mut(col - 1).expect("column range checked") = column;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_shape() {
+        let csv = PunchCard::from_text("A,").to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 81);
+        assert_eq!(lines[0], "col,12,11,0,1,2,3,4,5,6,7,8,9,char");
+        assert_eq!(lines[1], "1,1,0,0,1,0,0,0,0,0,0,0,0,A");
+        assert_eq!(lines[2], "2,0,0,1,0,0,1,0,0,0,0,1,0,\",\"");
+        assert_eq!(lines[80], "80,0,0,0,0,0,0,0,0,0,0,0,0, ");
+    }
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let card = PunchCard::from_text("HELLO, \"WORLD\" 1130");
+        let loaded = PunchCard::from_csv(&card.to_csv()).unwrap();
+        assert!(loaded.punches_eq(&card));
+        assert_eq!(loaded.to_text_trimmed(), "HELLO, \"WORLD\" 1130");
+    }
+
+    #[test]
+    fn test_csv_missing_columns_are_blank() {
+        let csv = "col,12,11,0,1,2,3,4,5,6,7,8,9,char\n\
+                   3,0,0,0,0,0,0,0,0,0,0,0,1,9\n\
+                   1,1,0,0,1,0,0,0,0,0,0,0,0,\n";
+        let card = PunchCard::from_csv(csv).unwrap();
+        assert_eq!(card.to_text_trimmed(), "A 9");
+    }
+
+    #[test]
+    fn test_csv_errors_report_line() {
+        let header = "col,12,11,0,1,2,3,4,5,6,7,8,9,char\n";
+        let short = format!("{}1,1,0,0,1,0,0,0,0,0,0,0,0,A\n2,1,0\n", header);
+        assert_eq!(
+            PunchCard::from_csv(&short).unwrap_err(),
+            "Line 3: expected 14 fields, got 3"
+        );
+        let bad_cell = format!("{}1,1,0,2,1,0,0,0,0,0,0,0,0,A\n", header);
+        assert_eq!(
+            PunchCard::from_csv(&bad_cell).unwrap_err(),
+            "Line 2: row 0 cell must be 0 or 1, got \"2\""
+        );
+        let bad_col = format!("{}81,0,0,0,0,0,0,0,0,0,0,0,0,\n", header);
+        assert!(
+            PunchCard::from_csv(&bad_col)
+                .unwrap_err()
+                .starts_with("Line 2:")
+        );
+        let dup = format!(
+            "{0}1,0,0,0,0,0,0,0,0,0,0,0,0,\n1,0,0,0,0,0,0,0,0,0,0,0,0,\n",
+            header
+        );
+        assert_eq!(
+            PunchCard::from_csv(&dup).unwrap_err(),
+            "Line 3: column 1 appears more than once"
+        );
+        assert!(
+            PunchCard::from_csv("card,col\n")
+                .unwrap_err()
+                .starts_with("Line 1:")
+        );
+    }
+
+    #[test]
+    fn test_deck_csv_roundtrip() {
+        let deck: Deck = ["FIRST", "SECOND CARD"]
+            .iter()
+            .map(|t| PunchCard::from_text(t))
+            .collect();
+        let csv = deck.to_csv();
+        assert!(csv.starts_with("card,col,12,"));
+        assert_eq!(csv.lines().count(), 161);
+        let loaded = Deck::from_csv(&csv).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.get(1).unwrap().to_text_trimmed(), "SECOND CARD");
+    }
+
+    #[test]
+    fn test_deck_csv_sparse_and_sequence() {
+        let header = "card,col,12,11,0,1,2,3,4,5,6,7,8,9,char\n";
+        let csv = format!(
+            "{}1,1,1,0,0,1,0,0,0,0,0,0,0,0,A\n2,2,1,0,0,0,1,0,0,0,0,0,0,0,B\n",
+            header
+        );
+        let deck = Deck::from_csv(&csv).unwrap();
+        assert_eq!(deck.get(0).unwrap().to_text_trimmed(), "A");
+        assert_eq!(deck.get(1).unwrap().to_text_trimmed(), " B");
+
+        let skipped = format!("{}2,1,0,0,0,0,0,0,0,0,0,0,0,0,\n", header);
+        assert_eq!(
+            Deck::from_csv(&skipped).unwrap_err(),
+            "Line 2: card 2 out of sequence (expected 1)"
+        );
+    }
+}
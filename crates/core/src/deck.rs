@@ -0,0 +1,316 @@
+// Deck Module
+//
+// An ordered collection of punch cards, as stacked in a card tray
+
+use crate::punch_card::{PunchCard, UnsupportedCharPolicy, fnv1a, fnv1a_start};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::ops::Range;
+
+/// Represents an ordered deck of punch cards
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Deck {
+    /// The cards in deck order (first card is read first)
+    cards: Vec<PunchCard>,
+}
+
+impl Deck {
+    /// Create a new empty deck
+    pub fn new() -> Self {
+        Deck { cards: Vec::new() }
+    }
+
+    /// Create a deck from a list of cards
+    pub fn from_cards(cards: Vec<PunchCard>) -> Self {
+        Deck { cards }
+    }
+
+    /// Create a deck from an ASCII deck file (one 80-column card image per line)
+    ///
+    /// Handles both LF and CRLF line endings and tolerates short lines or
+    /// trailing spaces. Errors name the 1-based line of the offending card.
+    pub fn from_ascii_file(text: &str, policy: UnsupportedCharPolicy) -> Result<Self, String> {
+        text.lines()
+            .enumerate()
+            .map(|(i, line)| {
+                PunchCard::from_ascii_record(line, policy)
+                    .map_err(|e| format!("Line {}: {}", i + 1, e))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Deck::from_cards)
+    }
+
+    /// Convert the deck to an ASCII deck file (one 80-column line per card)
+    pub fn to_ascii_file(&self) -> String {
+        self.cards
+            .iter()
+            .map(|card| card.to_ascii_record() + "\n")
+            .collect()
+    }
+
+    /// Write every card in IBM 1130 binary format (108 bytes per card)
+    ///
+    /// Cards are packed into one stack buffer in turn, so no memory is
+    /// allocated per card.
+    pub fn write_binary<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let mut buf = [0u8; 108];
+        for card in &self.cards {
+            card.to_binary_into(&mut buf);
+            out.write_all(&buf)?;
+        }
+        Ok(())
+    }
+
+    /// Add a card to the end of the deck
+    pub fn push(&mut self, card: PunchCard) {
+        self.cards.push(card);
+    }
+
+    /// Get the number of cards in the deck
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Check if the deck has no cards
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Get a reference to a card
+    pub fn get(&self, index: usize) -> Option<&PunchCard> {
+        self.cards.get(index)
+    }
+
+    /// Get a mutable reference to a card
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut PunchCard> {
+        self.cards.get_mut(index)
+    }
+
+    /// Get all cards as a slice
+    pub fn cards(&self) -> &[PunchCard] {
+        &self.cards
+    }
+
+    /// Iterate over the cards in deck order
+    pub fn iter(&self) -> std::slice::Iter<'_, PunchCard> {
+        self.cards.iter()
+    }
+
+    /// Iterate mutably over the cards in deck order
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, PunchCard> {
+        self.cards.iter_mut()
+    }
+
+    /// Consume the deck and return its cards
+    pub fn into_cards(self) -> Vec<PunchCard> {
+        self.cards
+    }
+
+    /// Stamp a field from a template card into every card of the deck
+    ///
+    /// Copies columns `cols` of `template` into the same columns of each card,
+    /// e.g. a constant header punched into columns 1-10 of a data deck.
+    pub fn stamp_field(
+        &mut self,
+        template: &PunchCard,
+        cols: Range<usize>,
+    ) -> Result<(), &'static str> {
+        let start = cols.start;
+        for card in &mut self.cards {
+            card.copy_columns(cols.clone(), start, template)?;
+        }
+        Ok(())
+    }
+
+    /// Sort the deck as a card sorter would on a field of columns
+    ///
+    /// Cards are ordered on the first column of `cols`, then the next, and
+    /// so on (see `SorterKey`). Like sorting on each column from the right,
+    /// the sort is stable: cards with the same field keep their order.
+    pub fn sort_by_columns(&mut self, cols: Range<usize>) {
+        self.cards.sort_by_cached_key(|card| {
+            cols.clone()
+                .map(|col| card.column_sort_key(col))
+                .collect::<Vec<_>>()
+        });
+    }
+
+    /// Compute a stable fingerprint of the deck
+    ///
+    /// Combines each card's `content_hash` in order, so two decks with the
+    /// same holes in the same order match regardless of printing or card type,
+    /// while reordering the cards changes the fingerprint.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hash = fnv1a(fnv1a_start(), &(self.cards.len() as u64).to_be_bytes());
+        for card in &self.cards {
+            hash = fnv1a(hash, &card.content_hash().to_be_bytes());
+        }
+        hash
+    }
+}
+
+impl FromIterator<PunchCard> for Deck {
+    fn from_iter<I: IntoIterator<Item = PunchCard>>(iter: I) -> Self {
+        Deck {
+            cards: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for Deck {
+    type Item = PunchCard;
+    type IntoIter = std::vec::IntoIter<PunchCard>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Deck {
+    type Item = &'a PunchCard;
+    type IntoIter = std::slice::Iter<'a, PunchCard>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::punch_card::CardType;
+
+    #[test]
+    fn test_deck_new() {
+        let deck = Deck::new();
+        assert!(deck.is_empty());
+        assert_eq!(deck.len(), 0);
+    }
+
+    #[test]
+    fn test_deck_push_and_get() {
+        let mut deck = Deck::new();
+        deck.push(PunchCard::from_text("FIRST"));
+        deck.push(PunchCard::from_text("SECOND"));
+        assert_eq!(deck.len(), 2);
+        assert!(deck.get(1).unwrap().to_text().starts_with("SECOND"));
+        assert!(deck.get(2).is_none());
+    }
+
+    #[test]
+    fn test_from_ascii_file() {
+        let text = "START DC   0\r\n      LD   X   \nLOOP  STO  Y\n";
+        let deck = Deck::from_ascii_file(text, UnsupportedCharPolicy::Error).unwrap();
+        assert_eq!(deck.len(), 3);
+        assert_eq!(deck.get(0).unwrap().to_text_trimmed(), "START DC   0");
+        assert_eq!(deck.get(1).unwrap().to_text_trimmed(), "      LD   X");
+        assert_eq!(deck.get(2).unwrap().to_text_trimmed(), "LOOP  STO  Y");
+    }
+
+    #[test]
+    fn test_from_ascii_file_error_names_line() {
+        let text = "GOOD\nBAD [\n";
+        let err = Deck::from_ascii_file(text, UnsupportedCharPolicy::Error).unwrap_err();
+        assert!(err.starts_with("Line 2:"), "{}", err);
+    }
+
+    #[test]
+    fn test_ascii_file_roundtrip() {
+        let text = "FIRST CARD\nSECOND CARD\n";
+        let deck = Deck::from_ascii_file(text, UnsupportedCharPolicy::Error).unwrap();
+        let written = deck.to_ascii_file();
+        assert_eq!(written.lines().count(), 2);
+        assert!(written.lines().all(|line| line.len() == 80));
+        let reread = Deck::from_ascii_file(&written, UnsupportedCharPolicy::Error).unwrap();
+        assert_eq!(reread.fingerprint(), deck.fingerprint());
+        assert_eq!(reread.to_ascii_file(), written);
+    }
+
+    #[test]
+    fn test_stamp_field() {
+        let mut deck = Deck::from_cards(vec![
+            PunchCard::from_text("          00001"),
+            PunchCard::from_text("          00002"),
+        ]);
+        let template = PunchCard::from_text("PAYROLL 70");
+        deck.stamp_field(&template, 0..10).unwrap();
+        assert_eq!(deck.get(0).unwrap().to_text_trimmed(), "PAYROLL 7000001");
+        assert_eq!(deck.get(1).unwrap().to_text_trimmed(), "PAYROLL 7000002");
+        assert!(deck.stamp_field(&template, 75..81).is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_matches_for_same_punches() {
+        let a = Deck::from_cards(vec![
+            PunchCard::from_text("HELLO"),
+            PunchCard::from_text("WORLD"),
+        ]);
+        let b: Deck = a
+            .iter()
+            .map(|card| PunchCard::from_binary(&card.to_binary()))
+            .collect();
+        assert_eq!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_depends_on_order() {
+        let a = Deck::from_cards(vec![
+            PunchCard::from_text("HELLO"),
+            PunchCard::from_text("WORLD"),
+        ]);
+        let b = Deck::from_cards(vec![
+            PunchCard::from_text("WORLD"),
+            PunchCard::from_text("HELLO"),
+        ]);
+        assert_ne!(a.fingerprint(), b.fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_depends_on_length() {
+        let one = Deck::from_cards(vec![PunchCard::new(CardType::Text)]);
+        let two = Deck::from_cards(vec![
+            PunchCard::new(CardType::Text),
+            PunchCard::new(CardType::Text),
+        ]);
+        assert_ne!(one.fingerprint(), two.fingerprint());
+        assert_ne!(Deck::new().fingerprint(), one.fingerprint());
+    }
+
+    #[test]
+    fn test_write_binary() {
+        let deck = Deck::from_cards(vec![
+            PunchCard::from_text("ONE"),
+            PunchCard::from_text("TWO"),
+        ]);
+        let mut out = Vec::new();
+        deck.write_binary(&mut out).unwrap();
+        assert_eq!(out.len(), 216);
+        assert_eq!(&out[..108], deck.get(0).unwrap().to_binary().as_slice());
+        assert_eq!(&out[108..], deck.get(1).unwrap().to_binary().as_slice());
+    }
+
+    #[test]
+    fn test_sort_by_columns() {
+        let mut deck: Deck = ["0042 B", "  7  A", "0100 C", "0042 A", "0009 D"]
+            .into_iter()
+            .map(PunchCard::from_text)
+            .collect();
+        deck.sort_by_columns(0..4);
+        let order: Vec<String> = deck
+            .iter()
+            .map(|card| card.to_text()[..6].to_string())
+            .collect();
+        // Blank columns sort first; equal fields keep their order
+        assert_eq!(order, ["  7  A", "0009 D", "0042 B", "0042 A", "0100 C"]);
+
+        let mut names: Deck = ["SMITH", "ADAMS", "JONES", "12345", "&CO", "ZED"]
+            .into_iter()
+            .map(PunchCard::from_text)
+            .collect();
+        names.sort_by_columns(0..5);
+        let order: Vec<String> = names.iter().map(|card| card.to_text_trimmed()).collect();
+        assert_eq!(order, ["&CO", "ADAMS", "JONES", "SMITH", "ZED", "12345"]);
+    }
+}
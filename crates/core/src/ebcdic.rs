@@ -1,8 +1,103 @@
 // EBCDIC Encoding Module
 //
 // Maps Hollerith punch patterns to EBCDIC character codes (80-column format)
+// using the System/360 card code, which gives every one of the 256 byte
+// values its own punch pattern. Bytes without a graphic (controls and
+// unassigned codes) are punched with 9-row combinations, so any byte string
+// survives a trip through cards. Both directions come from one table.
 
-use crate::hollerith::HollerithCode;
+use crate::deck::Deck;
+use crate::hollerith::{
+    BitOrder, Charset, HollerithCode, char_to_hollerith_with, hollerith_to_char_with,
+};
+use crate::punch_card::PunchCard;
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+
+/// A punch pattern that is not the card code of any EBCDIC byte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmappedPattern(pub HollerithCode);
+
+impl fmt::Display for UnmappedPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Punches {} have no EBCDIC byte", self.0.to_notation())
+    }
+}
+
+impl std::error::Error for UnmappedPattern {}
+
+/// Columns that could not be written as EBCDIC
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EbcdicExportError {
+    /// Zero-based columns whose punches have no EBCDIC byte
+    pub columns: Vec<usize>,
+}
+
+impl fmt::Display for EbcdicExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| (column + 1).to_string())
+            .collect();
+        write!(f, "No EBCDIC byte for column(s) {}", columns.join(", "))
+    }
+}
+
+impl std::error::Error for EbcdicExportError {}
+
+/// Punches for each EBCDIC byte, as row-12-high masks (see
+/// `BitOrder::Row12High`)
+///
+/// The low nibble picks the digit punch: 1-9, or 8 plus 2-7 for A-F. The
+/// high nibble picks the zones: 12, 11, 0 for the graphics in 4x-Fx, with a
+/// 9 punch added for controls in 0x-3x.
+#[rustfmt::skip]
+static EBCDIC_TO_MASK: [u16; 256] = [
+    0xB03, 0x901, 0x881, 0x841, 0x821, 0x811, 0x809, 0x805, // 0x
+    0x803, 0x903, 0x883, 0x843, 0x823, 0x813, 0x80B, 0x807,
+    0xD03, 0x501, 0x481, 0x441, 0x421, 0x411, 0x409, 0x405, // 1x
+    0x403, 0x503, 0x483, 0x443, 0x423, 0x413, 0x40B, 0x407,
+    0x703, 0x301, 0x281, 0x241, 0x221, 0x211, 0x209, 0x205, // 2x
+    0x203, 0x303, 0x283, 0x243, 0x223, 0x213, 0x20B, 0x207,
+    0xF03, 0x101, 0x081, 0x041, 0x021, 0x011, 0x009, 0x005, // 3x
+    0x003, 0x103, 0x083, 0x043, 0x023, 0x013, 0x00B, 0x007,
+    0x000, 0xB01, 0xA81, 0xA41, 0xA21, 0xA11, 0xA09, 0xA05, // 4x
+    0xA03, 0x902, 0x882, 0x842, 0x822, 0x812, 0x80A, 0x806,
+    0x800, 0xD01, 0xC81, 0xC41, 0xC21, 0xC11, 0xC09, 0xC05, // 5x
+    0xC03, 0x502, 0x482, 0x442, 0x422, 0x412, 0x40A, 0x406,
+    0x400, 0x300, 0x681, 0x641, 0x621, 0x611, 0x609, 0x605, // 6x
+    0x603, 0x302, 0xC00, 0x242, 0x222, 0x212, 0x20A, 0x206,
+    0xE00, 0xF01, 0xE81, 0xE41, 0xE21, 0xE11, 0xE09, 0xE05, // 7x
+    0xE03, 0x102, 0x082, 0x042, 0x022, 0x012, 0x00A, 0x006,
+    0xB02, 0xB00, 0xA80, 0xA40, 0xA20, 0xA10, 0xA08, 0xA04, // 8x
+    0xA02, 0xA01, 0xA82, 0xA42, 0xA22, 0xA12, 0xA0A, 0xA06,
+    0xD02, 0xD00, 0xC80, 0xC40, 0xC20, 0xC10, 0xC08, 0xC04, // 9x
+    0xC02, 0xC01, 0xC82, 0xC42, 0xC22, 0xC12, 0xC0A, 0xC06,
+    0x702, 0x700, 0x680, 0x640, 0x620, 0x610, 0x608, 0x604, // Ax
+    0x602, 0x601, 0x682, 0x642, 0x622, 0x612, 0x60A, 0x606,
+    0xF02, 0xF00, 0xE80, 0xE40, 0xE20, 0xE10, 0xE08, 0xE04, // Bx
+    0xE02, 0xE01, 0xE82, 0xE42, 0xE22, 0xE12, 0xE0A, 0xE06,
+    0xA00, 0x900, 0x880, 0x840, 0x820, 0x810, 0x808, 0x804, // Cx
+    0x802, 0x801, 0xA83, 0xA43, 0xA23, 0xA13, 0xA0B, 0xA07,
+    0x600, 0x500, 0x480, 0x440, 0x420, 0x410, 0x408, 0x404, // Dx
+    0x402, 0x401, 0xC83, 0xC43, 0xC23, 0xC13, 0xC0B, 0xC07,
+    0x282, 0x701, 0x280, 0x240, 0x220, 0x210, 0x208, 0x204, // Ex
+    0x202, 0x201, 0x683, 0x643, 0x623, 0x613, 0x60B, 0x607,
+    0x200, 0x100, 0x080, 0x040, 0x020, 0x010, 0x008, 0x004, // Fx
+    0x002, 0x001, 0xE83, 0xE43, 0xE23, 0xE13, 0xE0B, 0xE07,
+];
+
+/// Reverse of `EBCDIC_TO_MASK`; None for patterns no byte is punched as
+static MASK_TO_EBCDIC: [Option<u8>; 4096] = {
+    let mut table = [None; 4096];
+    let mut byte = 0;
+    while byte < 256 {
+        table[EBCDIC_TO_MASK[byte] as usize] = Some(byte as u8);
+        byte += 1;
+    }
+    table
+};
 
 /// Convert a Hollerith pattern to an EBCDIC byte
 ///
@@ -11,155 +106,661 @@ use crate::hollerith::HollerithCode;
 /// - Letters A-I: 0xC1-0xC9
 /// - Letters J-R: 0xD1-0xD9
 /// - Letters S-Z: 0xE2-0xE9
+/// - 8-punch specials: 0x4A-0x4F, 0x5A-0x5F, 0x6B-0x6F, 0x7A-0x7F
+/// - `&`, `-`, `/`: 0x50, 0x60, 0x61
 /// - Space: 0x40
-pub fn hollerith_to_ebcdic(code: &HollerithCode) -> u8 {
-    // Check for common patterns
-    let rows = &code.rows;
-
-    // Space (no punches)
-    if rows.is_empty() {
-        return 0x40;
-    }
-
-    // Single digit punches (0-9)
-    if rows.len() == 1 {
-        match rows[0] {
-            0 => return 0xF0,  // '0'
-            1 => return 0xF1,  // '1'
-            2 => return 0xF2,  // '2'
-            3 => return 0xF3,  // '3'
-            4 => return 0xF4,  // '4'
-            5 => return 0xF5,  // '5'
-            6 => return 0xF6,  // '6'
-            7 => return 0xF7,  // '7'
-            8 => return 0xF8,  // '8'
-            9 => return 0xF9,  // '9'
-            12 => return 0x4C, // '&' ampersand
-            11 => return 0x60, // '-' hyphen
-            _ => return 0x40,  // default to space
-        }
-    }
-
-    // Two punches (letters or special characters)
-    // Note: rows are sorted, so we need to match in sorted order
-    if rows.len() == 2 {
-        match (rows[0], rows[1]) {
-            // Letters A-I (1-9 + 12) - sorted order
-            (1, 12) => return 0xC1, // 'A'
-            (2, 12) => return 0xC2, // 'B'
-            (3, 12) => return 0xC3, // 'C'
-            (4, 12) => return 0xC4, // 'D'
-            (5, 12) => return 0xC5, // 'E'
-            (6, 12) => return 0xC6, // 'F'
-            (7, 12) => return 0xC7, // 'G'
-            (8, 12) => return 0xC8, // 'H'
-            (9, 12) => return 0xC9, // 'I'
-
-            // Letters J-R (1-9 + 11) - sorted order
-            (1, 11) => return 0xD1, // 'J'
-            (2, 11) => return 0xD2, // 'K'
-            (3, 11) => return 0xD3, // 'L'
-            (4, 11) => return 0xD4, // 'M'
-            (5, 11) => return 0xD5, // 'N'
-            (6, 11) => return 0xD6, // 'O'
-            (7, 11) => return 0xD7, // 'P'
-            (8, 11) => return 0xD8, // 'Q'
-            (9, 11) => return 0xD9, // 'R'
-
-            // Letters S-Z (0 + 2-9) - sorted order
-            (0, 2) => return 0xE2, // 'S'
-            (0, 3) => return 0xE3, // 'T'
-            (0, 4) => return 0xE4, // 'U'
-            (0, 5) => return 0xE5, // 'V'
-            (0, 6) => return 0xE6, // 'W'
-            (0, 7) => return 0xE7, // 'X'
-            (0, 8) => return 0xE8, // 'Y'
-            (0, 9) => return 0xE9, // 'Z'
-
-            // Special characters
-            (0, 1) => return 0x61, // '/' slash
-
-            _ => return 0x40, // default to space
-        }
-    }
-
-    // Three or more punches (special characters with overpunch)
-    // For now, default to space for unsupported patterns
-    0x40
+///
+/// Fails for the 3840 patterns that are not the card code of any byte.
+pub fn hollerith_to_ebcdic(code: &HollerithCode) -> Result<u8, UnmappedPattern> {
+    MASK_TO_EBCDIC[usize::from(code.to_u16(BitOrder::Row12High))]
+        .ok_or_else(|| UnmappedPattern(code.clone()))
 }
 
 /// Convert an EBCDIC byte to a Hollerith pattern
 ///
 /// This is the inverse of hollerith_to_ebcdic
 pub fn ebcdic_to_hollerith(byte: u8) -> HollerithCode {
-    let rows = match byte {
-        // Space
-        0x40 => vec![],
-
-        // Digits 0-9 (0xF0-0xF9)
-        0xF0 => vec![0],
-        0xF1 => vec![1],
-        0xF2 => vec![2],
-        0xF3 => vec![3],
-        0xF4 => vec![4],
-        0xF5 => vec![5],
-        0xF6 => vec![6],
-        0xF7 => vec![7],
-        0xF8 => vec![8],
-        0xF9 => vec![9],
-
-        // Letters A-I (0xC1-0xC9)
-        0xC1 => vec![12, 1], // 'A'
-        0xC2 => vec![12, 2], // 'B'
-        0xC3 => vec![12, 3], // 'C'
-        0xC4 => vec![12, 4], // 'D'
-        0xC5 => vec![12, 5], // 'E'
-        0xC6 => vec![12, 6], // 'F'
-        0xC7 => vec![12, 7], // 'G'
-        0xC8 => vec![12, 8], // 'H'
-        0xC9 => vec![12, 9], // 'I'
-
-        // Letters J-R (0xD1-0xD9)
-        0xD1 => vec![11, 1], // 'J'
-        0xD2 => vec![11, 2], // 'K'
-        0xD3 => vec![11, 3], // 'L'
-        0xD4 => vec![11, 4], // 'M'
-        0xD5 => vec![11, 5], // 'N'
-        0xD6 => vec![11, 6], // 'O'
-        0xD7 => vec![11, 7], // 'P'
-        0xD8 => vec![11, 8], // 'Q'
-        0xD9 => vec![11, 9], // 'R'
-
-        // Letters S-Z (0xE2-0xE9)
-        0xE2 => vec![0, 2], // 'S'
-        0xE3 => vec![0, 3], // 'T'
-        0xE4 => vec![0, 4], // 'U'
-        0xE5 => vec![0, 5], // 'V'
-        0xE6 => vec![0, 6], // 'W'
-        0xE7 => vec![0, 7], // 'X'
-        0xE8 => vec![0, 8], // 'Y'
-        0xE9 => vec![0, 9], // 'Z'
-
-        // Special characters
-        0x4C => vec![12],   // '&' ampersand
-        0x60 => vec![11],   // '-' hyphen
-        0x61 => vec![0, 1], // '/' slash
-
-        // Default to space for unknown codes
-        _ => vec![],
-    };
-
-    HollerithCode::new(rows)
+    HollerithCode::from_u16(EBCDIC_TO_MASK[usize::from(byte)], BitOrder::Row12High)
+}
+
+/// Convert an EBCDIC byte to its character, if its punches make one
+///
+/// Characters are read in `Charset::Ibm029Extended`, so lowercase letters
+/// (0x81-0xA9) and the record and group marks keep their own characters.
+pub fn ebcdic_to_char(byte: u8) -> Option<char> {
+    hollerith_to_char_with(Charset::Ibm029Extended, &ebcdic_to_hollerith(byte))
+}
+
+/// Convert a character to its EBCDIC byte
+///
+/// Takes the characters `ebcdic_to_char` returns, so `a` is 0x81; see
+/// `ascii_to_ebcdic` for keypunch-style uppercasing.
+pub fn char_to_ebcdic(c: char) -> Option<u8> {
+    hollerith_to_ebcdic(&char_to_hollerith_with(Charset::Ibm029Extended, c)?).ok()
+}
+
+/// Check if a byte is EBCDIC text: a character the extended 029 punches
+///
+/// The space (0x40) is printable; 0x00 padding and the other controls are
+/// not.
+pub fn is_printable(byte: u8) -> bool {
+    ebcdic_to_char(byte).is_some()
+}
+
+/// Fraction of the bytes that are printable EBCDIC (see `is_printable`)
+///
+/// 0x00 bytes are taken as padding and left out, so a short record padded
+/// with nulls scores like the text it holds. Returns 0.0 if nothing but
+/// padding is left.
+pub fn printable_fraction(data: &[u8]) -> f32 {
+    let content = data.iter().filter(|&&byte| byte != 0x00);
+    let (printable, total) = content.fold((0usize, 0usize), |(printable, total), &byte| {
+        (printable + usize::from(is_printable(byte)), total + 1)
+    });
+    if total == 0 {
+        0.0
+    } else {
+        printable as f32 / total as f32
+    }
+}
+
+/// Which characters EBCDIC bytes stand for
+///
+/// The punches for each byte are the same in every code page; the code
+/// pages disagree on characters such as `[`, `]`, `!`, `|`, `¬`, and the
+/// newline. `CardCode` reads bytes as whatever their punches are in
+/// `Charset::Ibm029Extended`: the 63 keypunch characters, lowercase
+/// letters, and the record and group marks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CodePage {
+    /// The extended 029 character for each byte's punches
+    #[default]
+    CardCode,
+    /// US/Canada EBCDIC
+    Cp037,
+    /// International EBCDIC
+    Cp500,
+    /// Latin-1 open systems EBCDIC (z/OS Unix)
+    Cp1047,
+}
+
+impl CodePage {
+    /// Get the character for a byte, if it has one
+    pub fn to_char(self, byte: u8) -> Option<char> {
+        match self.table() {
+            Some(table) => Some(table[usize::from(byte)]),
+            None => ebcdic_to_char(byte),
+        }
+    }
+
+    /// Get the byte for a character, if it has one
+    pub fn from_char(self, c: char) -> Option<u8> {
+        match self.table() {
+            Some(table) => table.iter().position(|&other| other == c).map(|i| i as u8),
+            None => char_to_ebcdic(c),
+        }
+    }
+
+    /// The byte-to-character table, for every page but `CardCode`
+    fn table(self) -> Option<&'static [char; 256]> {
+        match self {
+            CodePage::CardCode => None,
+            CodePage::Cp037 => Some(&CP037),
+            CodePage::Cp500 => Some(&CP500),
+            CodePage::Cp1047 => Some(&CP1047),
+        }
+    }
+}
+
+/// Code page 037 (US/Canada)
+#[rustfmt::skip]
+static CP037: [char; 256] = [
+    '\u{0}', '\u{1}', '\u{2}', '\u{3}', '\u{9C}', '\u{9}', '\u{86}', '\u{7F}', // 0x
+    '\u{97}', '\u{8D}', '\u{8E}', '\u{B}', '\u{C}', '\u{D}', '\u{E}', '\u{F}',
+    '\u{10}', '\u{11}', '\u{12}', '\u{13}', '\u{9D}', '\u{85}', '\u{8}', '\u{87}', // 1x
+    '\u{18}', '\u{19}', '\u{92}', '\u{8F}', '\u{1C}', '\u{1D}', '\u{1E}', '\u{1F}',
+    '\u{80}', '\u{81}', '\u{82}', '\u{83}', '\u{84}', '\u{A}', '\u{17}', '\u{1B}', // 2x
+    '\u{88}', '\u{89}', '\u{8A}', '\u{8B}', '\u{8C}', '\u{5}', '\u{6}', '\u{7}',
+    '\u{90}', '\u{91}', '\u{16}', '\u{93}', '\u{94}', '\u{95}', '\u{96}', '\u{4}', // 3x
+    '\u{98}', '\u{99}', '\u{9A}', '\u{9B}', '\u{14}', '\u{15}', '\u{9E}', '\u{1A}',
+    ' ', '\u{A0}', 'â', 'ä', 'à', 'á', 'ã', 'å', // 4x
+    'ç', 'ñ', '¢', '.', '<', '(', '+', '|',
+    '&', 'é', 'ê', 'ë', 'è', 'í', 'î', 'ï', // 5x
+    'ì', 'ß', '!', '$', '*', ')', ';', '¬',
+    '-', '/', 'Â', 'Ä', 'À', 'Á', 'Ã', 'Å', // 6x
+    'Ç', 'Ñ', '¦', ',', '%', '_', '>', '?',
+    'ø', 'É', 'Ê', 'Ë', 'È', 'Í', 'Î', 'Ï', // 7x
+    'Ì', '`', ':', '#', '@', '\'', '=', '"',
+    'Ø', 'a', 'b', 'c', 'd', 'e', 'f', 'g', // 8x
+    'h', 'i', '«', '»', 'ð', 'ý', 'þ', '±',
+    '°', 'j', 'k', 'l', 'm', 'n', 'o', 'p', // 9x
+    'q', 'r', 'ª', 'º', 'æ', '¸', 'Æ', '¤',
+    'µ', '~', 's', 't', 'u', 'v', 'w', 'x', // Ax
+    'y', 'z', '¡', '¿', 'Ð', 'Ý', 'Þ', '®',
+    '^', '£', '¥', '·', '©', '§', '¶', '¼', // Bx
+    '½', '¾', '[', ']', '¯', '¨', '´', '×',
+    '{', 'A', 'B', 'C', 'D', 'E', 'F', 'G', // Cx
+    'H', 'I', '\u{AD}', 'ô', 'ö', 'ò', 'ó', 'õ',
+    '}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', // Dx
+    'Q', 'R', '¹', 'û', 'ü', 'ù', 'ú', 'ÿ',
+    '\\', '÷', 'S', 'T', 'U', 'V', 'W', 'X', // Ex
+    'Y', 'Z', '²', 'Ô', 'Ö', 'Ò', 'Ó', 'Õ',
+    '0', '1', '2', '3', '4', '5', '6', '7', // Fx
+    '8', '9', '³', 'Û', 'Ü', 'Ù', 'Ú', '\u{9F}',
+];
+
+/// Code page 500 (International)
+#[rustfmt::skip]
+static CP500: [char; 256] = [
+    '\u{0}', '\u{1}', '\u{2}', '\u{3}', '\u{9C}', '\u{9}', '\u{86}', '\u{7F}', // 0x
+    '\u{97}', '\u{8D}', '\u{8E}', '\u{B}', '\u{C}', '\u{D}', '\u{E}', '\u{F}',
+    '\u{10}', '\u{11}', '\u{12}', '\u{13}', '\u{9D}', '\u{85}', '\u{8}', '\u{87}', // 1x
+    '\u{18}', '\u{19}', '\u{92}', '\u{8F}', '\u{1C}', '\u{1D}', '\u{1E}', '\u{1F}',
+    '\u{80}', '\u{81}', '\u{82}', '\u{83}', '\u{84}', '\u{A}', '\u{17}', '\u{1B}', // 2x
+    '\u{88}', '\u{89}', '\u{8A}', '\u{8B}', '\u{8C}', '\u{5}', '\u{6}', '\u{7}',
+    '\u{90}', '\u{91}', '\u{16}', '\u{93}', '\u{94}', '\u{95}', '\u{96}', '\u{4}', // 3x
+    '\u{98}', '\u{99}', '\u{9A}', '\u{9B}', '\u{14}', '\u{15}', '\u{9E}', '\u{1A}',
+    ' ', '\u{A0}', 'â', 'ä', 'à', 'á', 'ã', 'å', // 4x
+    'ç', 'ñ', '[', '.', '<', '(', '+', '!',
+    '&', 'é', 'ê', 'ë', 'è', 'í', 'î', 'ï', // 5x
+    'ì', 'ß', ']', '$', '*', ')', ';', '^',
+    '-', '/', 'Â', 'Ä', 'À', 'Á', 'Ã', 'Å', // 6x
+    'Ç', 'Ñ', '¦', ',', '%', '_', '>', '?',
+    'ø', 'É', 'Ê', 'Ë', 'È', 'Í', 'Î', 'Ï', // 7x
+    'Ì', '`', ':', '#', '@', '\'', '=', '"',
+    'Ø', 'a', 'b', 'c', 'd', 'e', 'f', 'g', // 8x
+    'h', 'i', '«', '»', 'ð', 'ý', 'þ', '±',
+    '°', 'j', 'k', 'l', 'm', 'n', 'o', 'p', // 9x
+    'q', 'r', 'ª', 'º', 'æ', '¸', 'Æ', '¤',
+    'µ', '~', 's', 't', 'u', 'v', 'w', 'x', // Ax
+    'y', 'z', '¡', '¿', 'Ð', 'Ý', 'Þ', '®',
+    '¢', '£', '¥', '·', '©', '§', '¶', '¼', // Bx
+    '½', '¾', '¬', '|', '¯', '¨', '´', '×',
+    '{', 'A', 'B', 'C', 'D', 'E', 'F', 'G', // Cx
+    'H', 'I', '\u{AD}', 'ô', 'ö', 'ò', 'ó', 'õ',
+    '}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', // Dx
+    'Q', 'R', '¹', 'û', 'ü', 'ù', 'ú', 'ÿ',
+    '\\', '÷', 'S', 'T', 'U', 'V', 'W', 'X', // Ex
+    'Y', 'Z', '²', 'Ô', 'Ö', 'Ò', 'Ó', 'Õ',
+    '0', '1', '2', '3', '4', '5', '6', '7', // Fx
+    '8', '9', '³', 'Û', 'Ü', 'Ù', 'Ú', '\u{9F}',
+];
+
+/// Code page 1047 (Latin-1 open systems)
+#[rustfmt::skip]
+static CP1047: [char; 256] = [
+    '\u{0}', '\u{1}', '\u{2}', '\u{3}', '\u{9C}', '\u{9}', '\u{86}', '\u{7F}', // 0x
+    '\u{97}', '\u{8D}', '\u{8E}', '\u{B}', '\u{C}', '\u{D}', '\u{E}', '\u{F}',
+    '\u{10}', '\u{11}', '\u{12}', '\u{13}', '\u{9D}', '\u{A}', '\u{8}', '\u{87}', // 1x
+    '\u{18}', '\u{19}', '\u{92}', '\u{8F}', '\u{1C}', '\u{1D}', '\u{1E}', '\u{1F}',
+    '\u{80}', '\u{81}', '\u{82}', '\u{83}', '\u{84}', '\u{85}', '\u{17}', '\u{1B}', // 2x
+    '\u{88}', '\u{89}', '\u{8A}', '\u{8B}', '\u{8C}', '\u{5}', '\u{6}', '\u{7}',
+    '\u{90}', '\u{91}', '\u{16}', '\u{93}', '\u{94}', '\u{95}', '\u{96}', '\u{4}', // 3x
+    '\u{98}', '\u{99}', '\u{9A}', '\u{9B}', '\u{14}', '\u{15}', '\u{9E}', '\u{1A}',
+    ' ', '\u{A0}', 'â', 'ä', 'à', 'á', 'ã', 'å', // 4x
+    'ç', 'ñ', '¢', '.', '<', '(', '+', '|',
+    '&', 'é', 'ê', 'ë', 'è', 'í', 'î', 'ï', // 5x
+    'ì', 'ß', '!', '$', '*', ')', ';', '^',
+    '-', '/', 'Â', 'Ä', 'À', 'Á', 'Ã', 'Å', // 6x
+    'Ç', 'Ñ', '¦', ',', '%', '_', '>', '?',
+    'ø', 'É', 'Ê', 'Ë', 'È', 'Í', 'Î', 'Ï', // 7x
+    'Ì', '`', ':', '#', '@', '\'', '=', '"',
+    'Ø', 'a', 'b', 'c', 'd', 'e', 'f', 'g', // 8x
+    'h', 'i', '«', '»', 'ð', 'ý', 'þ', '±',
+    '°', 'j', 'k', 'l', 'm', 'n', 'o', 'p', // 9x
+    'q', 'r', 'ª', 'º', 'æ', '¸', 'Æ', '¤',
+    'µ', '~', 's', 't', 'u', 'v', 'w', 'x', // Ax
+    'y', 'z', '¡', '¿', 'Ð', '[', 'Þ', '®',
+    '¬', '£', '¥', '·', '©', '§', '¶', '¼', // Bx
+    '½', '¾', 'Ý', '¨', '¯', ']', '´', '×',
+    '{', 'A', 'B', 'C', 'D', 'E', 'F', 'G', // Cx
+    'H', 'I', '\u{AD}', 'ô', 'ö', 'ò', 'ó', 'õ',
+    '}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', // Dx
+    'Q', 'R', '¹', 'û', 'ü', 'ù', 'ú', 'ÿ',
+    '\\', '÷', 'S', 'T', 'U', 'V', 'W', 'X', // Ex
+    'Y', 'Z', '²', 'Ô', 'Ö', 'Ò', 'Ó', 'Õ',
+    '0', '1', '2', '3', '4', '5', '6', '7', // Fx
+    '8', '9', '³', 'Û', 'Ü', 'Ù', 'Ú', '\u{9F}',
+];
+
+/// What text conversions write for bytes and characters they cannot convert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextConversionOptions {
+    /// Characters the bytes stand for
+    pub code_page: CodePage,
+    /// Character for an EBCDIC byte with no character
+    pub unmappable_byte: char,
+    /// Byte for a character with no EBCDIC code
+    pub unmappable_char: u8,
+}
+
+impl Default for TextConversionOptions {
+    /// '?' as `PunchCard::to_text` shows undecodable columns, and the EBCDIC
+    /// blank as `PunchCard::from_text` leaves unpunchable ones
+    fn default() -> Self {
+        TextConversionOptions {
+            code_page: CodePage::CardCode,
+            unmappable_byte: '?',
+            unmappable_char: 0x40,
+        }
+    }
+}
+
+/// What loading a card does with control bytes
+///
+/// A control byte is one with no character in the code page, or whose
+/// character is a control character.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ControlPolicy {
+    /// Punch the byte's card code, unprinted
+    #[default]
+    Punch,
+    /// Leave the column blank
+    Blank,
+    /// Fail, naming the column
+    Error,
+    /// End the card at NL (0x15) or LF (0x25), and leave other controls
+    /// blank; deck loaders read one card per line instead of per 80 bytes
+    RecordBoundary,
+}
+
+/// How EBCDIC bytes are loaded into cards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EbcdicLoadOptions {
+    /// Characters the bytes stand for
+    pub code_page: CodePage,
+    /// What to do with control bytes
+    pub controls: ControlPolicy,
+}
+
+/// A control byte refused by `ControlPolicy::Error`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlByte {
+    /// Zero-based column
+    pub column: usize,
+    /// The byte
+    pub byte: u8,
+}
+
+impl fmt::Display for ControlByte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Column {}: control byte 0x{:02X}",
+            self.column + 1,
+            self.byte
+        )
+    }
+}
+
+impl std::error::Error for ControlByte {}
+
+/// Check if a byte ends a line: NL or LF (swapped in code page 1047)
+pub fn is_line_end(byte: u8) -> bool {
+    matches!(byte, 0x15 | 0x25)
+}
+
+/// Convert EBCDIC bytes to text with the default options
+///
+/// For a card record this is the printing `PunchCard::from_ebcdic` gives
+/// each column, without building the card.
+pub fn ebcdic_to_ascii(data: &[u8]) -> String {
+    ebcdic_to_ascii_with(data, &TextConversionOptions::default())
+}
+
+/// Convert EBCDIC bytes to text, one character per byte
+pub fn ebcdic_to_ascii_with(data: &[u8], options: &TextConversionOptions) -> String {
+    data.iter()
+        .map(|&byte| {
+            options
+                .code_page
+                .to_char(byte)
+                .unwrap_or(options.unmappable_byte)
+        })
+        .collect()
+}
+
+/// Convert text to EBCDIC bytes with the default options
+pub fn ascii_to_ebcdic(text: &str) -> Vec<u8> {
+    ascii_to_ebcdic_with(text, &TextConversionOptions::default())
+}
+
+/// Convert text to EBCDIC bytes, one byte per character
+///
+/// With `CodePage::CardCode`, letters are uppercased as on the keypunch.
+pub fn ascii_to_ebcdic_with(text: &str, options: &TextConversionOptions) -> Vec<u8> {
+    text.chars()
+        .map(|c| match options.code_page {
+            CodePage::CardCode => c.to_ascii_uppercase(),
+            _ => c,
+        })
+        .map(|c| {
+            options
+                .code_page
+                .from_char(c)
+                .unwrap_or(options.unmappable_char)
+        })
+        .collect()
+}
+
+/// Bytes shown on each hexdump line
+const HEXDUMP_WIDTH: usize = 20;
+
+/// Format EBCDIC records as a hex dump
+///
+/// Each line shows the offset, 20 bytes in hex, and a gutter with each
+/// byte's character, or `.` for bytes without a printable ASCII one. A
+/// header line starts each 80-byte record.
+pub fn hexdump(data: &[u8]) -> String {
+    hexdump_records(data, RECORD_LEN)
+}
+
+/// Format `data` as a hex dump, marking a record every `record_len` bytes
+pub(crate) fn hexdump_records(data: &[u8], record_len: usize) -> String {
+    let mut out = String::new();
+    for (record, chunk) in data.chunks(record_len).enumerate() {
+        let start = record * record_len;
+        out += &format!("record {} (offset {:#x})\n", record + 1, start);
+        for (line, bytes) in chunk.chunks(HEXDUMP_WIDTH).enumerate() {
+            let hex: Vec<String> = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+            let gutter: String = bytes
+                .iter()
+                .map(|&b| {
+                    ebcdic_to_char(b)
+                        .filter(|c| c.is_ascii_graphic() || *c == ' ')
+                        .unwrap_or('.')
+                })
+                .collect();
+            out += &format!(
+                "{:06x}  {:<width$}  |{}|\n",
+                start + line * HEXDUMP_WIDTH,
+                hex.join(" "),
+                gutter,
+                width = HEXDUMP_WIDTH * 3 - 1
+            );
+        }
+    }
+    out
+}
+
+/// Bytes per card in an EBCDIC deck file
+pub const RECORD_LEN: usize = 80;
+
+/// Errors from reading or writing an EBCDIC deck file
+#[derive(Debug)]
+pub enum EbcdicDeckError {
+    /// A card has punches with no EBCDIC byte
+    Unmapped {
+        /// Zero-based card in the deck
+        card: usize,
+        /// The columns that could not be written
+        error: EbcdicExportError,
+    },
+    /// A card has a control byte refused by `ControlPolicy::Error`
+    Control {
+        /// Zero-based card in the deck
+        card: usize,
+        /// The column and byte
+        error: ControlByte,
+    },
+    /// The data ends part way through a record
+    PartialRecord {
+        /// Byte offset of the start of the record
+        offset: u64,
+        /// Bytes of the record that were present
+        len: usize,
+    },
+    /// A line read under `ControlPolicy::RecordBoundary` is longer than a
+    /// card
+    LongLine {
+        /// Zero-based card in the deck
+        card: usize,
+        /// Bytes in the line, not counting the line end
+        len: usize,
+    },
+    /// Reading or writing the underlying stream failed
+    Io(io::Error),
+}
+
+impl fmt::Display for EbcdicDeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EbcdicDeckError::Unmapped { card, error } => write!(f, "Card {}: {}", card + 1, error),
+            EbcdicDeckError::Control { card, error } => write!(f, "Card {}: {}", card + 1, error),
+            EbcdicDeckError::PartialRecord { offset, len } => write!(
+                f,
+                "Partial record at byte offset {}: {} of {} bytes",
+                offset, len, RECORD_LEN
+            ),
+            EbcdicDeckError::LongLine { card, len } => write!(
+                f,
+                "Card {}: line is {} bytes; a card holds {}",
+                card + 1,
+                len,
+                RECORD_LEN
+            ),
+            EbcdicDeckError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for EbcdicDeckError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EbcdicDeckError::Unmapped { error, .. } => Some(error),
+            EbcdicDeckError::Control { error, .. } => Some(error),
+            EbcdicDeckError::Io(e) => Some(e),
+            EbcdicDeckError::PartialRecord { .. } | EbcdicDeckError::LongLine { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for EbcdicDeckError {
+    fn from(e: io::Error) -> Self {
+        EbcdicDeckError::Io(e)
+    }
+}
+
+/// Convert a deck to EBCDIC, one 80-byte record per card back to back
+///
+/// Fails on the first card with punches that have no EBCDIC byte.
+pub fn deck_to_ebcdic(deck: &Deck) -> Result<Vec<u8>, EbcdicDeckError> {
+    let mut data = Vec::with_capacity(deck.len() * RECORD_LEN);
+    write_ebcdic_deck(deck, &mut data)?;
+    Ok(data)
+}
+
+/// Read a deck from back-to-back 80-byte EBCDIC records
+///
+/// Fails if the length is not a multiple of 80.
+pub fn deck_from_ebcdic(data: &[u8]) -> Result<Deck, EbcdicDeckError> {
+    ebcdic_records(data).collect()
+}
+
+/// Read a deck from EBCDIC with load options
+///
+/// With `ControlPolicy::RecordBoundary` each NL- or LF-terminated line is
+/// a card, so the length need not be a multiple of 80; a line longer than
+/// 80 bytes is an error.
+pub fn deck_from_ebcdic_with(
+    data: &[u8],
+    options: &EbcdicLoadOptions,
+) -> Result<Deck, EbcdicDeckError> {
+    ebcdic_records_with(data, options).collect()
+}
+
+/// Write cards as back-to-back 80-byte EBCDIC records
+///
+/// Cards are converted one at a time, so the whole file is never in
+/// memory. Cards already written stay written if a later card fails.
+pub fn write_ebcdic_deck<'a, W: Write>(
+    cards: impl IntoIterator<Item = &'a PunchCard>,
+    out: &mut W,
+) -> Result<(), EbcdicDeckError> {
+    for (card, punch_card) in cards.into_iter().enumerate() {
+        let record = punch_card
+            .to_ebcdic()
+            .map_err(|error| EbcdicDeckError::Unmapped { card, error })?;
+        out.write_all(&record)?;
+    }
+    Ok(())
+}
+
+/// Read cards one at a time from back-to-back 80-byte EBCDIC records
+///
+/// The iterator yields an error and stops at a trailing partial record or
+/// a read failure.
+pub fn ebcdic_records<R: Read>(reader: R) -> EbcdicRecords<R> {
+    ebcdic_records_with(reader, &EbcdicLoadOptions::default())
+}
+
+/// Read cards one at a time from EBCDIC with load options
+///
+/// With `ControlPolicy::RecordBoundary` each line is a card: a line longer
+/// than 80 bytes is an error, and a last line without a line end is still
+/// read.
+pub fn ebcdic_records_with<R: Read>(reader: R, options: &EbcdicLoadOptions) -> EbcdicRecords<R> {
+    EbcdicRecords {
+        reader: io::BufReader::new(reader),
+        options: *options,
+        card: 0,
+        offset: 0,
+        done: false,
+    }
+}
+
+/// Iterator over the cards of an EBCDIC deck file (see `ebcdic_records`)
+#[derive(Debug)]
+pub struct EbcdicRecords<R> {
+    reader: io::BufReader<R>,
+    options: EbcdicLoadOptions,
+    card: usize,
+    offset: u64,
+    done: bool,
+}
+
+impl<R: Read> EbcdicRecords<R> {
+    /// Fill `record` as far as the data goes, returning the bytes read
+    fn fill(&mut self, record: &mut [u8; RECORD_LEN]) -> io::Result<usize> {
+        let mut len = 0;
+        while len < RECORD_LEN {
+            match self.reader.read(&mut record[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(len)
+    }
+
+    /// Read up to the next line end, or None at the end of the data
+    fn read_line(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut line = Vec::new();
+        loop {
+            let buf = match self.reader.fill_buf() {
+                Ok(buf) => buf,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if buf.is_empty() {
+                return Ok((!line.is_empty()).then_some(line));
+            }
+            let end = buf.iter().position(|&byte| is_line_end(byte));
+            let take = end.unwrap_or(buf.len());
+            line.extend_from_slice(&buf[..take]);
+            let used = take + usize::from(end.is_some());
+            self.reader.consume(used);
+            self.offset += used as u64;
+            if end.is_some() {
+                return Ok(Some(line));
+            }
+        }
+    }
+
+    /// Read the next record's bytes
+    fn next_record(&mut self) -> Option<Result<Vec<u8>, EbcdicDeckError>> {
+        if self.options.controls == ControlPolicy::RecordBoundary {
+            return match self.read_line() {
+                Ok(Some(line)) if line.len() > RECORD_LEN => Some(Err(EbcdicDeckError::LongLine {
+                    card: self.card,
+                    len: line.len(),
+                })),
+                result => result.map_err(EbcdicDeckError::Io).transpose(),
+            };
+        }
+        let mut record = [0u8; RECORD_LEN];
+        let result = match self.fill(&mut record) {
+            Ok(0) => None,
+            Ok(RECORD_LEN) => Some(Ok(record.to_vec())),
+            Ok(len) => Some(Err(EbcdicDeckError::PartialRecord {
+                offset: self.offset,
+                len,
+            })),
+            Err(e) => Some(Err(EbcdicDeckError::Io(e))),
+        };
+        self.offset += RECORD_LEN as u64;
+        result
+    }
+}
+
+impl<R: Read> Iterator for EbcdicRecords<R> {
+    type Item = Result<PunchCard, EbcdicDeckError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let card = self.card;
+        let result = self.next_record().map(|record| {
+            PunchCard::from_ebcdic_with(&record?, &self.options)
+                .map_err(|error| EbcdicDeckError::Control { card, error })
+        });
+        self.card += 1;
+        self.done = !matches!(result, Some(Ok(_)));
+        result
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hollerith::char_to_hollerith;
+
+    /// The 029 special characters and their EBCDIC bytes
+    const SPECIALS: &[(char, u8)] = &[
+        ('&', 0x50),
+        ('-', 0x60),
+        ('/', 0x61),
+        ('¢', 0x4A),
+        ('.', 0x4B),
+        ('<', 0x4C),
+        ('(', 0x4D),
+        ('+', 0x4E),
+        ('|', 0x4F),
+        ('!', 0x5A),
+        ('$', 0x5B),
+        ('*', 0x5C),
+        (')', 0x5D),
+        (';', 0x5E),
+        ('¬', 0x5F),
+        (',', 0x6B),
+        ('%', 0x6C),
+        ('_', 0x6D),
+        ('>', 0x6E),
+        ('?', 0x6F),
+        (':', 0x7A),
+        ('#', 0x7B),
+        ('@', 0x7C),
+        ('\'', 0x7D),
+        ('=', 0x7E),
+        ('"', 0x7F),
+    ];
 
     #[test]
     fn test_ebcdic_space() {
         let code = HollerithCode::empty();
-        assert_eq!(hollerith_to_ebcdic(&code), 0x40);
+        assert_eq!(hollerith_to_ebcdic(&code), Ok(0x40));
 
         let decoded = ebcdic_to_hollerith(0x40);
         assert_eq!(decoded.rows.len(), 0);
@@ -169,44 +770,44 @@ mod tests {
     fn test_ebcdic_digits() {
         // Test '0' (0xF0 -> row 0)
         let code_0 = HollerithCode::new(vec![0]);
-        assert_eq!(hollerith_to_ebcdic(&code_0), 0xF0);
+        assert_eq!(hollerith_to_ebcdic(&code_0), Ok(0xF0));
 
         // Test '5' (0xF5 -> row 5)
         let code_5 = HollerithCode::new(vec![5]);
-        assert_eq!(hollerith_to_ebcdic(&code_5), 0xF5);
+        assert_eq!(hollerith_to_ebcdic(&code_5), Ok(0xF5));
 
         // Test '9' (0xF9 -> row 9)
         let code_9 = HollerithCode::new(vec![9]);
-        assert_eq!(hollerith_to_ebcdic(&code_9), 0xF9);
+        assert_eq!(hollerith_to_ebcdic(&code_9), Ok(0xF9));
     }
 
     #[test]
     fn test_ebcdic_letters_a_i() {
         // Test 'A' (0xC1 -> rows 12+1)
         let code_a = HollerithCode::new(vec![12, 1]);
-        assert_eq!(hollerith_to_ebcdic(&code_a), 0xC1);
+        assert_eq!(hollerith_to_ebcdic(&code_a), Ok(0xC1));
 
         // Test 'E' (0xC5 -> rows 12+5)
         let code_e = HollerithCode::new(vec![12, 5]);
-        assert_eq!(hollerith_to_ebcdic(&code_e), 0xC5);
+        assert_eq!(hollerith_to_ebcdic(&code_e), Ok(0xC5));
     }
 
     #[test]
     fn test_ebcdic_letters_j_r() {
         // Test 'J' (0xD1 -> rows 11+1)
         let code_j = HollerithCode::new(vec![11, 1]);
-        assert_eq!(hollerith_to_ebcdic(&code_j), 0xD1);
+        assert_eq!(hollerith_to_ebcdic(&code_j), Ok(0xD1));
     }
 
     #[test]
     fn test_ebcdic_letters_s_z() {
         // Test 'S' (0xE2 -> rows 0+2)
         let code_s = HollerithCode::new(vec![0, 2]);
-        assert_eq!(hollerith_to_ebcdic(&code_s), 0xE2);
+        assert_eq!(hollerith_to_ebcdic(&code_s), Ok(0xE2));
 
         // Test 'Z' (0xE9 -> rows 0+9)
         let code_z = HollerithCode::new(vec![0, 9]);
-        assert_eq!(hollerith_to_ebcdic(&code_z), 0xE9);
+        assert_eq!(hollerith_to_ebcdic(&code_z), Ok(0xE9));
     }
 
     #[test]
@@ -218,14 +819,357 @@ mod tests {
         ] {
             let hollerith = ebcdic_to_hollerith(ebcdic);
             let result = hollerith_to_ebcdic(&hollerith);
-            assert_eq!(result, ebcdic, "Roundtrip failed for 0x{:02X}", ebcdic);
+            assert_eq!(result, Ok(ebcdic), "Roundtrip failed for 0x{:02X}", ebcdic);
         }
 
         // Test 0-9
         for ebcdic in 0xF0..=0xF9 {
             let hollerith = ebcdic_to_hollerith(ebcdic);
             let result = hollerith_to_ebcdic(&hollerith);
-            assert_eq!(result, ebcdic, "Roundtrip failed for 0x{:02X}", ebcdic);
+            assert_eq!(result, Ok(ebcdic), "Roundtrip failed for 0x{:02X}", ebcdic);
+        }
+
+        // Test every 029 special character
+        for &(c, ebcdic) in SPECIALS {
+            let code = char_to_hollerith(c).unwrap();
+            assert_eq!(hollerith_to_ebcdic(&code), Ok(ebcdic), "{:?}", c);
+            assert_eq!(ebcdic_to_hollerith(ebcdic), code, "0x{:02X}", ebcdic);
+        }
+        // 0x6A is not the 0-8-2 special, which is the record mark
+        assert_eq!(ebcdic_to_hollerith(0x6A).rows, vec![11, 12]);
+    }
+
+    #[test]
+    fn test_ebcdic_lowercase_roundtrip() {
+        assert_eq!(ebcdic_to_hollerith(0x81).rows, vec![0, 1, 12]);
+        assert_eq!(ebcdic_to_hollerith(0x91).rows, vec![1, 11, 12]);
+        assert_eq!(ebcdic_to_hollerith(0xA2).rows, vec![0, 2, 11]);
+        for ebcdic in (0x81..=0x89).chain(0x91..=0x99).chain(0xA2..=0xA9) {
+            let hollerith = ebcdic_to_hollerith(ebcdic);
+            assert_eq!(
+                hollerith_to_ebcdic(&hollerith),
+                Ok(ebcdic),
+                "0x{:02X}",
+                ebcdic
+            );
+        }
+        // The gap between the letter runs is still punched in the same zone
+        assert_eq!(ebcdic_to_hollerith(0xA1).rows, vec![0, 1, 11]);
+    }
+
+    #[test]
+    fn test_ebcdic_record_and_group_marks() {
+        for ebcdic in [0xE0, 0xBB] {
+            assert_eq!(
+                hollerith_to_ebcdic(&ebcdic_to_hollerith(ebcdic)),
+                Ok(ebcdic)
+            );
+        }
+        assert_eq!(ebcdic_to_hollerith(0xE0).rows, vec![0, 2, 8]);
+    }
+
+    #[test]
+    fn test_every_byte_roundtrips() {
+        for byte in 0..=255u8 {
+            let code = ebcdic_to_hollerith(byte);
+            assert_eq!(hollerith_to_ebcdic(&code), Ok(byte), "0x{:02X}", byte);
+            // Controls and other codes below 0x40 carry a 9 punch
+            if byte < 0x40 {
+                assert!(code.is_punched(9), "0x{:02X}", byte);
+            }
+        }
+        let mapped = (0..=0x0FFF)
+            .map(|value| HollerithCode::from_u16(value, BitOrder::Row12High))
+            .filter_map(|code| hollerith_to_ebcdic(&code).ok().map(|byte| (byte, code)))
+            .inspect(|(byte, code)| assert_eq!(&ebcdic_to_hollerith(*byte), code))
+            .count();
+        assert_eq!(mapped, 256);
+        let unmapped = HollerithCode::new(vec![12, 11, 0, 1, 2]);
+        let err = hollerith_to_ebcdic(&unmapped).unwrap_err();
+        assert_eq!(err, UnmappedPattern(unmapped));
+        assert_eq!(err.to_string(), "Punches 12-11-0-1-2 have no EBCDIC byte");
+        // Known control punches: NL, LF, and HT
+        assert_eq!(ebcdic_to_hollerith(0x15).rows, vec![5, 9, 11]);
+        assert_eq!(ebcdic_to_hollerith(0x25).rows, vec![0, 5, 9]);
+        assert_eq!(ebcdic_to_hollerith(0x05).rows, vec![5, 9, 12]);
+    }
+
+    #[test]
+    fn test_char_conversions() {
+        assert_eq!(char_to_ebcdic('A'), Some(0xC1));
+        assert_eq!(char_to_ebcdic('¢'), Some(0x4A));
+        assert_eq!(char_to_ebcdic('a'), Some(0x81));
+        assert_eq!(char_to_ebcdic('['), None);
+        assert_eq!(ebcdic_to_char(0x40), Some(' '));
+        assert_eq!(ebcdic_to_char(0x7D), Some('\''));
+        assert_eq!(ebcdic_to_char(0x15), None);
+        assert_eq!(ebcdic_to_char(0xE0), Some('‡'));
+        for &(c, byte) in SPECIALS {
+            assert_eq!(char_to_ebcdic(c), Some(byte));
+            assert_eq!(ebcdic_to_char(byte), Some(c));
+        }
+        let lowercase = (0x81..=0x89).chain(0x91..=0x99).chain(0xA2..=0xA9);
+        for (c, byte) in ('a'..='z').zip(lowercase) {
+            assert_eq!(char_to_ebcdic(c), Some(byte), "{:?}", c);
+            assert_eq!(ebcdic_to_char(byte), Some(c), "0x{:02X}", byte);
+        }
+    }
+
+    #[test]
+    fn test_text_conversions_match_cards() {
+        use crate::punch_card::PunchCard;
+
+        let bytes: Vec<u8> = (0..=255).collect();
+        for record in bytes.chunks(80) {
+            let card_text: String = PunchCard::from_ebcdic(record).columns()[..record.len()]
+                .iter()
+                .map(|column| column.printed_char.unwrap_or('?'))
+                .collect();
+            assert_eq!(ebcdic_to_ascii(record), card_text);
+        }
+
+        let text = "Total: $1,000 (net) ~ 5%";
+        let ebcdic = ascii_to_ebcdic(text);
+        assert_eq!(
+            ebcdic,
+            PunchCard::from_text(text).to_ebcdic().unwrap()[..text.len()]
+        );
+        assert_eq!(ebcdic_to_ascii(&ebcdic), "TOTAL: $1,000 (NET)   5%");
+
+        let options = TextConversionOptions {
+            unmappable_byte: '�',
+            unmappable_char: 0x6F,
+            ..TextConversionOptions::default()
+        };
+        assert_eq!(ascii_to_ebcdic_with("a~", &options), vec![0xC1, 0x6F]);
+        assert_eq!(ebcdic_to_ascii_with(&[0xC1, 0x00], &options), "A�");
+    }
+
+    #[test]
+    fn test_code_pages_disagree() {
+        assert_eq!(CodePage::Cp037.to_char(0x4F), Some('|'));
+        assert_eq!(CodePage::Cp500.to_char(0x4F), Some('!'));
+        assert_eq!(CodePage::Cp1047.to_char(0x4F), Some('|'));
+        assert_eq!(CodePage::CardCode.to_char(0x4F), Some('|'));
+
+        assert_eq!(CodePage::Cp037.to_char(0xBA), Some('['));
+        assert_eq!(CodePage::Cp1047.to_char(0xAD), Some('['));
+        assert_eq!(CodePage::Cp500.to_char(0x4A), Some('['));
+        assert_eq!(CodePage::CardCode.from_char('['), None);
+
+        assert_eq!(CodePage::Cp037.from_char('¬'), Some(0x5F));
+        assert_eq!(CodePage::Cp500.from_char('¬'), Some(0xBA));
+        assert_eq!(CodePage::Cp1047.from_char('¬'), Some(0xB0));
+
+        // The newline and line feed swap places in 1047
+        assert_eq!(CodePage::Cp037.from_char('\n'), Some(0x25));
+        assert_eq!(CodePage::Cp1047.from_char('\n'), Some(0x15));
+        assert_eq!(CodePage::CardCode.to_char(0x15), None);
+
+        for page in [CodePage::Cp037, CodePage::Cp500, CodePage::Cp1047] {
+            for byte in 0..=255u8 {
+                let c = page.to_char(byte).unwrap();
+                assert_eq!(page.from_char(c), Some(byte), "{:?} 0x{:02X}", page, byte);
+            }
+            // Letters and digits agree with the card code in every page
+            for c in ('A'..='Z').chain('0'..='9') {
+                assert_eq!(page.from_char(c), char_to_ebcdic(c), "{:?} {}", page, c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_deck_roundtrip() {
+        let deck: Deck = ["LOOP  LD   X", "      STO  Y", "abc"]
+            .into_iter()
+            .map(PunchCard::from_text)
+            .collect();
+        let data = deck_to_ebcdic(&deck).unwrap();
+        assert_eq!(data.len(), 3 * RECORD_LEN);
+        assert_eq!(&data[80..86], &[0x40; 6]);
+        let loaded = deck_from_ebcdic(&data).unwrap();
+        assert_eq!(loaded.len(), 3);
+        for (a, b) in deck.iter().zip(&loaded) {
+            assert!(a.punches_eq(b));
         }
+        assert!(deck_from_ebcdic(&[]).unwrap().is_empty());
+
+        // Streaming through a reader that returns a few bytes at a time
+        let chunked = io::Read::chain(&data[..7], &data[7..]);
+        let cards: Vec<PunchCard> = ebcdic_records(chunked).map(Result::unwrap).collect();
+        assert_eq!(cards.len(), 3);
+        assert_eq!(cards[1].to_text_trimmed(), "      STO  Y");
+    }
+
+    #[test]
+    fn test_printable_fraction() {
+        // Known EBCDIC text: card records and a code page conversion
+        let corpus = [
+            PunchCard::from_text("LOOP  LD   X        PAYROLL 1401")
+                .to_ebcdic()
+                .unwrap(),
+            PunchCard::from_text("").to_ebcdic().unwrap(),
+            ascii_to_ebcdic("Total: $1,000 (net) 5%, a & b < c"),
+            ascii_to_ebcdic_with(
+                "lowercase text, too",
+                &TextConversionOptions {
+                    code_page: CodePage::Cp037,
+                    ..TextConversionOptions::default()
+                },
+            ),
+        ];
+        for record in &corpus {
+            assert_eq!(printable_fraction(record), 1.0, "{:02X?}", record);
+        }
+
+        // Random binary: bytes from a fixed xorshift sequence, and the
+        // 1130 object card's binary image
+        let mut state = 0x2545_F491_u32;
+        let random: Vec<u8> = (0..800)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        for record in random.chunks(80) {
+            assert!(printable_fraction(record) < 0.6, "{:02X?}", record);
+        }
+        let object = crate::ibm1130::generate_example_object().to_binary();
+        assert!(printable_fraction(&object) < 0.6);
+
+        // Null padding is ignored; spaces count as text
+        let mut padded = ascii_to_ebcdic("HELLO");
+        padded.resize(80, 0x00);
+        assert_eq!(printable_fraction(&padded), 1.0);
+        assert_eq!(printable_fraction(&[0x00; 80]), 0.0);
+        assert_eq!(printable_fraction(&[]), 0.0);
+        assert_eq!(printable_fraction(&[0xC1, 0x40, 0x15, 0xFF]), 0.5);
+        assert!(is_printable(0x40) && !is_printable(0x00) && !is_printable(0x15));
+    }
+
+    #[test]
+    fn test_hexdump() {
+        let mut data = PunchCard::from_text("HELLO, world ¢").to_ebcdic().unwrap();
+        data[79] = 0x00;
+        data.extend_from_slice(&[0xC1, 0x81, 0x25]);
+        let golden = "\
+record 1 (offset 0x0)
+000000  C8 C5 D3 D3 D6 6B 40 E6 D6 D9 D3 C4 40 4A 40 40 40 40 40 40  |HELLO, WORLD .      |
+000014  40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40  |                    |
+000028  40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40  |                    |
+00003c  40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 40 00  |                   .|
+record 2 (offset 0x50)
+000050  C1 81 25                                                     |Aa.|
+";
+        assert_eq!(hexdump(&data), golden);
+        assert_eq!(hexdump(&[]), "");
+    }
+
+    #[test]
+    fn test_nl_delimited_deck() {
+        const FIXTURE: &[u8] = include_bytes!("../tests/fixtures/ebcdic_nl_deck.bin");
+        let options = EbcdicLoadOptions {
+            code_page: CodePage::Cp037,
+            controls: ControlPolicy::RecordBoundary,
+        };
+        let deck = deck_from_ebcdic_with(FIXTURE, &options).unwrap();
+        let texts: Vec<String> = deck.iter().map(PunchCard::to_text_trimmed).collect();
+        assert_eq!(
+            texts,
+            [
+                "//PAYROLL  JOB (ACCT),'SMITH',CLASS=A",
+                "//STEP1    EXEC PGM=PAYCALC",
+                "//SYSIN DD *",
+                "0042 SMITH, J.   1,250.00",
+                "/*",
+            ]
+        );
+        // The tab is left blank
+        assert!(deck.get(2).unwrap().columns()[7].is_blank());
+
+        // Streaming gives the same cards, and a last line needs no NL
+        let chunked = io::Read::chain(&FIXTURE[..50], &FIXTURE[50..FIXTURE.len() - 1]);
+        let streamed: Vec<String> = ebcdic_records_with(chunked, &options)
+            .map(|card| card.unwrap().to_text_trimmed())
+            .collect();
+        assert_eq!(streamed, texts);
+
+        // Read as fixed records the lines do not fit
+        assert!(matches!(
+            deck_from_ebcdic(FIXTURE),
+            Err(EbcdicDeckError::PartialRecord {
+                offset: 80,
+                len: 28
+            })
+        ));
+        let strict = EbcdicLoadOptions {
+            controls: ControlPolicy::Error,
+            ..options
+        };
+        let err = deck_from_ebcdic_with(&FIXTURE[..80], &strict).unwrap_err();
+        assert_eq!(err.to_string(), "Card 1: Column 38: control byte 0x15");
+
+        // A line too long for a card is not cut short
+        let mut long = vec![0xC1; 80];
+        long.push(0x15);
+        long.extend_from_slice(&[0xC2; 81]);
+        long.push(0x15);
+        let mut records = ebcdic_records_with(&long[..], &options);
+        assert!(records.next().unwrap().is_ok());
+        let err = records.next().unwrap().unwrap_err();
+        assert!(matches!(
+            err,
+            EbcdicDeckError::LongLine { card: 1, len: 81 }
+        ));
+        assert_eq!(err.to_string(), "Card 2: line is 81 bytes; a card holds 80");
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn test_deck_errors() {
+        let mut data =
+            deck_to_ebcdic(&Deck::from_cards(vec![PunchCard::from_text("A"); 2])).unwrap();
+        data.extend_from_slice(&[0xC1; 17]);
+        let err = deck_from_ebcdic(&data).unwrap_err();
+        assert!(matches!(
+            err,
+            EbcdicDeckError::PartialRecord {
+                offset: 160,
+                len: 17
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Partial record at byte offset 160: 17 of 80 bytes"
+        );
+        let mut records = ebcdic_records(&data[..]);
+        assert!(records.next().unwrap().is_ok());
+        assert!(records.next().unwrap().is_ok());
+        assert!(records.next().unwrap().is_err());
+        assert!(records.next().is_none());
+
+        let mut laced = PunchCard::from_text("B");
+        laced
+            .set_column_hollerith(3, HollerithCode::from_array([true; 12]))
+            .unwrap();
+        let deck = Deck::from_cards(vec![PunchCard::from_text("A"), laced]);
+        let mut out = Vec::new();
+        let err = write_ebcdic_deck(&deck, &mut out).unwrap_err();
+        assert_eq!(err.to_string(), "Card 2: No EBCDIC byte for column(s) 4");
+        assert_eq!(out.len(), RECORD_LEN);
+    }
+
+    #[test]
+    fn test_text_conversion_in_code_page() {
+        let options = TextConversionOptions {
+            code_page: CodePage::Cp500,
+            ..TextConversionOptions::default()
+        };
+        let bytes = ascii_to_ebcdic_with("a[b]!", &options);
+        assert_eq!(bytes, vec![0x81, 0x4A, 0x82, 0x5A, 0x4F]);
+        assert_eq!(ebcdic_to_ascii_with(&bytes, &options), "a[b]!");
+        assert_eq!(ebcdic_to_ascii(&bytes), "a¢b!|");
     }
 }
@@ -0,0 +1,689 @@
+// Field Module
+//
+// Fixed-width field helpers following unit-record data entry conventions:
+// numeric fields are right-justified, alphabetic fields are left-justified.
+
+use crate::hollerith::{HollerithCode, Zone, char_to_hollerith, hollerith_to_char};
+use crate::punch_card::{Column, PunchCard};
+use std::fmt;
+use std::ops::Range;
+
+/// How to fill the unused high-order columns of a numeric field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericFill {
+    /// Leading blanks (e.g. "   42")
+    Blanks,
+    /// Leading zeros (e.g. "00042")
+    Zeros,
+}
+
+/// Which signs are overpunched on the units digit of a signed field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignConvention {
+    /// Negative values get an 11 overpunch; positive values are unsigned
+    NegativeOnly,
+    /// Negative values get an 11 overpunch, positive values (and zero) a 12
+    BothSigns,
+}
+
+/// How to treat an all-blank signed numeric field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlankFieldPolicy {
+    /// Read a blank field as zero
+    Zero,
+    /// Reject a blank field with `FieldError::BlankField`
+    Error,
+}
+
+/// Errors from reading or writing a card field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldError {
+    /// The column range is empty or extends past column 80
+    InvalidRange { start: usize, end: usize },
+    /// The number needs more columns than the field has
+    Overflow { value: i64, width: usize },
+    /// The text is longer than the field
+    TooLong { len: usize, width: usize },
+    /// A character has no punch pattern
+    Unencodable { column: usize, ch: char },
+    /// A column in a numeric field is not a digit
+    InvalidDigit { column: usize },
+    /// A numeric field holds a number too large for an `i64`
+    NumberTooLarge { start: usize, end: usize },
+    /// A column in a signed numeric field has punches that are not a digit
+    /// (or, in the units column, a digit with a 12 or 11 sign overpunch)
+    InvalidPunch { column: usize, rows: Vec<u8> },
+    /// A numeric field is entirely blank
+    BlankField { start: usize, end: usize },
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::InvalidRange { start, end } => {
+                write!(f, "Invalid field columns {}..{}", start, end)
+            }
+            FieldError::Overflow { value, width } => {
+                write!(
+                    f,
+                    "Value {} does not fit in a {}-column field",
+                    value, width
+                )
+            }
+            FieldError::TooLong { len, width } => {
+                write!(
+                    f,
+                    "Text of {} characters does not fit in a {}-column field",
+                    len, width
+                )
+            }
+            FieldError::Unencodable { column, ch } => {
+                write!(
+                    f,
+                    "Column {}: character {:?} has no punch pattern",
+                    column + 1,
+                    ch
+                )
+            }
+            FieldError::InvalidDigit { column } => {
+                write!(f, "Column {}: not a digit", column + 1)
+            }
+            FieldError::NumberTooLarge { start, end } => {
+                write!(
+                    f,
+                    "Columns {}-{}: number is too large to read",
+                    start + 1,
+                    end
+                )
+            }
+            FieldError::InvalidPunch { column, rows } if rows.is_empty() => {
+                write!(
+                    f,
+                    "Column {}: unexpected blank in numeric field",
+                    column + 1
+                )
+            }
+            FieldError::InvalidPunch { column, rows } => {
+                let pattern: Vec<String> = ROW_ORDER
+                    .iter()
+                    .filter(|row| rows.contains(row))
+                    .map(u8::to_string)
+                    .collect();
+                write!(
+                    f,
+                    "Column {}: punches {} are not a digit",
+                    column + 1,
+                    pattern.join("-")
+                )
+            }
+            FieldError::BlankField { start, end } => {
+                write!(f, "Columns {}-{}: numeric field is blank", start + 1, end)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Row order for describing punch patterns (top of card to bottom)
+const ROW_ORDER: [u8; 12] = [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+/// Check that a field lies within a card `width` columns wide and is not empty
+pub(crate) fn check_field(cols: &Range<usize>, width: usize) -> Result<(), FieldError> {
+    if cols.start >= cols.end || cols.end > width {
+        return Err(FieldError::InvalidRange {
+            start: cols.start,
+            end: cols.end,
+        });
+    }
+    Ok(())
+}
+
+impl PunchCard {
+    /// Write a number right-justified into a field
+    ///
+    /// Negative values get a leading `-` counted in the field width; with
+    /// `NumericFill::Zeros` the sign goes in the first column of the field.
+    /// Fails if the number needs more columns than the field has.
+    pub fn write_numeric(
+        &mut self,
+        cols: Range<usize>,
+        value: i64,
+        fill: NumericFill,
+    ) -> Result<(), FieldError> {
+        check_field(&cols, self.width().columns())?;
+        let width = cols.len();
+        let digits = value.unsigned_abs().to_string();
+        let sign = if value < 0 { "-" } else { "" };
+        if digits.len() + sign.len() > width {
+            return Err(FieldError::Overflow { value, width });
+        }
+
+        let text = match fill {
+            NumericFill::Blanks => format!("{:>width$}", format!("{}{}", sign, digits)),
+            NumericFill::Zeros => {
+                format!("{}{:0>pad$}", sign, digits, pad = width - sign.len())
+            }
+        };
+        self.write_field_text(cols.start, &text);
+        Ok(())
+    }
+
+    /// Write text left-justified into a field, blank-filling the remainder
+    ///
+    /// Fails without modifying the card if the text is longer than the field
+    /// or contains a character with no punch pattern.
+    pub fn write_alpha(&mut self, cols: Range<usize>, text: &str) -> Result<(), FieldError> {
+        check_field(&cols, self.width().columns())?;
+        let width = cols.len();
+        let len = text.chars().count();
+        if len > width {
+            return Err(FieldError::TooLong { len, width });
+        }
+        for (i, c) in text.chars().enumerate() {
+            if char_to_hollerith(c.to_ascii_uppercase()).is_none() {
+                return Err(FieldError::Unencodable {
+                    column: cols.start + i,
+                    ch: c,
+                });
+            }
+        }
+        self.write_field_text(cols.start, &format!("{:<width$}", text));
+        Ok(())
+    }
+
+    /// Read an unsigned or minus-prefixed number from a field
+    ///
+    /// Leading blanks are skipped and an all-blank field reads as zero. Any
+    /// other non-digit punch (including embedded or trailing blanks) is an
+    /// error naming the column.
+    pub fn read_numeric(&self, cols: Range<usize>) -> Result<i64, FieldError> {
+        check_field(&cols, self.width().columns())?;
+        let columns = &self.columns()[cols.clone()];
+        let first = columns.iter().position(|col| !col.is_blank());
+        let Some(first) = first else {
+            return Ok(0);
+        };
+
+        let too_large = || FieldError::NumberTooLarge {
+            start: cols.start,
+            end: cols.end,
+        };
+        let mut negative = false;
+        let mut value: u64 = 0;
+        let mut seen_digit = false;
+        for (offset, col) in columns.iter().enumerate().skip(first) {
+            let column = cols.start + offset;
+            match col.to_char() {
+                Some('-') if offset == first => negative = true,
+                Some(c @ '0'..='9') => {
+                    seen_digit = true;
+                    value = value
+                        .checked_mul(10)
+                        .and_then(|v| v.checked_add(u64::from(c as u8 - b'0')))
+                        .ok_or_else(too_large)?;
+                }
+                _ => return Err(FieldError::InvalidDigit { column }),
+            }
+        }
+        if !seen_digit {
+            return Err(FieldError::InvalidDigit {
+                column: cols.start + first,
+            });
+        }
+        let signed = if negative {
+            -i128::from(value)
+        } else {
+            i128::from(value)
+        };
+        i64::try_from(signed).map_err(|_| too_large())
+    }
+
+    /// Write a number with its sign overpunched on the units digit
+    ///
+    /// The digits are right-justified and zero-filled, and the sign zone is
+    /// punched into the same column as the units digit (-5 punches 11 and 5,
+    /// which prints as N). Fails if the digits need more columns than the
+    /// field has.
+    pub fn write_signed_numeric(
+        &mut self,
+        cols: Range<usize>,
+        value: i64,
+        convention: SignConvention,
+    ) -> Result<(), FieldError> {
+        check_field(&cols, self.width().columns())?;
+        let width = cols.len();
+        let digits = value.unsigned_abs().to_string();
+        if digits.len() > width {
+            return Err(FieldError::Overflow { value, width });
+        }
+        self.write_field_text(cols.start, &format!("{:0>width$}", digits));
+
+        let zone = match convention {
+            _ if value < 0 => Some(11),
+            SignConvention::BothSigns => Some(12),
+            SignConvention::NegativeOnly => None,
+        };
+        if let Some(zone) = zone {
+            let units = self
+                .get_column_mut(cols.end - 1)
+                .expect("field checked against card width");
+            units.punches.rows.push(zone);
+            units.punches = HollerithCode::new(units.punches.rows.clone());
+            units.printed_char = hollerith_to_char(&units.punches);
+        }
+        Ok(())
+    }
+
+    /// Read a number whose sign is overpunched on the units digit
+    ///
+    /// Business decks marked negative values with an 11 punch over the last
+    /// digit (so -5 reads as the letter N) and sometimes positive values with
+    /// a 12 punch (+5 reads as E). Unsigned digits are positive. Leading
+    /// blanks are skipped; an all-blank field is handled per `blanks`.
+    pub fn read_signed_numeric(
+        &self,
+        cols: Range<usize>,
+        blanks: BlankFieldPolicy,
+    ) -> Result<i64, FieldError> {
+        check_field(&cols, self.width().columns())?;
+        let columns = &self.columns()[cols.clone()];
+        let Some(first) = columns.iter().position(|col| !col.is_blank()) else {
+            return match blanks {
+                BlankFieldPolicy::Zero => Ok(0),
+                BlankFieldPolicy::Error => Err(FieldError::BlankField {
+                    start: cols.start,
+                    end: cols.end,
+                }),
+            };
+        };
+
+        let too_large = || FieldError::NumberTooLarge {
+            start: cols.start,
+            end: cols.end,
+        };
+        let units = columns.len() - 1;
+        let mut negative = false;
+        let mut value: u64 = 0;
+        for (offset, col) in columns.iter().enumerate().skip(first) {
+            let column = cols.start + offset;
+            let invalid = || FieldError::InvalidPunch {
+                column,
+                rows: col.punches.rows.clone(),
+            };
+            let digit = match col.punches.classify().signed_digit() {
+                Some((None, digit)) => digit,
+                Some((Some(zone), digit)) if offset == units => {
+                    negative = zone == Zone::Eleven;
+                    digit
+                }
+                _ => return Err(invalid()),
+            };
+            value = value
+                .checked_mul(10)
+                .and_then(|v| v.checked_add(u64::from(digit)))
+                .ok_or_else(too_large)?;
+        }
+        let signed = if negative {
+            -i128::from(value)
+        } else {
+            i128::from(value)
+        };
+        i64::try_from(signed).map_err(|_| too_large())
+    }
+
+    /// Read a field as text with trailing blanks removed
+    pub fn read_alpha(&self, cols: Range<usize>) -> Result<String, FieldError> {
+        check_field(&cols, self.width().columns())?;
+        let text: String = self.columns()[cols]
+            .iter()
+            .map(|col| col.to_char().unwrap_or('?'))
+            .collect();
+        Ok(text.trim_end().to_string())
+    }
+
+    /// Punch already-validated text starting at a column
+    fn write_field_text(&mut self, start: usize, text: &str) {
+        for (i, c) in text.chars().enumerate() {
+            if let Some(col) = self.get_column_mut(start + i) {
+                *col = Column::from_char(c);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::punch_card::CardType;
+
+    fn blank() -> PunchCard {
+        PunchCard::new(CardType::Text)
+    }
+
+    #[test]
+    fn test_write_numeric_blank_fill() {
+        let mut card = blank();
+        card.write_numeric(0..5, 42, NumericFill::Blanks).unwrap();
+        assert_eq!(&card.to_text()[0..5], "   42");
+    }
+
+    #[test]
+    fn test_write_numeric_zero_fill() {
+        let mut card = blank();
+        card.write_numeric(10..15, 42, NumericFill::Zeros).unwrap();
+        assert_eq!(&card.to_text()[10..15], "00042");
+    }
+
+    #[test]
+    fn test_write_numeric_negative() {
+        let mut card = blank();
+        card.write_numeric(0..5, -42, NumericFill::Blanks).unwrap();
+        assert_eq!(&card.to_text()[0..5], "  -42");
+        card.write_numeric(0..5, -42, NumericFill::Zeros).unwrap();
+        assert_eq!(&card.to_text()[0..5], "-0042");
+    }
+
+    #[test]
+    fn test_write_numeric_exact_width() {
+        let mut card = blank();
+        card.write_numeric(0..5, 99999, NumericFill::Zeros).unwrap();
+        assert_eq!(&card.to_text()[0..5], "99999");
+        card.write_numeric(75..80, -9999, NumericFill::Blanks)
+            .unwrap();
+        assert_eq!(&card.to_text()[75..80], "-9999");
+    }
+
+    #[test]
+    fn test_write_numeric_overflow() {
+        let mut card = PunchCard::from_text("XXXXX");
+        assert_eq!(
+            card.write_numeric(0..5, 100000, NumericFill::Blanks),
+            Err(FieldError::Overflow {
+                value: 100000,
+                width: 5
+            })
+        );
+        assert!(
+            card.write_numeric(0..5, -10000, NumericFill::Zeros)
+                .is_err()
+        );
+        assert_eq!(card.to_text_trimmed(), "XXXXX");
+    }
+
+    #[test]
+    fn test_write_numeric_extremes() {
+        let mut card = blank();
+        card.write_numeric(0..20, i64::MIN, NumericFill::Blanks)
+            .unwrap();
+        assert_eq!(card.read_numeric(0..20).unwrap(), i64::MIN);
+        card.write_numeric(20..39, i64::MAX, NumericFill::Zeros)
+            .unwrap();
+        assert_eq!(card.read_numeric(20..39).unwrap(), i64::MAX);
+    }
+
+    #[test]
+    fn test_write_numeric_invalid_range() {
+        let mut card = blank();
+        assert!(card.write_numeric(78..81, 1, NumericFill::Blanks).is_err());
+        assert!(card.write_numeric(5..5, 1, NumericFill::Blanks).is_err());
+    }
+
+    #[test]
+    fn test_write_alpha() {
+        let mut card = PunchCard::from_text("XXXXXXXXXX");
+        card.write_alpha(0..10, "smith").unwrap();
+        assert_eq!(&card.to_text()[0..10], "SMITH     ");
+    }
+
+    #[test]
+    fn test_write_alpha_errors() {
+        let mut card = blank();
+        assert_eq!(
+            card.write_alpha(0..3, "TOOLONG"),
+            Err(FieldError::TooLong { len: 7, width: 3 })
+        );
+        assert_eq!(
+            card.write_alpha(4..10, "AB[C"),
+            Err(FieldError::Unencodable { column: 6, ch: '[' })
+        );
+        assert_eq!(card.punched_count(), 0);
+    }
+
+    #[test]
+    fn test_read_numeric() {
+        let card = PunchCard::from_text("  123-0045    0");
+        assert_eq!(card.read_numeric(0..5), Ok(123));
+        assert_eq!(card.read_numeric(5..10), Ok(-45));
+        assert_eq!(card.read_numeric(10..15), Ok(0));
+        assert_eq!(card.read_numeric(20..30), Ok(0));
+    }
+
+    #[test]
+    fn test_read_numeric_rejects_non_digits() {
+        let card = PunchCard::from_text(" 1 3 12A4 12   -   ");
+        assert_eq!(
+            card.read_numeric(0..4),
+            Err(FieldError::InvalidDigit { column: 2 })
+        );
+        assert_eq!(
+            card.read_numeric(5..9),
+            Err(FieldError::InvalidDigit { column: 7 })
+        );
+        assert_eq!(
+            card.read_numeric(9..14),
+            Err(FieldError::InvalidDigit { column: 12 })
+        );
+        assert_eq!(
+            card.read_numeric(14..18),
+            Err(FieldError::InvalidDigit { column: 16 })
+        );
+    }
+
+    #[test]
+    fn test_read_numeric_overflow_is_error() {
+        let card = PunchCard::from_text("99999999999999999999 9223372036854775808");
+        let too_large = FieldError::NumberTooLarge { start: 0, end: 20 };
+        assert_eq!(card.read_numeric(0..20), Err(too_large.clone()));
+        assert_eq!(
+            card.read_signed_numeric(0..20, BlankFieldPolicy::Zero),
+            Err(too_large.clone())
+        );
+        assert_eq!(
+            too_large.to_string(),
+            "Columns 1-20: number is too large to read"
+        );
+        // Fits a u64 but not an i64
+        assert_eq!(
+            card.read_numeric(21..40),
+            Err(FieldError::NumberTooLarge { start: 21, end: 40 })
+        );
+    }
+
+    #[test]
+    fn test_numeric_roundtrip() {
+        let mut card = blank();
+        for (value, fill) in [
+            (0, NumericFill::Zeros),
+            (7, NumericFill::Blanks),
+            (-7, NumericFill::Zeros),
+            (123456, NumericFill::Blanks),
+        ] {
+            card.write_numeric(30..40, value, fill).unwrap();
+            assert_eq!(card.read_numeric(30..40), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_read_alpha() {
+        let mut card = blank();
+        card.write_alpha(10..20, "JONES").unwrap();
+        assert_eq!(card.read_alpha(10..20).unwrap(), "JONES");
+    }
+
+    #[test]
+    fn test_field_error_display() {
+        let err = FieldError::InvalidDigit { column: 0 };
+        assert_eq!(err.to_string(), "Column 1: not a digit");
+    }
+
+    #[test]
+    fn test_field_bounds_on_stub_card() {
+        let mut card = PunchCard::new_with_width(51).unwrap();
+        card.write_numeric(46..51, 123, NumericFill::Zeros).unwrap();
+        assert_eq!(card.read_numeric(46..51).unwrap(), 123);
+        assert_eq!(
+            card.write_alpha(50..52, "AB"),
+            Err(FieldError::InvalidRange { start: 50, end: 52 })
+        );
+        assert!(card.read_numeric(70..80).is_err());
+    }
+
+    #[test]
+    fn test_read_signed_numeric_overpunch() {
+        let card = PunchCard::from_text("  12N 004E 0000 7J");
+        assert_eq!(
+            card.read_signed_numeric(0..5, BlankFieldPolicy::Zero),
+            Ok(-125)
+        );
+        assert_eq!(
+            card.read_signed_numeric(6..10, BlankFieldPolicy::Zero),
+            Ok(45)
+        );
+        assert_eq!(
+            card.read_signed_numeric(11..15, BlankFieldPolicy::Zero),
+            Ok(0)
+        );
+        assert_eq!(
+            card.read_signed_numeric(16..18, BlankFieldPolicy::Zero),
+            Ok(-71)
+        );
+    }
+
+    #[test]
+    fn test_read_signed_numeric_zero_overpunch() {
+        let mut card = blank();
+        card.write_alpha(0..2, "12").unwrap();
+        card.set_column_hollerith(2, HollerithCode::new(vec![11, 0]))
+            .unwrap();
+        assert_eq!(
+            card.read_signed_numeric(0..3, BlankFieldPolicy::Zero),
+            Ok(-120)
+        );
+        card.set_column_hollerith(2, HollerithCode::new(vec![12, 0]))
+            .unwrap();
+        assert_eq!(
+            card.read_signed_numeric(0..3, BlankFieldPolicy::Zero),
+            Ok(120)
+        );
+    }
+
+    #[test]
+    fn test_read_signed_numeric_blank_policy() {
+        let card = blank();
+        assert_eq!(
+            card.read_signed_numeric(0..5, BlankFieldPolicy::Zero),
+            Ok(0)
+        );
+        let err = card
+            .read_signed_numeric(0..5, BlankFieldPolicy::Error)
+            .unwrap_err();
+        assert_eq!(err, FieldError::BlankField { start: 0, end: 5 });
+        assert_eq!(err.to_string(), "Columns 1-5: numeric field is blank");
+    }
+
+    #[test]
+    fn test_read_signed_numeric_errors() {
+        // A zone punch is only a sign on the units digit
+        let card = PunchCard::from_text("1N5");
+        let err = card
+            .read_signed_numeric(0..3, BlankFieldPolicy::Zero)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            FieldError::InvalidPunch {
+                column: 1,
+                rows: vec![5, 11]
+            }
+        );
+        assert_eq!(err.to_string(), "Column 2: punches 11-5 are not a digit");
+
+        let card = PunchCard::from_text("1 5.");
+        assert_eq!(
+            card.read_signed_numeric(0..3, BlankFieldPolicy::Zero)
+                .unwrap_err()
+                .to_string(),
+            "Column 2: unexpected blank in numeric field"
+        );
+        assert_eq!(
+            card.read_signed_numeric(2..4, BlankFieldPolicy::Zero)
+                .unwrap_err()
+                .to_string(),
+            "Column 4: punches 12-3-8 are not a digit"
+        );
+    }
+
+    #[test]
+    fn test_write_signed_numeric_patterns() {
+        let mut card = blank();
+        card.write_signed_numeric(0..3, -5, SignConvention::NegativeOnly)
+            .unwrap();
+        assert_eq!(card.read_alpha(0..3).unwrap(), "00N");
+        assert_eq!(card.get_column(2).unwrap().punches.rows, vec![5, 11]);
+
+        card.write_signed_numeric(3..6, 5, SignConvention::NegativeOnly)
+            .unwrap();
+        assert_eq!(card.read_alpha(3..6).unwrap(), "005");
+
+        card.write_signed_numeric(6..9, 5, SignConvention::BothSigns)
+            .unwrap();
+        assert_eq!(card.read_alpha(6..9).unwrap(), "00E");
+        assert_eq!(card.get_column(8).unwrap().punches.rows, vec![5, 12]);
+    }
+
+    #[test]
+    fn test_signed_numeric_roundtrip() {
+        for convention in [SignConvention::NegativeOnly, SignConvention::BothSigns] {
+            for value in [0, 7, -7, 120, -120, 99999, -99999] {
+                let mut card = blank();
+                card.write_signed_numeric(10..15, value, convention)
+                    .unwrap();
+                assert_eq!(
+                    card.read_signed_numeric(10..15, BlankFieldPolicy::Error),
+                    Ok(value),
+                    "{} with {:?}",
+                    value,
+                    convention
+                );
+            }
+        }
+        let mut card = blank();
+        card.write_signed_numeric(0..19, i64::MIN, SignConvention::BothSigns)
+            .unwrap();
+        assert_eq!(
+            card.read_signed_numeric(0..19, BlankFieldPolicy::Error),
+            Ok(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn test_write_signed_numeric_overflow() {
+        let mut card = blank();
+        // The sign shares the units column, so -999 fits in three columns
+        assert!(
+            card.write_signed_numeric(0..3, -999, SignConvention::BothSigns)
+                .is_ok()
+        );
+        assert_eq!(
+            card.write_signed_numeric(0..3, -1000, SignConvention::BothSigns),
+            Err(FieldError::Overflow {
+                value: -1000,
+                width: 3
+            })
+        );
+        assert_eq!(
+            card.read_signed_numeric(0..3, BlankFieldPolicy::Zero),
+            Ok(-999)
+        );
+    }
+}
@@ -0,0 +1,728 @@
+// Card Format Module
+//
+// The byte-level file formats a single card can be stored in, and a single
+// entry point for parsing and serializing each of them.
+//
+// Byte layouts:
+//
+// - Ibm1130Binary108: 108 bytes. Columns 1-72 only, 12 bits per column packed
+//   LSB-first: bit n of the stream is byte n/8, bit n%8. Each column
+//   contributes its rows in the order [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9].
+//   Columns 73-80 (sequence numbers) are not stored. The packing itself is
+//   shared with FullBinary120 in the `bitpack` module.
+// - FullBinary120: 120 bytes. Same bit packing as Ibm1130Binary108 but for
+//   all 80 columns (960 bits).
+// - ColumnBinary160: 160 bytes. One big-endian 16-bit word per column; bits
+//   11..0 hold rows [12, 11, 0, 1, ..., 9] (row 12 is bit 11), bits 15..12 are 0.
+//   The words are `PunchCard::to_u16_columns`.
+// - Ebcdic80: 80 bytes. One EBCDIC character code per column.
+// - Bcdic80: 80 bytes. One 6-bit BCD code per column, top two bits 0.
+// - LegacyByte80: 80 bytes. One byte per column; bit n holds row position n
+//   of [12, 11, 0, 1, 2, 3, 4, 5], so rows 6-9 cannot be stored.
+// - AsciiRecord: 80 bytes. One ASCII character per column (card-image line).
+
+use crate::bcd::{bcdic_to_hollerith, hollerith_to_bcdic};
+use crate::bitpack;
+use crate::ebcdic::{ebcdic_to_char, hexdump_records, hollerith_to_ebcdic};
+use crate::hollerith::HollerithCode;
+use crate::punch_card::{CardType, CardWidth, PunchCard, UnsupportedCharPolicy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A byte-level storage format for a single card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CardFormat {
+    /// 108 bytes: IBM 1130 binary image of columns 1-72
+    Ibm1130Binary108,
+    /// 120 bytes: bit-packed image of all 80 columns
+    FullBinary120,
+    /// 160 bytes: one 16-bit word per column
+    ColumnBinary160,
+    /// 80 bytes: one EBCDIC character per column
+    Ebcdic80,
+    /// 80 bytes: one 6-bit BCD code per column
+    Bcdic80,
+    /// 80 bytes: one byte per column, rows 12-5 only
+    LegacyByte80,
+    /// 80 bytes: one ASCII character per column
+    AsciiRecord,
+}
+
+impl CardFormat {
+    /// All supported formats
+    pub const ALL: [CardFormat; 7] = [
+        CardFormat::Ibm1130Binary108,
+        CardFormat::FullBinary120,
+        CardFormat::ColumnBinary160,
+        CardFormat::Ebcdic80,
+        CardFormat::Bcdic80,
+        CardFormat::LegacyByte80,
+        CardFormat::AsciiRecord,
+    ];
+
+    /// Get the size in bytes of one card in this format
+    pub fn record_len(self) -> usize {
+        match self {
+            CardFormat::Ibm1130Binary108 => 108,
+            CardFormat::FullBinary120 => 120,
+            CardFormat::ColumnBinary160 => 160,
+            CardFormat::Ebcdic80
+            | CardFormat::Bcdic80
+            | CardFormat::LegacyByte80
+            | CardFormat::AsciiRecord => 80,
+        }
+    }
+
+    /// Whether the format stores raw punch images rather than characters
+    pub fn is_binary(self) -> bool {
+        matches!(
+            self,
+            CardFormat::Ibm1130Binary108 | CardFormat::FullBinary120 | CardFormat::ColumnBinary160
+        )
+    }
+}
+
+impl fmt::Display for CardFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CardFormat::Ibm1130Binary108 => "IBM 1130 binary (108 bytes)",
+            CardFormat::FullBinary120 => "full binary (120 bytes)",
+            CardFormat::ColumnBinary160 => "column binary (160 bytes)",
+            CardFormat::Ebcdic80 => "EBCDIC (80 bytes)",
+            CardFormat::Bcdic80 => "BCD (80 bytes)",
+            CardFormat::LegacyByte80 => "legacy byte-per-column (80 bytes)",
+            CardFormat::AsciiRecord => "ASCII card image (80 bytes)",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Errors from parsing or serializing a card format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardError {
+    /// The data is not the size the format requires
+    InvalidLength {
+        format: CardFormat,
+        expected: usize,
+        actual: usize,
+    },
+    /// A column's punches cannot be stored in the format
+    Unrepresentable { format: CardFormat, column: usize },
+    /// The data is the right size but its content is invalid
+    InvalidData { format: CardFormat, message: String },
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardError::InvalidLength {
+                format,
+                expected,
+                actual,
+            } => write!(f, "{} requires {} bytes, got {}", format, expected, actual),
+            CardError::Unrepresentable { format, column } => write!(
+                f,
+                "Column {} cannot be represented in {}",
+                column + 1,
+                format
+            ),
+            CardError::InvalidData { format, message } => {
+                write!(f, "Invalid {} data: {}", format, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardError {}
+
+/// How a column changes when saved in a format and loaded back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ColumnLoss {
+    /// The format does not store the column at all
+    Dropped,
+    /// The format cannot hold the column's punches
+    Unrepresentable,
+    /// The punches survive but the printed character does not
+    Printing,
+}
+
+/// A column that does not survive a round trip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LostColumn {
+    /// Zero-based column
+    pub column: usize,
+    /// What is lost
+    pub loss: ColumnLoss,
+}
+
+/// What saving a card in a format would lose (see `PunchCard::audit_export`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExportAudit {
+    /// The format audited
+    pub format: CardFormat,
+    /// Columns that come back different, in column order
+    pub lost: Vec<LostColumn>,
+}
+
+impl ExportAudit {
+    /// Check if the card comes back exactly as it was
+    pub fn is_lossless(&self) -> bool {
+        self.lost.is_empty()
+    }
+
+    /// Check if any column comes back with different punches
+    pub fn loses_punches(&self) -> bool {
+        self.lost
+            .iter()
+            .any(|lost| lost.loss != ColumnLoss::Printing)
+    }
+
+    /// Zero-based columns with the given loss
+    pub fn columns(&self, loss: ColumnLoss) -> Vec<usize> {
+        self.lost
+            .iter()
+            .filter(|lost| lost.loss == loss)
+            .map(|lost| lost.column)
+            .collect()
+    }
+}
+
+impl fmt::Display for ExportAudit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_lossless() {
+            return write!(f, "{}: lossless", self.format);
+        }
+        write!(
+            f,
+            "{}: {} columns dropped, {} unrepresentable, {} lose printing",
+            self.format,
+            self.columns(ColumnLoss::Dropped).len(),
+            self.columns(ColumnLoss::Unrepresentable).len(),
+            self.columns(ColumnLoss::Printing).len()
+        )
+    }
+}
+
+impl PunchCard {
+    /// Parse a card stored in the given format
+    ///
+    /// Binary formats produce binary cards; EBCDIC and ASCII produce text cards.
+    pub fn parse(data: &[u8], format: CardFormat) -> Result<PunchCard, CardError> {
+        if data.len() != format.record_len() {
+            return Err(CardError::InvalidLength {
+                format,
+                expected: format.record_len(),
+                actual: data.len(),
+            });
+        }
+
+        match format {
+            CardFormat::Ibm1130Binary108 => Ok(PunchCard::from_binary(data)),
+            CardFormat::FullBinary120 => {
+                let mut card = PunchCard::new(CardType::Binary);
+                for (idx, code) in bitpack::unpack_columns(data, 80).into_iter().enumerate() {
+                    card.set_column_hollerith(idx, code)
+                        .expect("80 columns unpacked");
+                }
+                Ok(card)
+            }
+            CardFormat::ColumnBinary160 => {
+                let mut words = Vec::with_capacity(80);
+                for (idx, pair) in data.chunks(2).enumerate() {
+                    let word = u16::from_be_bytes([pair[0], pair[1]]);
+                    if word & 0xF000 != 0 {
+                        return Err(CardError::InvalidData {
+                            format,
+                            message: format!(
+                                "column {} word {:#06X} uses bits above row 12",
+                                idx + 1,
+                                word
+                            ),
+                        });
+                    }
+                    words.push(word);
+                }
+                Ok(PunchCard::from_u16_columns(&words))
+            }
+            CardFormat::Ebcdic80 => Ok(PunchCard::from_ebcdic(data)),
+            CardFormat::Bcdic80 => match data.iter().position(|&code| code > 0o77) {
+                Some(idx) => Err(CardError::InvalidData {
+                    format,
+                    message: format!(
+                        "column {} byte {:#04X} uses bits above the 6-bit code",
+                        idx + 1,
+                        data[idx]
+                    ),
+                }),
+                None => Ok(PunchCard::from_bcdic(data)),
+            },
+            CardFormat::LegacyByte80 => Ok(PunchCard::from_binary(data)),
+            CardFormat::AsciiRecord => {
+                let text = std::str::from_utf8(data)
+                    .ok()
+                    .filter(|text| text.is_ascii())
+                    .ok_or_else(|| CardError::InvalidData {
+                        format,
+                        message: "record is not ASCII text".to_string(),
+                    })?;
+                PunchCard::from_ascii_record(text, UnsupportedCharPolicy::Error)
+                    .map_err(|message| CardError::InvalidData { format, message })
+            }
+        }
+    }
+
+    /// List the columns that would not survive saving the card in a format
+    /// and parsing it back
+    ///
+    /// Binary formats load without printing, and EBCDIC and ASCII records
+    /// print what their bytes decode to, so printing the punches do not
+    /// give is lost by every format. `serialize` fails on exactly the
+    /// `Unrepresentable` columns.
+    pub fn audit_export(&self, format: CardFormat) -> ExportAudit {
+        let lost = self
+            .columns()
+            .iter()
+            .enumerate()
+            .filter_map(|(column, col)| {
+                let printing = match format {
+                    CardFormat::Ibm1130Binary108 if column >= 72 && !col.is_blank() => {
+                        Err(ColumnLoss::Dropped)
+                    }
+                    CardFormat::Ibm1130Binary108
+                    | CardFormat::FullBinary120
+                    | CardFormat::ColumnBinary160 => Ok(None),
+                    CardFormat::Ebcdic80 => hollerith_to_ebcdic(&col.punches)
+                        .map(ebcdic_to_char)
+                        .map_err(|_| ColumnLoss::Unrepresentable),
+                    CardFormat::Bcdic80 => bcd_exact(&col.punches)
+                        .map(|_| col.to_char())
+                        .ok_or(ColumnLoss::Unrepresentable),
+                    CardFormat::LegacyByte80 if col.punches.as_array()[8..].contains(&true) => {
+                        Err(ColumnLoss::Unrepresentable)
+                    }
+                    CardFormat::LegacyByte80 => Ok(None),
+                    CardFormat::AsciiRecord => col
+                        .to_char()
+                        .filter(char::is_ascii)
+                        .map(Some)
+                        .ok_or(ColumnLoss::Unrepresentable),
+                };
+                let loss = match printing {
+                    Err(loss) => loss,
+                    Ok(printed) if col.printed_char.is_some() && printed != col.printed_char => {
+                        ColumnLoss::Printing
+                    }
+                    Ok(_) => return None,
+                };
+                Some(LostColumn { column, loss })
+            })
+            .collect();
+        ExportAudit { format, lost }
+    }
+
+    /// Hex dump the card serialized in the given format (see
+    /// `ebcdic::hexdump`)
+    ///
+    /// The gutter always reads bytes as EBCDIC. Fails where `serialize`
+    /// does.
+    pub fn hexdump(&self, format: CardFormat) -> Result<String, CardError> {
+        let data = self.serialize(format)?;
+        Ok(hexdump_records(&data, format.record_len()))
+    }
+
+    /// Serialize the card in the given format
+    ///
+    /// Fails if any column's punches cannot be stored exactly, rather than
+    /// silently writing something else. The 108-byte 1130 format is defined
+    /// to drop columns 73-80 and never fails. Stub cards are written as
+    /// full-width records with blank trailing columns.
+    pub fn serialize(&self, format: CardFormat) -> Result<Vec<u8>, CardError> {
+        if self.width() != CardWidth::STANDARD {
+            return self.to_standard_width().serialize(format);
+        }
+        match format {
+            CardFormat::Ibm1130Binary108 => Ok(self.to_binary()),
+            CardFormat::FullBinary120 => {
+                let mut data = vec![0u8; bitpack::packed_len(80)];
+                bitpack::pack_into(self.columns().iter().map(|col| &col.punches), &mut data);
+                Ok(data)
+            }
+            CardFormat::ColumnBinary160 => Ok(self
+                .to_u16_columns()
+                .iter()
+                .flat_map(|word| word.to_be_bytes())
+                .collect()),
+            CardFormat::Ebcdic80 => self.to_ebcdic().map_err(|err| CardError::Unrepresentable {
+                format,
+                column: err.columns[0],
+            }),
+            CardFormat::Bcdic80 => self
+                .columns()
+                .iter()
+                .enumerate()
+                .map(|(column, col)| {
+                    bcd_exact(&col.punches).ok_or(CardError::Unrepresentable { format, column })
+                })
+                .collect(),
+            CardFormat::LegacyByte80 => self
+                .columns()
+                .iter()
+                .enumerate()
+                .map(|(column, col)| {
+                    let punches = col.punches.as_array();
+                    if punches[8..].iter().any(|&p| p) {
+                        return Err(CardError::Unrepresentable { format, column });
+                    }
+                    Ok(punches[..8]
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_, &punched)| punched)
+                        .fold(0u8, |byte, (bit, _)| byte | (1 << bit)))
+                })
+                .collect(),
+            CardFormat::AsciiRecord => self
+                .columns()
+                .iter()
+                .enumerate()
+                .map(|(column, col)| {
+                    col.to_char()
+                        .filter(char::is_ascii)
+                        .map(|c| c as u8)
+                        .ok_or(CardError::Unrepresentable { format, column })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Get the BCD code for punches that read back exactly as they are
+///
+/// Patterns BCD reads as another, such as 8-2 for 0, have no exact code.
+fn bcd_exact(punches: &HollerithCode) -> Option<u8> {
+    hollerith_to_bcdic(punches).filter(|&code| bcdic_to_hollerith(code) == *punches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hollerith::HollerithCode;
+
+    fn laced_card() -> PunchCard {
+        let mut card = PunchCard::from_text("LACE");
+        card.set_column_hollerith(79, HollerithCode::from_array([true; 12]))
+            .unwrap();
+        card
+    }
+
+    #[test]
+    fn test_record_lengths() {
+        // Only rows 12-5 so every format can hold it
+        let card = PunchCard::from_text("ABE-&/1");
+        for format in CardFormat::ALL {
+            let data = card.serialize(format).unwrap();
+            assert_eq!(data.len(), format.record_len(), "{}", format);
+        }
+    }
+
+    #[test]
+    fn test_ibm1130_binary_matches_to_binary() {
+        let card = PunchCard::from_text("HELLO WORLD");
+        let data = card.serialize(CardFormat::Ibm1130Binary108).unwrap();
+        assert_eq!(data, card.to_binary());
+        let parsed = PunchCard::parse(&data, CardFormat::Ibm1130Binary108).unwrap();
+        assert_eq!(parsed.card_type(), CardType::Binary);
+        assert!(parsed.punches_eq(&card));
+    }
+
+    #[test]
+    fn test_full_binary_keeps_all_80_columns() {
+        let card = laced_card();
+        let data = card.serialize(CardFormat::FullBinary120).unwrap();
+        // The first 108 bytes are bit-identical to the 1130 format
+        assert_eq!(&data[..108], &card.to_binary()[..]);
+        let parsed = PunchCard::parse(&data, CardFormat::FullBinary120).unwrap();
+        assert!(parsed.punches_eq(&card));
+    }
+
+    #[test]
+    fn test_column_binary_layout() {
+        let card = PunchCard::from_text("A9");
+        let data = card.serialize(CardFormat::ColumnBinary160).unwrap();
+        // 'A' = 12-1: bit 11 (row 12) and bit 8 (row 1)
+        assert_eq!(&data[0..2], &[0x09, 0x00]);
+        // '9' = row 9: bit 0
+        assert_eq!(&data[2..4], &[0x00, 0x01]);
+        let parsed = PunchCard::parse(&data, CardFormat::ColumnBinary160).unwrap();
+        assert!(parsed.punches_eq(&card));
+    }
+
+    #[test]
+    fn test_column_binary_roundtrip_lace() {
+        let card = laced_card();
+        let data = card.serialize(CardFormat::ColumnBinary160).unwrap();
+        assert_eq!(&data[158..160], &[0x0F, 0xFF]);
+        let parsed = PunchCard::parse(&data, CardFormat::ColumnBinary160).unwrap();
+        assert!(parsed.punches_eq(&card));
+    }
+
+    #[test]
+    fn test_column_binary_rejects_high_bits() {
+        let mut data = vec![0u8; 160];
+        data[4] = 0x10;
+        let err = PunchCard::parse(&data, CardFormat::ColumnBinary160).unwrap_err();
+        assert!(matches!(err, CardError::InvalidData { .. }));
+        assert!(err.to_string().contains("column 3"));
+    }
+
+    #[test]
+    fn test_ebcdic_roundtrip() {
+        let card = PunchCard::from_text("HELLO 1130");
+        let data = card.serialize(CardFormat::Ebcdic80).unwrap();
+        let parsed = PunchCard::parse(&data, CardFormat::Ebcdic80).unwrap();
+        assert_eq!(parsed.card_type(), CardType::Text);
+        assert!(parsed.punches_eq(&card));
+    }
+
+    #[test]
+    fn test_ebcdic_rejects_lace_column() {
+        let err = laced_card().serialize(CardFormat::Ebcdic80).unwrap_err();
+        assert_eq!(
+            err,
+            CardError::Unrepresentable {
+                format: CardFormat::Ebcdic80,
+                column: 79
+            }
+        );
+    }
+
+    #[test]
+    fn test_bcd_roundtrip_and_limits() {
+        let card = PunchCard::from_text("MCW  CUST+12");
+        let data = card.serialize(CardFormat::Bcdic80).unwrap();
+        assert_eq!(data[0], 0o44);
+        let parsed = PunchCard::parse(&data, CardFormat::Bcdic80).unwrap();
+        assert_eq!(parsed.card_type(), CardType::Text);
+        assert!(parsed.punches_eq(&card));
+
+        // 8-2 reads back as 0 and 12-11-1 has no code at all
+        for rows in [vec![8, 2], vec![12, 11, 1]] {
+            let mut card = PunchCard::from_text("A");
+            card.set_column_hollerith(2, HollerithCode::new(rows))
+                .unwrap();
+            assert_eq!(
+                card.serialize(CardFormat::Bcdic80),
+                Err(CardError::Unrepresentable {
+                    format: CardFormat::Bcdic80,
+                    column: 2
+                })
+            );
+        }
+        let mut data = data;
+        data[4] = 0xC1;
+        let err = PunchCard::parse(&data, CardFormat::Bcdic80).unwrap_err();
+        assert!(err.to_string().contains("column 5 byte 0xC1"));
+    }
+
+    #[test]
+    fn test_hexdump() {
+        let card = PunchCard::from_text("A1");
+        let ebcdic = card.hexdump(CardFormat::Ebcdic80).unwrap();
+        assert_eq!(ebcdic, crate::ebcdic::hexdump(&card.to_ebcdic().unwrap()));
+
+        let binary = card.hexdump(CardFormat::Ibm1130Binary108).unwrap();
+        let lines: Vec<&str> = binary.lines().collect();
+        assert_eq!(lines.len(), 1 + 6);
+        assert_eq!(lines[0], "record 1 (offset 0x0)");
+        let data = card.to_binary();
+        let first = format!("000000  {:02X} {:02X} {:02X}", data[0], data[1], data[2]);
+        assert!(lines[1].starts_with(&first));
+        assert!(lines[6].starts_with("000064  00 00 00 00 00 00 00 00  "));
+        assert!(laced_card().hexdump(CardFormat::AsciiRecord).is_err());
+    }
+
+    #[test]
+    fn test_legacy_byte_roundtrip_and_limits() {
+        let card = PunchCard::from_text("AJ/&-");
+        let data = card.serialize(CardFormat::LegacyByte80).unwrap();
+        assert_eq!(data[0], 0b0000_1001);
+        let parsed = PunchCard::parse(&data, CardFormat::LegacyByte80).unwrap();
+        assert!(parsed.punches_eq(&card));
+
+        let err = PunchCard::from_text("ABZ")
+            .serialize(CardFormat::LegacyByte80)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            CardError::Unrepresentable {
+                format: CardFormat::LegacyByte80,
+                column: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_ascii_record_roundtrip() {
+        let card = PunchCard::from_text("LOOP  LD   X");
+        let data = card.serialize(CardFormat::AsciiRecord).unwrap();
+        assert_eq!(&data[..12], b"LOOP  LD   X");
+        let parsed = PunchCard::parse(&data, CardFormat::AsciiRecord).unwrap();
+        assert_eq!(parsed.to_text_trimmed(), "LOOP  LD   X");
+    }
+
+    #[test]
+    fn test_ascii_record_errors() {
+        assert!(matches!(
+            laced_card().serialize(CardFormat::AsciiRecord),
+            Err(CardError::Unrepresentable { column: 79, .. })
+        ));
+        let mut data = vec![b' '; 80];
+        data[3] = b'[';
+        assert!(matches!(
+            PunchCard::parse(&data, CardFormat::AsciiRecord),
+            Err(CardError::InvalidData { .. })
+        ));
+        data[3] = 0xC1;
+        assert!(PunchCard::parse(&data, CardFormat::AsciiRecord).is_err());
+    }
+
+    #[test]
+    fn test_audit_export_loss_modes() {
+        // 'Z' has a 9 punch, '¢' an 8 and no ASCII code, column 80 is laced
+        let mut card = laced_card();
+        card.set_column_char(4, 'Z').unwrap();
+        card.set_column_char(5, '¢').unwrap();
+        card.set_column_char(75, 'A').unwrap();
+        let lost = |format| {
+            let audit = card.audit_export(format);
+            [
+                ColumnLoss::Dropped,
+                ColumnLoss::Unrepresentable,
+                ColumnLoss::Printing,
+            ]
+            .map(|loss| audit.columns(loss))
+        };
+
+        let printed: Vec<usize> = vec![0, 1, 2, 3, 4, 5, 75];
+        let [dropped, _, printing] = lost(CardFormat::Ibm1130Binary108);
+        assert_eq!(dropped, vec![75, 79]);
+        assert_eq!(printing, printed[..6]);
+        assert_eq!(
+            lost(CardFormat::FullBinary120),
+            [vec![], vec![], printed.clone()]
+        );
+        assert_eq!(lost(CardFormat::ColumnBinary160), [vec![], vec![], printed]);
+        assert_eq!(lost(CardFormat::Ebcdic80), [vec![], vec![79], vec![]]);
+        assert_eq!(lost(CardFormat::Bcdic80), [vec![], vec![5, 79], vec![]]);
+        assert_eq!(lost(CardFormat::LegacyByte80)[1], vec![4, 5, 79]);
+        assert_eq!(lost(CardFormat::AsciiRecord), [vec![], vec![5, 79], vec![]]);
+
+        let audit = card.audit_export(CardFormat::Ebcdic80);
+        assert!(audit.loses_punches());
+        assert_eq!(
+            audit.to_string(),
+            "EBCDIC (80 bytes): 0 columns dropped, 1 unrepresentable, 0 lose printing"
+        );
+        let text = PunchCard::from_text("HELLO");
+        assert!(text.audit_export(CardFormat::Ebcdic80).is_lossless());
+        let binary = text.audit_export(CardFormat::FullBinary120);
+        assert!(!binary.is_lossless() && !binary.loses_punches());
+    }
+
+    #[test]
+    fn test_audit_export_matches_roundtrip() {
+        let mut card = laced_card();
+        card.set_column_char(5, '¢').unwrap();
+        card.set_column_hollerith(6, HollerithCode::new(vec![0, 1, 12]))
+            .unwrap();
+        card.get_column_mut(6).unwrap().set_printed_char(Some('a'));
+        card.get_column_mut(7).unwrap().set_printed_char(Some('Q'));
+        for format in CardFormat::ALL {
+            let audit = card.audit_export(format);
+            let mut savable = card.clone();
+            for column in audit.columns(ColumnLoss::Unrepresentable) {
+                savable
+                    .set_column_hollerith(column, HollerithCode::empty())
+                    .unwrap();
+            }
+            let data = savable.serialize(format).unwrap();
+            let loaded = PunchCard::parse(&data, format).unwrap();
+            for (column, (before, after)) in card.columns().iter().zip(loaded.columns()).enumerate()
+            {
+                let loss = audit
+                    .lost
+                    .iter()
+                    .find(|lost| lost.column == column)
+                    .map(|lost| lost.loss);
+                let printing_kept =
+                    before.printed_char.is_none() || before.printed_char == after.printed_char;
+                assert_eq!(
+                    (before.punches_eq(after), printing_kept),
+                    match loss {
+                        None => (true, true),
+                        Some(ColumnLoss::Printing) => (true, false),
+                        Some(_) => (false, before.printed_char.is_none()),
+                    },
+                    "{} column {}",
+                    format,
+                    column + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_export_audit_serde() {
+        let audit = laced_card().audit_export(CardFormat::Ibm1130Binary108);
+        let json = serde_json::to_value(&audit).unwrap();
+        assert_eq!(json["format"], "ibm1130_binary108");
+        assert_eq!(
+            json["lost"][4],
+            serde_json::json!({ "column": 79, "loss": "dropped" })
+        );
+        assert_eq!(serde_json::from_value::<ExportAudit>(json).unwrap(), audit);
+    }
+
+    #[test]
+    fn test_parse_rejects_wrong_length() {
+        for format in CardFormat::ALL {
+            let err = PunchCard::parse(&[0u8; 7], format).unwrap_err();
+            assert_eq!(
+                err,
+                CardError::InvalidLength {
+                    format,
+                    expected: format.record_len(),
+                    actual: 7
+                }
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_card_format_serde_names() {
+        let json = serde_json::to_string(&CardFormat::Ibm1130Binary108).unwrap();
+        assert_eq!(json, "\"ibm1130_binary108\"");
+        let format: CardFormat = serde_json::from_str("\"ebcdic80\"").unwrap();
+        assert_eq!(format, CardFormat::Ebcdic80);
+    }
+
+    #[test]
+    fn test_card_error_display() {
+        let err = CardError::Unrepresentable {
+            format: CardFormat::Ebcdic80,
+            column: 0,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Column 1 cannot be represented in EBCDIC (80 bytes)"
+        );
+    }
+}
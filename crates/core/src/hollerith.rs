@@ -3,25 +3,87 @@
 // Implements the Hollerith punch card encoding system used by IBM 029 keypunch.
 // Supports 64 printable characters with zone (12, 11, 0) and numeric (1-9) punches.
 
-use serde::{Deserialize, Serialize};
+use crate::ebcdic::hollerith_to_ebcdic;
+#[cfg(feature = "serde")]
+use serde::de::Error as _;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 
 /// Represents a Hollerith punch pattern for one column of a punch card
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Equality and hashing compare `rows` directly. The constructors keep rows
+/// sorted without duplicates (see `new`), so the same holes compare and hash
+/// equal however the code was built. Code that sets `rows` by hand must keep
+/// that order. Deserializing sorts the rows too, and rejects any that are
+/// not card rows.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct HollerithCode {
     /// The rows that are punched (12, 11, 0-9)
     /// Row 12 is represented as 12, row 11 as 11, rows 0-9 as their numeric value
     pub rows: Vec<u8>,
 }
 
+/// Serialized form of a punch pattern, checked on reading
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct HollerithCodeData {
+    rows: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for HollerithCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = HollerithCodeData::deserialize(deserializer)?;
+        HollerithCode::try_new(data.rows).map_err(D::Error::custom)
+    }
+}
+
+/// A row value that is not a card row (12, 11, 0-9)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidRow(pub u8);
+
+impl fmt::Display for InvalidRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Row {} is not a card row (12, 11, 0-9)", self.0)
+    }
+}
+
+impl std::error::Error for InvalidRow {}
+
+/// Card rows in card order, top to bottom (the `as_array` order)
+const CARD_ROWS: [u8; 12] = [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
 impl HollerithCode {
     /// Create a new HollerithCode with the specified punched rows
+    ///
+    /// Rows are normalized to ascending order without duplicates. Values
+    /// that are not card rows are dropped, and panic in debug builds; use
+    /// `try_new` for rows that come from outside the program.
     pub fn new(rows: Vec<u8>) -> Self {
+        debug_assert!(
+            rows.iter().all(|&row| is_valid_row(row)),
+            "invalid punch rows {:?}",
+            rows
+        );
         let mut sorted_rows = rows;
+        sorted_rows.retain(|&row| is_valid_row(row));
         sorted_rows.sort();
         sorted_rows.dedup();
         HollerithCode { rows: sorted_rows }
     }
 
+    /// Create a HollerithCode, failing on the first value that is not a
+    /// card row
+    pub fn try_new(rows: Vec<u8>) -> Result<Self, InvalidRow> {
+        match rows.iter().find(|&&row| !is_valid_row(row)) {
+            Some(&row) => Err(InvalidRow(row)),
+            None => Ok(HollerithCode::new(rows)),
+        }
+    }
+
     /// Create an empty HollerithCode (no punches - represents space/blank)
     pub fn empty() -> Self {
         HollerithCode { rows: Vec::new() }
@@ -35,262 +97,1372 @@ impl HollerithCode {
     /// Get the punches as a 12-element boolean array (index 0=row 12, 1=row 11, 2=row 0, 3-11=rows 1-9)
     pub fn as_array(&self) -> [bool; 12] {
         let mut arr = [false; 12];
-        for &row in &self.rows {
-            let idx = match row {
-                12 => 0,
-                11 => 1,
-                0 => 2,
-                1..=9 => (row + 2) as usize,
-                _ => continue,
-            };
+        for idx in self.rows.iter().filter_map(|&row| row_index(row)) {
             arr[idx] = true;
         }
         arr
     }
 
-    /// Create a HollerithCode from a 12-element boolean array
-    pub fn from_array(arr: [bool; 12]) -> Self {
-        let mut rows = Vec::new();
-        for (idx, &punched) in arr.iter().enumerate() {
-            if punched {
-                let row = match idx {
-                    0 => 12,
-                    1 => 11,
-                    2 => 0,
-                    3..=11 => (idx - 2) as u8,
-                    _ => continue,
-                };
-                rows.push(row);
-            }
+    /// Get the punches as a 12-bit integer in the given bit order
+    ///
+    /// Bits 12-15 are always 0.
+    pub fn to_u16(&self, order: BitOrder) -> u16 {
+        self.rows
+            .iter()
+            .filter_map(|&row| row_index(row))
+            .fold(0, |bits, idx| bits | (1 << order.bit(idx)))
+    }
+
+    /// Create a HollerithCode from a 12-bit integer in the given bit order
+    ///
+    /// Bits 12-15 are ignored.
+    pub fn from_u16(value: u16, order: BitOrder) -> Self {
+        let bits = match order {
+            BitOrder::Row12High => value & 0x0FFF,
+            BitOrder::Row9High => (value & 0x0FFF).reverse_bits() >> 4,
+        };
+        // Row 12 high: bit 11 is row 12, bit 10 row 11, bit 9 - r row r.
+        // Pushing rows in ascending order means no sorting is needed.
+        let mut rows = Vec::with_capacity(bits.count_ones() as usize);
+        rows.extend((0..=9).filter(|row| bits & (1 << (9 - row)) != 0));
+        if bits & (1 << 10) != 0 {
+            rows.push(11);
+        }
+        if bits & (1 << 11) != 0 {
+            rows.push(12);
+        }
+        HollerithCode { rows }
+    }
+
+    /// Write the punches in card order, e.g. "12-3-8", or "blank"
+    pub fn to_notation(&self) -> String {
+        if self.rows.is_empty() {
+            return "blank".to_string();
+        }
+        self.as_array()
+            .iter()
+            .zip(CARD_ROWS)
+            .filter(|&(&punched, _)| punched)
+            .map(|(_, row)| row.to_string())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Split an 8-punch special pattern into its zone and digit
+    ///
+    /// The 3-punch specials (and the 2-punch 8-2 to 8-7) are an 8 punch,
+    /// one digit 2-7, and at most one zone. Returns None for any other
+    /// pattern, whether or not the 029 has a character for it.
+    pub fn eight_punch_combo(&self) -> Option<(Option<Zone>, u8)> {
+        match self.rows[..] {
+            [digit @ 2..=7, 8] => Some((None, digit)),
+            [0, digit @ 2..=7, 8] => Some((Some(Zone::Zero), digit)),
+            [digit @ 2..=7, 8, 11] => Some((Some(Zone::Eleven), digit)),
+            [digit @ 2..=7, 8, 12] => Some((Some(Zone::Twelve), digit)),
+            _ => None,
+        }
+    }
+
+    /// Check if the pattern is a character of the 029 set, including blank
+    ///
+    /// That is 63 of the 4096 patterns: 0-8-2 is the one 8-punch special
+    /// the 029 leaves out.
+    pub fn is_valid_029_pattern(&self) -> bool {
+        self.mask().is_some_and(|mask| MASK_TO_CHAR[mask].is_some())
+    }
+
+    /// Row-12-high mask as a table index, or None if a row is not a card row
+    fn mask(&self) -> Option<usize> {
+        self.rows.iter().try_fold(0, |mask, &row| {
+            Some(mask | 1 << BitOrder::Row12High.bit(row_index(row)?))
+        })
+    }
+
+    /// Classify the pattern by the 029 character set (see `PunchClass`)
+    pub fn classify(&self) -> PunchClass {
+        match hollerith_to_char(self) {
+            Some(' ') => PunchClass::Blank,
+            Some('&') => PunchClass::ZoneOnly(Zone::Twelve),
+            Some('-') => PunchClass::ZoneOnly(Zone::Eleven),
+            Some(c @ '0'..='9') => PunchClass::Digit(c as u8 - b'0'),
+            Some(c @ 'A'..='Z') => PunchClass::Alphabetic(c),
+            Some(c) => PunchClass::Special(c),
+            None => match self.rows[..] {
+                [0, 12] => PunchClass::SignedZero(Zone::Twelve),
+                [0, 11] => PunchClass::SignedZero(Zone::Eleven),
+                _ => PunchClass::Invalid,
+            },
+        }
+    }
+
+    /// Get the key a card sorter orders this column by (see `SorterKey`)
+    pub fn sorter_key(&self) -> SorterKey {
+        let digit = self
+            .rows
+            .iter()
+            .copied()
+            .filter(|row| (1..=9).contains(row))
+            .max()
+            .unwrap_or(0);
+        let zone_rank = if self.rows.is_empty() {
+            0
+        } else if self.is_punched(12) {
+            1
+        } else if self.is_punched(11) {
+            2
+        } else if self.is_punched(0) && digit > 0 {
+            3
+        } else {
+            4
+        };
+        SorterKey {
+            zone_rank,
+            digit,
+            pattern: self.to_u16(BitOrder::Row12High),
         }
+    }
+
+    /// Create a HollerithCode from a 12-element boolean array (see
+    /// `as_array`)
+    pub fn from_array(arr: [bool; 12]) -> Self {
+        let rows = CARD_ROWS
+            .iter()
+            .zip(arr)
+            .filter(|&(_, punched)| punched)
+            .map(|(&row, _)| row)
+            .collect();
         HollerithCode::new(rows)
     }
 }
 
+/// A zone punch
+///
+/// Only 12 and 11 stand alone or sign a digit, so `PunchClass` never uses
+/// the 0 zone: alone it is the digit 0, and with a digit it makes a letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Zone {
+    /// Row 12 (`&` alone; plus sign over a digit)
+    Twelve,
+    /// Row 11 (`-` alone; minus sign over a digit)
+    Eleven,
+    /// Row 0, as the zone of S-Z and the 0-8 specials
+    Zero,
+}
+
+/// What kind of column a punch pattern is, in the 029 character set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PunchClass {
+    /// No punches
+    Blank,
+    /// A single digit punch, 0-9
+    Digit(u8),
+    /// A 12 or 11 punch alone (`&` or `-`)
+    ZoneOnly(Zone),
+    /// A letter: a zone punch and a digit punch
+    Alphabetic(char),
+    /// Any other 029 character
+    Special(char),
+    /// 12-0 or 11-0: a zero with a sign overpunch, which is not an 029
+    /// character
+    SignedZero(Zone),
+    /// A pattern with no 029 meaning
+    Invalid,
+}
+
+impl PunchClass {
+    /// Check if the column is an 029 character
+    pub fn is_character(&self) -> bool {
+        !matches!(self, PunchClass::SignedZero(_) | PunchClass::Invalid)
+    }
+
+    /// Read the column as a digit in a numeric field, with its sign zone if
+    /// overpunched
+    ///
+    /// Letters A-I are +1 to +9 and J-R are -1 to -9 (12-8 is both H and +8).
+    /// Returns None for columns that are not a digit at all.
+    pub fn signed_digit(&self) -> Option<(Option<Zone>, u8)> {
+        match *self {
+            PunchClass::Digit(digit) => Some((None, digit)),
+            PunchClass::SignedZero(zone) => Some((Some(zone), 0)),
+            PunchClass::Alphabetic(c @ 'A'..='I') => Some((Some(Zone::Twelve), c as u8 - b'A' + 1)),
+            PunchClass::Alphabetic(c @ 'J'..='R') => Some((Some(Zone::Eleven), c as u8 - b'J' + 1)),
+            _ => None,
+        }
+    }
+}
+
+/// A column's place in the card sorter collating sequence
+///
+/// An alphabetic sort on the sorter is a digit pass then a zone pass. The
+/// sorter reads from the 9 edge, so the digit is the highest of rows 1-9
+/// punched. That gives, in ascending order:
+///
+/// 1. blank
+/// 2. 12 zone: `&`, then A-I
+/// 3. 11 zone: `-`, then J-R
+/// 4. 0 zone: `/`, then S-Z
+/// 5. no zone: digits 0-9
+///
+/// Within a group, special characters fall by their highest digit (12-8-3
+/// `.` sorts with H), and patterns with the same zone and digit are ordered
+/// by their remaining holes so different patterns never compare equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SorterKey {
+    zone_rank: u8,
+    digit: u8,
+    pattern: u16,
+}
+
+/// How a column's 12 rows map to the bits of an integer
+///
+/// Both orders use the low 12 bits, with rows in card order
+/// [12, 11, 0, 1, ..., 9] (as in `HollerithCode::as_array`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// Row 12 is bit 11 and row 9 is bit 0, as in column-binary files and
+    /// IBM 1130 card reads
+    #[default]
+    Row12High,
+    /// Row 9 is bit 11 and row 12 is bit 0, as in the `bitpack` stream
+    Row9High,
+}
+
+impl BitOrder {
+    /// Bit number for an `as_array` index
+    fn bit(self, idx: usize) -> usize {
+        match self {
+            BitOrder::Row12High => 11 - idx,
+            BitOrder::Row9High => idx,
+        }
+    }
+}
+
+/// Position of a row in card order (the `as_array` index), if it is a row
+fn row_index(row: u8) -> Option<usize> {
+    match row {
+        12 => Some(0),
+        11 => Some(1),
+        0..=9 => Some(usize::from(row) + 2),
+        _ => None,
+    }
+}
+
+/// Check if a row value names a real card row (12, 11, 0-9)
+pub fn is_valid_row(row: u8) -> bool {
+    matches!(row, 0..=9 | 11 | 12)
+}
+
+/// Build a row-12-high punch mask (see `BitOrder::Row12High`) at compile time
+const fn rows_mask(rows: &[u8]) -> u16 {
+    let mut mask = 0;
+    let mut i = 0;
+    while i < rows.len() {
+        let bit = match rows[i] {
+            12 => 11,
+            11 => 10,
+            row => 9 - row,
+        };
+        mask |= 1 << bit;
+        i += 1;
+    }
+    mask
+}
+
+/// The IBM 029 keypunch character set (rows sorted)
+const IBM029_TABLE: &[(char, &[u8])] = &[
+    // Digits (numeric punch only)
+    ('0', &[0]),
+    ('1', &[1]),
+    ('2', &[2]),
+    ('3', &[3]),
+    ('4', &[4]),
+    ('5', &[5]),
+    ('6', &[6]),
+    ('7', &[7]),
+    ('8', &[8]),
+    ('9', &[9]),
+    // Letters A-I (12 zone + numeric)
+    ('A', &[1, 12]),
+    ('B', &[2, 12]),
+    ('C', &[3, 12]),
+    ('D', &[4, 12]),
+    ('E', &[5, 12]),
+    ('F', &[6, 12]),
+    ('G', &[7, 12]),
+    ('H', &[8, 12]),
+    ('I', &[9, 12]),
+    // Letters J-R (11 zone + numeric)
+    ('J', &[1, 11]),
+    ('K', &[2, 11]),
+    ('L', &[3, 11]),
+    ('M', &[4, 11]),
+    ('N', &[5, 11]),
+    ('O', &[6, 11]),
+    ('P', &[7, 11]),
+    ('Q', &[8, 11]),
+    ('R', &[9, 11]),
+    // Letters S-Z (0 zone + numeric)
+    ('S', &[0, 2]),
+    ('T', &[0, 3]),
+    ('U', &[0, 4]),
+    ('V', &[0, 5]),
+    ('W', &[0, 6]),
+    ('X', &[0, 7]),
+    ('Y', &[0, 8]),
+    ('Z', &[0, 9]),
+    // Blank and the single-zone specials
+    (' ', &[]),
+    ('&', &[12]),
+    ('-', &[11]),
+    ('/', &[0, 1]),
+    // 12 zone with 8
+    ('¢', &[2, 8, 12]),
+    ('.', &[3, 8, 12]),
+    ('<', &[4, 8, 12]),
+    ('(', &[5, 8, 12]),
+    ('+', &[6, 8, 12]),
+    ('|', &[7, 8, 12]),
+    // 11 zone with 8
+    ('!', &[2, 8, 11]),
+    ('$', &[3, 8, 11]),
+    ('*', &[4, 8, 11]),
+    (')', &[5, 8, 11]),
+    (';', &[6, 8, 11]),
+    ('¬', &[7, 8, 11]),
+    // 0 zone with 8
+    (',', &[0, 3, 8]),
+    ('%', &[0, 4, 8]),
+    ('_', &[0, 5, 8]),
+    ('>', &[0, 6, 8]),
+    ('?', &[0, 7, 8]),
+    // 8 with no zone
+    (':', &[2, 8]),
+    ('#', &[3, 8]),
+    ('@', &[4, 8]),
+    ('\'', &[5, 8]),
+    ('=', &[6, 8]),
+    ('"', &[7, 8]),
+];
+
+/// Punched rows for each ASCII character with an 029 punch pattern
+static ASCII_TO_ROWS: [Option<&[u8]>; 128] = {
+    let mut table = [None; 128];
+    let mut i = 0;
+    while i < IBM029_TABLE.len() {
+        let (c, rows) = IBM029_TABLE[i];
+        if c.is_ascii() {
+            table[c as usize] = Some(rows);
+        }
+        i += 1;
+    }
+    table
+};
+
+/// Character for each of the 4096 row-12-high punch masks
+static MASK_TO_CHAR: [Option<char>; 4096] = {
+    let mut table = [None; 4096];
+    let mut i = 0;
+    while i < IBM029_TABLE.len() {
+        let (c, rows) = IBM029_TABLE[i];
+        table[rows_mask(rows) as usize] = Some(c);
+        i += 1;
+    }
+    table
+};
+
 /// Convert a character to its Hollerith encoding
 ///
 /// Based on IBM 029 keypunch encoding table
 /// Returns None for unsupported characters
 pub fn char_to_hollerith(c: char) -> Option<HollerithCode> {
-    let code = match c {
-        // Digits (numeric punch only)
-        '0' => vec![0],
-        '1' => vec![1],
-        '2' => vec![2],
-        '3' => vec![3],
-        '4' => vec![4],
-        '5' => vec![5],
-        '6' => vec![6],
-        '7' => vec![7],
-        '8' => vec![8],
-        '9' => vec![9],
-
-        // Letters A-I (12 zone + numeric)
-        'A' => vec![12, 1],
-        'B' => vec![12, 2],
-        'C' => vec![12, 3],
-        'D' => vec![12, 4],
-        'E' => vec![12, 5],
-        'F' => vec![12, 6],
-        'G' => vec![12, 7],
-        'H' => vec![12, 8],
-        'I' => vec![12, 9],
-
-        // Letters J-R (11 zone + numeric)
-        'J' => vec![11, 1],
-        'K' => vec![11, 2],
-        'L' => vec![11, 3],
-        'M' => vec![11, 4],
-        'N' => vec![11, 5],
-        'O' => vec![11, 6],
-        'P' => vec![11, 7],
-        'Q' => vec![11, 8],
-        'R' => vec![11, 9],
-
-        // Letters S-Z (0 zone + numeric)
-        'S' => vec![0, 2],
-        'T' => vec![0, 3],
-        'U' => vec![0, 4],
-        'V' => vec![0, 5],
-        'W' => vec![0, 6],
-        'X' => vec![0, 7],
-        'Y' => vec![0, 8],
-        'Z' => vec![0, 9],
-
-        // Special characters
-        ' ' => vec![],     // blank (no punches)
-        '&' => vec![12],   // ampersand
-        '-' => vec![11],   // minus/hyphen
-        '/' => vec![0, 1], // slash
-
-        // Special characters with 8 punch
-        '.' => vec![12, 3, 8], // period
-        '<' => vec![12, 4, 8], // less than
-        '(' => vec![12, 5, 8], // left paren
-        '+' => vec![12, 6, 8], // plus
-        '|' => vec![12, 7, 8], // vertical bar
-
-        '!' => vec![11, 2, 8], // exclamation
-        '$' => vec![11, 3, 8], // dollar
-        '*' => vec![11, 4, 8], // asterisk
-        ')' => vec![11, 5, 8], // right paren
-        ';' => vec![11, 6, 8], // semicolon
-        '¬' => vec![11, 7, 8], // logical not
-
-        ',' => vec![0, 3, 8], // comma
-        '%' => vec![0, 4, 8], // percent
-        '_' => vec![0, 5, 8], // underscore
-        '>' => vec![0, 6, 8], // greater than
-        '?' => vec![0, 7, 8], // question mark
-
-        ':' => vec![2, 8],  // colon
-        '#' => vec![3, 8],  // hash/pound
-        '@' => vec![4, 8],  // at sign
-        '\'' => vec![5, 8], // apostrophe
-        '=' => vec![6, 8],  // equals
-        '"' => vec![7, 8],  // quote
-
-        _ => return None,
+    let rows = match ASCII_TO_ROWS.get(c as usize) {
+        Some(rows) => (*rows)?,
+        None => IBM029_TABLE.iter().find(|&&(other, _)| other == c)?.1,
     };
-
-    Some(HollerithCode::new(code))
+    Some(HollerithCode {
+        rows: rows.to_vec(),
+    })
 }
 
 /// Convert a Hollerith encoding to its character representation
 ///
 /// Returns None for invalid or unsupported punch patterns
 pub fn hollerith_to_char(code: &HollerithCode) -> Option<char> {
-    // Handle empty (space)
-    if code.rows.is_empty() {
-        return Some(' ');
-    }
-
-    // Match against known patterns
-    let rows = &code.rows;
-
-    // Single punches (digits and zone punches)
-    if rows.len() == 1 {
-        return match rows[0] {
-            0 => Some('0'),
-            1 => Some('1'),
-            2 => Some('2'),
-            3 => Some('3'),
-            4 => Some('4'),
-            5 => Some('5'),
-            6 => Some('6'),
-            7 => Some('7'),
-            8 => Some('8'),
-            9 => Some('9'),
-            11 => Some('-'),
-            12 => Some('&'),
-            _ => None,
+    MASK_TO_CHAR[code.mask()?]
+}
+
+/// Why a punch pattern did not decode to a character
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeFailure {
+    /// A well-formed pattern that the character set does not define
+    NotInCharacterSet,
+    /// More than one of the digit rows 1-7 is punched; card codes add at
+    /// most an 8 and a 9 to a single digit
+    TooManyPunches,
+    /// All three zone rows (12, 11, and 0) are punched
+    IllegalZoneCombination,
+}
+
+impl fmt::Display for DecodeFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeFailure::NotInCharacterSet => write!(f, "not in the character set"),
+            DecodeFailure::TooManyPunches => write!(f, "too many digit punches"),
+            DecodeFailure::IllegalZoneCombination => write!(f, "12, 11, and 0 zones all punched"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeFailure {}
+
+/// Convert a Hollerith encoding to its character, saying why it failed
+///
+/// Like `hollerith_to_char`, but tells a malformed pattern apart from one
+/// that is well formed but has no 029 character.
+pub fn hollerith_to_char_detailed(code: &HollerithCode) -> Result<char, DecodeFailure> {
+    hollerith_to_char(code).ok_or_else(|| classify_failure(code))
+}
+
+/// Classify a pattern that has no character
+fn classify_failure(code: &HollerithCode) -> DecodeFailure {
+    if [12, 11, 0].iter().all(|row| code.is_punched(*row)) {
+        DecodeFailure::IllegalZoneCombination
+    } else if code
+        .rows
+        .iter()
+        .filter(|row| (1..=7).contains(*row))
+        .count()
+        > 1
+    {
+        DecodeFailure::TooManyPunches
+    } else {
+        DecodeFailure::NotInCharacterSet
+    }
+}
+
+/// A keypunch character set: which punch patterns the special characters use
+///
+/// Letters, digits, and blank are punched the same way in every set.
+///
+/// The 029 sets follow the EBCDIC card code: `¢` 12-8-2, `!` 11-8-2, `|`
+/// 12-8-7, and `¬` 11-8-7. ASCII code charts (`AnsiX326`) move `!` to
+/// 12-8-7 and `^` to 11-8-7 and use 12-8-2 and 11-8-2 for `[` and `]`, so
+/// cards punched from ASCII text decode differently in the two.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Charset {
+    /// IBM 029 (EBCDIC era), the crate default
+    #[default]
+    Ibm029,
+    /// IBM 029 plus the EBCDIC card codes for lowercase letters (`a` is
+    /// 12-0-1, `j` 12-11-1, `s` 11-0-2) and the record mark (`‡`, 0-8-2)
+    /// and group mark (`⧧`, 12-11-0-8-3) data delimiters
+    Ibm029Extended,
+    /// IBM 026 commercial (BCD "A" set): 11 special characters, with `&` as
+    /// 12 and a lozenge (⌑) where the 029 has `<`
+    Ibm026Commercial,
+    /// IBM 026 FORTRAN (BCD "H" set): the commercial patterns with `+`, `)`,
+    /// `(`, `=`, and `'` in place of `&`, `⌑`, `%`, `#`, and `@`
+    Ibm026Fortran,
+    /// ANSI X3.26 Hollerith card code for ASCII: every printable character,
+    /// lowercase as letters with a second zone (`a` is 12-0-1), and the
+    /// common control characters
+    AnsiX326,
+}
+
+/// Special characters of the 026 commercial set
+const IBM026_COMMERCIAL_SPECIALS: &[(char, &[u8])] = &[
+    ('&', &[12]),
+    ('-', &[11]),
+    ('/', &[0, 1]),
+    ('.', &[3, 8, 12]),
+    ('⌑', &[4, 8, 12]),
+    ('$', &[3, 8, 11]),
+    ('*', &[4, 8, 11]),
+    (',', &[0, 3, 8]),
+    ('%', &[0, 4, 8]),
+    ('#', &[3, 8]),
+    ('@', &[4, 8]),
+];
+
+/// Special characters of the 026 FORTRAN set
+const IBM026_FORTRAN_SPECIALS: &[(char, &[u8])] = &[
+    ('+', &[12]),
+    ('-', &[11]),
+    ('/', &[0, 1]),
+    ('.', &[3, 8, 12]),
+    (')', &[4, 8, 12]),
+    ('$', &[3, 8, 11]),
+    ('*', &[4, 8, 11]),
+    (',', &[0, 3, 8]),
+    ('(', &[0, 4, 8]),
+    ('=', &[3, 8]),
+    ('\'', &[4, 8]),
+];
+
+/// Delimiter marks added by the extended 029 set
+const IBM029_EXTENDED_MARKS: &[(char, &[u8])] = &[('‡', &[0, 2, 8]), ('⧧', &[0, 3, 8, 11, 12])];
+
+/// Special and control characters of the ANSI X3.26 set
+const ANSI_X3_26_SPECIALS: &[(char, &[u8])] = &[
+    ('!', &[7, 8, 12]),
+    ('"', &[7, 8]),
+    ('#', &[3, 8]),
+    ('$', &[3, 8, 11]),
+    ('%', &[0, 4, 8]),
+    ('&', &[12]),
+    ('\'', &[5, 8]),
+    ('(', &[5, 8, 12]),
+    (')', &[5, 8, 11]),
+    ('*', &[4, 8, 11]),
+    ('+', &[6, 8, 12]),
+    (',', &[0, 3, 8]),
+    ('-', &[11]),
+    ('.', &[3, 8, 12]),
+    ('/', &[0, 1]),
+    (':', &[2, 8]),
+    (';', &[6, 8, 11]),
+    ('<', &[4, 8, 12]),
+    ('=', &[6, 8]),
+    ('>', &[0, 6, 8]),
+    ('?', &[0, 7, 8]),
+    ('@', &[4, 8]),
+    ('[', &[2, 8, 12]),
+    ('\\', &[0, 2, 8]),
+    (']', &[2, 8, 11]),
+    ('^', &[7, 8, 11]),
+    ('_', &[0, 5, 8]),
+    ('`', &[1, 8]),
+    ('{', &[0, 12]),
+    ('|', &[11, 12]),
+    ('}', &[0, 11]),
+    ('~', &[0, 1, 11]),
+    ('\0', &[0, 1, 8, 9, 12]),
+    ('\u{8}', &[6, 9, 11]),
+    ('\t', &[5, 9, 12]),
+    ('\n', &[0, 5, 9]),
+    ('\u{b}', &[3, 8, 9, 12]),
+    ('\u{c}', &[4, 8, 9, 12]),
+    ('\r', &[5, 8, 9, 12]),
+    ('\u{1b}', &[0, 7, 9]),
+    ('\u{7f}', &[7, 9, 12]),
+];
+
+/// Encode a lowercase letter as EBCDIC and ANSI X3.26 do: the uppercase
+/// punches plus a second zone (a-i 12-0, j-r 12-11, s-z 11-0)
+fn lowercase_to_hollerith(c: char) -> Option<HollerithCode> {
+    let (zones, digit): ([u8; 2], u8) = match c {
+        'a'..='i' => ([12, 0], c as u8 - b'a' + 1),
+        'j'..='r' => ([12, 11], c as u8 - b'j' + 1),
+        's'..='z' => ([11, 0], c as u8 - b's' + 2),
+        _ => return None,
+    };
+    Some(HollerithCode::new(vec![zones[0], zones[1], digit]))
+}
+
+/// Decode a lowercase letter (see `lowercase_to_hollerith`)
+fn hollerith_to_lowercase(code: &HollerithCode) -> Option<char> {
+    let (first, digit) = match code.rows[..] {
+        [digit @ 1..=9, 11, 12] => (b'j', digit),
+        [0, digit @ 1..=9, 12] => (b'a', digit),
+        [0, digit @ 2..=9, 11] => (b's' - 1, digit),
+        _ => return None,
+    };
+    Some((first + digit - 1) as char)
+}
+
+impl Charset {
+    /// Every built-in character set
+    pub const ALL: [Charset; 5] = [
+        Charset::Ibm029,
+        Charset::Ibm029Extended,
+        Charset::Ibm026Commercial,
+        Charset::Ibm026Fortran,
+        Charset::AnsiX326,
+    ];
+
+    /// Special-character table for sets defined by one (rows sorted)
+    fn specials(self) -> Option<&'static [(char, &'static [u8])]> {
+        match self {
+            Charset::Ibm029 | Charset::Ibm029Extended => None,
+            Charset::Ibm026Commercial => Some(IBM026_COMMERCIAL_SPECIALS),
+            Charset::Ibm026Fortran => Some(IBM026_FORTRAN_SPECIALS),
+            Charset::AnsiX326 => Some(ANSI_X3_26_SPECIALS),
+        }
+    }
+
+    /// Characters the set adds on patterns its base table leaves undefined
+    fn extras(self) -> &'static [(char, &'static [u8])] {
+        match self {
+            Charset::Ibm029Extended => IBM029_EXTENDED_MARKS,
+            _ => &[],
+        }
+    }
+
+    /// Check if the set punches lowercase letters
+    pub fn has_lowercase(self) -> bool {
+        matches!(self, Charset::Ibm029Extended | Charset::AnsiX326)
+    }
+}
+
+/// Check if a character is punched the same way in every character set
+fn is_common_char(c: char) -> bool {
+    c == ' ' || c.is_ascii_uppercase() || c.is_ascii_digit()
+}
+
+/// Convert a character to its Hollerith encoding in the given character set
+///
+/// Returns None for characters the set does not have.
+pub fn char_to_hollerith_with(charset: Charset, c: char) -> Option<HollerithCode> {
+    if charset.has_lowercase() && c.is_ascii_lowercase() {
+        return lowercase_to_hollerith(c);
+    }
+    if let Some(&(_, rows)) = charset.extras().iter().find(|&&(extra, _)| extra == c) {
+        return Some(HollerithCode::new(rows.to_vec()));
+    }
+    let Some(specials) = charset.specials() else {
+        return char_to_hollerith(c);
+    };
+    if is_common_char(c) {
+        return char_to_hollerith(c);
+    }
+    specials
+        .iter()
+        .find(|&&(special, _)| special == c)
+        .map(|&(_, rows)| HollerithCode::new(rows.to_vec()))
+}
+
+/// Convert a Hollerith encoding to its character in the given character set
+///
+/// Returns None for patterns the set does not define.
+pub fn hollerith_to_char_with(charset: Charset, code: &HollerithCode) -> Option<char> {
+    if charset.has_lowercase()
+        && let Some(c) = hollerith_to_lowercase(code)
+    {
+        return Some(c);
+    }
+    if let Some(&(c, _)) = charset
+        .extras()
+        .iter()
+        .find(|&&(_, rows)| rows == code.rows.as_slice())
+    {
+        return Some(c);
+    }
+    let Some(specials) = charset.specials() else {
+        return hollerith_to_char(code);
+    };
+    if let Some(c) = hollerith_to_char(code).filter(|&c| is_common_char(c)) {
+        return Some(c);
+    }
+    specials
+        .iter()
+        .find(|&&(_, rows)| rows == code.rows.as_slice())
+        .map(|&(special, _)| special)
+}
+
+/// A table mapping characters to punch patterns
+///
+/// Implemented by the built-in `Charset`s and by `TableCharacterSet` for
+/// user-defined tables.
+pub trait CharacterSet {
+    /// Get the punch pattern for a character, if the set has it
+    fn encode(&self, c: char) -> Option<HollerithCode>;
+
+    /// Get the character for a punch pattern, if the set defines it
+    fn decode(&self, code: &HollerithCode) -> Option<char>;
+
+    /// Pick the character to punch for `c`: itself if the set has it,
+    /// otherwise its uppercase form, as the keypunch had no lowercase
+    fn keyed_char(&self, c: char) -> char {
+        if self.encode(c).is_some() {
+            c
+        } else {
+            c.to_ascii_uppercase()
+        }
+    }
+}
+
+impl CharacterSet for Charset {
+    fn encode(&self, c: char) -> Option<HollerithCode> {
+        char_to_hollerith_with(*self, c)
+    }
+
+    fn decode(&self, code: &HollerithCode) -> Option<char> {
+        hollerith_to_char_with(*self, code)
+    }
+}
+
+/// A character set defined by a table, e.g. a site-specific set of
+/// overprinted specials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCharacterSet {
+    entries: Vec<(char, HollerithCode)>,
+}
+
+impl TableCharacterSet {
+    /// Build a set from `(character, punched rows)` pairs
+    ///
+    /// Fails if a row is not 12, 11, or 0-9, or if a character or a punch
+    /// pattern appears twice.
+    pub fn new(table: &[(char, &[u8])]) -> Result<Self, String> {
+        let mut entries: Vec<(char, HollerithCode)> = Vec::with_capacity(table.len());
+        for &(c, rows) in table {
+            if let Some(&row) = rows.iter().find(|&&row| !is_valid_row(row)) {
+                return Err(format!("Character {:?}: invalid row {}", c, row));
+            }
+            let code = HollerithCode::new(rows.to_vec());
+            if entries.iter().any(|(existing, _)| *existing == c) {
+                return Err(format!("Character {:?} appears twice", c));
+            }
+            if let Some((other, _)) = entries.iter().find(|(_, existing)| *existing == code) {
+                return Err(format!(
+                    "Characters {:?} and {:?} have the same punch pattern",
+                    other, c
+                ));
+            }
+            entries.push((c, code));
+        }
+        Ok(TableCharacterSet { entries })
+    }
+}
+
+impl CharacterSet for TableCharacterSet {
+    fn encode(&self, c: char) -> Option<HollerithCode> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| *entry == c)
+            .map(|(_, code)| code.clone())
+    }
+
+    fn decode(&self, code: &HollerithCode) -> Option<char> {
+        self.entries
+            .iter()
+            .find(|(_, entry)| entry == code)
+            .map(|&(c, _)| c)
+    }
+}
+
+/// Encode a string into Hollerith punch patterns
+///
+/// Returns a vector of HollerithCode for each character
+/// Unsupported characters are replaced with a space (blank)
+pub fn encode_string(s: &str) -> Vec<HollerithCode> {
+    s.chars()
+        .map(|c| char_to_hollerith(c.to_ascii_uppercase()).unwrap_or_else(HollerithCode::empty))
+        .collect()
+}
+
+/// A character that has no punch pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeIssue {
+    /// The character
+    pub ch: char,
+    /// Zero-based index among the string's characters (the card column,
+    /// for text that starts in column 1)
+    pub char_index: usize,
+    /// Byte offset of the character in the string
+    pub byte_offset: usize,
+}
+
+impl fmt::Display for EncodeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Character {:?} at position {} cannot be punched",
+            self.ch,
+            self.char_index + 1
+        )
+    }
+}
+
+impl std::error::Error for EncodeIssue {}
+
+/// Encode a string, failing with every character that has no punch pattern
+///
+/// Letters are uppercased as in `encode_string`.
+pub fn encode_string_strict(s: &str) -> Result<Vec<HollerithCode>, Vec<EncodeIssue>> {
+    let (codes, issues) = encode_string_lossy_with_report(s);
+    if issues.is_empty() {
+        Ok(codes)
+    } else {
+        Err(issues)
+    }
+}
+
+/// Encode a string like `encode_string`, also listing the characters that
+/// were replaced with a blank
+pub fn encode_string_lossy_with_report(s: &str) -> (Vec<HollerithCode>, Vec<EncodeIssue>) {
+    let mut codes = Vec::with_capacity(s.len());
+    let mut issues = Vec::new();
+    for (char_index, (byte_offset, ch)) in s.char_indices().enumerate() {
+        match char_to_hollerith(ch.to_ascii_uppercase()) {
+            Some(code) => codes.push(code),
+            None => {
+                codes.push(HollerithCode::empty());
+                issues.push(EncodeIssue {
+                    ch,
+                    char_index,
+                    byte_offset,
+                });
+            }
+        }
+    }
+    (codes, issues)
+}
+
+/// Encode a string in the given character set
+///
+/// Characters are uppercased unless the set has them as given; characters
+/// the set lacks are replaced with a blank.
+pub fn encode_string_with(s: &str, charset: &dyn CharacterSet) -> Vec<HollerithCode> {
+    s.chars()
+        .map(|c| {
+            charset
+                .encode(charset.keyed_char(c))
+                .unwrap_or_else(HollerithCode::empty)
+        })
+        .collect()
+}
+
+/// Fallback characters checked before the 029 table when encoding
+///
+/// Build one with `SubstitutionMap::builder`, or start from the
+/// `ascii_approximation` preset.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubstitutionMap {
+    map: BTreeMap<char, char>,
+}
+
+impl SubstitutionMap {
+    /// Start an empty map
+    pub fn builder() -> SubstitutionMapBuilder {
+        SubstitutionMapBuilder::default()
+    }
+
+    /// Approximations for the printable ASCII characters the 029 lacks
+    pub fn ascii_approximation() -> Self {
+        Self::builder()
+            .ascii_approximation()
+            .build()
+            .expect("preset substitutes have punch patterns")
+    }
+
+    /// Get the substitute for a character, if it has one
+    pub fn get(&self, c: char) -> Option<char> {
+        self.map.get(&c).copied()
+    }
+}
+
+/// Builds a `SubstitutionMap`
+///
+/// A later entry for the same character replaces an earlier one.
+#[derive(Debug, Clone, Default)]
+pub struct SubstitutionMapBuilder {
+    map: BTreeMap<char, char>,
+}
+
+impl SubstitutionMapBuilder {
+    /// Punch `to` wherever `from` appears
+    pub fn substitute(mut self, from: char, to: char) -> Self {
+        self.map.insert(from, to);
+        self
+    }
+
+    /// Add the `SubstitutionMap::ascii_approximation` entries
+    pub fn ascii_approximation(self) -> Self {
+        [
+            ('[', '('),
+            (']', ')'),
+            ('{', '('),
+            ('}', ')'),
+            ('~', '-'),
+            ('^', '¬'),
+            ('\\', '/'),
+            ('`', '\''),
+        ]
+        .into_iter()
+        .fold(self, |builder, (from, to)| builder.substitute(from, to))
+    }
+
+    /// Build the map
+    ///
+    /// Fails if a substitute has no 029 punch pattern.
+    pub fn build(self) -> Result<SubstitutionMap, String> {
+        if let Some((from, to)) = self
+            .map
+            .iter()
+            .find(|&(_, to)| char_to_hollerith(to.to_ascii_uppercase()).is_none())
+        {
+            return Err(format!(
+                "Substitute {:?} for {:?} has no punch pattern",
+                to, from
+            ));
+        }
+        Ok(SubstitutionMap { map: self.map })
+    }
+}
+
+/// A character replaced by its substitute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Substitution {
+    pub from: char,
+    pub to: char,
+    /// Zero-based index among the string's characters
+    pub char_index: usize,
+}
+
+/// What `encode_string_with_substitutions` changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubstitutionReport {
+    /// Characters punched as their substitute
+    pub substitutions: Vec<Substitution>,
+    /// Characters with no pattern or substitute, left blank
+    pub issues: Vec<EncodeIssue>,
+}
+
+impl fmt::Display for SubstitutionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.substitutions.len() {
+            1 => write!(f, "1 character was approximated")?,
+            count => write!(f, "{} characters were approximated", count)?,
+        }
+        if !self.issues.is_empty() {
+            write!(f, ", {} could not be punched", self.issues.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Encode a string, punching substitutes from `map` before trying the 029
+/// table
+///
+/// Letters are uppercased as in `encode_string`, and characters with neither
+/// a pattern nor a substitute are left blank. The report lists both.
+pub fn encode_string_with_substitutions(
+    s: &str,
+    map: &SubstitutionMap,
+) -> (Vec<HollerithCode>, SubstitutionReport) {
+    let mut codes = Vec::with_capacity(s.len());
+    let mut report = SubstitutionReport::default();
+    for (char_index, (byte_offset, ch)) in s.char_indices().enumerate() {
+        let keyed = match map.get(ch) {
+            Some(to) if to != ch => {
+                report.substitutions.push(Substitution {
+                    from: ch,
+                    to,
+                    char_index,
+                });
+                to
+            }
+            _ => ch,
         };
+        match char_to_hollerith(keyed.to_ascii_uppercase()) {
+            Some(code) => codes.push(code),
+            None => {
+                codes.push(HollerithCode::empty());
+                report.issues.push(EncodeIssue {
+                    ch,
+                    char_index,
+                    byte_offset,
+                });
+            }
+        }
+    }
+    (codes, report)
+}
+
+/// Decode Hollerith punch patterns into a string
+///
+/// Invalid patterns are replaced with '?' character
+pub fn decode_string(codes: &[HollerithCode]) -> String {
+    codes
+        .iter()
+        .map(|code| hollerith_to_char(code).unwrap_or('?'))
+        .collect()
+}
+
+/// How decoding treats letter case
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CasePolicy {
+    /// Letters as the character set decodes them; the 029 only has capitals,
+    /// so this is the historical all-uppercase output
+    #[default]
+    Preserve,
+    /// Uppercase every letter
+    Upper,
+    /// Lowercase every letter
+    Lower,
+}
+
+impl CasePolicy {
+    /// Apply the policy to a decoded character
+    pub fn apply(self, c: char) -> char {
+        match self {
+            CasePolicy::Preserve => c,
+            CasePolicy::Upper => c.to_ascii_uppercase(),
+            CasePolicy::Lower => c.to_ascii_lowercase(),
+        }
+    }
+}
+
+/// Options for decoding punch patterns to text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Letter case of the decoded text
+    pub case: CasePolicy,
+    /// Character written for patterns that do not decode, or None to fail
+    /// instead
+    pub replacement: Option<char>,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            case: CasePolicy::default(),
+            replacement: Some('?'),
+        }
+    }
+}
+
+/// The patterns that did not decode
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodeReport {
+    /// Zero-based index and reason for each pattern that did not decode
+    pub failures: Vec<(usize, DecodeFailure)>,
+}
+
+impl DecodeReport {
+    /// Check if every pattern decoded
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Check if the character at an index is a replacement
+    pub fn is_replaced(&self, index: usize) -> bool {
+        self.failures.iter().any(|&(failed, _)| failed == index)
+    }
+}
+
+impl fmt::Display for DecodeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} patterns did not decode", self.failures.len())?;
+        for (index, failure) in &self.failures {
+            write!(f, "; position {}: {}", index + 1, failure)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DecodeReport {}
+
+/// Decode Hollerith punch patterns with a chosen replacement character
+///
+/// Returns the text and a report of the patterns that were replaced. With
+/// no replacement, fails with the report if any pattern does not decode.
+pub fn decode_string_with(
+    codes: &[HollerithCode],
+    options: &DecodeOptions,
+) -> Result<(String, DecodeReport), DecodeReport> {
+    decode_codes_with(codes.iter(), &Charset::Ibm029, options)
+}
+
+/// Decode patterns from any source in any character set (see
+/// `decode_string_with`)
+///
+/// Blank patterns read as a space even if the set does not define one.
+pub(crate) fn decode_codes_with<'a>(
+    codes: impl Iterator<Item = &'a HollerithCode>,
+    charset: &dyn CharacterSet,
+    options: &DecodeOptions,
+) -> Result<(String, DecodeReport), DecodeReport> {
+    let mut text = String::new();
+    let mut report = DecodeReport::default();
+    for (index, code) in codes.enumerate() {
+        match charset
+            .decode(code)
+            .or_else(|| code.rows.is_empty().then_some(' '))
+        {
+            Some(c) => text.push(options.case.apply(c)),
+            None => {
+                let failure = hollerith_to_char_detailed(code)
+                    .err()
+                    .unwrap_or(DecodeFailure::NotInCharacterSet);
+                report.failures.push((index, failure));
+                if let Some(replacement) = options.replacement {
+                    text.push(replacement);
+                }
+            }
+        }
+    }
+    if options.replacement.is_none() && !report.is_clean() {
+        return Err(report);
+    }
+    Ok((text, report))
+}
+
+/// What kind of character a chart entry is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChartCategory {
+    Digit,
+    Letter,
+    Special,
+    /// An ASCII control character (ANSI X3.26 only)
+    Control,
+}
+
+/// One character of a code chart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartEntry {
+    pub ch: char,
+    pub punches: HollerithCode,
+    /// Punches in card order, e.g. "12-3-8" (see `HollerithCode::to_notation`)
+    pub notation: String,
+    /// EBCDIC byte for the punches, if the EBCDIC conversion has one
+    pub ebcdic: Option<u8>,
+    pub category: ChartCategory,
+}
+
+/// List every character of a character set, in card sorter order (see
+/// `SorterKey`)
+pub fn chart(charset: Charset) -> Vec<ChartEntry> {
+    let mut codes: Vec<HollerithCode> = (0..=0x0FFF)
+        .map(|value| HollerithCode::from_u16(value, BitOrder::Row12High))
+        .collect();
+    codes.sort_by_key(HollerithCode::sorter_key);
+    codes
+        .into_iter()
+        .filter_map(|punches| {
+            let ch = hollerith_to_char_with(charset, &punches)?;
+            let ebcdic = hollerith_to_ebcdic(&punches).ok();
+            let category = if ch.is_ascii_digit() {
+                ChartCategory::Digit
+            } else if ch.is_alphabetic() {
+                ChartCategory::Letter
+            } else if ch.is_control() {
+                ChartCategory::Control
+            } else {
+                ChartCategory::Special
+            };
+            Some(ChartEntry {
+                ch,
+                notation: punches.to_notation(),
+                ebcdic,
+                punches,
+                category,
+            })
+        })
+        .collect()
+}
+
+/// Format `chart` as a plain-text table
+pub fn chart_as_table_string(charset: Charset) -> String {
+    let mut table = format!(
+        "{:<6} {:<14} {:<6} {}\n",
+        "Char", "Punches", "EBCDIC", "Category"
+    );
+    for entry in chart(charset) {
+        let ch = match entry.ch {
+            ' ' => "blank".to_string(),
+            c if c.is_control() => format!("U+{:04X}", c as u32),
+            c => c.to_string(),
+        };
+        let ebcdic = entry
+            .ebcdic
+            .map_or("-".to_string(), |byte| format!("{:02X}", byte));
+        table.push_str(&format!(
+            "{:<6} {:<14} {:<6} {:?}\n",
+            ch, entry.notation, ebcdic, entry.category
+        ));
+    }
+    table
+}
+
+/// Decode Hollerith punch patterns, keeping the reason each failed column
+/// did not decode
+pub fn decode_string_detailed(codes: &[HollerithCode]) -> Vec<Result<char, DecodeFailure>> {
+    codes.iter().map(hollerith_to_char_detailed).collect()
+}
+
+/// Why a punch notation string did not parse
+///
+/// Positions are byte offsets into the parsed string, so an editor can point
+/// at the offending token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    /// No rows and no "blank"
+    Empty,
+    /// A token that is not a row, a row alias, or "blank"
+    InvalidToken { position: usize, token: String },
+    /// A number that is not a punch row (0-9, 11, or 12)
+    InvalidRow { position: usize, row: String },
+    /// "blank" combined with punched rows
+    BlankWithRows { position: usize },
+    /// A line without a "col N:" prefix
+    MissingColumn { position: usize },
+    /// A column number outside 1-80
+    InvalidColumn { position: usize, column: String },
+}
+
+impl NotationError {
+    /// Byte offset of the error, if it has one
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            NotationError::Empty => None,
+            NotationError::InvalidToken { position, .. }
+            | NotationError::InvalidRow { position, .. }
+            | NotationError::BlankWithRows { position }
+            | NotationError::MissingColumn { position }
+            | NotationError::InvalidColumn { position, .. } => Some(*position),
+        }
     }
 
-    // Two punches (rows are sorted)
-    if rows.len() == 2 {
-        return match (rows[0], rows[1]) {
-            // 12 zone letters (A-I) - sorted order: (1..9, 12)
-            (1, 12) => Some('A'),
-            (2, 12) => Some('B'),
-            (3, 12) => Some('C'),
-            (4, 12) => Some('D'),
-            (5, 12) => Some('E'),
-            (6, 12) => Some('F'),
-            (7, 12) => Some('G'),
-            (8, 12) => Some('H'),
-            (9, 12) => Some('I'),
-
-            // 11 zone letters (J-R) - sorted order: (1..9, 11)
-            (1, 11) => Some('J'),
-            (2, 11) => Some('K'),
-            (3, 11) => Some('L'),
-            (4, 11) => Some('M'),
-            (5, 11) => Some('N'),
-            (6, 11) => Some('O'),
-            (7, 11) => Some('P'),
-            (8, 11) => Some('Q'),
-            (9, 11) => Some('R'),
-
-            // 0 zone letters (S-Z) - sorted order: (0, 2..9)
-            (0, 2) => Some('S'),
-            (0, 3) => Some('T'),
-            (0, 4) => Some('U'),
-            (0, 5) => Some('V'),
-            (0, 6) => Some('W'),
-            (0, 7) => Some('X'),
-            (0, 8) => Some('Y'),
-            (0, 9) => Some('Z'),
-
-            // Special two-punch characters
-            (0, 1) => Some('/'),
-            (2, 8) => Some(':'),
-            (3, 8) => Some('#'),
-            (4, 8) => Some('@'),
-            (5, 8) => Some('\''),
-            (6, 8) => Some('='),
-            (7, 8) => Some('"'),
+    /// Shift the position by `offset` bytes
+    fn offset(mut self, offset: usize) -> Self {
+        match &mut self {
+            NotationError::Empty => {}
+            NotationError::InvalidToken { position, .. }
+            | NotationError::InvalidRow { position, .. }
+            | NotationError::BlankWithRows { position }
+            | NotationError::MissingColumn { position }
+            | NotationError::InvalidColumn { position, .. } => *position += offset,
+        }
+        self
+    }
+}
 
-            _ => None,
-        };
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::Empty => write!(f, "no punches given"),
+            NotationError::InvalidToken { position, token } => {
+                write!(
+                    f,
+                    "position {}: {:?} is not a punch row",
+                    position + 1,
+                    token
+                )
+            }
+            NotationError::InvalidRow { position, row } => write!(
+                f,
+                "position {}: row {} is outside 0-9, 11, 12",
+                position + 1,
+                row
+            ),
+            NotationError::BlankWithRows { position } => {
+                write!(
+                    f,
+                    "position {}: \"blank\" cannot have punches",
+                    position + 1
+                )
+            }
+            NotationError::MissingColumn { position } => {
+                write!(f, "position {}: expected \"col N:\"", position + 1)
+            }
+            NotationError::InvalidColumn { position, column } => write!(
+                f,
+                "position {}: column {} is outside 1-80",
+                position + 1,
+                column
+            ),
+        }
     }
+}
 
-    // Three punches (special characters with 8) - rows are sorted
-    if rows.len() == 3 {
-        return match (rows[0], rows[1], rows[2]) {
-            // 12 zone with 8 - sorted order: (3..7, 8, 12)
-            (3, 8, 12) => Some('.'),
-            (4, 8, 12) => Some('<'),
-            (5, 8, 12) => Some('('),
-            (6, 8, 12) => Some('+'),
-            (7, 8, 12) => Some('|'),
-
-            // 11 zone with 8 - sorted order: (2..7, 8, 11)
-            (2, 8, 11) => Some('!'),
-            (3, 8, 11) => Some('$'),
-            (4, 8, 11) => Some('*'),
-            (5, 8, 11) => Some(')'),
-            (6, 8, 11) => Some(';'),
-            (7, 8, 11) => Some('¬'),
-
-            // 0 zone with 8 - sorted order: (0, 3..7, 8)
-            (0, 3, 8) => Some(','),
-            (0, 4, 8) => Some('%'),
-            (0, 5, 8) => Some('_'),
-            (0, 6, 8) => Some('>'),
-            (0, 7, 8) => Some('?'),
+impl std::error::Error for NotationError {}
 
-            _ => None,
-        };
+/// Split on `-`, `/`, and whitespace, keeping each token's byte offset
+fn notation_tokens(s: &str) -> Vec<(usize, &str)> {
+    let is_separator = |c: char| c == '-' || c == '/' || c.is_whitespace();
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (index, c) in s.char_indices() {
+        match (is_separator(c), start) {
+            (true, Some(begin)) => {
+                tokens.push((begin, &s[begin..index]));
+                start = None;
+            }
+            (false, None) => start = Some(index),
+            _ => {}
+        }
     }
-
-    // Unknown pattern
-    None
+    if let Some(begin) = start {
+        tokens.push((begin, &s[begin..]));
+    }
+    tokens
 }
 
-/// Encode a string into Hollerith punch patterns
+/// Parse punch notation such as "12-3-8" into a punch pattern
 ///
-/// Returns a vector of HollerithCode for each character
-/// Unsupported characters are replaced with a space (blank)
-pub fn encode_string(s: &str) -> Vec<HollerithCode> {
-    s.chars()
-        .map(|c| char_to_hollerith(c.to_ascii_uppercase()).unwrap_or_else(HollerithCode::empty))
-        .collect()
+/// Rows may be separated by `-`, `/`, or spaces, and `Y`, `X`, and `T` stand
+/// for rows 12, 11, and 0. "blank" on its own is an unpunched column. Rows
+/// may come in any order; repeats are punched once.
+pub fn parse_notation(s: &str) -> Result<HollerithCode, NotationError> {
+    let mut rows = Vec::new();
+    let mut blank = None;
+    for (position, token) in notation_tokens(s) {
+        let row = match token.to_ascii_uppercase().as_str() {
+            "BLANK" => {
+                blank = Some(position);
+                continue;
+            }
+            "Y" => 12,
+            "X" => 11,
+            "T" => 0,
+            _ if token.bytes().all(|b| b.is_ascii_digit()) => match token.parse::<u8>() {
+                Ok(row) if is_valid_row(row) => row,
+                _ => {
+                    return Err(NotationError::InvalidRow {
+                        position,
+                        row: token.to_string(),
+                    });
+                }
+            },
+            _ => {
+                return Err(NotationError::InvalidToken {
+                    position,
+                    token: token.to_string(),
+                });
+            }
+        };
+        rows.push(row);
+    }
+    match blank {
+        Some(position) if !rows.is_empty() => Err(NotationError::BlankWithRows { position }),
+        None if rows.is_empty() => Err(NotationError::Empty),
+        _ => Ok(HollerithCode::new(rows)),
+    }
 }
 
-/// Decode Hollerith punch patterns into a string
+/// Parse a card-authoring line such as "col 5: 12-3-8"
 ///
-/// Invalid patterns are replaced with '?' character
-pub fn decode_string(codes: &[HollerithCode]) -> String {
-    codes
-        .iter()
-        .map(|code| hollerith_to_char(code).unwrap_or('?'))
-        .collect()
+/// `col` may also be written `column`, in any case. Returns the zero-based
+/// column and its punches; error positions are offsets into `line`.
+pub fn parse_notation_line(line: &str) -> Result<(usize, HollerithCode), NotationError> {
+    let start = line.len() - line.trim_start().len();
+    let missing = NotationError::MissingColumn { position: start };
+    let (head, rest) = line.split_once(':').ok_or(missing.clone())?;
+    let mut words = head.split_whitespace();
+    let keyword = words.next().ok_or(missing.clone())?.to_ascii_lowercase();
+    let number = words.next().ok_or(missing.clone())?;
+    if (keyword != "col" && keyword != "column") || words.next().is_some() {
+        return Err(missing);
+    }
+    let number_position = head.rfind(number).unwrap_or(start);
+    let column = number
+        .parse::<usize>()
+        .ok()
+        .filter(|column| (1..=80).contains(column))
+        .ok_or_else(|| NotationError::InvalidColumn {
+            position: number_position,
+            column: number.to_string(),
+        })?;
+    let punches = parse_notation(rest).map_err(|e| e.offset(head.len() + 1))?;
+    Ok((column - 1, punches))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ebcdic::ebcdic_to_hollerith;
 
     #[test]
     fn test_hollerith_code_new() {
@@ -336,6 +1508,16 @@ mod tests {
         assert_eq!(code.rows, vec![1, 12]);
     }
 
+    #[test]
+    fn test_is_valid_row() {
+        for row in [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9] {
+            assert!(is_valid_row(row));
+        }
+        assert!(!is_valid_row(10));
+        assert!(!is_valid_row(13));
+        assert!(!is_valid_row(255));
+    }
+
     #[test]
     fn test_char_to_hollerith_digits() {
         assert_eq!(char_to_hollerith('0').unwrap().rows, vec![0]);
@@ -366,7 +1548,7 @@ mod tests {
 
     #[test]
     fn test_char_to_hollerith_special() {
-        assert_eq!(char_to_hollerith(' ').unwrap().rows, vec![]);
+        assert_eq!(char_to_hollerith(' ').unwrap().rows, Vec::<u8>::new());
         assert_eq!(char_to_hollerith('&').unwrap().rows, vec![12]);
         assert_eq!(char_to_hollerith('-').unwrap().rows, vec![11]);
         assert_eq!(char_to_hollerith('/').unwrap().rows, vec![0, 1]);
@@ -466,4 +1648,687 @@ mod tests {
         let invalid_code = HollerithCode::new(vec![12, 11, 0]); // Invalid combination
         assert_eq!(hollerith_to_char(&invalid_code), None);
     }
+
+    #[test]
+    fn test_charset_029_matches_default_functions() {
+        for c in (' '..='~').chain(['¬']) {
+            assert_eq!(
+                char_to_hollerith_with(Charset::Ibm029, c),
+                char_to_hollerith(c)
+            );
+        }
+        let code = HollerithCode::new(vec![12, 4, 8]);
+        assert_eq!(hollerith_to_char_with(Charset::Ibm029, &code), Some('<'));
+    }
+
+    #[test]
+    fn test_charset_026_commercial() {
+        let set = Charset::Ibm026Commercial;
+        // Shared characters punch the same
+        for c in "AZ09 &-/.$*,%".chars() {
+            assert_eq!(
+                char_to_hollerith_with(set, c),
+                char_to_hollerith(c),
+                "{}",
+                c
+            );
+        }
+        // The 026 has the lozenge but none of the 029 additions
+        assert_eq!(
+            char_to_hollerith_with(set, '⌑').unwrap().rows,
+            vec![4, 8, 12]
+        );
+        assert_eq!(char_to_hollerith_with(set, '#').unwrap().rows, vec![3, 8]);
+        assert_eq!(char_to_hollerith_with(set, '<'), None);
+        assert_eq!(char_to_hollerith_with(set, '('), None);
+        assert_eq!(char_to_hollerith_with(set, '+'), None);
+
+        // 12-4-8 is < on the 029 but a lozenge on the 026
+        let code = HollerithCode::new(vec![12, 4, 8]);
+        assert_eq!(hollerith_to_char_with(set, &code), Some('⌑'));
+        let paren = HollerithCode::new(vec![12, 5, 8]);
+        assert_eq!(hollerith_to_char_with(set, &paren), None);
+
+        // Every 026 character round trips
+        for &(c, _) in IBM026_COMMERCIAL_SPECIALS {
+            let code = char_to_hollerith_with(set, c).unwrap();
+            assert_eq!(hollerith_to_char_with(set, &code), Some(c));
+        }
+    }
+
+    #[test]
+    fn test_charset_026_fortran() {
+        let set = Charset::Ibm026Fortran;
+        assert_eq!(char_to_hollerith_with(set, '+').unwrap().rows, vec![12]);
+        assert_eq!(char_to_hollerith_with(set, '=').unwrap().rows, vec![3, 8]);
+        assert_eq!(
+            char_to_hollerith_with(set, '(').unwrap().rows,
+            vec![0, 4, 8]
+        );
+        assert_eq!(
+            char_to_hollerith_with(set, ')').unwrap().rows,
+            vec![4, 8, 12]
+        );
+        assert_eq!(char_to_hollerith_with(set, '\'').unwrap().rows, vec![4, 8]);
+        assert_eq!(char_to_hollerith_with(set, '&'), None);
+
+        // Same holes, different glyphs in each set
+        let pairs = [('+', '&'), ('=', '#'), ('(', '%'), (')', '⌑'), ('\'', '@')];
+        for (fortran, commercial) in pairs {
+            let code = char_to_hollerith_with(set, fortran).unwrap();
+            assert_eq!(hollerith_to_char_with(set, &code), Some(fortran));
+            assert_eq!(
+                hollerith_to_char_with(Charset::Ibm026Commercial, &code),
+                Some(commercial)
+            );
+        }
+    }
+
+    #[test]
+    fn test_builtin_sets_implement_character_set() {
+        let sets: [&dyn CharacterSet; 2] = [&Charset::Ibm029, &Charset::Ibm026Fortran];
+        let codes: Vec<Vec<HollerithCode>> = sets
+            .iter()
+            .map(|set| encode_string_with("a=b", *set))
+            .collect();
+        assert_eq!(codes[0], encode_string("A=B"));
+        assert_eq!(codes[1][1].rows, vec![3, 8]);
+        assert_eq!(sets[1].decode(&codes[1][1]), Some('='));
+    }
+
+    #[test]
+    fn test_table_character_set() {
+        let table: &[(char, &[u8])] = &[('a', &[12, 0, 1]), ('X', &[0, 7]), ('~', &[12, 11])];
+        let set = TableCharacterSet::new(table).unwrap();
+        assert_eq!(set.encode('a').unwrap().rows, vec![0, 1, 12]);
+        assert_eq!(set.decode(&HollerithCode::new(vec![11, 12])), Some('~'));
+        assert_eq!(set.decode(&HollerithCode::new(vec![1])), None);
+        // Lowercase is kept when the set has it, otherwise uppercased
+        assert_eq!(set.keyed_char('a'), 'a');
+        assert_eq!(set.keyed_char('x'), 'X');
+        let codes = encode_string_with("ax?", &set);
+        assert_eq!(codes[1].rows, vec![0, 7]);
+        assert!(codes[2].rows.is_empty());
+    }
+
+    #[test]
+    fn test_table_character_set_rejects_duplicates() {
+        let same_pattern: &[(char, &[u8])] = &[('A', &[12, 1]), ('B', &[1, 12])];
+        assert!(
+            TableCharacterSet::new(same_pattern)
+                .unwrap_err()
+                .contains("same punch pattern")
+        );
+        let same_char: &[(char, &[u8])] = &[('A', &[12, 1]), ('A', &[12, 2])];
+        assert!(TableCharacterSet::new(same_char).is_err());
+        let bad_row: &[(char, &[u8])] = &[('A', &[10])];
+        assert!(TableCharacterSet::new(bad_row).is_err());
+    }
+
+    #[test]
+    fn test_ansi_x3_26_lowercase() {
+        let set = Charset::AnsiX326;
+        let a = char_to_hollerith_with(set, 'a').unwrap();
+        assert_eq!(a.rows, vec![0, 1, 12]);
+        assert_eq!(
+            char_to_hollerith_with(set, 'j').unwrap().rows,
+            vec![1, 11, 12]
+        );
+        assert_eq!(
+            char_to_hollerith_with(set, 's').unwrap().rows,
+            vec![0, 2, 11]
+        );
+        assert_eq!(
+            char_to_hollerith_with(set, 'z').unwrap().rows,
+            vec![0, 9, 11]
+        );
+        assert_eq!(char_to_hollerith_with(Charset::Ibm029, 'a'), None);
+        for c in ('a'..='z').chain('A'..='Z') {
+            let code = char_to_hollerith_with(set, c).unwrap();
+            assert_eq!(hollerith_to_char_with(set, &code), Some(c));
+        }
+    }
+
+    #[test]
+    fn test_ansi_x3_26_covers_printable_ascii() {
+        let set = Charset::AnsiX326;
+        let mut seen = std::collections::HashSet::new();
+        for c in (' '..='~').chain(['\t', '\n', '\r', '\u{7f}']) {
+            let code = char_to_hollerith_with(set, c).unwrap_or_else(|| panic!("{:?}", c));
+            assert!(seen.insert(code.clone()), "{:?} reuses a pattern", c);
+            assert_eq!(hollerith_to_char_with(set, &code), Some(c));
+        }
+        assert_eq!(
+            char_to_hollerith_with(set, '[').unwrap().rows,
+            vec![2, 8, 12]
+        );
+        assert_eq!(char_to_hollerith_with(set, '\u{7}'), None);
+        assert_eq!(char_to_hollerith_with(set, '¬'), None);
+    }
+
+    #[test]
+    fn test_decode_failure_kinds() {
+        let decode = |rows: Vec<u8>| hollerith_to_char_detailed(&HollerithCode::new(rows));
+        assert_eq!(decode(vec![12, 1]), Ok('A'));
+        assert_eq!(
+            decode(vec![12, 11, 0]),
+            Err(DecodeFailure::IllegalZoneCombination)
+        );
+        assert_eq!(
+            decode(vec![12, 11, 0, 1, 2]),
+            Err(DecodeFailure::IllegalZoneCombination)
+        );
+        assert_eq!(decode(vec![1, 2]), Err(DecodeFailure::TooManyPunches));
+        assert_eq!(
+            decode(vec![12, 3, 5, 8]),
+            Err(DecodeFailure::TooManyPunches)
+        );
+        // Valid in other card codes, e.g. ANSI X3.26 `a` and NUL
+        assert_eq!(
+            decode(vec![12, 0, 1]),
+            Err(DecodeFailure::NotInCharacterSet)
+        );
+        assert_eq!(
+            decode(vec![12, 0, 9, 8, 1]),
+            Err(DecodeFailure::NotInCharacterSet)
+        );
+        assert_eq!(
+            DecodeFailure::TooManyPunches.to_string(),
+            "too many digit punches"
+        );
+
+        let codes = encode_string("A");
+        let detailed = decode_string_detailed(&[codes[0].clone(), HollerithCode::new(vec![2, 3])]);
+        assert_eq!(detailed, vec![Ok('A'), Err(DecodeFailure::TooManyPunches)]);
+    }
+
+    #[test]
+    fn test_ibm029_extended_lowercase() {
+        let set = Charset::Ibm029Extended;
+        assert!(set.has_lowercase() && !Charset::Ibm029.has_lowercase());
+        assert_eq!(
+            char_to_hollerith_with(set, 'a').unwrap().rows,
+            vec![0, 1, 12]
+        );
+        assert_eq!(
+            char_to_hollerith_with(set, 'r').unwrap().rows,
+            vec![9, 11, 12]
+        );
+        // Specials stay as on the 029
+        assert_eq!(char_to_hollerith_with(set, '¬'), char_to_hollerith('¬'));
+        for c in ('a'..='z').chain(['A', '!', '¢']) {
+            if let Some(code) = char_to_hollerith_with(set, c) {
+                assert_eq!(hollerith_to_char_with(set, &code), Some(c));
+            }
+        }
+        assert_eq!(
+            hollerith_to_char(&char_to_hollerith_with(set, 'q').unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_every_029_graphic_roundtrips() {
+        let graphics = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ &-/¢.<(+|!$*);¬,%_>?:#@'=\"";
+        let mut seen = std::collections::HashSet::new();
+        for c in graphics.chars() {
+            let code = char_to_hollerith(c).unwrap_or_else(|| panic!("{:?}", c));
+            assert!(seen.insert(code.clone()), "{:?} reuses a pattern", c);
+            assert_eq!(hollerith_to_char(&code), Some(c));
+            assert_eq!(
+                hollerith_to_char_with(Charset::Ibm029Extended, &code),
+                Some(c)
+            );
+        }
+        assert_eq!(seen.len(), 63);
+        assert_eq!(char_to_hollerith('¢').unwrap().rows, vec![2, 8, 12]);
+        // The ASCII chart puts `[` where the 029 has `¢`
+        let bracket = char_to_hollerith_with(Charset::AnsiX326, '[').unwrap();
+        assert_eq!(hollerith_to_char(&bracket), Some('¢'));
+    }
+
+    #[test]
+    fn test_encode_string_with_substitutions() {
+        let map = SubstitutionMap::ascii_approximation();
+        for c in ' '..='~' {
+            let (_, report) = encode_string_with_substitutions(&c.to_string(), &map);
+            assert!(report.issues.is_empty(), "{:?}", c);
+        }
+
+        let (codes, report) = encode_string_with_substitutions("a[1]~€", &map);
+        assert_eq!(codes[..5], encode_string("A(1)-"));
+        assert!(codes[5].rows.is_empty());
+        let substituted: Vec<(char, char, usize)> = report
+            .substitutions
+            .iter()
+            .map(|s| (s.from, s.to, s.char_index))
+            .collect();
+        assert_eq!(
+            substituted,
+            vec![('[', '(', 1), (']', ')', 3), ('~', '-', 4)]
+        );
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(
+            report.to_string(),
+            "3 characters were approximated, 1 could not be punched"
+        );
+
+        // Custom entries are checked before the table and override presets
+        let map = SubstitutionMap::builder()
+            .ascii_approximation()
+            .substitute('[', '<')
+            .substitute('€', 'E')
+            .substitute('A', 'B')
+            .build()
+            .unwrap();
+        let (codes, report) = encode_string_with_substitutions("[€A", &map);
+        assert_eq!(codes, encode_string("<EB"));
+        assert_eq!(report.to_string(), "3 characters were approximated");
+
+        assert!(
+            SubstitutionMap::builder()
+                .substitute('[', '~')
+                .build()
+                .is_err()
+        );
+    }
+    #[test]
+    fn test_encode_string_strict() {
+        assert_eq!(
+            encode_string_strict("Hello, 029"),
+            Ok(encode_string("HELLO, 029"))
+        );
+
+        let issues = encode_string_strict("a€b~c").unwrap_err();
+        assert_eq!(
+            issues,
+            vec![
+                EncodeIssue {
+                    ch: '€',
+                    char_index: 1,
+                    byte_offset: 1
+                },
+                EncodeIssue {
+                    ch: '~',
+                    char_index: 3,
+                    byte_offset: 5
+                },
+            ]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "Character '€' at position 2 cannot be punched"
+        );
+
+        let (codes, issues) = encode_string_lossy_with_report("a€b~c");
+        assert_eq!(codes, encode_string("a€b~c"));
+        assert_eq!(issues.len(), 2);
+    }
+
+    #[test]
+    fn test_decode_string_with_report() {
+        let mut codes = encode_string("WHY?");
+        codes.push(HollerithCode::new(vec![12, 11, 0]));
+
+        // A punched `?` and a replacement look the same in the text...
+        let (text, report) = decode_string_with(&codes, &DecodeOptions::default()).unwrap();
+        assert_eq!(text, "WHY??");
+        // ...but only the replacement is in the report
+        assert!(!report.is_replaced(3));
+        assert!(report.is_replaced(4));
+        assert_eq!(
+            report.failures,
+            vec![(4, DecodeFailure::IllegalZoneCombination)]
+        );
+
+        let star = DecodeOptions {
+            replacement: Some('*'),
+            ..DecodeOptions::default()
+        };
+        assert_eq!(decode_string_with(&codes, &star).unwrap().0, "WHY?*");
+        let lower = DecodeOptions {
+            case: CasePolicy::Lower,
+            ..star
+        };
+        assert_eq!(decode_string_with(&codes, &lower).unwrap().0, "why?*");
+
+        let strict = DecodeOptions {
+            replacement: None,
+            ..DecodeOptions::default()
+        };
+        let err = decode_string_with(&codes, &strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "1 patterns did not decode; position 5: 12, 11, and 0 zones all punched"
+        );
+        let (text, report) = decode_string_with(&codes[..4], &strict).unwrap();
+        assert_eq!(text, "WHY?");
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_u16_matches_as_array() {
+        for value in 0..=0x0FFFu16 {
+            for order in [BitOrder::Row12High, BitOrder::Row9High] {
+                let code = HollerithCode::from_u16(value, order);
+                assert_eq!(code.to_u16(order), value);
+                for (idx, punched) in code.as_array().into_iter().enumerate() {
+                    assert_eq!(punched, value & (1 << order.bit(idx)) != 0);
+                }
+            }
+            assert_eq!(
+                HollerithCode::from_u16(value | 0xF000, BitOrder::Row12High)
+                    .to_u16(BitOrder::Row12High),
+                value
+            );
+        }
+        let a = char_to_hollerith('A').unwrap();
+        assert_eq!(a.to_u16(BitOrder::Row12High), 0x0900);
+        assert_eq!(a.to_u16(BitOrder::Row9High), 0x0009);
+    }
+
+    #[test]
+    fn test_sorter_collating_sequence() {
+        let ascending = " &ABCDEFGHI-JKLMNOPQR/STUVWXYZ0123456789";
+        let keys: Vec<SorterKey> = ascending
+            .chars()
+            .map(|c| char_to_hollerith(c).unwrap().sorter_key())
+            .collect();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let key = |c| char_to_hollerith(c).unwrap().sorter_key();
+        // 12-8-3 sorts on its 8 punch, between H (12-8) and I (12-9)
+        assert!(key('H') < key('.') && key('.') < key('I'));
+        // Distinct patterns never tie
+        let keys: std::collections::HashSet<SorterKey> = (0..=0x0FFF)
+            .map(|v| HollerithCode::from_u16(v, BitOrder::Row12High).sorter_key())
+            .collect();
+        assert_eq!(keys.len(), 4096);
+    }
+
+    #[test]
+    fn test_classify_matches_029_table() {
+        for value in 0..=0x0FFFu16 {
+            let code = HollerithCode::from_u16(value, BitOrder::Row12High);
+            let class = code.classify();
+            assert_eq!(class.is_character(), hollerith_to_char(&code).is_some());
+            match class {
+                PunchClass::Blank => assert!(code.rows.is_empty()),
+                PunchClass::Digit(d) => assert_eq!(code.rows, vec![d]),
+                PunchClass::ZoneOnly(Zone::Twelve) => assert_eq!(code.rows, vec![12]),
+                PunchClass::ZoneOnly(Zone::Eleven) => assert_eq!(code.rows, vec![11]),
+                PunchClass::ZoneOnly(Zone::Zero) => unreachable!("0 alone is a digit"),
+                PunchClass::Alphabetic(c) | PunchClass::Special(c) => {
+                    assert_eq!(char_to_hollerith(c), Some(code.clone()));
+                    assert_eq!(
+                        c.is_ascii_uppercase(),
+                        matches!(class, PunchClass::Alphabetic(_))
+                    );
+                }
+                PunchClass::SignedZero(_) => assert_eq!(code.rows.len(), 2),
+                PunchClass::Invalid => assert_eq!(hollerith_to_char(&code), None),
+            }
+        }
+        let class = |c| char_to_hollerith(c).unwrap().classify();
+        assert_eq!(class('7'), PunchClass::Digit(7));
+        assert_eq!(class('&'), PunchClass::ZoneOnly(Zone::Twelve));
+        assert_eq!(class('Q'), PunchClass::Alphabetic('Q'));
+        assert_eq!(class('¢'), PunchClass::Special('¢'));
+        assert_eq!(
+            HollerithCode::new(vec![11, 0]).classify(),
+            PunchClass::SignedZero(Zone::Eleven)
+        );
+        assert_eq!(
+            HollerithCode::new(vec![12, 11, 0]).classify(),
+            PunchClass::Invalid
+        );
+    }
+
+    #[test]
+    fn test_signed_digit() {
+        let class = |c| char_to_hollerith(c).unwrap().classify();
+        assert_eq!(class('5').signed_digit(), Some((None, 5)));
+        assert_eq!(class('E').signed_digit(), Some((Some(Zone::Twelve), 5)));
+        assert_eq!(class('N').signed_digit(), Some((Some(Zone::Eleven), 5)));
+        assert_eq!(class('R').signed_digit(), Some((Some(Zone::Eleven), 9)));
+        assert_eq!(class('S').signed_digit(), None);
+        assert_eq!(class('-').signed_digit(), None);
+        assert_eq!(
+            HollerithCode::new(vec![12, 0]).classify().signed_digit(),
+            Some((Some(Zone::Twelve), 0))
+        );
+    }
+
+    #[test]
+    fn test_eight_punch_combo() {
+        assert_eq!(
+            char_to_hollerith('.').unwrap().eight_punch_combo(),
+            Some((Some(Zone::Twelve), 3))
+        );
+        assert_eq!(
+            char_to_hollerith('%').unwrap().eight_punch_combo(),
+            Some((Some(Zone::Zero), 4))
+        );
+        assert_eq!(
+            char_to_hollerith('@').unwrap().eight_punch_combo(),
+            Some((None, 4))
+        );
+        // Letters with an 8 and patterns outside 2-7 are not 8-punch specials
+        for c in ['H', 'Q', 'Y', '8'] {
+            assert_eq!(char_to_hollerith(c).unwrap().eight_punch_combo(), None);
+        }
+        assert_eq!(HollerithCode::new(vec![12, 1, 8]).eight_punch_combo(), None);
+        assert_eq!(
+            HollerithCode::new(vec![12, 11, 3, 8]).eight_punch_combo(),
+            None
+        );
+        assert!(!HollerithCode::new(vec![12, 11, 3, 8]).is_valid_029_pattern());
+        assert!(!HollerithCode { rows: vec![13] }.is_valid_029_pattern());
+    }
+
+    #[test]
+    fn test_029_patterns_consistent_with_ebcdic() {
+        let mut valid = 0;
+        let mut combos = 0;
+        for value in 0..=0x0FFF {
+            let code = HollerithCode::from_u16(value, BitOrder::Row12High);
+            let notation = code.to_notation();
+            let ch = hollerith_to_char(&code);
+            assert_eq!(code.is_valid_029_pattern(), ch.is_some(), "{}", notation);
+            if ch.is_some() {
+                valid += 1;
+                let roundtrip = hollerith_to_ebcdic(&code).ok().map(ebcdic_to_hollerith);
+                assert_eq!(roundtrip, Some(code.clone()), "{}", notation);
+                // Every 3-punch 029 character is an 8-punch special
+                if code.rows.len() == 3 {
+                    assert!(code.eight_punch_combo().is_some(), "{}", notation);
+                }
+            }
+            if let Some((zone, digit)) = code.eight_punch_combo() {
+                combos += 1;
+                let zone = zone.map(|zone| match zone {
+                    Zone::Twelve => 12,
+                    Zone::Eleven => 11,
+                    Zone::Zero => 0,
+                });
+                let rows: Vec<u8> = zone.into_iter().chain([digit, 8]).collect();
+                assert_eq!(HollerithCode::new(rows), code);
+                let roundtrip = hollerith_to_ebcdic(&code).ok().map(ebcdic_to_hollerith);
+                assert_eq!(roundtrip, Some(code.clone()), "{}", notation);
+            }
+        }
+        assert_eq!(valid, 63);
+        assert_eq!(combos, 24);
+    }
+
+    #[test]
+    fn test_try_new_rejects_invalid_rows() {
+        assert_eq!(
+            HollerithCode::try_new(vec![12, 3, 3, 8]),
+            Ok(char_to_hollerith('.').unwrap())
+        );
+        let err = HollerithCode::try_new(vec![12, 13, 255]).unwrap_err();
+        assert_eq!(err, InvalidRow(13));
+        assert_eq!(err.to_string(), "Row 13 is not a card row (12, 11, 0-9)");
+        assert_eq!(HollerithCode::try_new(vec![10]), Err(InvalidRow(10)));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialize_normalizes_rows() {
+        let code: HollerithCode = serde_json::from_str(r#"{"rows":[8,3,12,3]}"#).unwrap();
+        assert_eq!(code, char_to_hollerith('.').unwrap());
+        let err = serde_json::from_str::<HollerithCode>(r#"{"rows":[1,13]}"#).unwrap_err();
+        assert!(err.to_string().contains("Row 13"));
+        assert_eq!(
+            serde_json::to_string(&code).unwrap(),
+            r#"{"rows":[3,8,12]}"#
+        );
+    }
+
+    #[test]
+    fn test_to_notation() {
+        assert_eq!(char_to_hollerith('.').unwrap().to_notation(), "12-3-8");
+        assert_eq!(char_to_hollerith('/').unwrap().to_notation(), "0-1");
+        assert_eq!(HollerithCode::empty().to_notation(), "blank");
+    }
+
+    #[test]
+    fn test_parse_notation() {
+        let period = char_to_hollerith('.').unwrap();
+        for text in ["12-3-8", "12/3/8", "12 3 8", " y-8-3 ", "Y-3-8-3"] {
+            assert_eq!(parse_notation(text), Ok(period.clone()), "{}", text);
+        }
+        assert_eq!(parse_notation("X-T"), Ok(HollerithCode::new(vec![11, 0])));
+        assert_eq!(parse_notation("Blank"), Ok(HollerithCode::empty()));
+        for code in chart(Charset::Ibm029Extended) {
+            assert_eq!(parse_notation(&code.notation), Ok(code.punches));
+        }
+    }
+
+    #[test]
+    fn test_parse_notation_errors() {
+        assert_eq!(parse_notation(" - "), Err(NotationError::Empty));
+        let error = parse_notation("12-10-8").unwrap_err();
+        assert_eq!(
+            error,
+            NotationError::InvalidRow {
+                position: 3,
+                row: "10".to_string()
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "position 4: row 10 is outside 0-9, 11, 12"
+        );
+        assert_eq!(parse_notation("12-300").unwrap_err().position(), Some(3));
+        assert_eq!(
+            parse_notation("12\u{a0}Q"),
+            Err(NotationError::InvalidToken {
+                position: 4,
+                token: "Q".to_string()
+            })
+        );
+        assert_eq!(
+            parse_notation("blank-1"),
+            Err(NotationError::BlankWithRows { position: 0 })
+        );
+    }
+
+    #[test]
+    fn test_parse_notation_line() {
+        assert_eq!(
+            parse_notation_line("col 5: 12-3-8"),
+            Ok((4, char_to_hollerith('.').unwrap()))
+        );
+        assert_eq!(
+            parse_notation_line("  COLUMN 80 : blank"),
+            Ok((79, HollerithCode::empty()))
+        );
+        assert_eq!(
+            parse_notation_line("col 81: 1"),
+            Err(NotationError::InvalidColumn {
+                position: 4,
+                column: "81".to_string()
+            })
+        );
+        assert_eq!(
+            parse_notation_line("  row 5: 1"),
+            Err(NotationError::MissingColumn { position: 2 })
+        );
+        assert_eq!(
+            parse_notation_line("col 5"),
+            Err(NotationError::MissingColumn { position: 0 })
+        );
+        // Positions are relative to the whole line
+        assert_eq!(
+            parse_notation_line("col 5: 12-13").unwrap_err().position(),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn test_chart_consistent_with_tables() {
+        for charset in [
+            Charset::Ibm029,
+            Charset::Ibm029Extended,
+            Charset::Ibm026Commercial,
+            Charset::Ibm026Fortran,
+            Charset::AnsiX326,
+        ] {
+            let entries = chart(charset);
+            for entry in &entries {
+                assert_eq!(
+                    char_to_hollerith_with(charset, entry.ch),
+                    Some(entry.punches.clone())
+                );
+                assert_eq!(entry.notation, entry.punches.to_notation());
+                assert_eq!(entry.ebcdic, hollerith_to_ebcdic(&entry.punches).ok());
+            }
+            assert!(
+                entries
+                    .windows(2)
+                    .all(|pair| pair[0].punches.sorter_key() < pair[1].punches.sorter_key())
+            );
+        }
+
+        let entries = chart(Charset::Ibm029);
+        assert_eq!(entries.len(), 63);
+        assert_eq!(entries[0].ch, ' ');
+        assert_eq!(entries[1].ch, '&');
+        assert_eq!(entries.last().unwrap().ch, '9');
+        let a = entries.iter().find(|entry| entry.ch == 'A').unwrap();
+        assert_eq!((a.ebcdic, a.category), (Some(0xC1), ChartCategory::Letter));
+        assert_eq!(chart(Charset::Ibm029Extended).len(), 63 + 26 + 2);
+        assert!(
+            chart(Charset::AnsiX326)
+                .iter()
+                .any(|entry| entry.category == ChartCategory::Control)
+        );
+    }
+
+    #[test]
+    fn test_chart_as_table_string() {
+        let table = chart_as_table_string(Charset::Ibm026Commercial);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("Char"));
+        assert_eq!(lines[1], "blank  blank          40     Special");
+        assert!(lines.iter().any(|line| line.starts_with("⌑      12-4-8")));
+    }
+
+    #[test]
+    fn test_record_and_group_marks() {
+        let set = Charset::Ibm029Extended;
+        let record = char_to_hollerith_with(set, '‡').unwrap();
+        let group = char_to_hollerith_with(set, '⧧').unwrap();
+        assert_eq!(record.to_notation(), "0-2-8");
+        assert_eq!(group.to_notation(), "12-11-0-3-8");
+        assert_eq!(hollerith_to_char_with(set, &record), Some('‡'));
+        assert_eq!(hollerith_to_char_with(set, &group), Some('⧧'));
+        // The default set is unchanged
+        assert_eq!(char_to_hollerith_with(Charset::Ibm029, '‡'), None);
+        assert_eq!(hollerith_to_char(&record), None);
+        assert_eq!(hollerith_to_ebcdic(&record), Ok(0xE0));
+        assert_eq!(hollerith_to_ebcdic(&group), Ok(0xBB));
+    }
 }
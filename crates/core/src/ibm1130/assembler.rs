@@ -0,0 +1,683 @@
+// IBM 1130 Assembler
+//
+// A two-pass assembler for a subset of 1130 assembler language, reading a
+// deck of source cards (see `SourceCard`) and punching an object deck (see
+// `ObjectCard`). The program is relocatable unless the source has an ABS
+// statement, in which case it is absolute.
+//
+// Supported:
+// - Labels, and expressions of decimal and /hex numbers, symbols and `*`
+//   joined with + and -
+// - ORG, EQU, DC, DEC (integers), BSS (E for an even start), END, ABS; the
+//   listing directives are accepted and ignored
+// - Every machine instruction in `opcodes`, short and long (L, I) forms,
+//   with index tags and branch/skip conditions
+//
+// A short instruction with tag 0 addresses relative to the next instruction;
+// with a tag its displacement is the operand's value. LDX short loads the
+// value, and STX short always addresses relative. B short assembles as an
+// MDX branch.
+
+use super::opcodes::{self, LabelRule, OpcodeInfo, OpcodeKind, OperandSyntax};
+use super::{
+    FORMAT_COLUMN, LABEL_COLUMNS, OPCODE_COLUMNS, OPERAND_COLUMNS, ParseError, Relocation,
+    SourceCard, TAG_COLUMN, punch_object_deck,
+};
+use crate::deck::Deck;
+use std::collections::HashMap;
+use std::fmt;
+
+/// An assembly error at a source card (0-based) and column (0-based)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub card: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Card {}, column {}: {}",
+            self.card + 1,
+            self.column + 1,
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+/// A symbol's value, and whether it moves when the program is relocated
+#[derive(Debug, Clone, Copy)]
+struct Symbol {
+    value: u16,
+    relocatable: bool,
+}
+
+type Symbols = HashMap<String, Symbol>;
+
+/// A statement that passed the first pass
+struct Line {
+    card: usize,
+    source: SourceCard,
+    info: &'static OpcodeInfo,
+    /// Address of the statement's first word
+    location: u16,
+    /// Whether the program is relocatable (no ABS)
+    relocatable: bool,
+}
+
+impl Line {
+    fn error(&self, column: usize, message: String) -> AsmError {
+        AsmError {
+            card: self.card,
+            column,
+            message,
+        }
+    }
+
+    fn long(&self) -> bool {
+        matches!(self.source.format(), Some('L' | 'I'))
+    }
+
+    fn tag(&self) -> u16 {
+        u16::from(self.source.tag().unwrap_or(0))
+    }
+}
+
+/// Assemble a source deck into an object deck
+///
+/// Comment and blank cards are skipped and assembly stops at END, whose
+/// operand (if any) is the entry point. Data cards are punched for each run
+/// of consecutive words, so ORG and BSS start new cards. Fails with every
+/// error found.
+///
+/// Unless the deck has an ABS statement the program is relocatable: labels
+/// and `*` move with it, and DC constants and long-form address words that
+/// evaluate to a relocatable address are marked for the loader (see
+/// `Relocation`).
+pub fn assemble(deck: &Deck) -> Result<Deck, Vec<AsmError>> {
+    let mut errors = Vec::new();
+    let mut symbols = Symbols::new();
+    let mut lines = Vec::new();
+    let mut location: u16 = 0;
+    let mut relocatable = true;
+    let mut end: Option<Line> = None;
+
+    // Pass 1: assign locations and define symbols
+    for (card, punched) in deck.iter().enumerate() {
+        let source = match SourceCard::parse(punched) {
+            Ok(source) => source,
+            Err(error) => {
+                errors.push(parse_error(card, error));
+                continue;
+            }
+        };
+        let mnemonic = source.opcode().trim_start();
+        if source.is_comment() || mnemonic.is_empty() {
+            continue;
+        }
+        let opcode_column = OPCODE_COLUMNS.start + source.opcode().len() - mnemonic.len();
+        let Some(info) = opcodes::lookup(mnemonic) else {
+            errors.push(AsmError {
+                card,
+                column: opcode_column,
+                message: format!("unknown opcode {:?}", mnemonic),
+            });
+            continue;
+        };
+        if info.mnemonic == "ABS" {
+            relocatable = false;
+        }
+        let mut line = Line {
+            card,
+            source,
+            info,
+            location,
+            relocatable,
+        };
+        match pass_one(&mut line, &mut symbols) {
+            Ok(size) => {
+                location = line.location.wrapping_add(size);
+                if info.mnemonic == "END" {
+                    end = Some(line);
+                    break;
+                }
+                lines.push(line);
+            }
+            Err(error) => errors.push(error),
+        }
+    }
+
+    // Pass 2: encode each statement
+    let mut runs: Vec<(u16, Vec<u16>, Vec<Relocation>)> = Vec::new();
+    for line in &lines {
+        match encode(line, &symbols) {
+            Ok(words) if words.is_empty() => {}
+            Ok(words) => {
+                let (words, relocation): (Vec<u16>, Vec<Relocation>) = words.into_iter().unzip();
+                match runs.last_mut() {
+                    Some((start, run, flags))
+                        if start.wrapping_add(run.len() as u16) == line.location =>
+                    {
+                        run.extend(words);
+                        flags.extend(relocation);
+                    }
+                    _ => runs.push((line.location, words, relocation)),
+                }
+            }
+            Err(error) => errors.push(error),
+        }
+    }
+    let entry = match end
+        .as_ref()
+        .filter(|line| !line.source.operands().is_empty())
+    {
+        Some(line) => match evaluate(line, line.source.operands(), &symbols) {
+            Ok(value) => value as u16,
+            Err(error) => {
+                errors.push(error);
+                0
+            }
+        },
+        None => runs.first().map_or(0, |(start, _, _)| *start),
+    };
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(Deck::from_cards(punch_object_deck(
+        runs.iter()
+            .map(|(start, words, relocation)| (*start, words.as_slice(), relocation.as_slice())),
+        entry,
+    )))
+}
+
+/// Place a statement and define its label, returning its size in words
+fn pass_one(line: &mut Line, symbols: &mut Symbols) -> Result<u16, AsmError> {
+    let operands = line.source.operands();
+    let size = match (line.info.kind, line.info.mnemonic) {
+        (OpcodeKind::Instruction, _) if line.long() => 2,
+        (OpcodeKind::Instruction, _) => 1,
+        (_, "ORG") => {
+            line.location = evaluate(line, operands, symbols)? as u16;
+            0
+        }
+        (_, "DC") => 1,
+        (_, "DEC") => {
+            line.location = line.location.wrapping_add(line.location & 1);
+            2
+        }
+        (_, "BSS") => {
+            if line.source.format() == Some('E') {
+                line.location = line.location.wrapping_add(line.location & 1);
+            }
+            let count = evaluate(line, operands, symbols)?;
+            u16::try_from(count).map_err(|_| {
+                line.error(
+                    OPERAND_COLUMNS.start,
+                    format!("BSS count {} is negative", count),
+                )
+            })?
+        }
+        (_, "EQU" | "END" | "ABS" | "LIST" | "SPAC" | "EJCT" | "HDNG") => 0,
+        (_, mnemonic) => {
+            return Err(line.error(
+                OPCODE_COLUMNS.start,
+                format!("{} is not supported by this assembler", mnemonic),
+            ));
+        }
+    };
+
+    let label = line.source.label().trim();
+    let value = match line.info.label {
+        LabelRule::NotAllowed => return Ok(size),
+        LabelRule::Required if label.is_empty() => {
+            return Err(line.error(
+                LABEL_COLUMNS.start,
+                format!("{} needs a label", line.info.mnemonic),
+            ));
+        }
+        LabelRule::Required => {
+            let (value, relocation) = evaluate_address(line, operands, symbols)?;
+            Symbol {
+                value,
+                relocatable: relocation == Relocation::Relocatable,
+            }
+        }
+        LabelRule::Optional => Symbol {
+            value: line.location,
+            relocatable: line.relocatable,
+        },
+    };
+    if !label.is_empty() && symbols.insert(label.to_string(), value).is_some() {
+        return Err(line.error(
+            LABEL_COLUMNS.start,
+            format!("label {:?} is already defined", label),
+        ));
+    }
+    Ok(size)
+}
+
+/// Assemble the words of one statement, with how each is relocated
+fn encode(line: &Line, symbols: &Symbols) -> Result<Vec<(u16, Relocation)>, AsmError> {
+    use Relocation::Absolute;
+    let info = line.info;
+    let operands = line.source.operands();
+    if info.kind == OpcodeKind::Directive {
+        return match info.mnemonic {
+            "DC" => Ok(vec![evaluate_address(line, operands, symbols)?]),
+            "DEC" => {
+                let value: i32 = operands.parse().map_err(|_| {
+                    line.error(
+                        OPERAND_COLUMNS.start,
+                        format!("DEC {:?} is not an integer", operands),
+                    )
+                })?;
+                Ok(vec![
+                    ((value >> 16) as u16, Absolute),
+                    (value as u16, Absolute),
+                ])
+            }
+            _ => Ok(Vec::new()),
+        };
+    }
+    if line.long() && !info.long {
+        return Err(line.error(FORMAT_COLUMN, format!("{} has no long form", info.mnemonic)));
+    }
+    if line.tag() != 0 && !info.indexed {
+        return Err(line.error(TAG_COLUMN, format!("{} cannot be indexed", info.mnemonic)));
+    }
+
+    let mut first = info.word | line.tag() << 8;
+    let (address, modifiers) = operands.split_once(',').unwrap_or((operands, ""));
+    match info.operands {
+        OperandSyntax::None => return Ok(vec![(first, Absolute)]),
+        OperandSyntax::Conditions => {
+            return Ok(vec![(first | conditions(line, operands, 0)?, Absolute)]);
+        }
+        OperandSyntax::Value => {
+            return Ok(vec![(
+                first | displacement(line, operands, symbols, false)?,
+                Absolute,
+            )]);
+        }
+        OperandSyntax::Shift if operands.is_empty() && line.tag() != 0 => {
+            return Ok(vec![(first, Absolute)]);
+        }
+        OperandSyntax::Shift => {
+            let count = evaluate(line, operands, symbols)?;
+            if !(0..64).contains(&count) {
+                return Err(line.error(
+                    OPERAND_COLUMNS.start,
+                    format!("shift count {} is not 0-63", count),
+                ));
+            }
+            return Ok(vec![(first | count as u16, Absolute)]);
+        }
+        _ => {}
+    }
+
+    if !line.long() {
+        if info.word & 0xFF != 0 {
+            return Err(line.error(
+                FORMAT_COLUMN,
+                format!("{} needs the long form (L in column 32)", info.mnemonic),
+            ));
+        }
+        let relative = match info.mnemonic {
+            "LDX" => false,
+            "STX" => true,
+            _ => line.tag() == 0,
+        };
+        if info.mnemonic == "B" {
+            // A short branch is MDX with no tag; a tag would make it an
+            // index register modify
+            if line.tag() != 0 {
+                return Err(line.error(
+                    TAG_COLUMN,
+                    "B cannot be indexed in the short form (L in column 32)".to_string(),
+                ));
+            }
+            first = 0x7000;
+        }
+        return Ok(vec![(
+            first | displacement(line, operands, symbols, relative)?,
+            Absolute,
+        )]);
+    }
+
+    first |= 0x0400;
+    if line.source.format() == Some('I') {
+        first |= 0x0080;
+    }
+    let offset = address.len() + 1;
+    first |= match info.operands {
+        OperandSyntax::Branch => conditions(line, modifiers, offset)?,
+        _ if info.mnemonic == "MDX" && !modifiers.is_empty() => {
+            let value =
+                evaluate(line, modifiers, symbols).map_err(|error| shift_column(error, offset))?;
+            byte(line, value, offset)?
+        }
+        _ => 0,
+    };
+    Ok(vec![
+        (first, Absolute),
+        evaluate_address(line, address, symbols)?,
+    ])
+}
+
+/// Get the 8-bit displacement of a short instruction
+fn displacement(
+    line: &Line,
+    operands: &str,
+    symbols: &Symbols,
+    relative: bool,
+) -> Result<u16, AsmError> {
+    let value = evaluate(line, operands, symbols)?;
+    let value = if relative {
+        value - i32::from(line.location) - 1
+    } else {
+        value
+    };
+    byte(line, value, 0).map_err(|_| {
+        line.error(
+            OPERAND_COLUMNS.start,
+            format!(
+                "displacement {} does not fit the short form (-128 to 127)",
+                value
+            ),
+        )
+    })
+}
+
+/// Check that a value fits a signed 8-bit displacement
+fn byte(line: &Line, value: i32, offset: usize) -> Result<u16, AsmError> {
+    if !(-128..=127).contains(&value) {
+        return Err(line.error(
+            OPERAND_COLUMNS.start + offset,
+            format!("{} does not fit in 8 bits", value),
+        ));
+    }
+    Ok(value as u16 & 0xFF)
+}
+
+/// Check that a value fits a 16-bit word
+fn word(line: &Line, value: i32) -> Result<u16, AsmError> {
+    if !(-32768..=65535).contains(&value) {
+        return Err(line.error(
+            OPERAND_COLUMNS.start,
+            format!("{} does not fit in a word", value),
+        ));
+    }
+    Ok(value as u16)
+}
+
+/// Read branch conditions (`Z`, `-`, `+`, `E`, `C`, `O`) into bits 10-15
+fn conditions(line: &Line, text: &str, offset: usize) -> Result<u16, AsmError> {
+    text.chars().enumerate().try_fold(0, |bits, (index, ch)| {
+        let bit = match ch {
+            'Z' => 0x20,
+            '-' => 0x10,
+            '+' => 0x08,
+            'E' => 0x04,
+            'C' => 0x02,
+            'O' => 0x01,
+            _ => {
+                return Err(line.error(
+                    OPERAND_COLUMNS.start + offset + index,
+                    format!("{:?} is not a branch condition", ch),
+                ));
+            }
+        };
+        Ok(bits | bit)
+    })
+}
+
+/// Evaluate an operand expression, reporting errors at their column
+fn evaluate(line: &Line, expr: &str, symbols: &Symbols) -> Result<i32, AsmError> {
+    evaluate_terms(line, expr, symbols).map(|(value, _)| value)
+}
+
+/// Evaluate an expression that is loaded as a word, such as an address
+///
+/// The word is relocatable if the expression has one more relocatable term
+/// added than subtracted, and absolute if they cancel out.
+fn evaluate_address(
+    line: &Line,
+    expr: &str,
+    symbols: &Symbols,
+) -> Result<(u16, Relocation), AsmError> {
+    let (value, relocatable_terms) = evaluate_terms(line, expr, symbols)?;
+    let relocation = match relocatable_terms {
+        0 => Relocation::Absolute,
+        1 => Relocation::Relocatable,
+        _ => {
+            return Err(line.error(
+                OPERAND_COLUMNS.start,
+                format!("{:?} is neither absolute nor relocatable", expr),
+            ));
+        }
+    };
+    Ok((word(line, value)?, relocation))
+}
+
+/// Evaluate an expression and count its relocatable terms (subtracted ones
+/// count -1)
+fn evaluate_terms(line: &Line, expr: &str, symbols: &Symbols) -> Result<(i32, i32), AsmError> {
+    let location = Symbol {
+        value: line.location,
+        relocatable: line.relocatable,
+    };
+    eval(expr, symbols, location)
+        .map_err(|(offset, message)| line.error(OPERAND_COLUMNS.start + offset, message))
+}
+
+/// Move an error found in part of the operand field to that part's column
+fn shift_column(mut error: AsmError, offset: usize) -> AsmError {
+    error.column += offset;
+    error
+}
+
+/// Evaluate terms joined by + and -, failing with the offset of the problem
+fn eval(expr: &str, symbols: &Symbols, location: Symbol) -> Result<(i32, i32), (usize, String)> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut pos = 0;
+    let mut total = 0i32;
+    let mut relocatable_terms = 0;
+    let mut sign = 1;
+    if let Some(&ch @ ('+' | '-')) = chars.first() {
+        sign = if ch == '-' { -1 } else { 1 };
+        pos = 1;
+    }
+    loop {
+        let start = pos;
+        let take = |pos: &mut usize, pred: fn(char) -> bool| -> String {
+            let run: String = chars[*pos..].iter().take_while(|&&ch| pred(ch)).collect();
+            *pos += run.len();
+            run
+        };
+        let term = match chars.get(pos) {
+            None => return Err((pos, "missing operand".to_string())),
+            Some('*') => {
+                pos += 1;
+                location
+            }
+            Some('/') => {
+                pos += 1;
+                let digits = take(&mut pos, |ch| ch.is_ascii_hexdigit());
+                u16::from_str_radix(&digits, 16)
+                    .map(absolute)
+                    .map_err(|_| (start, format!("bad hex constant /{}", digits)))?
+            }
+            Some(ch) if ch.is_ascii_digit() => {
+                let digits = take(&mut pos, |ch| ch.is_ascii_digit());
+                digits
+                    .parse::<u16>()
+                    .map(absolute)
+                    .map_err(|_| (start, format!("{} does not fit in a word", digits)))?
+            }
+            Some(ch) if ch.is_ascii_alphabetic() => {
+                let name = take(&mut pos, |ch| ch.is_ascii_alphanumeric());
+                *symbols
+                    .get(&name)
+                    .ok_or_else(|| (start, format!("undefined symbol {:?}", name)))?
+            }
+            Some(ch) => return Err((pos, format!("unexpected {:?}", ch))),
+        };
+        total += sign * i32::from(term.value);
+        relocatable_terms += sign * i32::from(term.relocatable);
+        match chars.get(pos) {
+            None => return Ok((total, relocatable_terms)),
+            Some('+') => sign = 1,
+            Some('-') => sign = -1,
+            Some(ch) => return Err((pos, format!("unexpected {:?}", ch))),
+        }
+        pos += 1;
+    }
+}
+
+fn absolute(value: u16) -> Symbol {
+    Symbol {
+        value,
+        relocatable: false,
+    }
+}
+
+/// Place a source card parse error at its column
+fn parse_error(card: usize, error: ParseError) -> AsmError {
+    let column = match &error {
+        ParseError::Undecodable(column) | ParseError::NotBlank { column, .. } => *column,
+        ParseError::InvalidTag(_) => TAG_COLUMN,
+        _ => 0,
+    };
+    AsmError {
+        card,
+        column,
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ibm1130::{ObjectCard, ObjectCardKind, parse_object_deck, words_from_object_deck};
+    use crate::punch_card::PunchCard;
+
+    const SOURCE_DECK: &str = include_str!("../../tests/fixtures/ibm1130_source.txt");
+
+    fn source(lines: &[&str]) -> Deck {
+        lines
+            .iter()
+            .map(|line| PunchCard::from_text(&format!("{:20}{}", "", line)))
+            .collect()
+    }
+
+    #[test]
+    fn test_assemble_sum_program() {
+        let deck: Deck = SOURCE_DECK.lines().map(PunchCard::from_text).collect();
+        let object = assemble(&deck).unwrap();
+        let cards = parse_object_deck(&object).unwrap();
+        assert_eq!(cards.len(), 2);
+        assert!(cards.iter().all(ObjectCard::verify_checksum));
+        assert_eq!(cards[1].card_kind(), ObjectCardKind::End);
+        assert_eq!(cards[1].load_address(), 0x0100);
+        // Only the TABLE-1 address is relocatable, as in the fixture
+        assert_eq!(cards[0].relocation(), &[0x0100, 0, 0, 0, 0, 0]);
+        // Matches the hand-assembled fixture
+        assert_eq!(
+            words_from_object_deck(&object).unwrap(),
+            vec![(
+                0x0100,
+                vec![
+                    0x6500, 0x000A, 0xC500, 0x0109, 0x8004, 0xD003, 0x71FF, 0x70FA, 0x3000, 0x0000
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_assemble_forms_and_directives() {
+        let deck = source(&[
+            "      ORG     /0200",
+            "TEN   EQU     10",
+            "START LDX  L2 TEN+TEN",
+            "      BSC  L  START,Z+",
+            "      BSI  I  PTR",
+            "      BP   L  START",
+            "      SLA     4",
+            "      SRT   1",
+            "      SKP     E",
+            "      LDS     1",
+            "      MDX  L  CNT,-1",
+            "      B    L  START",
+            "PTR   DC      START",
+            "CNT   DC      -1",
+            "      BSS  E  1",
+            "BIG   DEC     65537",
+            "      END",
+        ]);
+        let runs = words_from_object_deck(&assemble(&deck).unwrap()).unwrap();
+        assert_eq!(
+            runs,
+            vec![
+                (
+                    0x0200,
+                    vec![
+                        0x6600, 0x0014, 0x4C28, 0x0200, 0x4480, 0x0210, 0x4C30, 0x0200, 0x1004,
+                        0x1980, 0x4804, 0x2001, 0x74FF, 0x0211, 0x4C00, 0x0200, 0x0200, 0xFFFF
+                    ]
+                ),
+                (0x0214, vec![0x0001, 0x0001]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assemble_reports_errors_with_columns() {
+        let deck = source(&[
+            "LOOP  LD      TABL",
+            "LOOP  STO     X+",
+            "      LDA     X",
+            "      CALL    FSIN",
+            "      BP      LOOP",
+            "      LD      FAR",
+            "      BSS     300",
+            "FAR   DC      0",
+            "      BSC  L  LOOP,Q",
+            "      DC      LOOP+FAR",
+            "      B     1  LOOP",
+        ]);
+        let errors = assemble(&deck).unwrap_err();
+        let found: Vec<(usize, usize)> = errors.iter().map(|e| (e.card, e.column)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (1, 20),
+                (2, 26),
+                (3, 26),
+                (0, 34),
+                (4, 31),
+                (5, 34),
+                (8, 39),
+                (9, 34),
+                (10, 32)
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "Card 2, column 21: label \"LOOP\" is already defined"
+        );
+        assert_eq!(
+            errors[3].to_string(),
+            "Card 1, column 35: undefined symbol \"TABL\""
+        );
+        assert!(errors[5].message.contains("does not fit the short form"));
+        assert!(errors[8].message.contains("B cannot be indexed"));
+
+        let binary = Deck::from_cards(vec![PunchCard::from_binary(&[0xFF; 108])]);
+        assert_eq!(assemble(&binary).unwrap_err()[0].card, 0);
+    }
+}
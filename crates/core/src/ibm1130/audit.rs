@@ -0,0 +1,454 @@
+// IBM 1130 Object Deck Audit
+//
+// Checks a whole object deck before it is loaded, reporting every problem
+// rather than stopping at the first:
+// - Each card is an object card with a matching checksum
+// - Data cards come first and one end card closes the deck
+// - No two cards load the same address
+//
+// A data card loading past the highest address loaded so far leaves a gap,
+// which is only a warning, since ORG and BSS leave gaps in intact decks.
+// A card whose words would run past address /FFFF is reported as out of
+// range and not checked for overlaps or gaps.
+
+use super::{
+    OBJECT_DATA_WORDS, ObjectCard, ObjectCardError, ObjectCardKind, Severity, object_words,
+};
+use crate::deck::Deck;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+/// A problem found by `audit_object_deck` (cards are 0-based)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AuditIssue {
+    /// The card does not read as an object card
+    NotObject { card: usize, message: String },
+    /// The checksum word does not match the card
+    Checksum {
+        card: usize,
+        stored: u16,
+        computed: u16,
+    },
+    /// The word count is more than the 45 data words a card holds
+    WordCount { card: usize, count: u8 },
+    /// A card type other than data or end
+    UnexpectedType { card: usize, code: u8 },
+    /// A card after the end card
+    AfterEnd { card: usize, end: usize },
+    /// The deck has no end card
+    MissingEnd,
+    /// A card loads words an earlier card already loaded
+    Overlap {
+        card: usize,
+        earlier: usize,
+        address: u16,
+    },
+    /// Addresses from `start`, the end of everything loaded so far, up to
+    /// `end` are skipped before a data card
+    Gap { card: usize, start: u16, end: u16 },
+    /// A data card's words run past address /FFFF
+    OutOfRange {
+        card: usize,
+        address: u16,
+        words: usize,
+    },
+}
+
+impl AuditIssue {
+    /// Gaps are warnings; everything else means the deck is damaged
+    pub fn severity(&self) -> Severity {
+        match self {
+            AuditIssue::Gap { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for AuditIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditIssue::NotObject { card, message } => {
+                write!(f, "Card {}: not an object card: {}", card + 1, message)
+            }
+            AuditIssue::Checksum {
+                card,
+                stored,
+                computed,
+            } => write!(
+                f,
+                "Card {}: checksum {:04X} does not match (expected {:04X})",
+                card + 1,
+                stored,
+                computed
+            ),
+            AuditIssue::WordCount { card, count } => write!(
+                f,
+                "Card {}: word count {} is more than {}",
+                card + 1,
+                count,
+                OBJECT_DATA_WORDS
+            ),
+            AuditIssue::UnexpectedType { card, code } => {
+                write!(f, "Card {}: unexpected card type {:02X}", card + 1, code)
+            }
+            AuditIssue::AfterEnd { card, end } => {
+                write!(f, "Card {}: follows the end card {}", card + 1, end + 1)
+            }
+            AuditIssue::MissingEnd => write!(f, "Deck has no end card"),
+            AuditIssue::Overlap {
+                card,
+                earlier,
+                address,
+            } => write!(
+                f,
+                "Card {}: reloads address {:04X}, loaded by card {}",
+                card + 1,
+                address,
+                earlier + 1
+            ),
+            AuditIssue::Gap { card, start, end } => write!(
+                f,
+                "Card {}: addresses {:04X}-{:04X} are not loaded",
+                card + 1,
+                start,
+                end - 1
+            ),
+            AuditIssue::OutOfRange {
+                card,
+                address,
+                words,
+            } => write!(
+                f,
+                "Card {}: {} word(s) from {:04X} run past address FFFF",
+                card + 1,
+                words,
+                address
+            ),
+        }
+    }
+}
+
+/// The result of auditing an object deck
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ObjectDeckAudit {
+    /// Cards in the deck
+    pub cards: usize,
+    /// Data cards read, including those with bad checksums
+    pub data_cards: usize,
+    /// Words the data cards load
+    pub words: usize,
+    /// The end card's entry point
+    pub entry: Option<u16>,
+    pub issues: Vec<AuditIssue>,
+}
+
+impl ObjectDeckAudit {
+    /// Count the issues of a severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity() == severity)
+            .count()
+    }
+
+    /// Whether the deck has no errors, so can be loaded
+    pub fn is_intact(&self) -> bool {
+        self.count(Severity::Error) == 0
+    }
+}
+
+impl fmt::Display for ObjectDeckAudit {
+    /// A summary line, one line per issue, then the verdict
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} card(s), {} data card(s), {} word(s)",
+            self.cards, self.data_cards, self.words
+        )?;
+        if let Some(entry) = self.entry {
+            write!(f, ", entry {:04X}", entry)?;
+        }
+        writeln!(f)?;
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        write!(
+            f,
+            "{}: {} error(s), {} warning(s)",
+            if self.is_intact() {
+                "Intact"
+            } else {
+                "Damaged"
+            },
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        )
+    }
+}
+
+/// Audit an object deck
+///
+/// A card with a bad checksum is still checked for where it loads, so one
+/// damaged card does not hide other problems.
+pub fn audit_object_deck(deck: &Deck) -> ObjectDeckAudit {
+    let mut audit = ObjectDeckAudit {
+        cards: deck.len(),
+        ..ObjectDeckAudit::default()
+    };
+    let mut end = None;
+    let mut loaded: Vec<(usize, Range<u32>)> = Vec::new();
+    for (index, card) in deck.iter().enumerate() {
+        if let Some(end) = end {
+            audit.issues.push(AuditIssue::AfterEnd { card: index, end });
+            continue;
+        }
+        let (kind, address, data) = match ObjectCard::parse(card) {
+            Ok(object) => (
+                object.card_kind(),
+                object.load_address(),
+                object.data_words().len(),
+            ),
+            Err(ObjectCardError::Checksum { stored, computed }) => {
+                audit.issues.push(AuditIssue::Checksum {
+                    card: index,
+                    stored,
+                    computed,
+                });
+                let words = object_words(card);
+                let kind = ObjectCardKind::from_code((words[2] >> 8) as u8);
+                let count = usize::from(words[2] & 0xFF).min(OBJECT_DATA_WORDS);
+                (kind, words[0], count)
+            }
+            Err(ObjectCardError::WordCount(count)) => {
+                audit
+                    .issues
+                    .push(AuditIssue::WordCount { card: index, count });
+                continue;
+            }
+            Err(error) => {
+                audit.issues.push(AuditIssue::NotObject {
+                    card: index,
+                    message: error.to_string(),
+                });
+                continue;
+            }
+        };
+
+        match kind {
+            ObjectCardKind::End => {
+                end = Some(index);
+                audit.entry = Some(address);
+                continue;
+            }
+            ObjectCardKind::Other(code) => {
+                audit
+                    .issues
+                    .push(AuditIssue::UnexpectedType { card: index, code });
+                continue;
+            }
+            ObjectCardKind::Data => {}
+        }
+        audit.data_cards += 1;
+        if data == 0 {
+            continue;
+        }
+        let range = u32::from(address)..u32::from(address) + data as u32;
+        if range.end > 0x1_0000 {
+            audit.issues.push(AuditIssue::OutOfRange {
+                card: index,
+                address,
+                words: data,
+            });
+            continue;
+        }
+        audit.words += data;
+        // Every loaded range ends by /10000, so anything below `address`
+        // fits in a word
+        if let Some(high) = loaded.iter().map(|(_, other)| other.end).max()
+            && let Ok(start) = u16::try_from(high)
+            && start < address
+        {
+            audit.issues.push(AuditIssue::Gap {
+                card: index,
+                start,
+                end: address,
+            });
+        }
+        if let Some((earlier, other)) = loaded
+            .iter()
+            .find(|(_, other)| other.start < range.end && range.start < other.end)
+        {
+            audit.issues.push(AuditIssue::Overlap {
+                card: index,
+                earlier: *earlier,
+                address: address
+                    .max(u16::try_from(other.start).expect("ranges start at an address")),
+            });
+        }
+        loaded.push((index, range));
+    }
+    if end.is_none() {
+        audit.issues.push(AuditIssue::MissingEnd);
+    }
+    audit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::CardFormat;
+    use crate::ibm1130::words_to_object_card;
+    use crate::punch_card::PunchCard;
+
+    const SUM_OBJECT_DECK: &[u8] =
+        include_bytes!("../../tests/fixtures/ibm1130_sum_object_synthetic.bin");
+
+    fn fixture() -> Vec<PunchCard> {
+        SUM_OBJECT_DECK
+            .chunks(120)
+            .map(|data| PunchCard::parse(data, CardFormat::FullBinary120).unwrap())
+            .collect()
+    }
+
+    fn data_card(address: u16, words: usize) -> PunchCard {
+        ObjectCard::new(ObjectCardKind::Data, address, &vec![0x1000; words])
+            .unwrap()
+            .to_card()
+    }
+
+    #[test]
+    fn test_intact_fixture() {
+        let audit = audit_object_deck(&Deck::from_cards(fixture()));
+        assert!(audit.is_intact());
+        assert!(audit.issues.is_empty());
+        assert_eq!(
+            (audit.cards, audit.data_cards, audit.words, audit.entry),
+            (2, 1, 10, Some(0x0100))
+        );
+        assert_eq!(
+            audit.to_string(),
+            "2 card(s), 1 data card(s), 10 word(s), entry 0100\nIntact: 0 error(s), 0 warning(s)"
+        );
+    }
+
+    #[test]
+    fn test_corrupted_fixture() {
+        let [data, end] = <[PunchCard; 2]>::try_from(fixture()).unwrap();
+
+        // A flipped punch in a data word breaks the checksum
+        let mut words = object_words(&data);
+        words[9] ^= 0x8000;
+        let damaged = words_to_object_card(&words);
+        let audit = audit_object_deck(&Deck::from_cards(vec![damaged, end.clone()]));
+        assert!(matches!(
+            audit.issues[..],
+            [AuditIssue::Checksum { card: 0, .. }]
+        ));
+        assert_eq!(audit.words, 10);
+
+        // A text card, a header card, a duplicated data card, a gap, a
+        // card after the end, and the end card lost
+        let cards = vec![
+            PunchCard::from_text("SUM"),
+            ObjectCard::new(ObjectCardKind::Other(0x01), 0, &[])
+                .unwrap()
+                .to_card(),
+            data.clone(),
+            data_card(0x0105, 3),
+            data_card(0x0120, 2),
+            end.clone(),
+            data.clone(),
+        ];
+        let audit = audit_object_deck(&Deck::from_cards(cards));
+        assert_eq!(
+            audit.issues,
+            vec![
+                AuditIssue::NotObject {
+                    card: 0,
+                    message: "Object cards must be binary type".to_string(),
+                },
+                AuditIssue::UnexpectedType {
+                    card: 1,
+                    code: 0x01
+                },
+                AuditIssue::Overlap {
+                    card: 3,
+                    earlier: 2,
+                    address: 0x0105,
+                },
+                AuditIssue::Gap {
+                    card: 4,
+                    start: 0x010A,
+                    end: 0x0120,
+                },
+                AuditIssue::AfterEnd { card: 6, end: 5 },
+            ]
+        );
+        assert_eq!((audit.data_cards, audit.words), (3, 15));
+        assert_eq!(audit.count(Severity::Warning), 1);
+        let report = audit.to_string();
+        assert!(report.contains("Card 4: reloads address 0105, loaded by card 3\n"));
+        assert!(report.contains("Card 5: addresses 010A-011F are not loaded\n"));
+        assert!(report.ends_with("Damaged: 4 error(s), 1 warning(s)"));
+
+        let audit = audit_object_deck(&Deck::from_cards(vec![data]));
+        assert_eq!(audit.issues, vec![AuditIssue::MissingEnd]);
+        assert_eq!(audit.entry, None);
+    }
+
+    #[test]
+    fn test_bad_word_count_and_range() {
+        let [data, end] = <[PunchCard; 2]>::try_from(fixture()).unwrap();
+        let mut words = object_words(&data);
+        words[2] |= 0x00FF;
+        let damaged = words_to_object_card(&words);
+        let cards = vec![
+            damaged,
+            data.clone(),
+            data_card(0xFFF0, 20),
+            data_card(0xFFF6, 10),
+            end,
+        ];
+        let audit = audit_object_deck(&Deck::from_cards(cards));
+        assert_eq!(
+            audit.issues,
+            vec![
+                AuditIssue::WordCount {
+                    card: 0,
+                    count: 0xFF
+                },
+                AuditIssue::OutOfRange {
+                    card: 2,
+                    address: 0xFFF0,
+                    words: 20
+                },
+                AuditIssue::Gap {
+                    card: 3,
+                    start: 0x010A,
+                    end: 0xFFF6
+                },
+            ]
+        );
+        assert_eq!(audit.words, 20);
+        let report = audit.to_string();
+        assert!(report.contains("Card 1: word count 255 is more than 45\n"));
+        assert!(report.contains("Card 3: 20 word(s) from FFF0 run past address FFFF\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_audit_serde() {
+        let mut cards = fixture();
+        cards.pop();
+        let audit = audit_object_deck(&Deck::from_cards(cards));
+        let json = serde_json::to_value(&audit).unwrap();
+        assert_eq!(json["words"], 10);
+        assert_eq!(json["issues"][0], "MissingEnd");
+        let back: ObjectDeckAudit = serde_json::from_value(json).unwrap();
+        assert_eq!(back, audit);
+    }
+}
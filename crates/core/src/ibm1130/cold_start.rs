@@ -0,0 +1,83 @@
+// IBM 1130 Cold Start Card
+//
+// Pressing PROGRAM LOAD reads one card in load mode: each of the 80 columns
+// becomes the word at the same address, 0-79, and the CPU starts at
+// address 0. The 12 rows cannot fill 16 bits, so the card reader spreads
+// them out:
+//
+//     rows 12, 11, 0, 1, 2  ->  bits 0-4   (operation code)
+//     row 3                 ->  bits 8-9   (sign of the displacement)
+//     rows 4-9              ->  bits 10-15
+//
+// Bits 5-7 (format and tag) are always 0, so a cold start card holds short,
+// unindexed instructions and small constants, just enough of a loader to
+// read the rest of the system.
+//
+// The monitor's own cold start card is not reproduced here; these functions
+// convert between a card and the words PROGRAM LOAD puts in core.
+
+use crate::punch_card::PunchCard;
+
+/// Read a card as PROGRAM LOAD does: the words for addresses 0-79
+pub fn cold_start_words(card: &PunchCard) -> [u16; 80] {
+    card.to_u16_columns().map(|image| {
+        let sign = if image & 0x040 != 0 { 0x00C0 } else { 0 };
+        (image & 0xF80) << 4 | sign | image & 0x03F
+    })
+}
+
+/// Punch words as a card that PROGRAM LOAD reads back
+///
+/// Fails on more than 80 words, or a word PROGRAM LOAD cannot produce: one
+/// with any of bits 5-7 set, or with bits 8 and 9 different.
+pub fn cold_start_card_from_words(words: &[u16]) -> Result<PunchCard, String> {
+    if words.len() > 80 {
+        return Err(format!("{} words do not fit in 80 columns", words.len()));
+    }
+    let mut columns = Vec::with_capacity(words.len());
+    for (address, &word) in words.iter().enumerate() {
+        let sign = word & 0x00C0;
+        if word & 0x0700 != 0 || (sign != 0 && sign != 0x00C0) {
+            return Err(format!(
+                "Word {:04X} at address {} cannot be punched for program load",
+                word, address
+            ));
+        }
+        columns.push((word & 0xF800) >> 4 | if sign != 0 { 0x040 } else { 0 } | word & 0x003F);
+    }
+    Ok(PunchCard::from_u16_columns(&columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hollerith::HollerithCode;
+    use crate::punch_card::CardType;
+
+    #[test]
+    fn test_program_load_reads_rows_into_bits() {
+        let mut card = PunchCard::new(CardType::Binary);
+        // Rows 12 and 3: bit 0 and the displacement sign in bits 8-9
+        card.set_column_hollerith(0, HollerithCode::new(vec![12, 3]))
+            .unwrap();
+        // Rows 2 and 9: bits 4 and 15
+        card.set_column_hollerith(1, HollerithCode::new(vec![2, 9]))
+            .unwrap();
+        let words = cold_start_words(&card);
+        assert_eq!(words[..2], [0x80C0, 0x0801]);
+        assert!(words[2..].iter().all(|&word| word == 0));
+    }
+
+    #[test]
+    fn test_load_mode_conversion() {
+        // Row 3 sets both bits 8 and 9, so displacements sign-extend
+        let words = [0x70FD, 0x3000, 0x00C0, 0xF83F];
+        let card = cold_start_card_from_words(&words).unwrap();
+        assert_eq!(card.to_u16_columns()[0], 0x77D);
+        assert_eq!(cold_start_words(&card)[..4], words);
+
+        assert!(cold_start_card_from_words(&[0xC400]).is_err());
+        assert!(cold_start_card_from_words(&[0x0040]).is_err());
+        assert!(cold_start_card_from_words(&[0; 81]).is_err());
+    }
+}
@@ -0,0 +1,363 @@
+// IBM 1130 Core Image
+//
+// A program as it sits in memory after loading: one contiguous block of
+// words, plus the entry point. Loading an object deck applies the relocation
+// indicators of each card, so this is a minimal 1130 loader, and a core
+// image is what an emulator needs to run a program.
+//
+// The monitor's disk core image (DCI) card format is not implemented; an
+// image is punched back out as an absolute object deck.
+
+use super::{ObjectCard, ObjectCardKind, ObjectDeckError, Relocation, punch_object_deck};
+use crate::deck::Deck;
+use std::fmt;
+
+/// Words of memory on the largest 1130
+pub const MEMORY_WORDS: usize = 32768;
+
+/// Errors from loading an object deck into a core image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreImageError {
+    /// A card did not parse as an object card
+    Object(ObjectDeckError),
+    /// A data word is marked as a subroutine call (LIBF or CALL), which needs
+    /// a linking loader (0-based card and data word)
+    Unresolved { card: usize, word: usize },
+    /// A card loads, or the end card starts the program, past the end of
+    /// memory (0-based card)
+    OutOfMemory { card: usize, address: usize },
+    /// The deck has no end card
+    MissingEnd,
+}
+
+impl fmt::Display for CoreImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoreImageError::Object(error) => write!(f, "{}", error),
+            CoreImageError::Unresolved { card, word } => write!(
+                f,
+                "Card {}: data word {} calls a subroutine that needs linking",
+                card + 1,
+                word + 1
+            ),
+            CoreImageError::OutOfMemory { card, address } => write!(
+                f,
+                "Card {}: address {:05X} is past the end of memory",
+                card + 1,
+                address
+            ),
+            CoreImageError::MissingEnd => write!(f, "Object deck has no end card"),
+        }
+    }
+}
+
+impl std::error::Error for CoreImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CoreImageError::Object(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A loaded program: memory words from `origin`, and the entry point
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreImage {
+    origin: u16,
+    words: Vec<u16>,
+    entry: u16,
+}
+
+impl CoreImage {
+    /// Create an image of `words` loaded at `origin`
+    pub fn new(origin: u16, words: Vec<u16>, entry: u16) -> Self {
+        CoreImage {
+            origin,
+            words,
+            entry,
+        }
+    }
+
+    /// Load an object deck, relocating it to `origin`
+    ///
+    /// `origin` is added to each card's load address, to the entry point and
+    /// to every data word marked `Relocation::Relocatable`, so origin 0 loads
+    /// a program at the addresses it was assembled for. The image
+    /// runs from the lowest to the highest loaded address; words in between
+    /// that no card loads are 0. Reading stops at the end card.
+    pub fn from_object_deck(deck: &Deck, origin: u16) -> Result<Self, CoreImageError> {
+        let mut loaded: Vec<(usize, Vec<u16>)> = Vec::new();
+        let mut entry = None;
+        for (index, card) in deck.iter().enumerate() {
+            let object = ObjectCard::parse(card)
+                .map_err(|error| CoreImageError::Object(ObjectDeckError { card: index, error }))?;
+            let address = usize::from(object.load_address()) + usize::from(origin);
+            match object.card_kind() {
+                ObjectCardKind::End => {
+                    entry = Some((index, address));
+                    break;
+                }
+                ObjectCardKind::Data => {}
+                ObjectCardKind::Other(_) => continue,
+            }
+            let end = address + object.data_words().len();
+            if end > MEMORY_WORDS {
+                return Err(CoreImageError::OutOfMemory {
+                    card: index,
+                    address: end - 1,
+                });
+            }
+            let words = object
+                .data_words()
+                .iter()
+                .enumerate()
+                .zip(object.relocations())
+                .map(|((word, &value), relocation)| match relocation {
+                    Relocation::Absolute => Ok(value),
+                    Relocation::Relocatable => Ok(value.wrapping_add(origin)),
+                    Relocation::Libf | Relocation::Call => {
+                        Err(CoreImageError::Unresolved { card: index, word })
+                    }
+                })
+                .collect::<Result<Vec<u16>, _>>()?;
+            loaded.push((address, words));
+        }
+        let (end_card, entry) = entry.ok_or(CoreImageError::MissingEnd)?;
+        let address = |address: usize| {
+            u16::try_from(address)
+                .ok()
+                .filter(|_| address < MEMORY_WORDS)
+                .ok_or(CoreImageError::OutOfMemory {
+                    card: end_card,
+                    address,
+                })
+        };
+        let entry = address(entry)?;
+
+        let start = loaded
+            .iter()
+            .map(|(address, _)| *address)
+            .min()
+            .unwrap_or(usize::from(entry));
+        let end = loaded
+            .iter()
+            .map(|(address, words)| address + words.len())
+            .max()
+            .unwrap_or(start);
+        let mut words = vec![0; end - start];
+        for (address, data) in loaded {
+            words[address - start..address - start + data.len()].copy_from_slice(&data);
+        }
+        Ok(CoreImage {
+            origin: address(start)?,
+            words,
+            entry,
+        })
+    }
+
+    /// Address of the first word
+    pub fn origin(&self) -> u16 {
+        self.origin
+    }
+
+    /// Address the program starts at
+    pub fn entry(&self) -> u16 {
+        self.entry
+    }
+
+    /// The memory words from `origin`
+    pub fn to_words(&self) -> Vec<u16> {
+        self.words.clone()
+    }
+
+    /// Get the word at a memory address, if the image holds it
+    pub fn word(&self, address: u16) -> Option<u16> {
+        let offset = usize::from(address.checked_sub(self.origin)?);
+        self.words.get(offset).copied()
+    }
+
+    /// Punch the image as an absolute object deck
+    ///
+    /// Cards follow the object card layout (see `ObjectCard`), not the
+    /// core image card format: data cards of up to 45 absolute words at
+    /// consecutive addresses from `origin`, with no relocation indicators,
+    /// then an end card holding the entry point. Loading the deck at origin
+    /// 0 gives back the same image.
+    pub fn to_object_deck(&self) -> Deck {
+        Deck::from_cards(punch_object_deck(
+            [(self.origin, self.words.as_slice(), &[][..])],
+            self.entry,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::CardFormat;
+    use crate::ibm1130::assemble;
+    use crate::punch_card::PunchCard;
+
+    const SOURCE_DECK: &str = include_str!("../../tests/fixtures/ibm1130_source.txt");
+    const SUM_OBJECT_DECK: &[u8] =
+        include_bytes!("../../tests/fixtures/ibm1130_sum_object_synthetic.bin");
+
+    const SUM_WORDS: [u16; 10] = [
+        0x6500, 0x000A, 0xC500, 0x0109, 0x8004, 0xD003, 0x71FF, 0x70FA, 0x3000, 0x0000,
+    ];
+
+    fn source(lines: &[&str]) -> Deck {
+        lines
+            .iter()
+            .map(|line| PunchCard::from_text(&format!("{:20}{}", "", line)))
+            .collect()
+    }
+
+    #[test]
+    fn test_load_assembled_program() {
+        let deck: Deck = SOURCE_DECK.lines().map(PunchCard::from_text).collect();
+        let image = CoreImage::from_object_deck(&assemble(&deck).unwrap(), 0).unwrap();
+        assert_eq!(image.origin(), 0x0100);
+        assert_eq!(image.entry(), 0x0100);
+        assert_eq!(image.to_words(), SUM_WORDS);
+        assert_eq!(image.word(0x0103), Some(0x0109));
+        assert_eq!(image.word(0x00FF), None);
+        assert_eq!(image.word(0x010A), None);
+    }
+
+    #[test]
+    fn test_gaps_are_zero() {
+        let deck = source(&[
+            "      ORG     /0200",
+            "START LD   L  DATA",
+            "      WAIT",
+            "      ORG     /0210",
+            "DATA  DC      /1234",
+            "      END     START",
+        ]);
+        let image = CoreImage::from_object_deck(&assemble(&deck).unwrap(), 0).unwrap();
+        assert_eq!(image.origin(), 0x0200);
+        let words = image.to_words();
+        assert_eq!(words.len(), 0x11);
+        assert_eq!(&words[..3], &[0xC400, 0x0210, 0x3000]);
+        assert!(words[3..0x10].iter().all(|&word| word == 0));
+        assert_eq!(words[0x10], 0x1234);
+    }
+
+    #[test]
+    fn test_relocate_fixture() {
+        let deck: Deck = SUM_OBJECT_DECK
+            .chunks(120)
+            .map(|data| PunchCard::parse(data, CardFormat::FullBinary120).unwrap())
+            .collect();
+        let image = CoreImage::from_object_deck(&deck, 0x0200).unwrap();
+        assert_eq!(image.origin(), 0x0300);
+        assert_eq!(image.entry(), 0x0300);
+        // Only the LD address word is marked relocatable
+        let mut expected = SUM_WORDS;
+        expected[3] = 0x0309;
+        assert_eq!(image.to_words(), expected);
+    }
+
+    #[test]
+    fn test_relocate_address_constant() {
+        let deck = source(&[
+            "START LD   L  VALUE",
+            "      WAIT",
+            "PTR   DC      VALUE",
+            "VALUE DC      7",
+            "SIZE  DC      VALUE-START",
+            "      END     START",
+        ]);
+        let object = assemble(&deck).unwrap();
+        let card = ObjectCard::parse(object.get(0).unwrap()).unwrap();
+        assert_eq!(
+            card.relocations(),
+            vec![
+                Relocation::Absolute,
+                Relocation::Relocatable,
+                Relocation::Absolute,
+                Relocation::Relocatable,
+                Relocation::Absolute,
+                Relocation::Absolute,
+            ]
+        );
+
+        // Loaded where it was assembled, nothing changes
+        let image = CoreImage::from_object_deck(&object, 0).unwrap();
+        assert_eq!(image.to_words(), [0xC400, 0x0004, 0x3000, 0x0004, 7, 4]);
+        // Loaded at /0500, the addresses move but not the constants
+        let moved = CoreImage::from_object_deck(&object, 0x0500).unwrap();
+        assert_eq!(moved.origin(), 0x0500);
+        assert_eq!(moved.entry(), 0x0500);
+        assert_eq!(moved.to_words(), [0xC400, 0x0504, 0x3000, 0x0504, 7, 4]);
+
+        // An absolute program has nothing to relocate
+        let mut absolute = vec![format!("{:6}ABS", "")];
+        absolute.extend(SOURCE_DECK.lines().map(|line| line[20..].to_string()));
+        let lines: Vec<&str> = absolute.iter().map(String::as_str).collect();
+        let object = assemble(&source(&lines)).unwrap();
+        let image = CoreImage::from_object_deck(&object, 0).unwrap();
+        assert_eq!(image.to_words(), SUM_WORDS);
+        assert!(
+            ObjectCard::parse(object.get(0).unwrap())
+                .unwrap()
+                .relocations()
+                .iter()
+                .all(|&relocation| relocation == Relocation::Absolute)
+        );
+    }
+
+    #[test]
+    fn test_punch_deck_roundtrip() {
+        let words: Vec<u16> = (0..100).collect();
+        let image = CoreImage::new(0x0400, words, 0x0432);
+        let deck = image.to_object_deck();
+        assert_eq!(deck.len(), 4);
+        assert_eq!(CoreImage::from_object_deck(&deck, 0).unwrap(), image);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let data = ObjectCard::new(ObjectCardKind::Data, 0x7FF0, &[0; 20]).unwrap();
+        let end = ObjectCard::new(ObjectCardKind::End, 0, &[]).unwrap();
+        let deck = Deck::from_cards(vec![data.to_card(), end.to_card()]);
+        assert_eq!(
+            CoreImage::from_object_deck(&deck, 0),
+            Err(CoreImageError::OutOfMemory {
+                card: 0,
+                address: 0x8003
+            })
+        );
+
+        let call = ObjectCard::new(ObjectCardKind::Data, 0, &[0, 0])
+            .unwrap()
+            .with_relocation([0b0011 << 12, 0, 0, 0, 0, 0]);
+        let deck = Deck::from_cards(vec![call.to_card(), end.to_card()]);
+        assert_eq!(
+            CoreImage::from_object_deck(&deck, 0),
+            Err(CoreImageError::Unresolved { card: 0, word: 1 })
+        );
+
+        // Relocating the end card past the end of memory
+        let start = ObjectCard::new(ObjectCardKind::End, 0xF000, &[]).unwrap();
+        let deck = Deck::from_cards(vec![start.to_card()]);
+        assert_eq!(
+            CoreImage::from_object_deck(&deck, 0x2000),
+            Err(CoreImageError::OutOfMemory {
+                card: 0,
+                address: 0x11000
+            })
+        );
+
+        assert_eq!(
+            CoreImage::from_object_deck(&Deck::new(), 0),
+            Err(CoreImageError::MissingEnd)
+        );
+        let text = Deck::from_cards(vec![PunchCard::from_text("// XEQ")]);
+        assert!(matches!(
+            CoreImage::from_object_deck(&text, 0),
+            Err(CoreImageError::Object(_))
+        ));
+    }
+}
@@ -0,0 +1,653 @@
+// IBM 1130 Data Cards
+//
+// FORTRAN programs read and write data cards through FORMAT statements such
+// as `(I5, A20, F4.1, F5.2)`: each descriptor takes the next columns of the
+// card. Supported descriptors:
+// - `Iw`: integer in w columns
+// - `Fw.d`: real in w columns, with d digits after an implied decimal point
+// - `Ew.d`: real with an exponent, read like `Fw.d`
+// - `Aw`: w characters of text
+// - `wX`: skip w columns
+//
+// A descriptor or a parenthesized group may have a repeat count, as in
+// `3I5` or `2(I3, 2X)`. As in 1130 FORTRAN, blanks in a numeric field read
+// as zeros, so `"  12 "` in an I5 field is 120 and a blank field is 0.
+
+use super::punch_text;
+use crate::punch_card::PunchCard;
+use std::fmt;
+use std::ops::Range;
+
+/// Columns on a card
+const CARD_COLUMNS: usize = 80;
+
+/// One field of a FORMAT statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatItem {
+    Integer { width: usize },
+    Fixed { width: usize, decimals: usize },
+    Exponent { width: usize, decimals: usize },
+    Alpha { width: usize },
+    Skip { width: usize },
+}
+
+impl FormatItem {
+    /// Columns the field takes
+    pub fn width(self) -> usize {
+        match self {
+            FormatItem::Integer { width }
+            | FormatItem::Fixed { width, .. }
+            | FormatItem::Exponent { width, .. }
+            | FormatItem::Alpha { width }
+            | FormatItem::Skip { width } => width,
+        }
+    }
+}
+
+impl fmt::Display for FormatItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatItem::Integer { width } => write!(f, "I{}", width),
+            FormatItem::Fixed { width, decimals } => write!(f, "F{}.{}", width, decimals),
+            FormatItem::Exponent { width, decimals } => write!(f, "E{}.{}", width, decimals),
+            FormatItem::Alpha { width } => write!(f, "A{}", width),
+            FormatItem::Skip { width } => write!(f, "{}X", width),
+        }
+    }
+}
+
+/// A value read from or written to a data card
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Real(f64),
+    Text(String),
+}
+
+/// Errors from parsing a FORMAT or reading and writing cards with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// The FORMAT does not parse (0-based character position)
+    Syntax { position: usize, message: String },
+    /// A card field does not hold a value of its type (0-based columns)
+    Field {
+        columns: Range<usize>,
+        message: String,
+    },
+    /// The values do not match the FORMAT's fields
+    Values(String),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Syntax { position, message } => {
+                write!(f, "FORMAT position {}: {}", position + 1, message)
+            }
+            FormatError::Field { columns, message } => {
+                write!(
+                    f,
+                    "Columns {}-{}: {}",
+                    columns.start + 1,
+                    columns.end,
+                    message
+                )
+            }
+            FormatError::Values(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// A parsed FORMAT statement, with repeats expanded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatSpec {
+    items: Vec<FormatItem>,
+}
+
+impl FormatSpec {
+    /// The fields in card order
+    pub fn items(&self) -> &[FormatItem] {
+        &self.items
+    }
+
+    /// Columns the fields take
+    pub fn width(&self) -> usize {
+        span(&self.items)
+    }
+
+    /// The fields that hold values, with their columns (0-based)
+    fn fields(&self) -> impl Iterator<Item = (FormatItem, Range<usize>)> + '_ {
+        let mut start = 0;
+        self.items.iter().filter_map(move |&item| {
+            let columns = start..start + item.width();
+            start = columns.end;
+            (!matches!(item, FormatItem::Skip { .. })).then_some((item, columns))
+        })
+    }
+
+    /// Read one value per field from a card
+    ///
+    /// Text fields keep their trailing blanks.
+    pub fn read_card(&self, card: &PunchCard) -> Result<Vec<Value>, FormatError> {
+        let mut text = [' '; CARD_COLUMNS];
+        for (index, column) in card.columns().iter().enumerate() {
+            text[index] = column.to_char().ok_or_else(|| FormatError::Field {
+                columns: index..index + 1,
+                message: "punches are not a character".to_string(),
+            })?;
+        }
+        self.fields()
+            .map(|(item, columns)| {
+                let field: String = text[columns.clone()].iter().collect();
+                let error = |message: String| FormatError::Field {
+                    columns: columns.clone(),
+                    message,
+                };
+                match item {
+                    FormatItem::Integer { .. } => read_integer(&field).map(Value::Integer),
+                    FormatItem::Fixed { decimals, .. } | FormatItem::Exponent { decimals, .. } => {
+                        read_real(&field, decimals).map(Value::Real)
+                    }
+                    _ => Ok(Value::Text(field)),
+                }
+                .map_err(error)
+            })
+            .collect()
+    }
+
+    /// Punch values into a card, one per field
+    ///
+    /// Numbers are right-justified and text left-justified. A number too
+    /// wide for its field is punched as asterisks, as FORTRAN prints it.
+    /// Integers may be written to real fields, but not reals to integer
+    /// fields.
+    pub fn write_card(&self, values: &[Value]) -> Result<PunchCard, FormatError> {
+        let count = self.fields().count();
+        if values.len() != count {
+            return Err(FormatError::Values(format!(
+                "FORMAT has {} field(s), but {} value(s) were given",
+                count,
+                values.len()
+            )));
+        }
+        let mut values = values.iter();
+        let mut text = String::new();
+        for &item in &self.items {
+            let width = item.width();
+            if let FormatItem::Skip { .. } = item {
+                text.push_str(&" ".repeat(width));
+                continue;
+            }
+            let value = values.next().expect("one value per field");
+            let field = match (item, value) {
+                (FormatItem::Integer { .. }, Value::Integer(value)) => value.to_string(),
+                (FormatItem::Fixed { decimals, .. }, value) => {
+                    format!("{:.*}", decimals, real(item, value)?)
+                }
+                (FormatItem::Exponent { decimals, .. }, value) => {
+                    write_exponent(real(item, value)?, decimals)
+                }
+                (FormatItem::Alpha { .. }, Value::Text(value)) => {
+                    text.push_str(&format!("{:<width$.width$}", value));
+                    continue;
+                }
+                _ => {
+                    return Err(FormatError::Values(format!(
+                        "{} cannot hold {:?}",
+                        item, value
+                    )));
+                }
+            };
+            if field.len() > width {
+                text.push_str(&"*".repeat(width));
+            } else {
+                text.push_str(&format!("{:>width$}", field));
+            }
+        }
+        punch_text(&text).map_err(FormatError::Values)
+    }
+}
+
+impl fmt::Display for FormatSpec {
+    /// The expanded FORMAT, as `(I5,A20,F4.1)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let items: Vec<String> = self.items.iter().map(ToString::to_string).collect();
+        write!(f, "({})", items.join(","))
+    }
+}
+
+/// Parse a FORMAT statement's field list, such as `(I5, F10.2, A20)`
+///
+/// The outer parentheses are optional, and blanks are ignored. Fails on
+/// descriptors other than I, F, E, A and X, or fields wider than a card.
+pub fn parse_format(format: &str) -> Result<FormatSpec, FormatError> {
+    let chars: Vec<(usize, char)> = format
+        .char_indices()
+        .filter(|(_, ch)| !ch.is_whitespace())
+        .map(|(position, ch)| (position, ch.to_ascii_uppercase()))
+        .collect();
+    let mut parser = Parser {
+        chars: &chars,
+        index: 0,
+        end: format.len(),
+    };
+    let wrapped = chars.first().is_some_and(|&(_, ch)| ch == '(')
+        && parser.closing(0) == Some(chars.len() - 1);
+    if wrapped {
+        parser.index = 1;
+    }
+    let items = parser.list()?;
+    if wrapped {
+        parser.index += 1;
+    }
+    if let Some(&(position, ch)) = chars.get(parser.index) {
+        return Err(FormatError::Syntax {
+            position,
+            message: format!("unexpected {:?}", ch),
+        });
+    }
+    Ok(FormatSpec { items })
+}
+
+struct Parser<'a> {
+    chars: &'a [(usize, char)],
+    index: usize,
+    /// Position to report for an unexpected end
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> FormatError {
+        FormatError::Syntax {
+            position: self.chars.get(self.index).map_or(self.end, |&(p, _)| p),
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).map(|&(_, ch)| ch)
+    }
+
+    /// Index of the parenthesis closing the one at `open`
+    fn closing(&self, open: usize) -> Option<usize> {
+        let mut depth = 0;
+        for (index, &(_, ch)) in self.chars.iter().enumerate().skip(open) {
+            match ch {
+                '(' => depth += 1,
+                ')' if depth == 1 => return Some(index),
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// A repeat count, width or number of decimals, if there are digits
+    ///
+    /// Fails on a number over 80, which no field on a card can use.
+    fn number(&mut self) -> Result<Option<usize>, FormatError> {
+        let start = self.index;
+        while self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.index += 1;
+        }
+        if start == self.index {
+            return Ok(None);
+        }
+        let digits: String = self.chars[start..self.index]
+            .iter()
+            .map(|&(_, ch)| ch)
+            .collect();
+        match digits.parse() {
+            Ok(number) if number <= CARD_COLUMNS => Ok(Some(number)),
+            _ => {
+                self.index = start;
+                Err(self.error(&format!("{} is more than 80", digits)))
+            }
+        }
+    }
+
+    /// A width, which must be at least 1
+    fn width(&mut self) -> Result<usize, FormatError> {
+        let start = self.index;
+        match self.number()? {
+            Some(0) | None => {
+                self.index = start;
+                Err(self.error("expected a field width"))
+            }
+            Some(width) => Ok(width),
+        }
+    }
+
+    /// Descriptors separated by commas, up to `)` or the end
+    fn list(&mut self) -> Result<Vec<FormatItem>, FormatError> {
+        let mut items = Vec::new();
+        loop {
+            let start = self.index;
+            items.extend(self.item()?);
+            if span(&items) > CARD_COLUMNS {
+                self.index = start;
+                return Err(self.error("fields take more than 80 columns"));
+            }
+            match self.peek() {
+                Some(',') => self.index += 1,
+                _ => return Ok(items),
+            }
+        }
+    }
+
+    /// One descriptor or group, expanded by its repeat count
+    ///
+    /// Fails before expanding if the repeats would take more than 80
+    /// columns.
+    fn item(&mut self) -> Result<Vec<FormatItem>, FormatError> {
+        let start = self.index;
+        let count = self.number()?;
+        if count == Some(0) {
+            return Err(self.error("repeat count must be at least 1"));
+        }
+        let repeat = |parser: &mut Self, items: Vec<FormatItem>| {
+            let count = count.unwrap_or(1);
+            match span(&items).checked_mul(count) {
+                Some(width) if width <= CARD_COLUMNS => Ok(items.repeat(count)),
+                _ => {
+                    parser.index = start;
+                    Err(parser.error("repeated fields take more than 80 columns"))
+                }
+            }
+        };
+        let Some(ch) = self.peek() else {
+            return Err(self.error("expected a descriptor"));
+        };
+        self.index += 1;
+        let item = match ch {
+            '(' => {
+                let group = self.list()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error("expected )"));
+                }
+                self.index += 1;
+                return repeat(self, group);
+            }
+            'X' => {
+                return Ok(vec![FormatItem::Skip {
+                    width: count.unwrap_or(1),
+                }]);
+            }
+            'I' => FormatItem::Integer {
+                width: self.width()?,
+            },
+            'A' => FormatItem::Alpha {
+                width: self.width()?,
+            },
+            'F' | 'E' => {
+                let width = self.width()?;
+                if self.peek() != Some('.') {
+                    return Err(self.error("expected . and the digits after the point"));
+                }
+                self.index += 1;
+                let decimals = self
+                    .number()?
+                    .ok_or_else(|| self.error("expected the digits after the point"))?;
+                if decimals >= width {
+                    return Err(self.error("decimals must be fewer than the width"));
+                }
+                if ch == 'F' {
+                    FormatItem::Fixed { width, decimals }
+                } else {
+                    FormatItem::Exponent { width, decimals }
+                }
+            }
+            _ => {
+                self.index -= 1;
+                return Err(self.error(&format!("unsupported descriptor {:?}", ch)));
+            }
+        };
+        repeat(self, vec![item])
+    }
+}
+
+/// Columns a run of fields takes
+fn span(items: &[FormatItem]) -> usize {
+    items.iter().map(|item| item.width()).sum()
+}
+
+/// Blanks after the first nonblank character read as zeros
+fn blanks_as_zeros(field: &str) -> String {
+    field.trim_start().replace(' ', "0")
+}
+
+/// Split off a leading sign, returning whether it was minus
+fn sign(text: &str) -> (bool, &str) {
+    match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    }
+}
+
+fn read_integer(field: &str) -> Result<i64, String> {
+    let text = blanks_as_zeros(field);
+    let (negative, digits) = sign(&text);
+    if !digits.chars().all(|ch| ch.is_ascii_digit()) {
+        return Err(format!("{:?} is not an integer", field));
+    }
+    let value: i64 = match digits {
+        "" => 0,
+        _ => digits
+            .parse()
+            .map_err(|_| format!("{:?} is out of range", field))?,
+    };
+    Ok(if negative { -value } else { value })
+}
+
+fn read_real(field: &str, decimals: usize) -> Result<f64, String> {
+    let error = || format!("{:?} is not a number", field);
+    let text = blanks_as_zeros(field);
+    let (mantissa, exponent) = text.split_once('E').unwrap_or((&text, "0"));
+    let (negative, mantissa) = sign(mantissa);
+    let (_, exponent_digits) = sign(exponent);
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if ![whole, fraction, exponent_digits]
+        .iter()
+        .all(|part| part.chars().all(|ch| ch.is_ascii_digit()))
+        || exponent_digits.is_empty()
+    {
+        return Err(error());
+    }
+    let exponent: i32 = exponent.parse().map_err(|_| error())?;
+    // Without a written point, the last `decimals` digits are the fraction
+    let scale = if mantissa.contains('.') {
+        exponent
+    } else {
+        exponent - decimals as i32
+    };
+    let digits = format!("{}{}", whole, fraction);
+    let digits = if digits.is_empty() { "0" } else { &digits };
+    let value: f64 = format!("{}e{}", digits, scale - fraction.len() as i32)
+        .parse()
+        .map_err(|_| error())?;
+    Ok(if negative { -value } else { value })
+}
+
+/// The value to write to a real field
+fn real(item: FormatItem, value: &Value) -> Result<f64, FormatError> {
+    match value {
+        Value::Real(value) => Ok(*value),
+        Value::Integer(value) => Ok(*value as f64),
+        Value::Text(_) => Err(FormatError::Values(format!(
+            "{} cannot hold {:?}",
+            item, value
+        ))),
+    }
+}
+
+/// FORTRAN E output: `-0.1235E+03`, with `decimals` digits after the point
+fn write_exponent(value: f64, decimals: usize) -> String {
+    let mut exponent = 0;
+    let mut mantissa = value.abs();
+    if mantissa != 0.0 {
+        exponent = mantissa.log10().floor() as i32 + 1;
+        mantissa /= 10f64.powi(exponent);
+    }
+    let mut digits = format!("{:.*}", decimals, mantissa);
+    // Rounding can carry into the units place: 0.99995 to 1.0000
+    if digits.starts_with('1') {
+        exponent += 1;
+        digits = format!("{:.*}", decimals, mantissa / 10.0);
+    }
+    format!(
+        "{}{}E{}{:02}",
+        if value < 0.0 { "-" } else { "" },
+        digits,
+        if exponent < 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA_CARD: &str = include_str!("../../tests/fixtures/ibm1130_data_card.txt");
+
+    fn card() -> PunchCard {
+        PunchCard::from_text(DATA_CARD.trim_end_matches('\n'))
+    }
+
+    fn read(format: &str) -> Vec<Value> {
+        parse_format(format).unwrap().read_card(&card()).unwrap()
+    }
+
+    fn text(value: &str) -> Value {
+        Value::Text(value.to_string())
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let spec = parse_format("(I5, 2(F6.2, 1X), A4, 3X, E10.3)").unwrap();
+        assert_eq!(spec.to_string(), "(I5,F6.2,1X,F6.2,1X,A4,3X,E10.3)");
+        assert_eq!(spec.width(), 36);
+        assert_eq!(parse_format("2i3,x").unwrap().to_string(), "(I3,I3,1X)");
+
+        let error = |format: &str| match parse_format(format) {
+            Err(FormatError::Syntax { position, .. }) => position,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(error("(I5, H3)"), 5);
+        assert_eq!(error("(I5, F6)"), 7);
+        assert_eq!(error("(I0)"), 2);
+        assert_eq!(error("(2(I3)"), 6);
+        assert_eq!(error("(I5))"), 4);
+        assert_eq!(error("(F3.3)"), 5);
+        assert_eq!(error("(9A10)"), 1);
+        assert_eq!(error("(A50, A40)"), 6);
+        assert_eq!(error("(I81)"), 2);
+        assert_eq!(error("(99999999999999I1)"), 1);
+        assert_eq!(error("(I18446744073709551615,I5)"), 2);
+        assert_eq!(error("(20(20(20(20(I1)))))"), 7);
+    }
+
+    #[test]
+    fn test_read_fixture_as_payroll() {
+        assert_eq!(
+            read("(I5, A20, F4.1, F5.2)"),
+            vec![
+                Value::Integer(42),
+                text("JONES, MARY         "),
+                Value::Real(37.5),
+                Value::Real(22.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_fixture_other_formats() {
+        // The same columns read as plain digits and text
+        assert_eq!(
+            read("(I3, I2, 5X, A1, 14X, 2I2)"),
+            vec![
+                Value::Integer(0),
+                Value::Integer(42),
+                text(","),
+                Value::Integer(3),
+                Value::Integer(75),
+            ]
+        );
+        // The numbers after the payroll fields: an explicit point, an
+        // exponent, and a field with a trailing blank that reads as zero
+        assert_eq!(
+            read("(34X, F7.2, E11.4, I4)"),
+            vec![Value::Real(-12.5), Value::Real(1250.0), Value::Integer(70)]
+        );
+        assert_eq!(read("(36X, I3)"), vec![Value::Integer(-12)]);
+
+        let spec = parse_format("(5X, I5)").unwrap();
+        assert_eq!(
+            spec.read_card(&card()),
+            Err(FormatError::Field {
+                columns: 5..10,
+                message: "\"JONES\" is not an integer".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_blanks_read_as_zero() {
+        let spec = parse_format("(I5, I5, F5.2, F6.1)").unwrap();
+        let card = PunchCard::from_text("  12       -3 5  1 2");
+        assert_eq!(
+            spec.read_card(&card).unwrap(),
+            vec![
+                Value::Integer(120),
+                Value::Integer(0),
+                Value::Real(-3.05),
+                Value::Real(102.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_card() {
+        let spec = parse_format("(I5, A20, F4.1, F5.2)").unwrap();
+        let values = read("(I5, A20, F4.1, F5.2)");
+        let card = spec.write_card(&values).unwrap();
+        assert_eq!(
+            card.to_text().trim_end(),
+            "   42JONES, MARY         37.522.50"
+        );
+        assert_eq!(spec.read_card(&card).unwrap(), values);
+
+        let spec = parse_format("(I3, 1X, E11.4, 1X, E10.3, F6.1)").unwrap();
+        let card = spec
+            .write_card(&[
+                Value::Integer(12345),
+                Value::Real(1234.6),
+                Value::Real(-0.000999951),
+                Value::Integer(7),
+            ])
+            .unwrap();
+        assert_eq!(
+            card.to_text().trim_end(),
+            "***  0.1235E+04 -0.100E-02   7.0"
+        );
+
+        assert!(matches!(
+            spec.write_card(&[Value::Integer(1)]),
+            Err(FormatError::Values(_))
+        ));
+        let integer = parse_format("(I5)").unwrap();
+        assert!(integer.write_card(&[Value::Real(1.0)]).is_err());
+        let alpha = parse_format("(A3)").unwrap();
+        assert!(alpha.write_card(&[text("A~B")]).is_err());
+        assert_eq!(
+            alpha
+                .write_card(&[text("ABCDE")])
+                .unwrap()
+                .to_text()
+                .trim_end(),
+            "ABC"
+        );
+    }
+}
@@ -0,0 +1,235 @@
+// IBM 1442 Card Reader/Punch
+//
+// A device model for an 1130 emulator. Cards feed from the hopper to the
+// read station, where the program reads them a column at a time, and blank
+// cards are punched a column at a time and go to the stacker. Each column
+// is a 12-bit image, row 12 in bit 11 down to row 9 in bit 0 (see
+// `PunchCard::to_u16_columns`); the 1130 reads it into the high 12 bits of
+// a word, so shift it left 4.
+//
+// The model does no timing: an emulator decides when a column is ready and
+// when to raise its interrupts, and asks the model for the data.
+
+use super::classify_read_card;
+use crate::deck::Deck;
+use crate::punch_card::PunchCard;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Columns on a card
+const CARD_COLUMNS: usize = 80;
+
+/// Errors from punching a card
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceError {
+    /// All 80 columns of the card have been punched; feed first
+    CardFull,
+    /// The column image has bits above row 12
+    InvalidImage(u16),
+}
+
+impl fmt::Display for DeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceError::CardFull => write!(f, "All 80 columns are punched; feed a card"),
+            DeviceError::InvalidImage(image) => {
+                write!(f, "Column image {:#06X} has bits above row 12", image)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeviceError {}
+
+/// A 1442 card reader/punch
+#[derive(Debug, Clone, Default)]
+pub struct CardReaderPunch {
+    hopper: VecDeque<PunchCard>,
+    /// The card being read, and the next column to read
+    read_station: Option<(PunchCard, usize)>,
+    /// Column images punched into the card being punched
+    punch_station: Vec<u16>,
+    stacker: Deck,
+    cards_read: usize,
+}
+
+impl CardReaderPunch {
+    /// Create a reader/punch with a deck in the hopper
+    pub fn new(deck: Deck) -> Self {
+        CardReaderPunch {
+            hopper: deck.into_cards().into(),
+            ..CardReaderPunch::default()
+        }
+    }
+
+    /// Put more cards at the back of the hopper
+    pub fn load(&mut self, deck: Deck) {
+        self.hopper.extend(deck.into_cards());
+    }
+
+    /// Move the next card into the read station, and stack the card being
+    /// punched
+    ///
+    /// Returns false when the hopper was empty, leaving the read station
+    /// empty. A card is only stacked if at least one column was punched.
+    pub fn feed(&mut self) -> bool {
+        if !self.punch_station.is_empty() {
+            let columns = std::mem::take(&mut self.punch_station);
+            self.stacker
+                .push(classify_read_card(PunchCard::from_u16_columns(&columns)));
+        }
+        self.read_station = self.hopper.pop_front().map(|card| (card, 0));
+        if self.read_station.is_some() {
+            self.cards_read += 1;
+        }
+        self.read_station.is_some()
+    }
+
+    /// Read the next column of the card in the read station
+    ///
+    /// Returns `None` once all 80 columns are read, or with no card.
+    pub fn read_column(&mut self) -> Option<u16> {
+        let (card, column) = self.read_station.as_mut()?;
+        if *column >= CARD_COLUMNS {
+            return None;
+        }
+        let image = card.to_u16_columns()[*column];
+        *column += 1;
+        Some(image)
+    }
+
+    /// The next column `read_column` returns (0-based), if a card is there
+    pub fn read_position(&self) -> Option<usize> {
+        self.read_station.as_ref().map(|&(_, column)| column)
+    }
+
+    /// Punch the next column of the card being punched
+    pub fn punch_column(&mut self, image: u16) -> Result<(), DeviceError> {
+        if image & 0xF000 != 0 {
+            return Err(DeviceError::InvalidImage(image));
+        }
+        if self.punch_station.len() >= CARD_COLUMNS {
+            return Err(DeviceError::CardFull);
+        }
+        self.punch_station.push(image);
+        Ok(())
+    }
+
+    /// The punched cards, in punching order
+    pub fn stacker(&self) -> &Deck {
+        &self.stacker
+    }
+
+    /// Empty the stacker
+    pub fn take_stacker(&mut self) -> Deck {
+        std::mem::take(&mut self.stacker)
+    }
+
+    /// Whether no cards are left to feed
+    pub fn hopper_empty(&self) -> bool {
+        self.hopper.is_empty()
+    }
+
+    /// Whether the card in the read station is the last one, so the 1442
+    /// would signal last card
+    pub fn last_card(&self) -> bool {
+        self.read_station.is_some() && self.hopper.is_empty()
+    }
+
+    /// Cards fed to the read station so far
+    pub fn cards_read(&self) -> usize {
+        self.cards_read
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::CardFormat;
+    use crate::ibm1130::{ObjectCard, SourceCard};
+
+    const SOURCE_DECK: &str = include_str!("../../tests/fixtures/ibm1130_source.txt");
+    const SUM_OBJECT_DECK: &[u8] =
+        include_bytes!("../../tests/fixtures/ibm1130_sum_object_synthetic.bin");
+
+    fn three_cards() -> Deck {
+        let mut cards: Vec<PunchCard> = SOURCE_DECK
+            .lines()
+            .take(2)
+            .map(PunchCard::from_text)
+            .collect();
+        cards.push(PunchCard::parse(&SUM_OBJECT_DECK[..120], CardFormat::FullBinary120).unwrap());
+        Deck::from_cards(cards)
+    }
+
+    #[test]
+    fn test_read_and_punch_a_copy() {
+        let deck = three_cards();
+        let mut device = CardReaderPunch::new(deck.clone());
+        assert!(!device.hopper_empty());
+        assert_eq!(device.read_column(), None);
+
+        let mut read = Vec::new();
+        while device.feed() {
+            assert_eq!(device.last_card(), device.cards_read() == 3);
+            let mut columns = Vec::new();
+            while let Some(image) = device.read_column() {
+                columns.push(image);
+                device.punch_column(image).unwrap();
+            }
+            assert_eq!(device.read_position(), Some(80));
+            read.push(columns);
+        }
+        assert!(device.hopper_empty());
+        assert!(!device.last_card());
+        assert_eq!(device.cards_read(), 3);
+
+        let expected: Vec<Vec<u16>> = deck
+            .iter()
+            .map(|card| card.to_u16_columns().to_vec())
+            .collect();
+        assert_eq!(read, expected);
+        // The asterisk in column 21 is rows 11, 4 and 8
+        assert_eq!(read[0][20], 0b0100_0010_0010);
+
+        let copy = device.take_stacker();
+        assert_eq!(copy.len(), 3);
+        for (original, punched) in deck.iter().zip(&copy) {
+            assert_eq!(punched.to_u16_columns(), original.to_u16_columns());
+        }
+        assert!(SourceCard::parse(copy.get(1).unwrap()).is_ok());
+        assert_eq!(
+            ObjectCard::parse(copy.get(2).unwrap()).unwrap(),
+            ObjectCard::parse(deck.get(2).unwrap()).unwrap()
+        );
+        assert!(device.stacker().is_empty());
+    }
+
+    #[test]
+    fn test_punch_errors_and_partial_cards() {
+        let mut device = CardReaderPunch::default();
+        assert!(device.hopper_empty());
+        assert!(!device.feed());
+        assert_eq!(
+            device.punch_column(0x1000),
+            Err(DeviceError::InvalidImage(0x1000))
+        );
+        for _ in 0..80 {
+            device.punch_column(0x0800).unwrap();
+        }
+        assert_eq!(device.punch_column(0x0800), Err(DeviceError::CardFull));
+        device.feed();
+        assert_eq!(device.stacker().get(0).unwrap().to_text(), "&".repeat(80));
+
+        // A partly punched card is stacked blank after the last column
+        device.punch_column(0x0000).unwrap();
+        device.punch_column(0x0900).unwrap();
+        device.load(three_cards());
+        assert!(device.feed());
+        assert_eq!(device.stacker().len(), 2);
+        assert_eq!(device.stacker().get(1).unwrap().to_text().trim_end(), " A");
+        // Feeding with nothing punched stacks nothing
+        assert!(device.feed());
+        assert_eq!(device.stacker().len(), 2);
+    }
+}
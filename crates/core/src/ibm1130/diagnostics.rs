@@ -0,0 +1,270 @@
+// IBM 1130 Deck Diagnostics
+//
+// Validates a whole assembler source deck: every card's format (see
+// `validate_source_format`), then checks that need the whole deck:
+// - Sequence numbers rise through the deck
+// - There is exactly one END statement and nothing after it
+// - No label is defined twice
+//
+// The 1130 coding form has no continuation column, so there are no
+// continuation cards to check.
+
+use super::opcodes::{self, LabelRule};
+use super::{
+    LABEL_COLUMNS, OPCODE_COLUMNS, SEQUENCE_COLUMNS, SequenceError, Severity, SourceCard,
+    check_sequence, validate_source_format,
+};
+use crate::deck::Deck;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+/// A problem with one card of a deck
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Diagnostic {
+    /// Deck position of the card (0-based)
+    pub card: usize,
+    /// The columns to underline (0-based)
+    pub columns: Range<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(card: usize, columns: Range<usize>, severity: Severity, message: String) -> Self {
+        Diagnostic {
+            card,
+            columns,
+            severity,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    /// Compiler style: `card 3, column 27: error: unknown opcode "LDA"`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "card {}, column {}: {}: {}",
+            self.card + 1,
+            self.columns.start + 1,
+            severity,
+            self.message
+        )
+    }
+}
+
+/// Every problem found in a source deck, in card order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeckDiagnostics {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl DeckDiagnostics {
+    /// Count the diagnostics of a severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+
+    /// Whether the assembler would reject the deck
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+}
+
+impl fmt::Display for DeckDiagnostics {
+    /// One line per diagnostic, then a summary line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}", diagnostic)?;
+        }
+        write!(
+            f,
+            "{} error(s), {} warning(s)",
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        )
+    }
+}
+
+/// Validate an assembler source deck
+///
+/// Sequence numbers are only checked if some card has one. Blank cards may
+/// follow END.
+pub fn validate_source_deck(deck: &Deck) -> DeckDiagnostics {
+    let mut diagnostics = Vec::new();
+    for (index, card) in deck.iter().enumerate() {
+        diagnostics.extend(
+            validate_source_format(card)
+                .into_iter()
+                .map(|issue| Diagnostic::new(index, issue.columns, issue.severity, issue.message)),
+        );
+    }
+
+    let sequence = check_sequence(deck);
+    let numbered = sequence.len() < deck.len()
+        || sequence
+            .iter()
+            .any(|error| !matches!(error, SequenceError::Missing { .. }));
+    for error in sequence.into_iter().filter(|_| numbered) {
+        let (card, severity) = match error {
+            SequenceError::Missing { card } if !is_blank(deck, card) => (card, Severity::Warning),
+            SequenceError::Duplicate { card, .. } | SequenceError::OutOfOrder { card, .. } => {
+                (card, Severity::Error)
+            }
+            // Non-digit identification is already warned about per card
+            _ => continue,
+        };
+        let message = error.to_string();
+        let message = message.split_once(": ").map_or(&*message, |(_, rest)| rest);
+        diagnostics.push(Diagnostic::new(
+            card,
+            SEQUENCE_COLUMNS,
+            severity,
+            message.to_string(),
+        ));
+    }
+
+    let mut end: Option<usize> = None;
+    let mut labels: HashMap<String, usize> = HashMap::new();
+    for (index, card) in deck.iter().enumerate() {
+        if let Some(end) = end.filter(|_| !is_blank(deck, index)) {
+            diagnostics.push(Diagnostic::new(
+                index,
+                0..80,
+                Severity::Error,
+                format!("card follows END on card {}", end + 1),
+            ));
+            continue;
+        }
+        let Ok(source) = SourceCard::parse(card) else {
+            continue;
+        };
+        let Some(info) = opcodes::lookup(source.opcode().trim()) else {
+            continue;
+        };
+        if info.mnemonic == "END" {
+            end = Some(index);
+        }
+        let label = source.label().trim();
+        if label.is_empty() || info.label == LabelRule::NotAllowed {
+            continue;
+        }
+        if let Some(first) = labels.get(label) {
+            diagnostics.push(Diagnostic::new(
+                index,
+                LABEL_COLUMNS,
+                Severity::Error,
+                format!("label {:?} is already defined on card {}", label, first + 1),
+            ));
+        } else {
+            labels.insert(label.to_string(), index);
+        }
+    }
+    if end.is_none() {
+        diagnostics.push(Diagnostic::new(
+            deck.len().saturating_sub(1),
+            OPCODE_COLUMNS,
+            Severity::Error,
+            "deck has no END statement".to_string(),
+        ));
+    }
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.card, diagnostic.columns.start));
+    DeckDiagnostics { diagnostics }
+}
+
+fn is_blank(deck: &Deck, card: usize) -> bool {
+    deck.get(card)
+        .is_some_and(|card| card.columns().iter().all(|column| column.is_blank()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::punch_card::PunchCard;
+
+    const SOURCE_DECK: &str = include_str!("../../tests/fixtures/ibm1130_source.txt");
+    const BROKEN_DECK: &str = include_str!("../../tests/fixtures/ibm1130_broken_source.txt");
+
+    fn deck(text: &str) -> Deck {
+        text.lines().map(PunchCard::from_text).collect()
+    }
+
+    #[test]
+    fn test_valid_deck_has_no_errors() {
+        let report = validate_source_deck(&deck(SOURCE_DECK));
+        assert!(!report.has_errors(), "{}", report);
+        // The SUM identification letters in columns 73-75
+        assert_eq!(report.count(Severity::Warning), 12);
+    }
+
+    #[test]
+    fn test_broken_deck_diagnostics() {
+        let report = validate_source_deck(&deck(BROKEN_DECK));
+        let found: Vec<(usize, usize, Severity)> = report
+            .diagnostics
+            .iter()
+            .map(|d| (d.card, d.columns.start, d.severity))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (3, 26, Severity::Error),
+                (4, 72, Severity::Error),
+                (5, 20, Severity::Error),
+                (6, 72, Severity::Warning),
+                (7, 20, Severity::Error),
+                (9, 0, Severity::Error),
+                (10, 0, Severity::Error),
+            ]
+        );
+        let lines: Vec<String> = report.to_string().lines().map(String::from).collect();
+        assert_eq!(lines[0], "card 4, column 27: error: unknown opcode \"LDA\"");
+        assert_eq!(
+            lines[1],
+            "card 5, column 73: error: sequence 40 is out of order after 50"
+        );
+        assert_eq!(
+            lines[4],
+            "card 8, column 21: error: label \"LOOP\" is already defined on card 3"
+        );
+        assert_eq!(
+            lines[5],
+            "card 10, column 1: error: card follows END on card 9"
+        );
+        assert_eq!(lines[7], "6 error(s), 1 warning(s)");
+    }
+
+    #[test]
+    fn test_missing_end() {
+        let report = validate_source_deck(&deck(&format!("{:26}WAIT", "")));
+        assert_eq!(report.diagnostics.len(), 1);
+        assert_eq!(report.diagnostics[0].message, "deck has no END statement");
+        assert_eq!(validate_source_deck(&Deck::new()).diagnostics[0].card, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_diagnostics_serde() {
+        let report = validate_source_deck(&deck(BROKEN_DECK));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["diagnostics"][0]["card"], 3);
+        assert_eq!(json["diagnostics"][0]["columns"]["start"], 26);
+        assert_eq!(json["diagnostics"][0]["severity"], "Error");
+        let back: DeckDiagnostics = serde_json::from_value(json).unwrap();
+        assert_eq!(back, report);
+    }
+}
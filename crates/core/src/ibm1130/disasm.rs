@@ -0,0 +1,340 @@
+// IBM 1130 Disassembler
+//
+// Turns the words loaded by an object deck back into assembler statements,
+// one listing line per instruction. Decoding is best effort: a word that is
+// not a valid instruction (or a long instruction missing its address word)
+// is shown as `DC /xxxx`.
+//
+// Operands are shown the way the assembler (see `assemble`) reads them, so a
+// listing can be punched and assembled again:
+// - Short instructions with tag 0 address relative to the next word and are
+//   shown with the absolute target (`/0109`)
+// - Indexed displacements and immediate values are shown in decimal
+// - A short MDX with tag 0 is an unconditional branch and is shown as B, and
+//   a long BSC whose conditions match an extended mnemonic (BP, BZ, ...) is
+//   shown with that mnemonic
+
+use super::opcodes::{self, OpcodeKind, OperandSyntax};
+use super::{ObjectCard, ObjectCardKind, ObjectDeckError, words_from_object_deck};
+use crate::deck::Deck;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One disassembled statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DisasmLine {
+    /// Address of the first word
+    pub address: u16,
+    /// The instruction word, and the address word of a long instruction
+    pub words: Vec<u16>,
+    pub mnemonic: String,
+    /// `L` (long) or `I` (indirect), as in column 32 of a source card
+    pub format: Option<char>,
+    /// Index register 1-3, as in column 33 of a source card
+    pub tag: Option<u8>,
+    pub operands: String,
+}
+
+impl DisasmLine {
+    fn data(address: u16, word: u16) -> Self {
+        DisasmLine {
+            address,
+            words: vec![word],
+            mnemonic: "DC".to_string(),
+            format: None,
+            tag: None,
+            operands: format!("/{:04X}", word),
+        }
+    }
+
+    /// Whether the word was not decoded as an instruction
+    pub fn is_data(&self) -> bool {
+        self.mnemonic == "DC"
+    }
+}
+
+impl fmt::Display for DisasmLine {
+    /// Listing columns: address, words, then the statement laid out as on
+    /// the coding form
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let words: Vec<String> = self.words.iter().map(|w| format!("{:04X}", w)).collect();
+        let line = format!(
+            "{:04X}  {:<9}  {:<4} {}{} {}",
+            self.address,
+            words.join(" "),
+            self.mnemonic,
+            self.format.unwrap_or(' '),
+            self.tag.map_or(' ', |tag| char::from(b'0' + tag)),
+            self.operands
+        );
+        f.write_str(line.trim_end())
+    }
+}
+
+/// Disassemble the data words of an object card
+///
+/// End cards and other non-data cards give no lines. A long instruction in
+/// the card's last word is shown as data, since its address word is on the
+/// next card; use `disassemble_deck` to decode across cards.
+pub fn disassemble(card: &ObjectCard) -> Vec<DisasmLine> {
+    match card.card_kind() {
+        ObjectCardKind::Data => disassemble_words(card.load_address(), card.data_words()),
+        _ => Vec::new(),
+    }
+}
+
+/// Disassemble every word loaded by an object deck
+///
+/// Cards loading consecutive addresses are decoded as one run (see
+/// `words_from_object_deck`). Fails on the first card that does not parse.
+pub fn disassemble_deck(deck: &Deck) -> Result<Vec<DisasmLine>, ObjectDeckError> {
+    Ok(words_from_object_deck(deck)?
+        .iter()
+        .flat_map(|(origin, words)| disassemble_words(*origin, words))
+        .collect())
+}
+
+/// Disassemble words loaded at consecutive addresses from `origin`
+pub fn disassemble_words(origin: u16, words: &[u16]) -> Vec<DisasmLine> {
+    let mut lines = Vec::new();
+    let mut index = 0;
+    while index < words.len() {
+        let address = origin.wrapping_add(index as u16);
+        let line = decode(address, words[index], words.get(index + 1).copied())
+            .unwrap_or_else(|| DisasmLine::data(address, words[index]));
+        index += line.words.len();
+        lines.push(line);
+    }
+    lines
+}
+
+/// Decode one instruction, or `None` if the word is not one
+fn decode(address: u16, word: u16, next: Option<u16>) -> Option<DisasmLine> {
+    let long = word & 0x0400 != 0;
+    let tag = ((word >> 8) & 3) as u8;
+    let modifiers = word & 0xFF;
+    let displacement = i32::from(modifiers as u8 as i8);
+    let mut line = DisasmLine {
+        address,
+        words: vec![word],
+        mnemonic: String::new(),
+        format: None,
+        tag: (tag != 0).then_some(tag),
+        operands: String::new(),
+    };
+
+    // Exact matches first: NOP, XCH, WAIT
+    if let Some(info) = opcodes::OPCODES.iter().find(|info| {
+        info.kind == OpcodeKind::Instruction
+            && info.operands == OperandSyntax::None
+            && info.word == word
+    }) {
+        line.mnemonic = info.mnemonic.to_string();
+        return Some(line);
+    }
+
+    let base = match word & 0xF800 {
+        // Shifts select the operation with bits 8-9
+        0x1000 | 0x1800 => word & 0xF8C0,
+        // BOSC is BSC with bit 9 set in the long form
+        0x4800 if long && word & 0x0040 != 0 => 0x4840,
+        base => base,
+    };
+    let info = opcodes::OPCODES.iter().find(|info| {
+        info.kind == OpcodeKind::Instruction
+            && info.word == base
+            && !matches!(info.mnemonic, "B" | "SKP")
+    })?;
+    if long && !info.long {
+        return None;
+    }
+    line.mnemonic = info.mnemonic.to_string();
+
+    match info.operands {
+        OperandSyntax::Shift => {
+            let count = word & 0x3F;
+            if count != 0 || tag == 0 {
+                line.operands = count.to_string();
+            }
+            return Some(line);
+        }
+        OperandSyntax::Value => {
+            line.operands = (word & 3).to_string();
+            return Some(line);
+        }
+        _ => {}
+    }
+
+    if !long {
+        line.operands = match info.mnemonic {
+            // Short BSC skips on its conditions
+            "BSC" if tag == 0 => {
+                line.mnemonic = "SKP".to_string();
+                conditions(modifiers)
+            }
+            "BSC" => return None,
+            "MDX" if tag == 0 => {
+                line.mnemonic = "B".to_string();
+                relative(address, displacement)
+            }
+            "LDX" => displacement.to_string(),
+            "STX" => relative(address, displacement),
+            _ if tag == 0 => relative(address, displacement),
+            _ => displacement.to_string(),
+        };
+        return Some(line);
+    }
+
+    let target = format!("/{:04X}", next?);
+    line.words.push(next?);
+    // A long MDX with tag 0 adds its 8-bit modifier to memory, so has no
+    // indirect bit
+    if info.mnemonic == "MDX" && tag == 0 {
+        line.format = Some('L');
+        line.operands = match displacement {
+            0 => target,
+            _ => format!("{},{}", target, displacement),
+        };
+        return Some(line);
+    }
+    line.format = Some(if word & 0x0080 != 0 { 'I' } else { 'L' });
+    let conditions = conditions(modifiers);
+    let extended = opcodes::OPCODES.iter().find(|other| {
+        info.mnemonic == "BSC"
+            && tag == 0
+            && other.operands == OperandSyntax::Address
+            && other.word == 0x4800 | modifiers & 0x3F
+    });
+    line.operands = match (info.operands, extended) {
+        (OperandSyntax::Branch, Some(other)) => {
+            line.mnemonic = other.mnemonic.to_string();
+            target
+        }
+        (OperandSyntax::Branch, None) if !conditions.is_empty() => {
+            format!("{},{}", target, conditions)
+        }
+        _ => target,
+    };
+    Some(line)
+}
+
+/// Show a short-form relative target as an absolute address
+fn relative(address: u16, displacement: i32) -> String {
+    format!("/{:04X}", (i32::from(address) + 1 + displacement) as u16)
+}
+
+/// Show branch condition bits 10-15 as `Z-+ECO` letters
+fn conditions(bits: u16) -> String {
+    [
+        (0x20, 'Z'),
+        (0x10, '-'),
+        (0x08, '+'),
+        (0x04, 'E'),
+        (0x02, 'C'),
+        (0x01, 'O'),
+    ]
+    .iter()
+    .filter(|(bit, _)| bits & bit != 0)
+    .map(|(_, ch)| ch)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::CardFormat;
+    use crate::ibm1130::{assemble, parse_object_deck};
+    use crate::punch_card::PunchCard;
+
+    const SOURCE_DECK: &str = include_str!("../../tests/fixtures/ibm1130_source.txt");
+    const OBJECT_DECK: &[u8] =
+        include_bytes!("../../tests/fixtures/ibm1130_sum_object_synthetic.bin");
+
+    fn source(lines: &[&str]) -> Deck {
+        lines
+            .iter()
+            .map(|line| PunchCard::from_text(&format!("{:20}{}", "", line)))
+            .collect()
+    }
+
+    #[test]
+    fn test_disassemble_sum_program() {
+        let deck: Deck = SOURCE_DECK.lines().map(PunchCard::from_text).collect();
+        let lines = disassemble_deck(&assemble(&deck).unwrap()).unwrap();
+        let listing: Vec<String> = lines.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            listing,
+            vec![
+                "0100  6500 000A  LDX  L1 /000A",
+                "0102  C500 0109  LD   L1 /0109",
+                "0104  8004       A       /0109",
+                "0105  D003       STO     /0109",
+                "0106  71FF       MDX   1 -1",
+                "0107  70FA       B       /0102",
+                "0108  3000       WAIT",
+                "0109  0000       DC      /0000",
+            ]
+        );
+        assert!(lines[7].is_data());
+
+        // The hand-assembled synthetic deck reads the same
+        let fixture: Deck = OBJECT_DECK
+            .chunks(120)
+            .map(|data| PunchCard::parse(data, CardFormat::FullBinary120).unwrap())
+            .collect();
+        assert_eq!(disassemble_deck(&fixture).unwrap(), lines);
+        let cards = parse_object_deck(&fixture).unwrap();
+        assert_eq!(disassemble(&cards[0]), lines);
+        assert!(disassemble(&cards[1]).is_empty());
+    }
+
+    #[test]
+    fn test_disassembly_matches_source_mnemonics() {
+        let statements = [
+            ("LDX  L2 /0014", 2),
+            ("BSC  L  /0200,Z+C", 2),
+            ("BSI  I  /0210", 2),
+            ("BP   L  /0200", 2),
+            ("BOSC L  /0200,C", 2),
+            ("MDX  L  /0211,-1", 2),
+            ("B    L  /0200", 2),
+            ("STS  I3 /0000", 2),
+            ("SLA     4", 1),
+            ("SRT   1", 1),
+            ("SLCA  2", 1),
+            ("RTE     17", 1),
+            ("XCH", 1),
+            ("NOP", 1),
+            ("SKP     E", 1),
+            ("LDS     1", 1),
+            ("EOR   3 -5", 1),
+            ("STX     /0200", 1),
+        ];
+        let mut text = vec!["      ORG     /0200".to_string()];
+        text.extend(statements.iter().map(|(stmt, _)| format!("      {}", stmt)));
+        let lines: Vec<&str> = text.iter().map(String::as_str).collect();
+        let object = assemble(&source(&lines)).unwrap();
+
+        let disassembled = disassemble_deck(&object).unwrap();
+        assert_eq!(disassembled.len(), statements.len());
+        for (line, (stmt, words)) in disassembled.iter().zip(statements) {
+            assert_eq!(line.to_string()[17..], *stmt);
+            assert_eq!(line.words.len(), words);
+        }
+    }
+
+    #[test]
+    fn test_undecodable_words_are_data() {
+        let lines = disassemble_words(0x0010, &[0x0000, 0x3800, 0x5000, 0x1440, 0x2400, 0xC400]);
+        let texts: Vec<String> = lines.iter().map(|line| line.operands.clone()).collect();
+        assert!(lines.iter().all(DisasmLine::is_data));
+        // The long LD at the end has no address word
+        assert_eq!(
+            texts,
+            vec!["/0000", "/3800", "/5000", "/1440", "/2400", "/C400"]
+        );
+        assert_eq!(lines[5].address, 0x0015);
+    }
+}
@@ -0,0 +1,189 @@
+// IBM 1130 Examples
+//
+// The built-in example cards, listed in one place so the web UI and the CLI
+// show the same set. To add an example, add an entry to `examples`.
+
+use super::{
+    CardLayout, ControlCard, LayoutField, SequenceOptions, generate_example_fortran,
+    generate_example_object, generate_example_source, punch_source_deck, punch_text,
+};
+use crate::punch_card::PunchCard;
+
+/// A built-in example card
+#[derive(Debug, Clone, Copy)]
+pub struct ExampleCard {
+    /// Short name, for the command line
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub generate: fn() -> PunchCard,
+}
+
+impl ExampleCard {
+    /// Punch the example
+    pub fn card(&self) -> PunchCard {
+        (self.generate)()
+    }
+}
+
+/// Fields of the example payroll data card
+pub const EXAMPLE_DATA_LAYOUT: CardLayout = CardLayout {
+    name: "Payroll",
+    fields: &[
+        LayoutField {
+            name: "Employee number",
+            columns: 0..5,
+        },
+        LayoutField {
+            name: "Name",
+            columns: 5..25,
+        },
+        LayoutField {
+            name: "Hours",
+            columns: 25..29,
+        },
+        LayoutField {
+            name: "Rate",
+            columns: 29..34,
+        },
+    ],
+};
+
+/// Every built-in example, in display order
+pub fn examples() -> Vec<ExampleCard> {
+    vec![
+        ExampleCard {
+            id: "source",
+            title: "Assembler Source Card",
+            description: "An 1130 assembler statement in the coding form columns: label \
+                          START, opcode DC and operand 0, with a remark",
+            generate: generate_example_source,
+        },
+        ExampleCard {
+            id: "fortran",
+            title: "FORTRAN Statement Card",
+            description: "Statement 10 of a DO loop, A(I) = FLOAT(I): statement number in \
+                          columns 1-5 and the statement from column 7",
+            generate: example_fortran,
+        },
+        ExampleCard {
+            id: "job",
+            title: "Monitor JOB Card",
+            description: "The // JOB control card that starts a Disk Monitor job",
+            generate: example_job,
+        },
+        ExampleCard {
+            id: "data",
+            title: "Payroll Data Card",
+            description: "Fixed fields: employee number in columns 1-5, name in 6-25, \
+                          hours in tenths in 26-29 and hourly rate in cents in 30-34",
+            generate: example_data,
+        },
+        ExampleCard {
+            id: "sequenced",
+            title: "Sequence-Numbered Card",
+            description: "A card numbered 00000010 in columns 73-80, so a dropped deck \
+                          can be sorted back into order",
+            generate: example_sequenced,
+        },
+        ExampleCard {
+            id: "object",
+            title: "Object Deck Card (Binary)",
+            description: "The first card of an assembled program: load address, checksum, \
+                          type, word count and relocation, then the machine words",
+            generate: generate_example_object,
+        },
+    ]
+}
+
+/// Look up an example by id
+pub fn example(id: &str) -> Option<ExampleCard> {
+    examples().into_iter().find(|example| example.id == id)
+}
+
+fn example_fortran() -> PunchCard {
+    generate_example_fortran()
+        .into_cards()
+        .into_iter()
+        .find(|card| card.to_text().starts_with("   10"))
+        .expect("the example program has statement 10")
+}
+
+fn example_job() -> PunchCard {
+    ControlCard::job().to_card().expect("JOB cards punch")
+}
+
+fn example_data() -> PunchCard {
+    punch_text("00042JONES, MARY         037502250").expect("example data punches")
+}
+
+fn example_sequenced() -> PunchCard {
+    let punched = punch_source_deck("HELLO, WORLD", &SequenceOptions::default())
+        .expect("one short line fits");
+    punched.deck.into_cards().swap_remove(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ibm1130::{
+        ControlOperation, FortranCard, ObjectCard, ObjectCardKind, SourceCard, check_sequence,
+    };
+    use crate::punch_card::CardType;
+
+    fn field(card: &PunchCard, columns: std::ops::Range<usize>) -> String {
+        card.to_text()[columns].trim_end().to_string()
+    }
+
+    #[test]
+    fn test_example_registry() {
+        let all = examples();
+        assert_eq!(all.len(), 6);
+        for (index, entry) in all.iter().enumerate() {
+            assert!(all[..index].iter().all(|other| other.id != entry.id));
+            assert_eq!(
+                example(entry.id).map(|found| found.title),
+                Some(entry.title)
+            );
+        }
+        assert!(example("missing").is_none());
+    }
+
+    #[test]
+    fn test_examples_decode_as_described() {
+        let card = |id: &str| example(id).unwrap().card();
+
+        let source = SourceCard::parse(&card("source")).unwrap();
+        assert_eq!(
+            (source.label(), source.opcode(), source.operands()),
+            ("START", "DC", "0")
+        );
+
+        let fortran = FortranCard::parse(&card("fortran")).unwrap();
+        assert_eq!(fortran.statement_number(), Some(10));
+        assert_eq!(fortran.text(), "A(I) = FLOAT(I)");
+
+        let job = ControlCard::parse(&card("job")).unwrap();
+        assert_eq!(job.operation(), ControlOperation::Job);
+
+        let data = card("data");
+        let fields: Vec<String> = EXAMPLE_DATA_LAYOUT
+            .fields
+            .iter()
+            .map(|layout| field(&data, layout.columns.clone()))
+            .collect();
+        assert_eq!(fields, ["00042", "JONES, MARY", "0375", "02250"]);
+
+        let sequenced = card("sequenced");
+        assert_eq!(field(&sequenced, 72..80), "00000010");
+        assert!(field(&sequenced, 0..72).starts_with("HELLO, WORLD"));
+        assert!(check_sequence(&[sequenced].into_iter().collect()).is_empty());
+
+        let object = card("object");
+        assert_eq!(object.card_type(), CardType::Binary);
+        let parsed = ObjectCard::parse(&object).unwrap();
+        assert_eq!(parsed.card_kind(), ObjectCardKind::Data);
+        assert_eq!(parsed.load_address(), 0x0100);
+        assert!(parsed.verify_checksum());
+    }
+}
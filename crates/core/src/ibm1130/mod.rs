@@ -0,0 +1,2850 @@
+// IBM 1130 Format Module
+//
+// Specific format handling for IBM 1130 assembler source and object deck cards
+
+pub mod assembler;
+pub mod audit;
+pub mod cold_start;
+pub mod core_image;
+pub mod data;
+pub mod device;
+pub mod diagnostics;
+pub mod disasm;
+pub mod examples;
+pub mod monitor;
+pub mod opcodes;
+pub mod printer;
+pub mod simh;
+
+pub use assembler::{AsmError, assemble};
+pub use audit::{AuditIssue, ObjectDeckAudit, audit_object_deck};
+pub use core_image::{CoreImage, CoreImageError};
+pub use data::{FormatError, FormatSpec, Value, parse_format};
+pub use device::{CardReaderPunch, DeviceError};
+pub use diagnostics::{DeckDiagnostics, Diagnostic, validate_source_deck};
+pub use disasm::{DisasmLine, disassemble, disassemble_deck};
+pub use examples::{EXAMPLE_DATA_LAYOUT, ExampleCard, example, examples};
+pub use monitor::{
+    ControlCard, ControlOperation, DeckCardKind, DupCard, DupDevice, DupFunction, classify_card,
+};
+pub use printer::{
+    ListingOptions, PrintChain, deck_to_listing, is_printable_1132, validate_printable,
+};
+
+use crate::bitpack;
+use crate::deck::Deck;
+use crate::hollerith::{Charset, HollerithCode, char_to_hollerith, hollerith_to_char_with};
+use crate::punch_card::{CardType, PunchCard};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+/// Generate an example IBM 1130 assembler source card
+///
+/// The statement is punched in the coding form columns (see `SourceCard`):
+/// label in 21-25, opcode in 27-30 and the operand in 35.
+pub fn generate_example_source() -> PunchCard {
+    SourceCard::builder()
+        .label("START")
+        .opcode("DC")
+        .operands("0")
+        .comment("IBM 1130 EXAMPLE PROGRAM")
+        .build()
+        .expect("example fields fit their columns")
+}
+
+/// The statement punched by `generate_example_fortran_source`
+pub const EXAMPLE_FORTRAN_STATEMENT: &str = "   10 Y = (A+B)*C/2.0 - F(X,'Z')";
+
+/// Generate an example FORTRAN source card, punched on an 026 FORTRAN keypunch
+///
+/// Format:
+/// - Column 1-5: Statement number
+/// - Column 6: Continuation
+/// - Column 7-72: Statement
+/// - Column 73-80: Identification
+pub fn generate_example_fortran_source() -> PunchCard {
+    PunchCard::from_text_with_charset(EXAMPLE_FORTRAN_STATEMENT, Charset::Ibm026Fortran)
+}
+
+/// Punch the example assembler program, which sums a table of ten words
+///
+/// `generate_example_object_deck` is this program assembled.
+pub fn generate_example_program() -> Deck {
+    type Statement = (
+        &'static str,
+        &'static str,
+        Option<char>,
+        Option<u8>,
+        &'static str,
+    );
+    let statements: [Statement; 11] = [
+        ("SUM", "ORG", None, None, "/0100"),
+        ("START", "LDX", Some('L'), Some(1), "10"),
+        ("LOOP", "LD", Some('L'), Some(1), "TABLE-1"),
+        ("", "A", None, None, "TOTAL"),
+        ("", "STO", None, None, "TOTAL"),
+        ("", "MDX", None, Some(1), "-1"),
+        ("", "B", None, None, "LOOP"),
+        ("", "WAIT", None, None, ""),
+        ("TOTAL", "DC", None, None, "0"),
+        ("TABLE", "BSS", None, None, "10"),
+        ("", "END", None, None, "START"),
+    ];
+    let comment = format!("{:20}{:52}SUM00010", "", "*SUM A TABLE OF TEN WORDS");
+    let mut deck = Deck::from_cards(vec![punch_text(&comment).expect("comment fits")]);
+    for (index, (label, opcode, format, tag, operands)) in statements.into_iter().enumerate() {
+        let mut card = SourceCard::builder()
+            .label(label)
+            .opcode(opcode)
+            .operands(operands)
+            .sequence(&format!("SUM{:05}", (index + 2) * 10));
+        if let Some(format) = format {
+            card = card.format(format);
+        }
+        if let Some(tag) = tag {
+            card = card.tag(tag);
+        }
+        deck.push(card.build().expect("example statements fit"));
+    }
+    deck
+}
+
+/// Generate the example object deck: `generate_example_program` assembled
+///
+/// A data card loads the program's ten words at /0100 and an end card gives
+/// the entry point. The cards are identified SUM00001 and SUM00002 in
+/// columns 73-80.
+pub fn generate_example_object_deck() -> Deck {
+    let object = assemble(&generate_example_program()).expect("the example assembles");
+    object
+        .iter()
+        .enumerate()
+        .map(|(index, card)| {
+            ObjectCard::parse(card)
+                .and_then(|object| object.with_sequence(&format!("SUM{:05}", index + 1)))
+                .expect("assembled cards parse")
+                .to_card()
+        })
+        .collect()
+}
+
+/// Generate an example IBM 1130 object deck card
+///
+/// This is the first card of `generate_example_object_deck`, a data card
+/// (see `ObjectCard`) whose columns 1-72 hold the load address, checksum,
+/// type and word count, relocation indicators and the program's words.
+pub fn generate_example_object() -> PunchCard {
+    generate_example_object_deck().into_cards().swap_remove(0)
+}
+
+/// Generate a dense binary card for visual demos
+///
+/// The punches use all 12 rows of columns 1-72 but do not decode to an
+/// object card; use `generate_example_object` for a real one.
+pub fn generate_demo_pattern() -> PunchCard {
+    // Pattern inspired by actual binary object cards - varied punch patterns
+    // using all 12 rows to create realistic machine code appearance
+    let punch_patterns: Vec<u16> = vec![
+        0x0E49, 0x0C31, 0x0842, 0x0421, 0x0E73, 0x0C52, 0x0946, 0x0735, 0x0E5A, 0x0C48, 0x08E3,
+        0x0467, 0x0F21, 0x0D84, 0x0B42, 0x09C6, 0x0E87, 0x0C39, 0x0A51, 0x0763, 0x0E94, 0x0CB2,
+        0x0856, 0x0429, 0x0F48, 0x0D31, 0x0B82, 0x0974, 0x0EC5, 0x0CA3, 0x0A61, 0x0847, 0x0E29,
+        0x0C74, 0x08B5, 0x0493, 0x0F52, 0x0DB1, 0x0B73, 0x0965, 0x0E38, 0x0C91, 0x0A42, 0x0826,
+        0x0F64, 0x0DC8, 0x0B51, 0x0937, 0x0EA7, 0x0C52, 0x0984, 0x0763, 0x0E41, 0x0CB5, 0x0A29,
+        0x0876, 0x0F93, 0x0D42, 0x0BC6, 0x0948, 0x0E72, 0x0CA4, 0x0851, 0x0639, 0x0F28, 0x0DB7,
+        0x0B94, 0x0962, 0x0E56, 0x0C83, 0x0A41, 0x0725,
+    ];
+
+    // Pack 72 12-bit patterns into 108 bytes (864 bits total)
+    let codes: Vec<HollerithCode> = punch_patterns
+        .into_iter()
+        .map(bitpack::code_from_bits)
+        .collect();
+    PunchCard::from_binary(&bitpack::pack_columns(&codes))
+}
+
+/// How serious a source format issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Severity {
+    /// The assembler would still read the card
+    Warning,
+    /// The assembler would reject the card
+    Error,
+}
+
+/// A problem found by `validate_source_format`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFormatIssue {
+    /// The columns to underline (0-based)
+    pub columns: Range<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl SourceFormatIssue {
+    fn error(columns: Range<usize>, message: String) -> Self {
+        SourceFormatIssue {
+            columns,
+            severity: Severity::Error,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for SourceFormatIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Column {}: {}", self.columns.start + 1, self.message)
+    }
+}
+
+/// Validate IBM 1130 source card format
+///
+/// Checks the coding form conventions (see `SourceCard`):
+/// - Separator columns 26, 31, 34 and 72 are blank
+/// - A label starts in column 21 and is letters and digits, starting with a
+///   letter
+/// - The opcode field holds a known opcode (see `opcodes::lookup`)
+/// - The format column is blank, L, I, X or E, and long, indirect and
+///   indexed forms are only used where allowed
+/// - EQU has a label
+/// - Columns 73-80 are digits or blanks (a warning, since decks were often
+///   identified with letters there)
+///
+/// Comment cards only have their sequence checked. A card blank in columns
+/// 21-71 must also be blank in columns 1-20, or its statement was punched
+/// in the wrong columns. Returns every issue found, or none for a valid
+/// card.
+pub fn validate_source_format(card: &PunchCard) -> Vec<SourceFormatIssue> {
+    if card.card_type() != CardType::Text {
+        return vec![SourceFormatIssue::error(
+            0..80,
+            ParseError::NotText.to_string(),
+        )];
+    }
+    let mut text = [' '; 80];
+    let mut issues = Vec::new();
+    for (index, column) in card.columns().iter().enumerate() {
+        match column.to_char() {
+            Some(ch) => text[index] = ch,
+            None => issues.push(SourceFormatIssue::error(
+                index..index + 1,
+                "punches are not a character".to_string(),
+            )),
+        }
+    }
+    if !issues.is_empty() {
+        return issues;
+    }
+    let field = |columns: Range<usize>| text[columns].iter().collect::<String>();
+
+    let odd: Vec<usize> = SEQUENCE_COLUMNS
+        .filter(|&index| !matches!(text[index], ' ' | '0'..='9'))
+        .collect();
+    if let (Some(&first), Some(&last)) = (odd.first(), odd.last()) {
+        issues.push(SourceFormatIssue {
+            columns: first..last + 1,
+            severity: Severity::Warning,
+            message: "sequence is not digits".to_string(),
+        });
+    }
+    let statement = field(LABEL_COLUMNS.start..OPERAND_COLUMNS.end);
+    if statement.trim().is_empty() {
+        let punched: Vec<usize> = (0..LABEL_COLUMNS.start)
+            .filter(|&index| text[index] != ' ')
+            .collect();
+        if let (Some(&first), Some(&last)) = (punched.first(), punched.last()) {
+            issues.push(SourceFormatIssue::error(
+                first..last + 1,
+                "statement must be punched from column 21; columns 1-20 are not read".to_string(),
+            ));
+        }
+        return issues;
+    }
+    if text[LABEL_COLUMNS.start] == '*' {
+        return issues;
+    }
+    for column in SEPARATOR_COLUMNS {
+        if text[column] != ' ' {
+            issues.push(SourceFormatIssue::error(
+                column..column + 1,
+                format!("must be blank, found {:?}", text[column]),
+            ));
+        }
+    }
+
+    let label = field(LABEL_COLUMNS);
+    let name = label.trim();
+    if !name.is_empty() {
+        let start = LABEL_COLUMNS.start + label.len() - label.trim_start().len();
+        let columns = start..start + name.len();
+        if start != LABEL_COLUMNS.start {
+            issues.push(SourceFormatIssue::error(
+                columns.clone(),
+                format!("label {:?} must start in column 21", name),
+            ));
+        }
+        if !name.starts_with(|ch: char| ch.is_ascii_alphabetic())
+            || !name.chars().all(|ch| ch.is_ascii_alphanumeric())
+        {
+            issues.push(SourceFormatIssue::error(
+                columns,
+                format!(
+                    "label {:?} must be letters and digits, starting with a letter",
+                    name
+                ),
+            ));
+        }
+    }
+
+    let format = text[FORMAT_COLUMN];
+    if !matches!(format, ' ' | 'L' | 'I' | 'X' | 'E') {
+        issues.push(SourceFormatIssue::error(
+            FORMAT_COLUMN..FORMAT_COLUMN + 1,
+            format!("format must be blank, L, I, X or E, found {:?}", format),
+        ));
+    }
+    let tag = text[TAG_COLUMN];
+    if !matches!(tag, ' ' | '0'..='3') {
+        issues.push(SourceFormatIssue::error(
+            TAG_COLUMN..TAG_COLUMN + 1,
+            format!("tag must be blank or 0-3, found {:?}", tag),
+        ));
+    }
+
+    let opcode = field(OPCODE_COLUMNS);
+    let mnemonic = opcode.trim();
+    let start = OPCODE_COLUMNS.start + opcode.len() - opcode.trim_start().len();
+    let Some(info) = opcodes::lookup(mnemonic) else {
+        issues.push(match mnemonic {
+            "" => SourceFormatIssue::error(OPCODE_COLUMNS, "missing opcode".to_string()),
+            _ => SourceFormatIssue::error(
+                start..start + mnemonic.len(),
+                format!("unknown opcode {:?}", mnemonic),
+            ),
+        });
+        return issues;
+    };
+    if matches!(format, 'L' | 'I') && !info.long {
+        issues.push(SourceFormatIssue::error(
+            FORMAT_COLUMN..FORMAT_COLUMN + 1,
+            format!("{} has no long form", mnemonic),
+        ));
+    }
+    if matches!(tag, '1'..='3') && !info.indexed {
+        issues.push(SourceFormatIssue::error(
+            TAG_COLUMN..TAG_COLUMN + 1,
+            format!("{} cannot be indexed", mnemonic),
+        ));
+    }
+    if info.label == opcodes::LabelRule::Required && name.is_empty() {
+        issues.push(SourceFormatIssue::error(
+            LABEL_COLUMNS,
+            format!("{} needs a label", mnemonic),
+        ));
+    }
+    issues
+}
+
+/// Columns 1-20, left for the assembler's listing (address and object code)
+const LISTING_COLUMNS: Range<usize> = 0..20;
+/// Columns 21-25
+const LABEL_COLUMNS: Range<usize> = 20..25;
+/// Columns 27-30
+const OPCODE_COLUMNS: Range<usize> = 26..30;
+/// Column 32
+const FORMAT_COLUMN: usize = 31;
+/// Column 33
+const TAG_COLUMN: usize = 32;
+/// Columns 35-71, the operands followed by remarks
+const OPERAND_COLUMNS: Range<usize> = 34..71;
+/// Columns 22-71 of a comment card
+const COMMENT_COLUMNS: Range<usize> = 21..71;
+/// Columns 73-80
+const SEQUENCE_COLUMNS: Range<usize> = 72..80;
+/// Columns 26, 31, 34 and 72, which separate the fields
+const SEPARATOR_COLUMNS: [usize; 4] = [25, 30, 33, 71];
+
+/// Errors from parsing an assembler, FORTRAN or monitor control card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The card is a binary card
+    NotText,
+    /// A column's punches do not decode to a character (0-based)
+    Undecodable(usize),
+    /// A column that separates fields is not blank (0-based)
+    NotBlank { column: usize, ch: char },
+    /// The tag column holds something other than blank or 0-3
+    InvalidTag(char),
+    /// A FORTRAN statement number field holds something other than digits
+    InvalidStatementNumber(String),
+    /// A FORTRAN continuation card has no statement before it
+    OrphanContinuation,
+    /// A control card does not start with `//`
+    NotControl,
+    /// A control card's operation is not one this crate knows
+    UnknownOperation(String),
+    /// A DUP control card does not start with `*`
+    NotDup,
+    /// A DUP card's from or to field is not a known device or area
+    UnknownDevice(String),
+    /// A DUP card's count field holds something other than digits
+    InvalidCount(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::NotText => write!(f, "Source cards must be text type"),
+            ParseError::Undecodable(column) => {
+                write!(f, "Column {}: punches are not a character", column + 1)
+            }
+            ParseError::NotBlank { column, ch } => {
+                write!(f, "Column {} must be blank, found {:?}", column + 1, ch)
+            }
+            ParseError::InvalidTag(ch) => {
+                write!(
+                    f,
+                    "Column {}: tag must be blank or 0-3, found {:?}",
+                    TAG_COLUMN + 1,
+                    ch
+                )
+            }
+            ParseError::InvalidStatementNumber(field) => {
+                write!(f, "Statement number {:?} is not a number", field)
+            }
+            ParseError::OrphanContinuation => {
+                write!(f, "Continuation card has no statement to continue")
+            }
+            ParseError::NotControl => write!(f, "Control cards start with // in columns 1-2"),
+            ParseError::UnknownOperation(name) => {
+                write!(f, "Unknown monitor operation {:?}", name)
+            }
+            ParseError::NotDup => write!(f, "DUP control cards start with * in column 1"),
+            ParseError::UnknownDevice(name) => write!(f, "Unknown DUP device {:?}", name),
+            ParseError::InvalidCount(field) => write!(f, "DUP count {:?} is not a number", field),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The fields of an IBM 1130 assembler source card
+///
+/// Columns follow the 1130 assembler coding form:
+/// - Columns 1-20: Reserved for the listing (ignored by the assembler)
+/// - Columns 21-25: Label, or `*` in column 21 for a comment card
+/// - Columns 27-30: Opcode
+/// - Column 32: Format (e.g. `L` for a long instruction, `I` for indirect)
+/// - Column 33: Tag (index register 1-3)
+/// - Columns 35-71: Operands, then remarks after the first blank
+/// - Columns 73-80: Identification/sequence
+///
+/// Columns 26, 31, 34 and 72 are blank. Fields keep any leading blanks and
+/// drop trailing ones, so `to_string()` gives back the original 80 columns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceCard {
+    listing: String,
+    label: String,
+    opcode: String,
+    format: Option<char>,
+    tag: Option<u8>,
+    operand_field: String,
+    comment: Option<String>,
+    sequence: String,
+}
+
+impl SourceCard {
+    /// Read the fields from a text card
+    ///
+    /// Columns past a stub card's width read as blank.
+    pub fn parse(card: &PunchCard) -> Result<Self, ParseError> {
+        if card.card_type() != CardType::Text {
+            return Err(ParseError::NotText);
+        }
+        let mut text = [' '; 80];
+        for (index, column) in card.columns().iter().enumerate() {
+            text[index] = column.to_char().ok_or(ParseError::Undecodable(index))?;
+        }
+        let field = |columns: Range<usize>| -> String {
+            text[columns]
+                .iter()
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        let blank = |column: usize| match text[column] {
+            ' ' => Ok(()),
+            ch => Err(ParseError::NotBlank { column, ch }),
+        };
+
+        blank(71)?;
+        let mut source = SourceCard {
+            listing: field(LISTING_COLUMNS),
+            sequence: field(SEQUENCE_COLUMNS),
+            ..SourceCard::default()
+        };
+        if text[LABEL_COLUMNS.start] == '*' {
+            source.comment = Some(field(COMMENT_COLUMNS));
+            return Ok(source);
+        }
+        for column in SEPARATOR_COLUMNS {
+            blank(column)?;
+        }
+        source.label = field(LABEL_COLUMNS);
+        source.opcode = field(OPCODE_COLUMNS);
+        source.format = Some(text[FORMAT_COLUMN]).filter(|&ch| ch != ' ');
+        source.tag = match text[TAG_COLUMN] {
+            ' ' => None,
+            ch @ '0'..='3' => ch.to_digit(10).map(|digit| digit as u8),
+            ch => return Err(ParseError::InvalidTag(ch)),
+        };
+        source.operand_field = field(OPERAND_COLUMNS);
+        Ok(source)
+    }
+
+    /// Start punching a source card field by field
+    pub fn builder() -> SourceCardBuilder {
+        SourceCardBuilder::default()
+    }
+
+    /// Check if this is a comment card (`*` in column 21)
+    pub fn is_comment(&self) -> bool {
+        self.comment.is_some()
+    }
+
+    /// Get the text of a comment card (columns 22-71)
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Get columns 1-20, normally blank on a source card
+    pub fn listing(&self) -> &str {
+        &self.listing
+    }
+
+    /// Get the label, empty if there is none
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Get the opcode, empty on a blank or comment card
+    pub fn opcode(&self) -> &str {
+        &self.opcode
+    }
+
+    /// Get the format code from column 32
+    pub fn format(&self) -> Option<char> {
+        self.format
+    }
+
+    /// Get the index register from column 33
+    pub fn tag(&self) -> Option<u8> {
+        self.tag
+    }
+
+    /// Get the operands: columns 35-71 up to the first blank
+    pub fn operands(&self) -> &str {
+        self.split_operand_field().0
+    }
+
+    /// Get the remarks following the operands
+    pub fn remarks(&self) -> &str {
+        self.split_operand_field().1
+    }
+
+    /// Get the identification/sequence field
+    pub fn sequence(&self) -> &str {
+        &self.sequence
+    }
+
+    fn split_operand_field(&self) -> (&str, &str) {
+        match self.operand_field.split_once(' ') {
+            Some((operands, remarks)) => (operands, remarks.trim_start()),
+            None => (&self.operand_field, ""),
+        }
+    }
+}
+
+impl fmt::Display for SourceCard {
+    /// Format the card as its 80 columns
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<20}", self.listing)?;
+        match &self.comment {
+            Some(text) => write!(f, "*{:<50}", text)?,
+            None => write!(
+                f,
+                "{:<5} {:<4} {}{} {:<37}",
+                self.label,
+                self.opcode,
+                self.format.unwrap_or(' '),
+                self.tag.map_or(' ', |tag| char::from(b'0' + tag)),
+                self.operand_field
+            )?,
+        }
+        write!(f, " {:<8}", self.sequence)
+    }
+}
+
+/// Builds an assembler source card, punching each field in its columns
+///
+/// Fields are left-justified and blank-filled; fields left unset are blank.
+#[derive(Debug, Clone, Default)]
+pub struct SourceCardBuilder {
+    label: String,
+    opcode: String,
+    format: Option<char>,
+    tag: Option<u8>,
+    operands: String,
+    comment: String,
+    sequence: String,
+}
+
+impl SourceCardBuilder {
+    /// Set the label (columns 21-25)
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    /// Set the opcode (columns 27-30)
+    pub fn opcode(mut self, opcode: &str) -> Self {
+        self.opcode = opcode.to_string();
+        self
+    }
+
+    /// Set the format code (column 32)
+    pub fn format(mut self, format: char) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Set the index register (column 33)
+    pub fn tag(mut self, tag: u8) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Set the operands (starting in column 35)
+    pub fn operands(mut self, operands: &str) -> Self {
+        self.operands = operands.to_string();
+        self
+    }
+
+    /// Set the remarks, punched one blank after the operands
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.comment = comment.to_string();
+        self
+    }
+
+    /// Set the identification/sequence field (columns 73-80)
+    pub fn sequence(mut self, sequence: &str) -> Self {
+        self.sequence = sequence.to_string();
+        self
+    }
+
+    /// Punch the source card
+    ///
+    /// Fails if a field is wider than its columns, the operands and remarks
+    /// together do not fit in columns 35-71, the tag is not 0-3, or a
+    /// character has no punch pattern.
+    pub fn build(self) -> Result<PunchCard, String> {
+        let operand_field = match (self.operands.as_str(), self.comment.as_str()) {
+            (operands, "") => operands.to_string(),
+            (operands, comment) => format!("{} {}", operands, comment),
+        };
+        for (name, text, width) in [
+            ("Label", &self.label, LABEL_COLUMNS.len()),
+            ("Opcode", &self.opcode, OPCODE_COLUMNS.len()),
+            (
+                "Operands and remarks",
+                &operand_field,
+                OPERAND_COLUMNS.len(),
+            ),
+            ("Sequence", &self.sequence, SEQUENCE_COLUMNS.len()),
+        ] {
+            if text.chars().count() > width {
+                return Err(format!(
+                    "{} {:?} is wider than {} columns",
+                    name, text, width
+                ));
+            }
+        }
+        if let Some(tag) = self.tag.filter(|&tag| tag > 3) {
+            return Err(format!("Tag {} is not an index register (0-3)", tag));
+        }
+
+        let source = SourceCard {
+            label: self.label,
+            opcode: self.opcode,
+            format: self.format.filter(|&format| format != ' '),
+            tag: self.tag,
+            operand_field,
+            sequence: self.sequence,
+            ..SourceCard::default()
+        };
+        punch_text(&source.to_string())
+    }
+}
+
+/// Punch a text card, failing on characters with no punch pattern
+fn punch_text(text: &str) -> Result<PunchCard, String> {
+    if let Some(ch) = text
+        .chars()
+        .find(|&ch| char_to_hollerith(ch.to_ascii_uppercase()).is_none())
+    {
+        return Err(format!("Character {:?} has no punch pattern", ch));
+    }
+    Ok(PunchCard::from_text(text))
+}
+
+/// Make a card read from column images a text card if every column is a
+/// character, so source cards parse; otherwise it stays binary
+fn classify_read_card(mut card: PunchCard) -> PunchCard {
+    if card
+        .columns()
+        .iter()
+        .all(|column| column.to_char().is_some())
+    {
+        card.set_card_type(CardType::Text);
+    }
+    card
+}
+
+/// A named range of columns in a card layout (0-based)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutField {
+    pub name: &'static str,
+    pub columns: Range<usize>,
+}
+
+/// The fields of a fixed-column card format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardLayout {
+    pub name: &'static str,
+    pub fields: &'static [LayoutField],
+}
+
+impl CardLayout {
+    /// Get the columns of a field by name
+    pub fn field(&self, name: &str) -> Option<Range<usize>> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.columns.clone())
+    }
+}
+
+/// Columns 1-5
+const STATEMENT_NUMBER_COLUMNS: Range<usize> = 0..5;
+/// Column 6
+const CONTINUATION_COLUMN: usize = 5;
+/// Columns 7-72
+const STATEMENT_COLUMNS: Range<usize> = 6..72;
+/// Columns 2-72 of a comment card
+const FORTRAN_COMMENT_COLUMNS: Range<usize> = 1..72;
+/// Columns 73-80
+const IDENTIFICATION_COLUMNS: Range<usize> = 72..80;
+
+/// The fields of a FORTRAN source card (see `FortranCard`)
+pub const FORTRAN_LAYOUT: CardLayout = CardLayout {
+    name: "FORTRAN",
+    fields: &[
+        LayoutField {
+            name: "Statement number",
+            columns: STATEMENT_NUMBER_COLUMNS,
+        },
+        LayoutField {
+            name: "Continuation",
+            columns: CONTINUATION_COLUMN..CONTINUATION_COLUMN + 1,
+        },
+        LayoutField {
+            name: "Statement",
+            columns: STATEMENT_COLUMNS,
+        },
+        LayoutField {
+            name: "Identification",
+            columns: IDENTIFICATION_COLUMNS,
+        },
+    ],
+};
+
+/// The fields of a FORTRAN source card
+///
+/// - Columns 1-5: Statement number, `C` in column 1 for a comment card, or
+///   `*` in column 1 for a compiler control record such as `*LIST ALL` or
+///   `*IOCS(CARD,1132 PRINTER)`
+/// - Column 6: Continuation (any character but blank or `0`)
+/// - Columns 7-72: Statement
+/// - Columns 73-80: Identification
+///
+/// As with `SourceCard`, fields drop trailing blanks only, so `to_string()`
+/// gives back the original 80 columns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FortranCard {
+    number_field: String,
+    continuation: Option<char>,
+    text: String,
+    comment: Option<String>,
+    control: Option<String>,
+    identification: String,
+}
+
+impl FortranCard {
+    /// Read the fields from a text card punched on an 029
+    pub fn parse(card: &PunchCard) -> Result<Self, ParseError> {
+        Self::parse_with_charset(card, Charset::Ibm029)
+    }
+
+    /// Read the fields from a text card punched in the given character set
+    ///
+    /// Columns past a stub card's width read as blank.
+    pub fn parse_with_charset(card: &PunchCard, charset: Charset) -> Result<Self, ParseError> {
+        if card.card_type() != CardType::Text {
+            return Err(ParseError::NotText);
+        }
+        let mut text = [' '; 80];
+        for (index, column) in card.columns().iter().enumerate() {
+            text[index] = hollerith_to_char_with(charset, &column.punches)
+                .ok_or(ParseError::Undecodable(index))?;
+        }
+        let field = |columns: Range<usize>| -> String {
+            text[columns]
+                .iter()
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+
+        let mut fortran = FortranCard {
+            identification: field(IDENTIFICATION_COLUMNS),
+            ..FortranCard::default()
+        };
+        if text[0] == 'C' {
+            fortran.comment = Some(field(FORTRAN_COMMENT_COLUMNS));
+            return Ok(fortran);
+        }
+        if text[0] == '*' {
+            fortran.control = Some(field(FORTRAN_COMMENT_COLUMNS));
+            return Ok(fortran);
+        }
+        let number_field = field(STATEMENT_NUMBER_COLUMNS);
+        if !number_field
+            .trim_start()
+            .chars()
+            .all(|ch| ch.is_ascii_digit())
+        {
+            return Err(ParseError::InvalidStatementNumber(number_field));
+        }
+        fortran.number_field = number_field;
+        fortran.continuation = Some(text[CONTINUATION_COLUMN]).filter(|&ch| ch != ' ');
+        fortran.text = field(STATEMENT_COLUMNS);
+        Ok(fortran)
+    }
+
+    /// Start punching a FORTRAN card field by field
+    pub fn builder() -> FortranCardBuilder {
+        FortranCardBuilder::default()
+    }
+
+    /// Check if this is a comment card (`C` in column 1)
+    pub fn is_comment(&self) -> bool {
+        self.comment.is_some()
+    }
+
+    /// Get the text of a comment card (columns 2-72)
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Check if this is a compiler control record (`*` in column 1)
+    pub fn is_control(&self) -> bool {
+        self.control.is_some()
+    }
+
+    /// Get a control record's text after the `*`, such as `LIST ALL`
+    /// (columns 2-72)
+    pub fn control(&self) -> Option<&str> {
+        self.control.as_deref()
+    }
+
+    /// Get the statement number, if any
+    pub fn statement_number(&self) -> Option<u32> {
+        self.number_field.trim_start().parse().ok()
+    }
+
+    /// Check if this card continues the previous statement
+    pub fn is_continuation(&self) -> bool {
+        self.continuation.is_some_and(|ch| ch != '0')
+    }
+
+    /// Get the character punched in column 6, if any
+    pub fn continuation_mark(&self) -> Option<char> {
+        self.continuation
+    }
+
+    /// Get the statement text (columns 7-72)
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the identification field
+    pub fn identification(&self) -> &str {
+        &self.identification
+    }
+}
+
+impl fmt::Display for FortranCard {
+    /// Format the card as its 80 columns
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.comment, &self.control) {
+            (Some(text), _) => write!(f, "C{:<71}", text)?,
+            (None, Some(text)) => write!(f, "*{:<71}", text)?,
+            (None, None) => write!(
+                f,
+                "{:<5}{}{:<66}",
+                self.number_field,
+                self.continuation.unwrap_or(' '),
+                self.text
+            )?,
+        }
+        write!(f, "{:<8}", self.identification)
+    }
+}
+
+/// Builds a FORTRAN source card, punching each field in its columns
+///
+/// The statement number is right-justified in columns 1-5 and the other
+/// fields are left-justified.
+#[derive(Debug, Clone, Default)]
+pub struct FortranCardBuilder {
+    number: Option<u32>,
+    continuation: Option<char>,
+    text: String,
+    comment: Option<String>,
+    control: Option<String>,
+    identification: String,
+}
+
+impl FortranCardBuilder {
+    /// Set the statement number (columns 1-5)
+    pub fn statement_number(mut self, number: u32) -> Self {
+        self.number = Some(number);
+        self
+    }
+
+    /// Mark the card as a continuation (column 6)
+    pub fn continuation(mut self, mark: char) -> Self {
+        self.continuation = Some(mark);
+        self
+    }
+
+    /// Set the statement text (columns 7-72)
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Make a comment card with this text (columns 2-72)
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.comment = Some(comment.to_string());
+        self
+    }
+
+    /// Make a compiler control record with this text after the `*`
+    /// (columns 2-72)
+    pub fn control(mut self, control: &str) -> Self {
+        self.control = Some(control.to_string());
+        self
+    }
+
+    /// Set the identification field (columns 73-80)
+    pub fn identification(mut self, identification: &str) -> Self {
+        self.identification = identification.to_string();
+        self
+    }
+
+    /// Punch the FORTRAN card
+    ///
+    /// Fails if a field is wider than its columns, the statement number is
+    /// over 99999, the continuation mark is blank or `0`, a comment card has
+    /// statement fields, or a character has no punch pattern.
+    pub fn build(self) -> Result<PunchCard, String> {
+        let number_field = match self.number {
+            Some(number) if number > 99999 => {
+                return Err(format!("Statement number {} is over 99999", number));
+            }
+            Some(number) => format!("{:>5}", number),
+            None => String::new(),
+        };
+        if let Some(mark @ (' ' | '0')) = self.continuation {
+            return Err(format!("Continuation mark {:?} does not continue", mark));
+        }
+        let statement =
+            self.number.is_some() || self.continuation.is_some() || !self.text.is_empty();
+        if self.comment.is_some() && (statement || self.control.is_some()) {
+            return Err("A comment card has no statement fields".to_string());
+        }
+        if self.control.is_some() && statement {
+            return Err("A control record has no statement fields".to_string());
+        }
+        for (name, text, width) in [
+            ("Statement", &self.text, STATEMENT_COLUMNS.len()),
+            (
+                "Comment",
+                self.comment.as_ref().unwrap_or(&String::new()),
+                FORTRAN_COMMENT_COLUMNS.len(),
+            ),
+            (
+                "Control record",
+                self.control.as_ref().unwrap_or(&String::new()),
+                FORTRAN_COMMENT_COLUMNS.len(),
+            ),
+            (
+                "Identification",
+                &self.identification,
+                IDENTIFICATION_COLUMNS.len(),
+            ),
+        ] {
+            if text.chars().count() > width {
+                return Err(format!(
+                    "{} {:?} is wider than {} columns",
+                    name, text, width
+                ));
+            }
+        }
+
+        let fortran = FortranCard {
+            number_field,
+            continuation: self.continuation,
+            text: self.text,
+            comment: self.comment,
+            control: self.control,
+            identification: self.identification,
+        };
+        punch_text(&fortran.to_string())
+    }
+}
+
+/// A FORTRAN statement joined from its initial and continuation cards
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    /// The statement number from the initial card
+    pub number: Option<u32>,
+    /// Columns 7-72 of each card, in order, with trailing blanks removed
+    /// from the end of the statement only
+    pub text: String,
+    /// Deck positions from the initial card to the last continuation
+    pub cards: Range<usize>,
+}
+
+impl Statement {
+    /// Punch the statement as an initial card and continuation cards
+    ///
+    /// The text is split every 66 columns, and continuation cards are
+    /// marked 1-9 in column 6.
+    pub fn to_cards(&self) -> Result<Vec<PunchCard>, String> {
+        let chars: Vec<char> = self.text.chars().collect();
+        let lines: Vec<String> = if chars.is_empty() {
+            vec![String::new()]
+        } else {
+            chars
+                .chunks(STATEMENT_COLUMNS.len())
+                .map(|chunk| chunk.iter().collect())
+                .collect()
+        };
+        if lines.len() > 10 {
+            return Err(format!(
+                "Statement needs {} continuation cards, more than 9",
+                lines.len() - 1
+            ));
+        }
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let builder = FortranCard::builder().text(line.trim_end());
+                let builder = match (index, self.number) {
+                    (0, Some(number)) => builder.statement_number(number),
+                    (0, None) => builder,
+                    (index, _) => builder.continuation(char::from(b'0' + index as u8)),
+                };
+                builder.build()
+            })
+            .collect()
+    }
+}
+
+/// A source card error and the (0-based) deck position of the card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceDeckError {
+    pub card: usize,
+    pub error: ParseError,
+}
+
+impl fmt::Display for SourceDeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Card {}: {}", self.card + 1, self.error)
+    }
+}
+
+impl std::error::Error for SourceDeckError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Join a FORTRAN deck's cards into statements
+///
+/// Comment cards and control records are skipped. Each continuation adds its columns 7-72 after
+/// the full 66 columns of the card before, as the compiler reads them. Fails
+/// on a card that does not parse or a continuation with no statement before
+/// it.
+pub fn join_continuations(deck: &Deck) -> Result<Vec<Statement>, SourceDeckError> {
+    let mut statements: Vec<Statement> = Vec::new();
+    // Cards read so far for the last statement
+    let mut parts = 0;
+    for (index, card) in deck.iter().enumerate() {
+        let fortran =
+            FortranCard::parse(card).map_err(|error| SourceDeckError { card: index, error })?;
+        if fortran.is_comment() || fortran.is_control() {
+            continue;
+        }
+        if fortran.is_continuation() {
+            let statement = statements.last_mut().ok_or(SourceDeckError {
+                card: index,
+                error: ParseError::OrphanContinuation,
+            })?;
+            statement.text = format!(
+                "{:<width$}{}",
+                statement.text,
+                fortran.text(),
+                width = parts * STATEMENT_COLUMNS.len()
+            );
+            statement.cards.end = index + 1;
+            parts += 1;
+        } else {
+            parts = 1;
+            statements.push(Statement {
+                number: fortran.statement_number(),
+                text: fortran.text().to_string(),
+                cards: index..index + 1,
+            });
+        }
+    }
+    Ok(statements)
+}
+
+/// Punch an example FORTRAN program: a DO loop summing squares
+pub fn generate_example_fortran() -> Deck {
+    let statements = [
+        (None, "DIMENSION A(10)"),
+        (None, "DO 10 I = 1, 10"),
+        (Some(10), "A(I) = FLOAT(I)"),
+        (None, "S = 0.0"),
+        (None, "DO 20 I = 1, 10"),
+        (Some(20), "S = S + A(I)*A(I)"),
+        (None, "WRITE (3, 30) S"),
+        (Some(30), "FORMAT (23H SUM OF SQUARES 1 TO 10, F10.2)"),
+        (None, "CALL EXIT"),
+        (None, "END"),
+    ];
+    let comment = FortranCard::builder()
+        .comment("     SUM OF SQUARES WITH A DO LOOP")
+        .build()
+        .expect("comment fits");
+    let mut deck = Deck::from_cards(vec![comment]);
+    for (number, text) in statements {
+        let card = match number {
+            Some(number) => FortranCard::builder().statement_number(number),
+            None => FortranCard::builder(),
+        };
+        deck.push(card.text(text).build().expect("example statements fit"));
+    }
+    deck
+}
+
+/// What to do with a source line too long for the columns before the
+/// sequence field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LongLinePolicy {
+    /// Fail, naming the line
+    #[default]
+    Error,
+    /// Continue the rest of the line on further cards
+    Wrap,
+    /// Drop the excess and list the line in `PunchedSource::truncated`
+    Truncate,
+}
+
+/// How `punch_source_deck` numbers cards
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceOptions {
+    /// Zero-based columns of the sequence number, normally 73-80 or 76-80
+    pub columns: Range<usize>,
+    /// Number of the first card
+    pub start: u32,
+    /// Step between cards
+    pub increment: u32,
+    /// What to do with long lines
+    pub long_lines: LongLinePolicy,
+}
+
+impl Default for SequenceOptions {
+    /// Columns 73-80, numbered 10, 20, 30, ... so cards can be inserted
+    fn default() -> Self {
+        SequenceOptions {
+            columns: SEQUENCE_COLUMNS,
+            start: 10,
+            increment: 10,
+            long_lines: LongLinePolicy::Error,
+        }
+    }
+}
+
+/// A deck punched from source text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PunchedSource {
+    pub deck: Deck,
+    /// Zero-based source lines cut short under `LongLinePolicy::Truncate`
+    pub truncated: Vec<usize>,
+}
+
+/// Punch assembler or FORTRAN source, one card per line, with sequence
+/// numbers
+///
+/// Each line is punched from column 1 as written, so it should already be
+/// in its coding form columns. Numbers are zero-filled to the width of the
+/// sequence field. Fails on a long line under `LongLinePolicy::Error`, a
+/// character with no punch pattern, or a number too wide for the field.
+pub fn punch_source_deck(text: &str, options: &SequenceOptions) -> Result<PunchedSource, String> {
+    let columns = &options.columns;
+    if columns.is_empty() || columns.end > 80 {
+        return Err(format!(
+            "Invalid sequence columns {}..{}",
+            columns.start, columns.end
+        ));
+    }
+    let width = columns.start;
+    let digits = columns.len();
+    let mut deck = Deck::new();
+    let mut truncated = Vec::new();
+    let mut number = Some(options.start);
+    for (index, line) in text.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let pieces: Vec<&[char]> = match options.long_lines {
+            _ if chars.len() <= width => vec![&chars[..]],
+            LongLinePolicy::Error => {
+                return Err(format!(
+                    "Line {}: {} characters do not fit before column {}",
+                    index + 1,
+                    chars.len(),
+                    width + 1
+                ));
+            }
+            LongLinePolicy::Wrap => chars.chunks(width.max(1)).collect(),
+            LongLinePolicy::Truncate => {
+                truncated.push(index);
+                vec![&chars[..width]]
+            }
+        };
+        for piece in pieces {
+            let sequence = number
+                .map(|number| format!("{:0digits$}", number))
+                .filter(|sequence| sequence.len() <= digits)
+                .ok_or_else(|| {
+                    format!(
+                        "Line {}: sequence number does not fit in columns {}-{}",
+                        index + 1,
+                        columns.start + 1,
+                        columns.end
+                    )
+                })?;
+            let piece: String = piece.iter().collect();
+            let card = punch_text(&format!("{:<width$}{}", piece, sequence))
+                .map_err(|error| format!("Line {}: {}", index + 1, error))?;
+            deck.push(card);
+            number = number.and_then(|number| number.checked_add(options.increment));
+        }
+    }
+    Ok(PunchedSource { deck, truncated })
+}
+
+/// A problem with a deck's sequence numbers (card positions are 0-based)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceError {
+    /// The sequence field is blank
+    Missing { card: usize },
+    /// The sequence field does not end in a number
+    NotNumeric { card: usize },
+    /// The number is the same as the last in-order card
+    Duplicate { card: usize, number: u64 },
+    /// The card is out of place; `previous` is the last in-order number
+    /// before it, if any
+    OutOfOrder {
+        card: usize,
+        number: u64,
+        previous: Option<u64>,
+    },
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceError::Missing { card } => {
+                write!(f, "Card {}: no sequence number", card + 1)
+            }
+            SequenceError::NotNumeric { card } => {
+                write!(f, "Card {}: sequence field is not a number", card + 1)
+            }
+            SequenceError::Duplicate { card, number } => {
+                write!(f, "Card {}: sequence {} is duplicated", card + 1, number)
+            }
+            SequenceError::OutOfOrder {
+                card,
+                number,
+                previous,
+            } => match previous {
+                Some(previous) => write!(
+                    f,
+                    "Card {}: sequence {} is out of order after {}",
+                    card + 1,
+                    number,
+                    previous
+                ),
+                None => write!(
+                    f,
+                    "Card {}: sequence {} is out of order at the start of the deck",
+                    card + 1,
+                    number
+                ),
+            },
+        }
+    }
+}
+
+impl std::error::Error for SequenceError {}
+
+/// Check that the sequence numbers in columns 73-80 rise through the deck
+///
+/// The number is the run of digits ending the field, so a deck ID before
+/// it (as in `SUM00010`) is allowed. The longest rising run of numbers
+/// (keeping earlier cards on a tie) is taken as the deck's order, and
+/// every other numbered card is reported, so one misplaced card gives one
+/// error whether it was moved up or down the deck.
+pub fn check_sequence(deck: &Deck) -> Vec<SequenceError> {
+    let mut errors = Vec::new();
+    let mut numbers: Vec<Result<u64, SequenceError>> = Vec::with_capacity(deck.len());
+    for (card_index, card) in deck.iter().enumerate() {
+        let field: String = SEQUENCE_COLUMNS
+            .map(|index| {
+                card.get_column(index)
+                    .and_then(|column| column.to_char())
+                    .unwrap_or('?')
+            })
+            .collect();
+        let field = field.trim_end();
+        let digits = field.len() - field.trim_end_matches(|ch: char| ch.is_ascii_digit()).len();
+        numbers.push(match &field[field.len() - digits..] {
+            _ if field.trim().is_empty() => Err(SequenceError::Missing { card: card_index }),
+            "" => Err(SequenceError::NotNumeric { card: card_index }),
+            digits => Ok(digits.parse::<u64>().expect("at most 8 digits")),
+        });
+    }
+
+    // run[i]: length of the longest rising run of numbers starting at card i
+    let mut run = vec![0usize; numbers.len()];
+    for i in (0..numbers.len()).rev() {
+        if let Ok(number) = numbers[i] {
+            run[i] = 1
+                + (i + 1..numbers.len())
+                    .filter(|&j| numbers[j].as_ref().is_ok_and(|&next| next > number))
+                    .map(|j| run[j])
+                    .max()
+                    .unwrap_or(0);
+        }
+    }
+    let mut in_order = vec![false; numbers.len()];
+    let mut wanted = run.iter().copied().max().unwrap_or(0);
+    let mut floor: Option<u64> = None;
+    for (i, number) in numbers.iter().enumerate() {
+        if wanted > 0
+            && run[i] == wanted
+            && let Ok(number) = *number
+            && floor.is_none_or(|floor| number > floor)
+        {
+            in_order[i] = true;
+            wanted -= 1;
+            floor = Some(number);
+        }
+    }
+
+    let mut previous: Option<u64> = None;
+    for (card_index, number) in numbers.into_iter().enumerate() {
+        let number = match number {
+            Ok(number) => number,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        if in_order[card_index] {
+            previous = Some(number);
+            continue;
+        }
+        match previous {
+            Some(previous) if number == previous => errors.push(SequenceError::Duplicate {
+                card: card_index,
+                number,
+            }),
+            _ => errors.push(SequenceError::OutOfOrder {
+                card: card_index,
+                number,
+                previous,
+            }),
+        }
+    }
+    errors
+}
+
+/// How much of an object card `validate_object_format` checks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ObjectValidation {
+    /// Only that the card is binary and not blank
+    #[default]
+    Format,
+    /// Also that it parses as an `ObjectCard`: type, word count and checksum
+    Structure,
+}
+
+/// Validate IBM 1130 object deck format
+///
+/// Checks if the card follows binary card conventions, and with
+/// `ObjectValidation::Structure` that its fields are consistent.
+pub fn validate_object_format(card: &PunchCard, check: ObjectValidation) -> Result<(), String> {
+    if card.card_type() != CardType::Binary {
+        return Err("Object cards must be binary type".to_string());
+    }
+
+    // Check that we have punches (not a blank card)
+    if card.punched_count() == 0 {
+        return Err("Object card cannot be blank".to_string());
+    }
+
+    if check == ObjectValidation::Structure {
+        ObjectCard::parse(card).map_err(|error| error.to_string())?;
+    }
+    Ok(())
+}
+
+/// Number of 16-bit data words in columns 1-72 of an object deck card
+pub const OBJECT_WORDS: usize = 54;
+
+/// Extract the data words from columns 1-72 of an object deck card
+///
+/// Each column is read as 12 bits, row 12 high through row 9 low, and the
+/// 864-bit stream is split into 54 16-bit words, so every 4 columns hold 3
+/// words. Columns 73-80 (sequence numbers) are not data.
+pub fn object_words(card: &PunchCard) -> [u16; OBJECT_WORDS] {
+    let columns = card.to_u16_columns();
+    let mut words = [0u16; OBJECT_WORDS];
+    for (group, chunk) in words.chunks_mut(3).enumerate() {
+        let bits = columns[group * 4..group * 4 + 4]
+            .iter()
+            .fold(0u64, |acc, &column| (acc << 12) | u64::from(column));
+        chunk[0] = (bits >> 32) as u16;
+        chunk[1] = (bits >> 16) as u16;
+        chunk[2] = bits as u16;
+    }
+    words
+}
+
+/// Punch data words into columns 1-72 of a binary card (see `object_words`)
+pub fn words_to_object_card(words: &[u16; OBJECT_WORDS]) -> PunchCard {
+    let mut columns = [0u16; 72];
+    for (group, chunk) in words.chunks(3).enumerate() {
+        let bits = chunk
+            .iter()
+            .fold(0u64, |acc, &word| (acc << 16) | u64::from(word));
+        for offset in 0..4 {
+            columns[group * 4 + offset] = (bits >> (12 * (3 - offset))) as u16 & 0x0FFF;
+        }
+    }
+    PunchCard::from_u16_columns(&columns)
+}
+
+impl PunchCard {
+    /// Format the object deck data words as 4-digit hex, separated by spaces
+    pub fn to_hex_words(&self) -> String {
+        object_words(self)
+            .iter()
+            .map(|word| format!("{:04X}", word))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Decode text cards as text and show binary cards as hex words
+    pub fn to_text_or_hex(&self) -> String {
+        match self.card_type() {
+            CardType::Text => self.to_text(),
+            CardType::Binary => self.to_hex_words(),
+        }
+    }
+}
+
+/// Number of data words an object card carries (words 10-54)
+pub const OBJECT_DATA_WORDS: usize = 45;
+
+/// What an object card holds, from its type code (the high byte of word 3)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ObjectCardKind {
+    /// Data words to load at the card's address (type 0x0A)
+    Data,
+    /// End of program; the address word holds the entry point (type 0x0F)
+    End,
+    /// Any other type, such as a program header
+    Other(u8),
+}
+
+impl ObjectCardKind {
+    /// Get the kind for a type code
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0x0A => ObjectCardKind::Data,
+            0x0F => ObjectCardKind::End,
+            code => ObjectCardKind::Other(code),
+        }
+    }
+
+    /// Get the type code punched for this kind
+    pub fn code(self) -> u8 {
+        match self {
+            ObjectCardKind::Data => 0x0A,
+            ObjectCardKind::End => 0x0F,
+            ObjectCardKind::Other(code) => code,
+        }
+    }
+}
+
+/// How the loader adjusts a data word, from its 2-bit relocation indicator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Relocation {
+    /// 00: loaded as punched
+    #[default]
+    Absolute,
+    /// 01: an address, which has the load origin added
+    Relocatable,
+    /// 10: part of the name of a LIBF subroutine
+    Libf,
+    /// 11: part of the name of a CALL subroutine
+    Call,
+}
+
+impl Relocation {
+    /// Get the relocation for an indicator (the low 2 bits)
+    pub fn from_bits(bits: u16) -> Self {
+        match bits & 0b11 {
+            0b00 => Relocation::Absolute,
+            0b01 => Relocation::Relocatable,
+            0b10 => Relocation::Libf,
+            _ => Relocation::Call,
+        }
+    }
+
+    /// Get the 2-bit indicator punched for this relocation
+    pub fn bits(self) -> u16 {
+        match self {
+            Relocation::Absolute => 0b00,
+            Relocation::Relocatable => 0b01,
+            Relocation::Libf => 0b10,
+            Relocation::Call => 0b11,
+        }
+    }
+}
+
+/// Errors from reading or building an object card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectCardError {
+    /// The card is a text card
+    NotBinary,
+    /// The word count is more than the card can hold
+    WordCount(u8),
+    /// The checksum word does not match the card's contents
+    Checksum { stored: u16, computed: u16 },
+    /// A sequence column does not decode to, or has no punches for, a
+    /// character (0-based)
+    InvalidSequence(usize),
+    /// The sequence is longer than columns 73-80
+    SequenceTooLong(usize),
+}
+
+impl fmt::Display for ObjectCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectCardError::NotBinary => write!(f, "Object cards must be binary type"),
+            ObjectCardError::WordCount(count) => write!(
+                f,
+                "Word count {} is more than {} data words",
+                count, OBJECT_DATA_WORDS
+            ),
+            ObjectCardError::Checksum { stored, computed } => write!(
+                f,
+                "Checksum {:04X} does not match the card (expected {:04X})",
+                stored, computed
+            ),
+            ObjectCardError::InvalidSequence(column) => {
+                write!(f, "Column {}: sequence is not a character", column + 1)
+            }
+            ObjectCardError::SequenceTooLong(len) => write!(
+                f,
+                "Sequence of {} characters does not fit in columns 73-80",
+                len
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ObjectCardError {}
+
+/// A relocatable object deck card, as punched by the 1130 assembler
+///
+/// Columns 1-72 hold 54 words (see `object_words`):
+/// - Word 1: Load address of the first data word
+/// - Word 2: Checksum, or 0 if the card is not checked
+/// - Word 3: Type code (high byte, see `ObjectCardKind`) and data word count
+///   (low byte)
+/// - Words 4-9: Relocation indicators, 2 bits per data word from the high
+///   bits of word 4 (00 absolute, 01 relocatable)
+/// - Words 10-54: Up to 45 data words; unused words are punched as 0
+///
+/// Columns 73-80 hold the deck identification/sequence in card code. The
+/// checksum is the 16-bit sum, with end-around carry, of words 1 and 3-9 and
+/// the data words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ObjectCardFields"))]
+pub struct ObjectCard {
+    kind: ObjectCardKind,
+    address: u16,
+    checksum: u16,
+    relocation: [u16; 6],
+    data: Vec<u16>,
+    sequence: String,
+}
+
+/// An object card as serialized, checked by `ObjectCard::try_from`
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct ObjectCardFields {
+    kind: ObjectCardKind,
+    address: u16,
+    checksum: u16,
+    relocation: [u16; 6],
+    data: Vec<u16>,
+    sequence: String,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ObjectCardFields> for ObjectCard {
+    type Error = ObjectCardError;
+
+    /// Rebuild the card through `new` and `with_sequence`, so a deserialized
+    /// card holds at most 45 data words, a punchable sequence, and a
+    /// matching checksum
+    fn try_from(fields: ObjectCardFields) -> Result<Self, Self::Error> {
+        let card = ObjectCard::new(fields.kind, fields.address, &fields.data)?
+            .with_relocation(fields.relocation)
+            .with_sequence(&fields.sequence)?;
+        if fields.checksum != card.checksum {
+            return Err(ObjectCardError::Checksum {
+                stored: fields.checksum,
+                computed: card.checksum,
+            });
+        }
+        Ok(card)
+    }
+}
+
+impl ObjectCard {
+    /// Create a checksummed card with absolute data words
+    ///
+    /// Fails if there are more than 45 data words.
+    pub fn new(kind: ObjectCardKind, address: u16, data: &[u16]) -> Result<Self, ObjectCardError> {
+        if data.len() > OBJECT_DATA_WORDS {
+            return Err(ObjectCardError::WordCount(data.len().min(255) as u8));
+        }
+        let mut card = ObjectCard {
+            kind,
+            address,
+            checksum: 0,
+            relocation: [0; 6],
+            data: data.to_vec(),
+            sequence: String::new(),
+        };
+        card.checksum = card.compute_checksum();
+        Ok(card)
+    }
+
+    /// Set the relocation indicator words, updating the checksum
+    pub fn with_relocation(mut self, relocation: [u16; 6]) -> Self {
+        self.relocation = relocation;
+        self.checksum = self.compute_checksum();
+        self
+    }
+
+    /// Set the relocation of each data word, updating the checksum
+    ///
+    /// Data words past the end of `relocation` are absolute.
+    pub fn with_word_relocation(self, relocation: &[Relocation]) -> Self {
+        let mut words = [0u16; 6];
+        for (index, flag) in relocation.iter().take(OBJECT_DATA_WORDS).enumerate() {
+            words[index / 8] |= flag.bits() << (14 - 2 * (index % 8));
+        }
+        self.with_relocation(words)
+    }
+
+    /// Set the identification/sequence punched in columns 73-80
+    pub fn with_sequence(mut self, sequence: &str) -> Result<Self, ObjectCardError> {
+        let len = sequence.chars().count();
+        if len > SEQUENCE_COLUMNS.len() {
+            return Err(ObjectCardError::SequenceTooLong(len));
+        }
+        if let Some(offset) = sequence
+            .chars()
+            .position(|ch| char_to_hollerith(ch.to_ascii_uppercase()).is_none())
+        {
+            return Err(ObjectCardError::InvalidSequence(
+                SEQUENCE_COLUMNS.start + offset,
+            ));
+        }
+        self.sequence = sequence.to_string();
+        Ok(self)
+    }
+
+    /// Read an object card from its punches
+    ///
+    /// Fails on a text card, a word count over 45, a nonzero checksum that
+    /// does not match, or undecodable sequence columns. Words past the word
+    /// count are ignored.
+    pub fn parse(card: &PunchCard) -> Result<Self, ObjectCardError> {
+        if card.card_type() != CardType::Binary {
+            return Err(ObjectCardError::NotBinary);
+        }
+        let words = object_words(card);
+        let count = (words[2] & 0xFF) as usize;
+        if count > OBJECT_DATA_WORDS {
+            return Err(ObjectCardError::WordCount(count as u8));
+        }
+        let mut sequence = String::new();
+        for index in SEQUENCE_COLUMNS {
+            let column = card.get_column(index);
+            let ch = column.map_or(Some(' '), |column| column.to_char());
+            sequence.push(ch.ok_or(ObjectCardError::InvalidSequence(index))?);
+        }
+        let object = ObjectCard {
+            kind: ObjectCardKind::from_code((words[2] >> 8) as u8),
+            address: words[0],
+            checksum: words[1],
+            relocation: words[3..9].try_into().expect("six relocation words"),
+            data: words[9..9 + count].to_vec(),
+            sequence: sequence.trim_end().to_string(),
+        };
+        if !object.verify_checksum() {
+            return Err(ObjectCardError::Checksum {
+                stored: object.checksum,
+                computed: object.compute_checksum(),
+            });
+        }
+        Ok(object)
+    }
+
+    /// Punch the card
+    pub fn to_card(&self) -> PunchCard {
+        let mut card = words_to_object_card(&self.words());
+        for (offset, ch) in self.sequence.chars().enumerate() {
+            let code = char_to_hollerith(ch.to_ascii_uppercase())
+                .expect("sequence characters are checked");
+            card.set_column_hollerith(SEQUENCE_COLUMNS.start + offset, code)
+                .expect("sequence lies within 80 columns");
+        }
+        card
+    }
+
+    /// Get what the card holds
+    pub fn card_kind(&self) -> ObjectCardKind {
+        self.kind
+    }
+
+    /// Get the load address of the first data word
+    pub fn load_address(&self) -> u16 {
+        self.address
+    }
+
+    /// Get the checksum word as punched
+    pub fn checksum(&self) -> u16 {
+        self.checksum
+    }
+
+    /// Get the relocation indicator words
+    pub fn relocation(&self) -> &[u16; 6] {
+        &self.relocation
+    }
+
+    /// Get the relocation of a data word (0-based), if the card has it
+    pub fn word_relocation(&self, index: usize) -> Option<Relocation> {
+        (index < self.data.len())
+            .then(|| Relocation::from_bits(self.relocation[index / 8] >> (14 - 2 * (index % 8))))
+    }
+
+    /// Get the relocation of every data word
+    pub fn relocations(&self) -> Vec<Relocation> {
+        (0..self.data.len())
+            .filter_map(|index| self.word_relocation(index))
+            .collect()
+    }
+
+    /// Get the data words
+    pub fn data_words(&self) -> &[u16] {
+        &self.data
+    }
+
+    /// Get the identification/sequence field
+    pub fn sequence(&self) -> &str {
+        &self.sequence
+    }
+
+    /// Check the checksum word against the card's contents
+    ///
+    /// A zero checksum is not checked and always passes.
+    pub fn verify_checksum(&self) -> bool {
+        self.checksum == 0 || self.checksum == self.compute_checksum()
+    }
+
+    /// Compute the checksum of the card's contents
+    pub fn compute_checksum(&self) -> u16 {
+        let count = [u16::from(self.kind.code()) << 8 | self.data.len() as u16];
+        [self.address]
+            .iter()
+            .chain(&count)
+            .chain(&self.relocation)
+            .chain(&self.data)
+            .fold(0u16, |sum, &word| {
+                let (sum, carry) = sum.overflowing_add(word);
+                sum + u16::from(carry)
+            })
+    }
+
+    /// Get all 54 words of the card
+    fn words(&self) -> [u16; OBJECT_WORDS] {
+        let mut words = [0u16; OBJECT_WORDS];
+        words[0] = self.address;
+        words[1] = self.checksum;
+        words[2] = u16::from(self.kind.code()) << 8 | self.data.len() as u16;
+        words[3..9].copy_from_slice(&self.relocation);
+        words[9..9 + self.data.len()].copy_from_slice(&self.data);
+        words
+    }
+}
+
+/// An object card error and the (0-based) deck position of the card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectDeckError {
+    pub card: usize,
+    pub error: ObjectCardError,
+}
+
+impl fmt::Display for ObjectDeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Card {}: {}", self.card + 1, self.error)
+    }
+}
+
+impl std::error::Error for ObjectDeckError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Punch a program as object cards loading `words` at `origin`
+///
+/// Each data card holds up to 45 words at consecutive addresses, and an end
+/// card with `origin` as the entry point closes the deck. `relocation` gives
+/// the relocation of each word; words past its end are absolute.
+pub fn object_card_from_words(
+    origin: u16,
+    words: &[u16],
+    relocation: &[Relocation],
+) -> Vec<PunchCard> {
+    punch_object_deck([(origin, words, relocation)], origin)
+}
+
+/// Punch data cards for each `(address, words, relocation)` run, then an end
+/// card
+pub(crate) fn punch_object_deck<'a>(
+    runs: impl IntoIterator<Item = (u16, &'a [u16], &'a [Relocation])>,
+    entry: u16,
+) -> Vec<PunchCard> {
+    runs.into_iter()
+        .flat_map(|(origin, words, relocation)| {
+            words
+                .chunks(OBJECT_DATA_WORDS)
+                .enumerate()
+                .map(move |(index, chunk)| {
+                    let offset = index * OBJECT_DATA_WORDS;
+                    let address = origin.wrapping_add(offset as u16);
+                    let flags = relocation.get(offset..).unwrap_or_default();
+                    ObjectCard::new(ObjectCardKind::Data, address, chunk)
+                        .expect("chunks fit on a card")
+                        .with_word_relocation(&flags[..flags.len().min(chunk.len())])
+                })
+        })
+        .chain(std::iter::once(
+            ObjectCard::new(ObjectCardKind::End, entry, &[]).expect("end cards have no data"),
+        ))
+        .map(|object| object.to_card())
+        .collect()
+}
+
+/// Parse every card of an object deck (see `ObjectCard::parse`)
+///
+/// Fails on the first card that does not parse, naming its position.
+pub fn parse_object_deck(deck: &Deck) -> Result<Vec<ObjectCard>, ObjectDeckError> {
+    deck.iter()
+        .enumerate()
+        .map(|(index, card)| {
+            ObjectCard::parse(card).map_err(|error| ObjectDeckError { card: index, error })
+        })
+        .collect()
+}
+
+/// Read the memory image loaded by an object deck
+///
+/// Returns `(address, words)` runs in deck order, joining data cards that
+/// continue at the next address. Reading stops at the end card; cards of
+/// other kinds are skipped. Fails on the first card that does not parse.
+pub fn words_from_object_deck(deck: &Deck) -> Result<Vec<(u16, Vec<u16>)>, ObjectDeckError> {
+    let mut runs: Vec<(u16, Vec<u16>)> = Vec::new();
+    for (index, card) in deck.iter().enumerate() {
+        let object =
+            ObjectCard::parse(card).map_err(|error| ObjectDeckError { card: index, error })?;
+        match object.card_kind() {
+            ObjectCardKind::End => break,
+            ObjectCardKind::Data if !object.data_words().is_empty() => {
+                if let Some((start, words)) = runs.last_mut()
+                    && start.wrapping_add(words.len() as u16) == object.load_address()
+                {
+                    words.extend_from_slice(object.data_words());
+                } else {
+                    runs.push((object.load_address(), object.data_words().to_vec()));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::CardFormat;
+
+    #[test]
+    fn test_generate_example_source() {
+        let card = generate_example_source();
+        assert_eq!(card.card_type(), CardType::Text);
+        assert!(card.punched_count() > 0);
+
+        // Check that it contains expected content
+        let text = card.to_text();
+        assert!(text.contains("START"));
+    }
+
+    const SOURCE_DECK: &str = include_str!("../../tests/fixtures/ibm1130_source.txt");
+
+    #[test]
+    fn test_parse_example_source() {
+        let card = generate_example_source();
+        let source = SourceCard::parse(&card).unwrap();
+        assert_eq!(source.label(), "START");
+        assert_eq!(source.opcode(), "DC");
+        assert_eq!(source.operands(), "0");
+        assert_eq!(source.remarks(), "IBM 1130 EXAMPLE PROGRAM");
+        assert_eq!((source.format(), source.tag()), (None, None));
+        assert!(!source.is_comment());
+        assert_eq!(source.to_string(), card.to_text());
+    }
+
+    #[test]
+    fn test_parse_source_deck_fixture() {
+        let cards: Vec<SourceCard> = SOURCE_DECK
+            .lines()
+            .map(|line| SourceCard::parse(&PunchCard::from_text(line)).unwrap())
+            .collect();
+        assert_eq!(cards.len(), 12);
+        for (card, line) in cards.iter().zip(SOURCE_DECK.lines()) {
+            assert_eq!(card.to_string(), line);
+        }
+
+        assert!(cards[0].is_comment());
+        assert_eq!(cards[0].comment(), Some("SUM A TABLE OF TEN WORDS"));
+        assert_eq!(cards[0].opcode(), "");
+        assert_eq!(cards[0].sequence(), "SUM00010");
+
+        assert_eq!(cards[2].label(), "START");
+        assert_eq!(cards[2].opcode(), "LDX");
+        assert_eq!(cards[2].format(), Some('L'));
+        assert_eq!(cards[2].tag(), Some(1));
+        assert_eq!(cards[2].operands(), "10");
+
+        assert_eq!(cards[3].operands(), "TABLE-1");
+        assert_eq!(cards[3].remarks(), "ADD UP FROM THE END");
+
+        assert_eq!(cards[8].label(), "");
+        assert_eq!(cards[8].opcode(), "WAIT");
+        assert_eq!((cards[8].operands(), cards[8].remarks()), ("", ""));
+    }
+
+    #[test]
+    fn test_parse_listing_and_remarks_only_cards() {
+        // An assembled listing card keeps the address and object code in
+        // columns 1-20
+        let listed = format!("{:<20}LOOP  LD    1 TABLE-1", "0102 C1 00 0109");
+        let source = SourceCard::parse(&PunchCard::from_text(&listed)).unwrap();
+        assert_eq!(source.listing(), "0102 C1 00 0109");
+        assert_eq!(source.label(), "LOOP");
+        assert_eq!(source.to_string().trim_end(), listed);
+
+        // Remarks carried over onto a card with no label or opcode
+        let carried = format!("{:34} ... AND STORE THE TOTAL", "");
+        let source = SourceCard::parse(&PunchCard::from_text(&carried)).unwrap();
+        assert_eq!((source.label(), source.opcode()), ("", ""));
+        assert_eq!(source.operands(), "");
+        assert_eq!(source.remarks(), "... AND STORE THE TOTAL");
+        assert_eq!(source.to_string().trim_end(), carried);
+
+        let stub = PunchCard::from_text_with_width(&format!("{:20}*", ""), 51).unwrap();
+        assert_eq!(SourceCard::parse(&stub).unwrap().comment(), Some(""));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let binary = PunchCard::from_binary(&[0x00]);
+        assert_eq!(SourceCard::parse(&binary), Err(ParseError::NotText));
+
+        let shifted = PunchCard::from_text(&format!("{:20}LABEL1 LD", ""));
+        assert_eq!(
+            SourceCard::parse(&shifted),
+            Err(ParseError::NotBlank {
+                column: 25,
+                ch: '1'
+            })
+        );
+        let bad_tag = PunchCard::from_text(&format!("{:20}      LD    4 X", ""));
+        let error = SourceCard::parse(&bad_tag).unwrap_err();
+        assert_eq!(error, ParseError::InvalidTag('4'));
+        assert_eq!(
+            error.to_string(),
+            "Column 33: tag must be blank or 0-3, found '4'"
+        );
+
+        let mut card = PunchCard::from_text(&format!("{:20}      LD      X", ""));
+        card.set_column_hollerith(35, HollerithCode::new(vec![12, 11, 0]))
+            .unwrap();
+        assert_eq!(SourceCard::parse(&card), Err(ParseError::Undecodable(35)));
+        assert_eq!(
+            validate_source_format(&card)[0].to_string(),
+            "Column 36: punches are not a character"
+        );
+    }
+
+    #[test]
+    fn test_builder_aligns_fields() {
+        let card = SourceCard::builder()
+            .label("LOOP")
+            .opcode("LD")
+            .tag(1)
+            .operands("TABLE-1")
+            .comment("NEXT WORD")
+            .sequence("SUM00040")
+            .build()
+            .unwrap();
+        let text = card.to_text();
+        assert_eq!(&text[20..34], "LOOP  LD    1 ");
+        assert_eq!(&text[34..51], "TABLE-1 NEXT WORD");
+        assert_eq!(&text[72..], "SUM00040");
+
+        let source = SourceCard::parse(&card).unwrap();
+        assert_eq!(source.label(), "LOOP");
+        assert_eq!(source.tag(), Some(1));
+        assert_eq!(source.operands(), "TABLE-1");
+        assert_eq!(source.remarks(), "NEXT WORD");
+        assert_eq!(source.to_string(), text);
+
+        // The fixture deck's LDX card, built from its fields
+        let ldx = SourceCard::builder()
+            .label("START")
+            .opcode("LDX")
+            .format('L')
+            .tag(1)
+            .operands("10")
+            .sequence("SUM00030")
+            .build()
+            .unwrap();
+        assert_eq!(ldx.to_text(), SOURCE_DECK.lines().nth(2).unwrap());
+    }
+
+    #[test]
+    fn test_builder_rejects_wide_fields() {
+        let error = SourceCard::builder().label("TOOLONG").build().unwrap_err();
+        assert_eq!(error, "Label \"TOOLONG\" is wider than 5 columns");
+        assert!(SourceCard::builder().opcode("LDXI").build().is_ok());
+        assert!(SourceCard::builder().opcode("LDXIL").build().is_err());
+        assert!(SourceCard::builder().sequence("123456789").build().is_err());
+        assert!(SourceCard::builder().tag(4).build().is_err());
+        assert!(SourceCard::builder().operands("~").build().is_err());
+
+        let operands = "A".repeat(30);
+        let fits = SourceCard::builder().operands(&operands).comment("REMARK");
+        assert!(fits.build().is_ok());
+        let overflows = SourceCard::builder().operands(&operands).comment("REMARKS");
+        assert!(
+            overflows
+                .build()
+                .unwrap_err()
+                .starts_with("Operands and remarks")
+        );
+    }
+
+    const FORTRAN_DECK: &str = include_str!("../../tests/fixtures/ibm1130_fortran.txt");
+
+    fn fortran_deck() -> Deck {
+        FORTRAN_DECK.lines().map(PunchCard::from_text).collect()
+    }
+
+    #[test]
+    fn test_parse_fortran_cards() {
+        let cards: Vec<FortranCard> = fortran_deck()
+            .iter()
+            .map(|card| FortranCard::parse(card).unwrap())
+            .collect();
+        assert!(cards[0].is_comment());
+        assert_eq!(
+            cards[0].comment(),
+            Some("     SUM OF SQUARES WITH A DO LOOP")
+        );
+        assert_eq!(cards[3].statement_number(), Some(10));
+        assert_eq!(cards[3].text(), "A(I) = FLOAT(I)");
+        assert!(!cards[8].is_continuation());
+        assert!(cards[9].is_continuation());
+        assert_eq!(cards[9].continuation_mark(), Some('1'));
+        assert_eq!(cards[9].text(), "    F10.2)");
+
+        for (card, line) in cards.iter().zip(FORTRAN_DECK.lines()) {
+            assert_eq!(card.to_string().trim_end(), line);
+        }
+
+        // The 026 example reads correctly with its own character set
+        let example = generate_example_fortran_source();
+        let card = FortranCard::parse_with_charset(&example, Charset::Ibm026Fortran).unwrap();
+        assert_eq!(card.statement_number(), Some(10));
+        assert_eq!(card.text(), "Y = (A+B)*C/2.0 - F(X,'Z')");
+
+        // Compiler control records
+        for (line, control) in [
+            ("*IOCS(CARD,1132 PRINTER)", "IOCS(CARD,1132 PRINTER)"),
+            ("*LIST ALL", "LIST ALL"),
+        ] {
+            let card = FortranCard::parse(&PunchCard::from_text(line)).unwrap();
+            assert!(card.is_control() && !card.is_comment());
+            assert_eq!(card.control(), Some(control));
+            assert_eq!(card.statement_number(), None);
+            assert_eq!(card.to_string().trim_end(), line);
+            let built = FortranCard::builder().control(control).build().unwrap();
+            assert_eq!(built.to_text().trim_end(), line);
+        }
+
+        assert_eq!(FORTRAN_LAYOUT.field("Statement"), Some(6..72));
+        assert_eq!(FORTRAN_LAYOUT.field("Sequence"), None);
+    }
+
+    #[test]
+    fn test_join_continuations_and_rebuild() {
+        let deck = fortran_deck();
+        let statements = join_continuations(&deck).unwrap();
+        assert_eq!(statements.len(), 10);
+        let format = &statements[7];
+        assert_eq!(format.number, Some(30));
+        assert_eq!(format.cards, 8..10);
+        assert_eq!(
+            format.text,
+            format!("{:<66}    F10.2)", "FORMAT (23H SUM OF SQUARES 1 TO 10,")
+        );
+
+        let rebuilt: Vec<PunchCard> = statements
+            .iter()
+            .flat_map(|statement| statement.to_cards().unwrap())
+            .collect();
+        assert_eq!(rebuilt.len(), deck.len() - 1);
+        for (card, original) in rebuilt.iter().zip(&deck.cards()[1..]) {
+            assert!(card.punches_eq(original));
+        }
+
+        let example = generate_example_fortran();
+        assert_eq!(example.len(), 11);
+        let joined = join_continuations(&example).unwrap();
+        assert_eq!(joined[7].text, "FORMAT (23H SUM OF SQUARES 1 TO 10, F10.2)");
+    }
+
+    #[test]
+    fn test_fortran_builder_and_errors() {
+        let card = FortranCard::builder()
+            .statement_number(100)
+            .text("GO TO 10")
+            .identification("DOLOOP01")
+            .build()
+            .unwrap();
+        let text = card.to_text();
+        assert_eq!(&text[..14], "  100 GO TO 10");
+        assert_eq!(&text[72..], "DOLOOP01");
+        assert!(
+            FortranCard::builder()
+                .statement_number(100000)
+                .build()
+                .is_err()
+        );
+        assert!(FortranCard::builder().continuation('0').build().is_err());
+        assert!(
+            FortranCard::builder()
+                .text(&"X".repeat(67))
+                .build()
+                .is_err()
+        );
+        let mixed = FortranCard::builder().comment("NOTE").text("END").build();
+        assert_eq!(mixed.unwrap_err(), "A comment card has no statement fields");
+
+        let control = FortranCard::builder()
+            .control("LIST ALL")
+            .text("END")
+            .build();
+        assert_eq!(
+            control.unwrap_err(),
+            "A control record has no statement fields"
+        );
+
+        let bad_number = PunchCard::from_text("  1A  CONTINUE");
+        assert_eq!(
+            FortranCard::parse(&bad_number),
+            Err(ParseError::InvalidStatementNumber("  1A".to_string()))
+        );
+        let orphan = Deck::from_cards(vec![
+            PunchCard::from_text("C     COMMENT"),
+            PunchCard::from_text("     1X = 1"),
+        ]);
+        let error = join_continuations(&orphan).unwrap_err();
+        assert_eq!(error.card, 1);
+        assert_eq!(error.error, ParseError::OrphanContinuation);
+
+        // A blank continuation still takes up 66 columns
+        let blank_line = Deck::from_cards(vec![
+            PunchCard::from_text("      X = 1"),
+            PunchCard::from_text("     1"),
+            PunchCard::from_text("     2 + 2"),
+        ]);
+        let statement = &join_continuations(&blank_line).unwrap()[0];
+        assert_eq!(statement.text, format!("{:<132} + 2", "X = 1"));
+    }
+
+    #[test]
+    fn test_punch_source_deck() {
+        let source: String = SOURCE_DECK
+            .lines()
+            .map(|line| format!("{}\n", &line[..72]))
+            .collect();
+        let punched = punch_source_deck(&source, &SequenceOptions::default()).unwrap();
+        assert_eq!(punched.deck.len(), 12);
+        assert!(punched.truncated.is_empty());
+        let first = punched.deck.get(0).unwrap().to_text();
+        assert_eq!(&first[72..], "00000010");
+        assert_eq!(&punched.deck.get(11).unwrap().to_text()[72..], "00000120");
+        assert!(check_sequence(&punched.deck).is_empty());
+
+        // Sequence in 76-80, starting at 1
+        let options = SequenceOptions {
+            columns: 75..80,
+            start: 1,
+            increment: 1,
+            ..SequenceOptions::default()
+        };
+        let punched = punch_source_deck("      END\r\n", &options).unwrap();
+        assert_eq!(&punched.deck.get(0).unwrap().to_text()[72..], "   00001");
+        let overflow = SequenceOptions {
+            start: 99999,
+            ..options.clone()
+        };
+        assert!(
+            punch_source_deck("A\nB", &overflow)
+                .unwrap_err()
+                .contains("Line 2")
+        );
+    }
+
+    #[test]
+    fn test_punch_source_long_lines() {
+        let long = "X".repeat(100);
+        let error = punch_source_deck(&long, &SequenceOptions::default()).unwrap_err();
+        assert_eq!(error, "Line 1: 100 characters do not fit before column 73");
+
+        let wrap = SequenceOptions {
+            long_lines: LongLinePolicy::Wrap,
+            ..SequenceOptions::default()
+        };
+        let punched = punch_source_deck(&format!("A\n{}", long), &wrap).unwrap();
+        assert_eq!(punched.deck.len(), 3);
+        assert_eq!(
+            &punched.deck.get(2).unwrap().to_text()[..29],
+            "X".repeat(28) + " "
+        );
+        assert_eq!(&punched.deck.get(2).unwrap().to_text()[72..], "00000030");
+
+        let truncate = SequenceOptions {
+            long_lines: LongLinePolicy::Truncate,
+            ..SequenceOptions::default()
+        };
+        let punched = punch_source_deck(&format!("A\n{}", long), &truncate).unwrap();
+        assert_eq!(punched.deck.len(), 2);
+        assert_eq!(punched.truncated, vec![1]);
+
+        assert!(punch_source_deck("A~", &SequenceOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_check_sequence_finds_dropped_deck_errors() {
+        let mut cards: Vec<PunchCard> = SOURCE_DECK.lines().map(PunchCard::from_text).collect();
+        // Drop the deck: two cards swapped, one duplicated, one unnumbered
+        cards.swap(3, 5);
+        cards[8] = cards[7].clone();
+        cards[10] = PunchCard::from_text("          END");
+        let errors = check_sequence(&Deck::from_cards(cards));
+        assert_eq!(
+            errors,
+            vec![
+                SequenceError::OutOfOrder {
+                    card: 4,
+                    number: 50,
+                    previous: Some(60)
+                },
+                SequenceError::OutOfOrder {
+                    card: 5,
+                    number: 40,
+                    previous: Some(60)
+                },
+                SequenceError::Duplicate {
+                    card: 8,
+                    number: 80
+                },
+                SequenceError::Missing { card: 10 },
+            ]
+        );
+        assert_eq!(errors[2].to_string(), "Card 9: sequence 80 is duplicated");
+
+        // One card moved up or down the deck gives one error
+        let numbered = |numbers: &[u64]| {
+            Deck::from_cards(
+                numbers
+                    .iter()
+                    .map(|number| PunchCard::from_text(&format!("{:72}{:08}", "", number)))
+                    .collect(),
+            )
+        };
+        assert_eq!(
+            check_sequence(&numbered(&[10, 50, 20, 30, 40, 60])),
+            vec![SequenceError::OutOfOrder {
+                card: 1,
+                number: 50,
+                previous: Some(10)
+            }]
+        );
+        assert_eq!(
+            check_sequence(&numbered(&[10, 20, 30, 15, 40])),
+            vec![SequenceError::OutOfOrder {
+                card: 3,
+                number: 15,
+                previous: Some(30)
+            }]
+        );
+        let errors = check_sequence(&numbered(&[90, 10, 20, 30]));
+        assert_eq!(
+            errors[0].to_string(),
+            "Card 1: sequence 90 is out of order at the start of the deck"
+        );
+        assert_eq!(errors.len(), 1);
+
+        let named = Deck::from_cards(vec![PunchCard::from_text(&format!("{:72}ABC", ""))]);
+        assert_eq!(
+            check_sequence(&named),
+            vec![SequenceError::NotNumeric { card: 0 }]
+        );
+    }
+
+    #[test]
+    fn test_validate_source_opcodes() {
+        let check = |text: &str| {
+            validate_source_format(&PunchCard::from_text(&format!("{:20}{}", "", text)))
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            check("LOOP  LDA     X"),
+            ["Column 27: unknown opcode \"LDA\""]
+        );
+        assert_eq!(
+            check("       LDA     X"),
+            ["Column 28: unknown opcode \"LDA\""]
+        );
+        assert_eq!(check("      WAIT L"), ["Column 32: WAIT has no long form"]);
+        assert_eq!(
+            check("      SKP   2 Z"),
+            ["Column 33: SKP cannot be indexed"]
+        );
+        assert_eq!(check("      EQU     5"), ["Column 21: EQU needs a label"]);
+        assert!(check("FIVE  EQU     5").is_empty());
+        assert!(check("*     LDA IS NOT CHECKED IN A COMMENT").is_empty());
+    }
+
+    #[test]
+    fn test_validate_source_conventions() {
+        // Comment, blank and well-formed cards pass
+        for line in SOURCE_DECK.lines() {
+            let issues = validate_source_format(&PunchCard::from_text(line));
+            assert!(
+                issues
+                    .iter()
+                    .all(|issue| issue.severity == Severity::Warning)
+            );
+        }
+        assert!(validate_source_format(&generate_example_source()).is_empty());
+        assert!(validate_source_format(&PunchCard::from_text("")).is_empty());
+        let numbered = PunchCard::from_text(&format!("{:20}*COMMENT{:44}00000010", "", ""));
+        assert!(validate_source_format(&numbered).is_empty());
+
+        let issues = |text: &str| {
+            validate_source_format(&PunchCard::from_text(&format!("{:20}{}", "", text)))
+        };
+        let found = issues(" LOOPXLD   Q5 TABLE");
+        let summary: Vec<(Range<usize>, Severity)> = found
+            .iter()
+            .map(|issue| (issue.columns.clone(), issue.severity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (25..26, Severity::Error),
+                (21..25, Severity::Error),
+                (31..32, Severity::Error),
+                (32..33, Severity::Error),
+            ]
+        );
+        assert_eq!(
+            found[1].to_string(),
+            "Column 22: label \"LOOP\" must start in column 21"
+        );
+        assert_eq!(
+            issues("1LOOP LD      X")[0].to_string(),
+            "Column 21: label \"1LOOP\" must be letters and digits, starting with a letter"
+        );
+        assert_eq!(
+            issues("LOOP          X")[0].to_string(),
+            "Column 27: missing opcode"
+        );
+
+        let sequenced = format!("{:6}WAIT{:42}AB001 0", "", "");
+        let found = issues(&sequenced);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].columns, 72..74);
+        assert_eq!(found[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_generate_example_object() {
+        let card = generate_example_object();
+        assert_eq!(card.card_type(), CardType::Binary);
+        let object = ObjectCard::parse(&card).unwrap();
+        assert_eq!(object.card_kind(), ObjectCardKind::Data);
+        assert_eq!(object.load_address(), 0x0100);
+        assert!(object.verify_checksum());
+        assert!(validate_object_format(&card, ObjectValidation::Structure).is_ok());
+
+        // The example deck is the SUM program, as in the synthetic deck
+        let deck = generate_example_object_deck();
+        assert_eq!(deck.len(), 2);
+        for (card, fixture) in deck.iter().zip(sum_object_cards()) {
+            assert!(card.punches_eq(&fixture));
+        }
+        let source = generate_example_program();
+        assert_eq!(source.len(), SOURCE_DECK.lines().count());
+        for (card, line) in source.iter().zip(SOURCE_DECK.lines()) {
+            let built = SourceCard::parse(card).unwrap();
+            let fixture = SourceCard::parse(&PunchCard::from_text(line)).unwrap();
+            // The fixture's remarks are aligned further right
+            assert_eq!(
+                (
+                    built.label(),
+                    built.opcode(),
+                    built.operands(),
+                    built.sequence()
+                ),
+                (
+                    fixture.label(),
+                    fixture.opcode(),
+                    fixture.operands(),
+                    fixture.sequence()
+                )
+            );
+            assert_eq!(
+                (built.format(), built.tag()),
+                (fixture.format(), fixture.tag())
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_demo_pattern() {
+        let card = generate_demo_pattern();
+        assert_eq!(card.card_type(), CardType::Binary);
+        assert!(card.punched_count() > 0);
+        assert!(ObjectCard::parse(&card).is_err());
+    }
+
+    #[test]
+    fn test_validate_source_format_valid() {
+        let card = PunchCard::from_text(&format!("{:20}LOOP  LD      X", ""));
+        assert!(validate_source_format(&card).is_empty());
+        assert!(validate_source_format(&PunchCard::new(CardType::Text)).is_empty());
+    }
+
+    #[test]
+    fn test_validate_source_format_wrong_columns() {
+        // The statement punched from column 1 leaves columns 21-71 blank
+        let card = PunchCard::from_text("LOOP  LD   X");
+        let issues = validate_source_format(&card);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].columns, 0..12);
+    }
+
+    #[test]
+    fn test_validate_source_format_invalid_type() {
+        let card = PunchCard::from_binary(&[0x00]);
+        let issues = validate_source_format(&card);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].columns, 0..80);
+    }
+
+    #[test]
+    fn test_validate_object_format_valid() {
+        let card = PunchCard::from_binary(&[0xC0, 0x00]);
+        assert!(validate_object_format(&card, ObjectValidation::Format).is_ok());
+    }
+
+    #[test]
+    fn test_validate_object_format_invalid_type() {
+        let card = PunchCard::from_text("HELLO");
+        assert!(validate_object_format(&card, ObjectValidation::Format).is_err());
+    }
+
+    #[test]
+    fn test_validate_object_format_blank() {
+        let card = PunchCard::new(CardType::Binary);
+        assert!(validate_object_format(&card, ObjectValidation::Format).is_err());
+    }
+
+    /// The SUM program in `ibm1130_source.txt`, hand-assembled at /0100
+    /// into a data card and an end card (FullBinary120, one card per 120
+    /// bytes). Synthetic: not the output of an IBM assembler.
+    const SUM_OBJECT_DECK: &[u8] =
+        include_bytes!("../../tests/fixtures/ibm1130_sum_object_synthetic.bin");
+
+    fn sum_object_cards() -> Vec<PunchCard> {
+        SUM_OBJECT_DECK
+            .chunks(120)
+            .map(|data| PunchCard::parse(data, CardFormat::FullBinary120).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_object_card_synthetic_deck_roundtrip() {
+        let cards = sum_object_cards();
+        let data = ObjectCard::parse(&cards[0]).unwrap();
+        assert_eq!(data.card_kind(), ObjectCardKind::Data);
+        assert_eq!(data.load_address(), 0x0100);
+        assert_eq!(data.checksum(), data.compute_checksum());
+        assert_eq!(data.data_words().len(), 10);
+        // LDX L1 10, then LD L1 TABLE-1 with a relocatable address
+        assert_eq!(&data.data_words()[..4], &[0x6500, 0x000A, 0xC500, 0x0109]);
+        assert_eq!(data.relocation()[0], 0x0100);
+        assert_eq!(data.word_relocation(3), Some(Relocation::Relocatable));
+        assert_eq!(data.word_relocation(2), Some(Relocation::Absolute));
+        assert_eq!(data.word_relocation(10), None);
+        assert_eq!(data.sequence(), "SUM00001");
+
+        let end = ObjectCard::parse(&cards[1]).unwrap();
+        assert_eq!(end.card_kind(), ObjectCardKind::End);
+        assert_eq!(end.load_address(), 0x0100);
+        assert!(end.data_words().is_empty());
+
+        for (object, raw) in [data, end].iter().zip(SUM_OBJECT_DECK.chunks(120)) {
+            let card = object.to_card();
+            assert_eq!(card.serialize(CardFormat::FullBinary120).unwrap(), raw);
+            assert!(validate_object_format(&card, ObjectValidation::Structure).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_object_card_new_matches_synthetic_deck() {
+        let data = [
+            0x6500, 0x000A, 0xC500, 0x0109, 0x8004, 0xD003, 0x71FF, 0x70FA, 0x3000, 0x0000,
+        ];
+        let card = ObjectCard::new(ObjectCardKind::Data, 0x0100, &data)
+            .unwrap()
+            .with_relocation([0x0100, 0, 0, 0, 0, 0])
+            .with_sequence("SUM00001")
+            .unwrap()
+            .to_card();
+        assert!(card.punches_eq(&sum_object_cards()[0]));
+
+        assert_eq!(
+            ObjectCard::new(ObjectCardKind::Data, 0, &[0; 46]),
+            Err(ObjectCardError::WordCount(46))
+        );
+        let object = ObjectCard::new(ObjectCardKind::End, 0, &[]).unwrap();
+        assert_eq!(
+            object.clone().with_sequence("SUM000001"),
+            Err(ObjectCardError::SequenceTooLong(9))
+        );
+        assert_eq!(
+            object.with_sequence("SUM~"),
+            Err(ObjectCardError::InvalidSequence(75))
+        );
+    }
+
+    #[test]
+    fn test_object_card_checksum() {
+        // End-around carry: 0xFFFF + 0x0A02 + 0x0001 + 0x0001 wraps to 0x0A04
+        let object = ObjectCard::new(ObjectCardKind::Data, 0xFFFF, &[1, 1]).unwrap();
+        assert_eq!(object.checksum(), 0x0A04);
+
+        let mut words = object_words(&sum_object_cards()[0]);
+        words[12] ^= 0x0001;
+        let corrupted = words_to_object_card(&words);
+        let error = ObjectCard::parse(&corrupted).unwrap_err();
+        assert!(matches!(
+            error,
+            ObjectCardError::Checksum { stored: 0x9A20, .. }
+        ));
+        assert!(
+            validate_object_format(&corrupted, ObjectValidation::Structure)
+                .unwrap_err()
+                .contains("9A20")
+        );
+        assert!(validate_object_format(&corrupted, ObjectValidation::Format).is_ok());
+
+        // A zero checksum is not checked
+        words[1] = 0;
+        assert!(ObjectCard::parse(&words_to_object_card(&words)).is_ok());
+
+        words[2] = 0x0A2E;
+        assert_eq!(
+            ObjectCard::parse(&words_to_object_card(&words)),
+            Err(ObjectCardError::WordCount(46))
+        );
+        assert_eq!(
+            ObjectCard::parse(&PunchCard::from_text("SUM")),
+            Err(ObjectCardError::NotBinary)
+        );
+    }
+
+    #[test]
+    fn test_object_deck_roundtrip_200_words() {
+        let program: Vec<u16> = (0..200u16)
+            .map(|i| i.wrapping_mul(0x2B5D) ^ 0x1130)
+            .collect();
+        let cards = object_card_from_words(0x01F0, &program, &[]);
+        assert_eq!(cards.len(), 6);
+        let objects: Vec<ObjectCard> = cards
+            .iter()
+            .map(|c| ObjectCard::parse(c).unwrap())
+            .collect();
+        assert_eq!(objects[4].load_address(), 0x01F0 + 180);
+        assert_eq!(objects[4].data_words().len(), 20);
+        assert_eq!(objects[5].card_kind(), ObjectCardKind::End);
+
+        // Through 108-byte images and back
+        let mut data = Vec::new();
+        Deck::from_cards(cards).write_binary(&mut data).unwrap();
+        let deck: Deck = data.chunks(108).map(PunchCard::from_binary).collect();
+        assert_eq!(
+            words_from_object_deck(&deck).unwrap(),
+            vec![(0x01F0, program)]
+        );
+    }
+
+    #[test]
+    fn test_object_card_word_relocation() {
+        let mut relocation = vec![Relocation::Absolute; 50];
+        for index in [0, 44, 45, 49] {
+            relocation[index] = Relocation::Relocatable;
+        }
+        relocation[7] = Relocation::Call;
+        let cards = object_card_from_words(0x0100, &[0; 50], &relocation);
+        let objects: Vec<ObjectCard> = cards
+            .iter()
+            .map(|card| ObjectCard::parse(card).unwrap())
+            .collect();
+        assert_eq!(objects[0].relocations(), relocation[..45]);
+        assert_eq!(objects[1].relocations(), relocation[45..]);
+        assert_eq!(objects[0].relocation()[0], 0x4003);
+        assert!(objects.iter().all(ObjectCard::verify_checksum));
+
+        // Missing flags are absolute
+        let cards = object_card_from_words(0, &[1, 2, 3], &[Relocation::Libf]);
+        let object = ObjectCard::parse(&cards[0]).unwrap();
+        assert_eq!(
+            object.relocations(),
+            vec![Relocation::Libf, Relocation::Absolute, Relocation::Absolute]
+        );
+        assert_eq!(Relocation::from_bits(0b101), Relocation::Relocatable);
+    }
+
+    #[test]
+    fn test_words_from_object_deck_runs_and_errors() {
+        let mut cards = object_card_from_words(0x0100, &[1, 2, 3], &[]);
+        cards.pop();
+        cards.extend(object_card_from_words(0x0200, &[4], &[]));
+        // Anything after the end card is not loaded
+        cards.extend(object_card_from_words(0x0300, &[5], &[]));
+        let deck = Deck::from_cards(cards.clone());
+        assert_eq!(
+            words_from_object_deck(&deck).unwrap(),
+            vec![(0x0100, vec![1, 2, 3]), (0x0200, vec![4])]
+        );
+
+        let mut words = object_words(&cards[1]);
+        words[9] ^= 0x8000;
+        cards[1] = words_to_object_card(&words);
+        let error = words_from_object_deck(&Deck::from_cards(cards)).unwrap_err();
+        assert_eq!(error.card, 1);
+        assert!(matches!(error.error, ObjectCardError::Checksum { .. }));
+        assert!(error.to_string().starts_with("Card 2: Checksum"));
+    }
+
+    #[test]
+    fn test_parse_object_deck_walks_cards() {
+        let deck: Deck = sum_object_cards().into_iter().collect();
+        let objects = parse_object_deck(&deck).unwrap();
+        let kinds: Vec<ObjectCardKind> = objects.iter().map(ObjectCard::card_kind).collect();
+        assert_eq!(kinds, vec![ObjectCardKind::Data, ObjectCardKind::End]);
+        assert!(objects.iter().all(ObjectCard::verify_checksum));
+        assert_eq!(ObjectCardKind::from_code(0x02), ObjectCardKind::Other(0x02));
+        assert_eq!(ObjectCardKind::Other(0x02).code(), 0x02);
+
+        let mut cards = deck.into_cards();
+        let mut words = object_words(&cards[1]);
+        words[2] = 0x0F40;
+        words[1] = 0;
+        cards[1] = words_to_object_card(&words);
+        assert_eq!(
+            parse_object_deck(&Deck::from_cards(cards)),
+            Err(ObjectDeckError {
+                card: 1,
+                error: ObjectCardError::WordCount(0x40)
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_object_card_serde() {
+        let object = ObjectCard::parse(&sum_object_cards()[0]).unwrap();
+        let json = serde_json::to_string(&object).unwrap();
+        assert!(json.contains("\"kind\":\"Data\""));
+        let mut tampered: ObjectCard = serde_json::from_str(&json).unwrap();
+        assert_eq!(tampered, object);
+        tampered.data[0] ^= 1;
+        assert!(!tampered.verify_checksum());
+
+        // Deserializing checks what `new` and `with_sequence` check
+        let mut value = serde_json::to_value(&object).unwrap();
+        value["data"] = serde_json::json!(vec![0; 60]);
+        let error = serde_json::from_value::<ObjectCard>(value).unwrap_err();
+        assert!(error.to_string().contains("Word count 60"));
+        let mut value = serde_json::to_value(&object).unwrap();
+        value["sequence"] = serde_json::json!("SUM000010");
+        assert!(serde_json::from_value::<ObjectCard>(value).is_err());
+        let mut value = serde_json::to_value(&object).unwrap();
+        value["sequence"] = serde_json::json!("SUM~");
+        assert!(serde_json::from_value::<ObjectCard>(value).is_err());
+        let mut value = serde_json::to_value(&object).unwrap();
+        value["checksum"] = serde_json::json!(object.checksum() ^ 1);
+        assert!(serde_json::from_value::<ObjectCard>(value).is_err());
+    }
+
+    #[test]
+    fn test_object_words_pack_four_columns_into_three_words() {
+        // Column 1 all punched, column 2 row 12 only: 0xFFF then 0x800
+        let mut card = PunchCard::new(CardType::Binary);
+        card.set_column_hollerith(0, HollerithCode::from_array([true; 12]))
+            .unwrap();
+        card.set_column_hollerith(1, HollerithCode::new(vec![12]))
+            .unwrap();
+        card.set_column_hollerith(3, HollerithCode::new(vec![9]))
+            .unwrap();
+        let words = object_words(&card);
+        assert_eq!(&words[..4], &[0xFFF8, 0x0000, 0x0001, 0x0000]);
+    }
+
+    #[test]
+    fn test_object_words_roundtrip() {
+        let mut words = [0u16; OBJECT_WORDS];
+        for (i, word) in words.iter_mut().enumerate() {
+            *word = (i as u16).wrapping_mul(0x1357) ^ 0xA5C3;
+        }
+        let card = words_to_object_card(&words);
+        assert_eq!(object_words(&card), words);
+        assert!(card.columns()[72..].iter().all(|col| col.is_blank()));
+    }
+
+    #[test]
+    fn test_to_text_or_hex() {
+        let words = [0x1130; OBJECT_WORDS];
+        let card = words_to_object_card(&words);
+        let hex = card.to_text_or_hex();
+        assert_eq!(hex, card.to_hex_words());
+        assert_eq!(hex.len(), OBJECT_WORDS * 5 - 1);
+        assert!(hex.starts_with("1130 1130 "));
+
+        let text = PunchCard::from_text("HELLO");
+        assert_eq!(text.to_text_or_hex(), text.to_text());
+    }
+
+    #[test]
+    fn test_fortran_source_under_026_and_029() {
+        let card = generate_example_fortran_source();
+        let decode = |charset| -> String {
+            card.columns()
+                .iter()
+                .map(|col| hollerith_to_char_with(charset, &col.punches).unwrap_or('?'))
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        assert_eq!(decode(Charset::Ibm026Fortran), EXAMPLE_FORTRAN_STATEMENT);
+        // An 029 reads the same holes with its own specials
+        assert_eq!(decode(Charset::Ibm029), "   10 Y # %A&B<*C/2.0 - F%X,@Z@<");
+    }
+}
@@ -0,0 +1,799 @@
+// IBM 1130 Monitor Control Cards
+//
+// Disk Monitor System jobs are decks sandwiched between monitor control
+// cards, which have `//` in columns 1-2:
+//
+//     // JOB
+//     // ASM
+//     ...assembler source cards...
+//     // XEQ PROG
+//     ...data cards...
+//
+// Columns 4-7 hold the operation and column 8 onward its operands.
+//
+// After `// DUP`, the disk utility program reads its own control cards,
+// which have `*` in column 1 and fixed fields:
+//
+//     columns  1-12   function (*STORE, *DUMP, ...)
+//     columns 13-16   from: device or disk area (WS, UA, FX, CD, PR, PT)
+//     columns 17-20   to
+//     columns 21-25   program name
+//     columns 27-30   count, right-justified
+
+use super::opcodes;
+use super::{FortranCard, ObjectCard, ParseError, SourceCard, punch_text};
+use crate::deck::Deck;
+use crate::hollerith::char_to_hollerith;
+use crate::punch_card::{CardType, PunchCard};
+use std::fmt;
+use std::ops::Range;
+
+/// Columns 4-7
+const OPERATION_COLUMNS: Range<usize> = 3..7;
+/// Columns 8-80
+const OPERAND_COLUMNS: Range<usize> = 7..80;
+/// Columns 2-12
+const DUP_FUNCTION_COLUMNS: Range<usize> = 1..12;
+/// Columns 13-16
+const DUP_FROM_COLUMNS: Range<usize> = 12..16;
+/// Columns 17-20
+const DUP_TO_COLUMNS: Range<usize> = 16..20;
+/// Columns 21-25
+const DUP_NAME_COLUMNS: Range<usize> = 20..25;
+/// Columns 27-30
+const DUP_COUNT_COLUMNS: Range<usize> = 26..30;
+/// The operand `*DEFINE` punches in columns 13-22
+const FIXED_AREA: &str = "FIXED AREA";
+
+/// The monitor operations this crate knows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlOperation {
+    /// Start a job
+    Job,
+    /// Assemble the following source cards
+    Asm,
+    /// Compile the following FORTRAN cards
+    For,
+    /// Load and run a program
+    Xeq,
+    /// Run the disk utility program
+    Dup,
+    /// Pause until the operator presses START
+    Paus,
+}
+
+impl ControlOperation {
+    /// The operation as punched in columns 4-7
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ControlOperation::Job => "JOB",
+            ControlOperation::Asm => "ASM",
+            ControlOperation::For => "FOR",
+            ControlOperation::Xeq => "XEQ",
+            ControlOperation::Dup => "DUP",
+            ControlOperation::Paus => "PAUS",
+        }
+    }
+
+    /// Look up an operation as punched
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            ControlOperation::Job,
+            ControlOperation::Asm,
+            ControlOperation::For,
+            ControlOperation::Xeq,
+            ControlOperation::Dup,
+            ControlOperation::Paus,
+        ]
+        .into_iter()
+        .find(|operation| operation.as_str() == name)
+    }
+}
+
+/// A monitor control card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlCard {
+    operation: ControlOperation,
+    operands: String,
+}
+
+impl ControlCard {
+    /// Create a control card with operands starting in column 8
+    pub fn new(operation: ControlOperation, operands: &str) -> Self {
+        ControlCard {
+            operation,
+            operands: operands.trim_end().to_string(),
+        }
+    }
+
+    /// `// JOB`
+    pub fn job() -> Self {
+        ControlCard::new(ControlOperation::Job, "")
+    }
+
+    /// `// ASM`
+    pub fn asm() -> Self {
+        ControlCard::new(ControlOperation::Asm, "")
+    }
+
+    /// `// FOR`
+    pub fn fortran() -> Self {
+        ControlCard::new(ControlOperation::For, "")
+    }
+
+    /// `// XEQ` running the named program
+    pub fn xeq(program: &str) -> Self {
+        ControlCard::new(ControlOperation::Xeq, program)
+    }
+
+    /// `// DUP`
+    pub fn dup() -> Self {
+        ControlCard::new(ControlOperation::Dup, "")
+    }
+
+    /// `// PAUS`
+    pub fn pause() -> Self {
+        ControlCard::new(ControlOperation::Paus, "")
+    }
+
+    /// Read a control card
+    ///
+    /// Fails on a binary card, undecodable columns, a card without `//` in
+    /// columns 1-2, or an operation this crate does not know.
+    pub fn parse(card: &PunchCard) -> Result<Self, ParseError> {
+        if card.card_type() != CardType::Text {
+            return Err(ParseError::NotText);
+        }
+        let mut text = [' '; 80];
+        for (index, column) in card.columns().iter().enumerate() {
+            text[index] = column.to_char().ok_or(ParseError::Undecodable(index))?;
+        }
+        if text[..2] != ['/', '/'] {
+            return Err(ParseError::NotControl);
+        }
+        if text[2] != ' ' {
+            return Err(ParseError::NotBlank {
+                column: 2,
+                ch: text[2],
+            });
+        }
+        let name: String = text[OPERATION_COLUMNS].iter().collect();
+        let name = name.trim_end();
+        let operation = ControlOperation::from_name(name)
+            .ok_or_else(|| ParseError::UnknownOperation(name.to_string()))?;
+        let operands: String = text[OPERAND_COLUMNS].iter().collect();
+        Ok(ControlCard::new(operation, &operands))
+    }
+
+    pub fn operation(&self) -> ControlOperation {
+        self.operation
+    }
+
+    /// Columns 8-80, without trailing blanks
+    pub fn operands(&self) -> &str {
+        &self.operands
+    }
+
+    /// The blank-separated operand fields (for XEQ, the program name first)
+    pub fn fields(&self) -> Vec<&str> {
+        self.operands.split_whitespace().collect()
+    }
+
+    /// Punch the card
+    ///
+    /// Fails if the operands run past column 80 or cannot be punched.
+    pub fn to_card(&self) -> Result<PunchCard, String> {
+        let len = self.operands.chars().count();
+        if len > OPERAND_COLUMNS.len() {
+            return Err(format!(
+                "Operands are {} characters, but columns 8-80 hold {}",
+                len,
+                OPERAND_COLUMNS.len()
+            ));
+        }
+        punch_text(&self.to_string())
+    }
+}
+
+impl fmt::Display for ControlCard {
+    /// The card text, without trailing blanks
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.operands.is_empty() {
+            write!(f, "// {}", self.operation.as_str())
+        } else {
+            write!(f, "// {:<4}{}", self.operation.as_str(), self.operands)
+        }
+    }
+}
+
+/// The DUP functions this crate knows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DupFunction {
+    /// Store a program on disk
+    Store,
+    /// Store a program in core image format, ready to run
+    StoreCi,
+    /// Copy a program from disk to cards, paper tape or the printer
+    Dump,
+    /// Delete a program from the disk
+    Delete,
+    /// Define the fixed area
+    Define,
+}
+
+impl DupFunction {
+    /// The function as punched after the `*` in column 1
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DupFunction::Store => "STORE",
+            DupFunction::StoreCi => "STORECI",
+            DupFunction::Dump => "DUMP",
+            DupFunction::Delete => "DELETE",
+            DupFunction::Define => "DEFINE",
+        }
+    }
+
+    /// Look up a function as punched
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            DupFunction::Store,
+            DupFunction::StoreCi,
+            DupFunction::Dump,
+            DupFunction::Delete,
+            DupFunction::Define,
+        ]
+        .into_iter()
+        .find(|function| function.as_str() == name)
+    }
+}
+
+/// Where a DUP function reads from or writes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DupDevice {
+    /// Working storage, where the assembler leaves its output
+    WorkingStorage,
+    /// The user area of the disk
+    UserArea,
+    /// The fixed area of the disk
+    FixedArea,
+    /// The card reader or punch
+    Card,
+    /// The printer
+    Printer,
+    /// The paper tape reader or punch
+    PaperTape,
+}
+
+impl DupDevice {
+    /// The device as punched in the from and to fields
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DupDevice::WorkingStorage => "WS",
+            DupDevice::UserArea => "UA",
+            DupDevice::FixedArea => "FX",
+            DupDevice::Card => "CD",
+            DupDevice::Printer => "PR",
+            DupDevice::PaperTape => "PT",
+        }
+    }
+
+    /// Look up a device as punched
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            DupDevice::WorkingStorage,
+            DupDevice::UserArea,
+            DupDevice::FixedArea,
+            DupDevice::Card,
+            DupDevice::Printer,
+            DupDevice::PaperTape,
+        ]
+        .into_iter()
+        .find(|device| device.as_str() == name)
+    }
+}
+
+/// A DUP control card
+///
+/// `*DEFINE` punches `FIXED AREA` across the from, to and name fields, so
+/// a define card has none of them, only a count of cylinders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DupCard {
+    function: DupFunction,
+    from: Option<DupDevice>,
+    to: Option<DupDevice>,
+    name: String,
+    count: Option<u16>,
+}
+
+impl DupCard {
+    /// Create a DUP card; the count is `None` when columns 27-30 are blank
+    ///
+    /// Private, as only some fields go with each function: the builders
+    /// below set the right ones. Fails on a name longer than columns 21-25
+    /// or that cannot be punched, or a count over 9999.
+    fn new(
+        function: DupFunction,
+        from: Option<DupDevice>,
+        to: Option<DupDevice>,
+        name: &str,
+        count: Option<u16>,
+    ) -> Result<Self, String> {
+        let name = name.trim();
+        let len = name.chars().count();
+        if len > DUP_NAME_COLUMNS.len() {
+            return Err(format!(
+                "Name {:?} is {} characters, but columns 21-25 hold {}",
+                name,
+                len,
+                DUP_NAME_COLUMNS.len()
+            ));
+        }
+        if let Some(ch) = name
+            .chars()
+            .find(|&ch| char_to_hollerith(ch.to_ascii_uppercase()).is_none())
+        {
+            return Err(format!("Name {:?}: {:?} has no punch pattern", name, ch));
+        }
+        if let Some(count) = count
+            && count > 9999
+        {
+            return Err(format!("Count {} does not fit in columns 27-30", count));
+        }
+        Ok(DupCard {
+            function,
+            from,
+            to,
+            name: name.to_string(),
+            count,
+        })
+    }
+
+    /// `*STORE`, such as `WS` to `UA` after an assembly
+    pub fn store(from: DupDevice, to: DupDevice, name: &str) -> Result<Self, String> {
+        DupCard::new(DupFunction::Store, Some(from), Some(to), name, None)
+    }
+
+    /// `*STORECI`; `count` is the number of cards when storing from cards
+    pub fn store_ci(
+        from: DupDevice,
+        to: DupDevice,
+        name: &str,
+        count: Option<u16>,
+    ) -> Result<Self, String> {
+        DupCard::new(DupFunction::StoreCi, Some(from), Some(to), name, count)
+    }
+
+    /// `*DUMP`
+    pub fn dump(from: DupDevice, to: DupDevice, name: &str) -> Result<Self, String> {
+        DupCard::new(DupFunction::Dump, Some(from), Some(to), name, None)
+    }
+
+    /// `*DELETE`
+    pub fn delete(name: &str) -> Result<Self, String> {
+        DupCard::new(DupFunction::Delete, None, None, name, None)
+    }
+
+    /// `*DEFINE FIXED AREA` with a number of cylinders
+    pub fn define_fixed_area(cylinders: u16) -> Result<Self, String> {
+        DupCard::new(DupFunction::Define, None, None, "", Some(cylinders))
+    }
+
+    /// Read a DUP card
+    ///
+    /// Fails on a binary card, undecodable columns, a card without `*` in
+    /// column 1, or a function or device this crate does not know.
+    pub fn parse(card: &PunchCard) -> Result<Self, ParseError> {
+        if card.card_type() != CardType::Text {
+            return Err(ParseError::NotText);
+        }
+        let mut text = [' '; 80];
+        for (index, column) in card.columns().iter().enumerate() {
+            text[index] = column.to_char().ok_or(ParseError::Undecodable(index))?;
+        }
+        if text[0] != '*' {
+            return Err(ParseError::NotDup);
+        }
+        let field = |columns: Range<usize>| -> String {
+            text[columns].iter().collect::<String>().trim().to_string()
+        };
+        let name = field(DUP_FUNCTION_COLUMNS);
+        let function = DupFunction::from_name(&name)
+            .ok_or_else(|| ParseError::UnknownOperation(name.clone()))?;
+        let device = |columns: Range<usize>| -> Result<Option<DupDevice>, ParseError> {
+            match field(columns) {
+                name if name.is_empty() => Ok(None),
+                name => DupDevice::from_name(&name)
+                    .map(Some)
+                    .ok_or(ParseError::UnknownDevice(name)),
+            }
+        };
+        let (from, to, name) = if function == DupFunction::Define {
+            let operand = field(DUP_FROM_COLUMNS.start..DUP_NAME_COLUMNS.end);
+            if operand != FIXED_AREA {
+                return Err(ParseError::UnknownOperation(format!("DEFINE {}", operand)));
+            }
+            (None, None, String::new())
+        } else {
+            (
+                device(DUP_FROM_COLUMNS)?,
+                device(DUP_TO_COLUMNS)?,
+                field(DUP_NAME_COLUMNS),
+            )
+        };
+        let count = match field(DUP_COUNT_COLUMNS) {
+            count if count.is_empty() => None,
+            count => Some(
+                count
+                    .parse()
+                    .map_err(|_| ParseError::InvalidCount(count.clone()))?,
+            ),
+        };
+        Ok(DupCard::new(function, from, to, &name, count)
+            .expect("fields read from their columns fit them"))
+    }
+
+    pub fn function(&self) -> DupFunction {
+        self.function
+    }
+
+    /// Columns 13-16
+    pub fn from(&self) -> Option<DupDevice> {
+        self.from
+    }
+
+    /// Columns 17-20
+    pub fn to(&self) -> Option<DupDevice> {
+        self.to
+    }
+
+    /// Columns 21-25, without blanks
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Columns 27-30
+    pub fn count(&self) -> Option<u16> {
+        self.count
+    }
+
+    /// Punch the card
+    pub fn to_card(&self) -> PunchCard {
+        punch_text(&self.to_string()).expect("DUP card fields are checked")
+    }
+}
+
+impl fmt::Display for DupCard {
+    /// The card text, without trailing blanks
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let device = |device: Option<DupDevice>| device.map_or("", DupDevice::as_str);
+        let fields = if self.function == DupFunction::Define {
+            format!("{:<13}", FIXED_AREA)
+        } else {
+            format!(
+                "{:<4}{:<4}{:<5}",
+                device(self.from),
+                device(self.to),
+                self.name
+            )
+        };
+        let count = self.count.map_or(String::new(), |count| count.to_string());
+        let text = format!("*{:<11}{} {:>4}", self.function.as_str(), fields, count);
+        write!(f, "{}", text.trim_end())
+    }
+}
+
+/// What a card in an 1130 job deck is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeckCardKind {
+    /// `//` in columns 1-2
+    Control,
+    /// A DUP function (`*STORE`, `*DUMP`, ...) with `*` in column 1
+    Dup,
+    /// An assembler statement (known opcode) or comment card
+    Source,
+    /// A FORTRAN statement, comment or control record after `// FOR`
+    Fortran,
+    /// A binary card that parses as an object card
+    Object,
+    /// Anything else, such as input for a program
+    Data,
+    /// No punches
+    Blank,
+}
+
+/// Tell what kind of card this is, from the card alone
+///
+/// FORTRAN statements and program input cannot be told apart without
+/// knowing what the deck is running, so both are `Data` here; use
+/// [`Deck::classify_cards`] to find the FORTRAN source.
+pub fn classify_card(card: &PunchCard) -> DeckCardKind {
+    if card.columns().iter().all(|column| column.is_blank()) {
+        return DeckCardKind::Blank;
+    }
+    if card.card_type() == CardType::Binary {
+        return match ObjectCard::parse(card) {
+            Ok(_) => DeckCardKind::Object,
+            Err(_) => DeckCardKind::Data,
+        };
+    }
+    if card.to_text().starts_with("//") {
+        return DeckCardKind::Control;
+    }
+    if DupCard::parse(card).is_ok() {
+        return DeckCardKind::Dup;
+    }
+    match SourceCard::parse(card) {
+        Ok(source)
+            if source.is_comment() || opcodes::lookup(source.opcode().trim_start()).is_some() =>
+        {
+            DeckCardKind::Source
+        }
+        _ => DeckCardKind::Data,
+    }
+}
+
+impl Deck {
+    /// Tell what kind each card is, in the context of the deck
+    ///
+    /// This is [`classify_card`] for each card, except that `Data` cards
+    /// after a `// FOR` card, up to the next monitor control card, are
+    /// `Fortran` if they parse as FORTRAN cards.
+    pub fn classify_cards(&self) -> Vec<DeckCardKind> {
+        let mut fortran = false;
+        self.iter()
+            .map(|card| match classify_card(card) {
+                DeckCardKind::Control => {
+                    fortran = ControlCard::parse(card)
+                        .is_ok_and(|control| control.operation() == ControlOperation::For);
+                    DeckCardKind::Control
+                }
+                DeckCardKind::Data if fortran && FortranCard::parse(card).is_ok() => {
+                    DeckCardKind::Fortran
+                }
+                kind => kind,
+            })
+            .collect()
+    }
+
+    /// Split a multi-job deck into one deck per `// JOB` card
+    ///
+    /// Each deck starts with its JOB card; any cards before the first JOB
+    /// card form a deck of their own.
+    pub fn split_jobs(&self) -> Vec<Deck> {
+        let mut jobs: Vec<Deck> = Vec::new();
+        for card in self {
+            let is_job = ControlCard::parse(card)
+                .is_ok_and(|control| control.operation() == ControlOperation::Job);
+            match jobs.last_mut() {
+                Some(job) if !is_job => job.push(card.clone()),
+                _ => jobs.push(Deck::from_cards(vec![card.clone()])),
+            }
+        }
+        jobs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::CardFormat;
+    use crate::ibm1130::generate_example_program;
+
+    const JOBS_DECK: &str = include_str!("../../tests/fixtures/ibm1130_jobs.txt");
+    const SUM_OBJECT_DECK: &[u8] =
+        include_bytes!("../../tests/fixtures/ibm1130_sum_object_synthetic.bin");
+
+    /// The text fixture with the SUM object deck after the second XEQ card
+    fn mixed_deck() -> Deck {
+        let mut cards: Vec<PunchCard> = JOBS_DECK.lines().map(PunchCard::from_text).collect();
+        let xeq = cards
+            .iter()
+            .rposition(|card| card.to_text().starts_with("// XEQ"))
+            .unwrap();
+        let object = SUM_OBJECT_DECK
+            .chunks(120)
+            .map(|data| PunchCard::parse(data, CardFormat::FullBinary120).unwrap());
+        cards.splice(xeq..xeq, object);
+        Deck::from_cards(cards)
+    }
+
+    #[test]
+    fn test_build_and_parse_control_cards() {
+        let card = ControlCard::xeq("PROG").to_card().unwrap();
+        assert_eq!(card.to_text().trim_end(), "// XEQ PROG");
+        assert_eq!(card.columns()[7].to_char(), Some('P'));
+        let parsed = ControlCard::parse(&card).unwrap();
+        assert_eq!(parsed.operation(), ControlOperation::Xeq);
+        assert_eq!(parsed.fields(), vec!["PROG"]);
+
+        let pause = ControlCard::pause().to_card().unwrap();
+        assert_eq!(pause.to_text().trim_end(), "// PAUS");
+        assert_eq!(ControlCard::parse(&pause).unwrap(), ControlCard::pause());
+        assert_eq!(ControlCard::fortran().to_string(), "// FOR");
+        let job = ControlCard::new(ControlOperation::Job, "   1234");
+        assert_eq!(job.to_string(), "// JOB    1234");
+        assert_eq!(ControlCard::parse(&job.to_card().unwrap()).unwrap(), job);
+
+        assert!(ControlCard::xeq(&"X".repeat(74)).to_card().is_err());
+        assert!(ControlCard::xeq("A~B").to_card().is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_other_cards() {
+        let parse = |text: &str| ControlCard::parse(&PunchCard::from_text(text));
+        assert_eq!(parse("* COMMENT"), Err(ParseError::NotControl));
+        assert_eq!(
+            parse("// LINK PROG"),
+            Err(ParseError::UnknownOperation("LINK".to_string()))
+        );
+        assert_eq!(
+            parse("//XEQ"),
+            Err(ParseError::NotBlank { column: 2, ch: 'X' })
+        );
+        assert_eq!(
+            ControlCard::parse(&PunchCard::new(CardType::Binary)),
+            Err(ParseError::NotText)
+        );
+    }
+
+    #[test]
+    fn test_classify_mixed_deck() {
+        use DeckCardKind::*;
+        let kinds: Vec<DeckCardKind> = mixed_deck().iter().map(classify_card).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Control, Control, Source, Source, Source, Source, Source, Control, Data, Data,
+                Blank, Control, Control, Data, Data, Object, Object, Control, Data, Control,
+            ]
+        );
+
+        // In context, the cards after `// FOR` are FORTRAN source
+        let kinds = mixed_deck().classify_cards();
+        assert_eq!(
+            &kinds[11..],
+            &[
+                Control, Control, Fortran, Fortran, Object, Object, Control, Data, Control,
+            ]
+        );
+        assert_eq!(kinds[8], Data);
+    }
+
+    #[test]
+    fn test_classify_fortran_deck() {
+        use DeckCardKind::*;
+        let deck = Deck::from_cards(
+            [
+                "// JOB",
+                "// FOR",
+                "*LIST ALL",
+                "C SUM THE TABLE",
+                "      DO 10 I=1,10",
+                "   10 X(I)=0.0",
+                "// XEQ",
+                "   10 X(I)=0.0",
+            ]
+            .into_iter()
+            .map(PunchCard::from_text)
+            .collect(),
+        );
+        assert_eq!(
+            deck.classify_cards(),
+            vec![
+                Control, Control, Fortran, Fortran, Fortran, Fortran, Control, Data
+            ]
+        );
+    }
+
+    #[test]
+    fn test_store_program_job_deck() {
+        let mut deck = Deck::from_cards(vec![
+            ControlCard::job().to_card().unwrap(),
+            ControlCard::asm().to_card().unwrap(),
+        ]);
+        let program = generate_example_program();
+        let source_cards = program.len();
+        for card in &program {
+            deck.push(card.clone());
+        }
+        deck.push(ControlCard::dup().to_card().unwrap());
+        deck.push(
+            DupCard::store(DupDevice::WorkingStorage, DupDevice::UserArea, "SUM")
+                .unwrap()
+                .to_card(),
+        );
+
+        let kinds: Vec<DeckCardKind> = deck.iter().map(classify_card).collect();
+        let mut expected = vec![DeckCardKind::Control; 2];
+        expected.extend(vec![DeckCardKind::Source; source_cards]);
+        expected.extend([DeckCardKind::Control, DeckCardKind::Dup]);
+        assert_eq!(kinds, expected);
+
+        let text: Vec<String> = deck.iter().map(PunchCard::to_text).collect();
+        assert_eq!(text[0].trim_end(), "// JOB");
+        assert_eq!(text[1].trim_end(), "// ASM");
+        assert_eq!(&text[2][20..45], "*SUM A TABLE OF TEN WORDS");
+        assert_eq!(&text[3][20..30], "SUM   ORG ");
+        assert_eq!(text[source_cards + 2].trim_end(), "// DUP");
+        let store = &text[source_cards + 3];
+        assert_eq!(&store[..12], "*STORE      ");
+        assert_eq!(&store[12..16], "WS  ");
+        assert_eq!(&store[16..20], "UA  ");
+        assert_eq!(&store[20..25], "SUM  ");
+        assert!(store[25..].trim().is_empty());
+
+        let parsed = DupCard::parse(deck.get(source_cards + 3).unwrap()).unwrap();
+        assert_eq!(parsed.function(), DupFunction::Store);
+        assert_eq!(parsed.from(), Some(DupDevice::WorkingStorage));
+        assert_eq!(parsed.to(), Some(DupDevice::UserArea));
+        assert_eq!(parsed.name(), "SUM");
+        assert_eq!(parsed.count(), None);
+        assert_eq!(deck.split_jobs().len(), 1);
+    }
+
+    #[test]
+    fn test_build_and_parse_dup_cards() {
+        let cards = [
+            (
+                DupCard::store_ci(DupDevice::Card, DupDevice::FixedArea, "PROG", Some(12)),
+                "*STORECI    CD  FX  PROG    12",
+            ),
+            (
+                DupCard::dump(DupDevice::UserArea, DupDevice::Printer, "SUM"),
+                "*DUMP       UA  PR  SUM",
+            ),
+            (DupCard::delete("SUM"), "*DELETE             SUM"),
+            (
+                DupCard::define_fixed_area(3),
+                "*DEFINE     FIXED AREA       3",
+            ),
+        ];
+        for (dup, text) in cards {
+            let dup = dup.unwrap();
+            assert_eq!(dup.to_string(), text);
+            let card = dup.to_card();
+            assert_eq!(classify_card(&card), DeckCardKind::Dup);
+            assert_eq!(DupCard::parse(&card).unwrap(), dup);
+        }
+
+        assert!(DupCard::delete("TOOLONG").is_err());
+        assert!(DupCard::delete("A~B").is_err());
+        assert!(DupCard::define_fixed_area(10000).is_err());
+
+        let parse = |text: &str| DupCard::parse(&PunchCard::from_text(text));
+        assert_eq!(parse("// DUP"), Err(ParseError::NotDup));
+        assert_eq!(
+            parse("*COPY       WS  UA  SUM"),
+            Err(ParseError::UnknownOperation("COPY".to_string()))
+        );
+        assert_eq!(
+            parse("*STORE      WS  XX  SUM"),
+            Err(ParseError::UnknownDevice("XX".to_string()))
+        );
+        assert_eq!(
+            parse("*STORECI    CD  FX  PROG   1X"),
+            Err(ParseError::InvalidCount("1X".to_string()))
+        );
+        assert!(parse("*DEFINE     VOID AREA").is_err());
+        // A comment with `*` in column 1 is not a DUP card
+        assert_eq!(
+            classify_card(&PunchCard::from_text("* COMMENT")),
+            DeckCardKind::Data
+        );
+    }
+
+    #[test]
+    fn test_split_jobs() {
+        let jobs = mixed_deck().split_jobs();
+        assert_eq!(jobs.iter().map(Deck::len).collect::<Vec<_>>(), vec![11, 9]);
+        for job in &jobs {
+            let first = ControlCard::parse(job.get(0).unwrap()).unwrap();
+            assert_eq!(first.operation(), ControlOperation::Job);
+        }
+
+        let mut leading = Deck::from_cards(vec![PunchCard::from_text("DATA")]);
+        leading.push(ControlCard::job().to_card().unwrap());
+        assert_eq!(leading.split_jobs().len(), 2);
+        assert!(Deck::new().split_jobs().is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+// IBM 1130 Opcodes
+//
+// The 1130 assembler mnemonics: machine instructions (with the instruction
+// word bits they assemble to), the extended branch and shift mnemonics, and
+// assembler directives.
+//
+// Instruction word, short form: bits 0-4 op code, bit 5 format (1 = long),
+// bits 6-7 tag (index register), bits 8-15 displacement or modifiers. A long
+// instruction's bit 8 is the indirect bit and its second word the address.
+
+use LabelRule::{NotAllowed, Optional, Required};
+use OperandSyntax as Op;
+
+/// Whether a mnemonic is a machine instruction or an assembler directive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpcodeKind {
+    Instruction,
+    Directive,
+}
+
+/// Whether a statement may have a label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelRule {
+    Optional,
+    /// The statement defines its label (EQU)
+    Required,
+    /// Any label is ignored
+    NotAllowed,
+}
+
+/// The form of a statement's operands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandSyntax {
+    /// No operands
+    None,
+    /// A memory address (relative in the short form)
+    Address,
+    /// A memory address, then optional branch conditions (`LOOP,Z+`)
+    Branch,
+    /// Skip conditions only (`Z+-`)
+    Conditions,
+    /// A shift count, or none when the count is in an index register
+    Shift,
+    /// A value placed in the instruction's displacement
+    Value,
+    /// An expression (`/0100`, `TABLE-1`, `10`)
+    Expression,
+    /// Decimal or floating-point constants
+    Decimal,
+    /// Text between delimiters (`.HELLO.`) or to the end of the field
+    Text,
+    /// Symbol names separated by commas
+    Symbols,
+}
+
+/// What the assembler knows about a mnemonic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpcodeInfo {
+    pub mnemonic: &'static str,
+    pub kind: OpcodeKind,
+    /// Short-form instruction word with the op code and any fixed modifier
+    /// bits; 0 for directives
+    pub word: u16,
+    pub label: LabelRule,
+    pub operands: OperandSyntax,
+    /// Whether the long (`L`) and indirect (`I`) formats are allowed
+    pub long: bool,
+    /// Whether a tag (index register 1-3) is allowed
+    pub indexed: bool,
+}
+
+const fn instruction(
+    mnemonic: &'static str,
+    word: u16,
+    operands: OperandSyntax,
+    long: bool,
+    indexed: bool,
+) -> OpcodeInfo {
+    OpcodeInfo {
+        mnemonic,
+        kind: OpcodeKind::Instruction,
+        word,
+        label: LabelRule::Optional,
+        operands,
+        long,
+        indexed,
+    }
+}
+
+const fn directive(
+    mnemonic: &'static str,
+    label: LabelRule,
+    operands: OperandSyntax,
+) -> OpcodeInfo {
+    OpcodeInfo {
+        mnemonic,
+        kind: OpcodeKind::Directive,
+        word: 0,
+        label,
+        operands,
+        long: false,
+        indexed: false,
+    }
+}
+
+/// Every mnemonic the assembler accepts
+pub const OPCODES: &[OpcodeInfo] = &[
+    // Load and store
+    instruction("LD", 0xC000, Op::Address, true, true),
+    instruction("LDD", 0xC800, Op::Address, true, true),
+    instruction("STO", 0xD000, Op::Address, true, true),
+    instruction("STD", 0xD800, Op::Address, true, true),
+    instruction("LDX", 0x6000, Op::Address, true, true),
+    instruction("STX", 0x6800, Op::Address, true, true),
+    instruction("LDS", 0x2000, Op::Value, false, false),
+    instruction("STS", 0x2800, Op::Address, true, true),
+    // Arithmetic and logical
+    instruction("A", 0x8000, Op::Address, true, true),
+    instruction("AD", 0x8800, Op::Address, true, true),
+    instruction("S", 0x9000, Op::Address, true, true),
+    instruction("SD", 0x9800, Op::Address, true, true),
+    instruction("M", 0xA000, Op::Address, true, true),
+    instruction("D", 0xA800, Op::Address, true, true),
+    instruction("AND", 0xE000, Op::Address, true, true),
+    instruction("OR", 0xE800, Op::Address, true, true),
+    instruction("EOR", 0xF000, Op::Address, true, true),
+    // Shifts (bits 8-9 select the shift; the tag supplies the count)
+    instruction("SLA", 0x1000, Op::Shift, false, true),
+    instruction("SLCA", 0x1040, Op::Shift, false, true),
+    instruction("SLT", 0x1080, Op::Shift, false, true),
+    instruction("SLC", 0x10C0, Op::Shift, false, true),
+    instruction("SRA", 0x1800, Op::Shift, false, true),
+    instruction("SRT", 0x1880, Op::Shift, false, true),
+    instruction("RTE", 0x18C0, Op::Shift, false, true),
+    instruction("NOP", 0x1000, Op::None, false, false),
+    instruction("XCH", 0x18D0, Op::None, false, false),
+    // Branches (condition bits 10-15: Z, -, +, E, C, O)
+    instruction("BSI", 0x4000, Op::Branch, true, true),
+    instruction("BSC", 0x4800, Op::Branch, true, true),
+    instruction("BOSC", 0x4840, Op::Branch, true, true),
+    instruction("SKP", 0x4800, Op::Conditions, false, false),
+    instruction("B", 0x4800, Op::Address, true, true),
+    instruction("BP", 0x4830, Op::Address, true, true),
+    instruction("BNP", 0x4808, Op::Address, true, true),
+    instruction("BN", 0x4828, Op::Address, true, true),
+    instruction("BNN", 0x4810, Op::Address, true, true),
+    instruction("BZ", 0x4818, Op::Address, true, true),
+    instruction("BNZ", 0x4820, Op::Address, true, true),
+    instruction("BC", 0x4802, Op::Address, true, true),
+    instruction("BO", 0x4801, Op::Address, true, true),
+    instruction("BOD", 0x4804, Op::Address, true, true),
+    instruction("MDX", 0x7000, Op::Address, true, true),
+    // Control and I/O
+    instruction("WAIT", 0x3000, Op::None, false, false),
+    instruction("XIO", 0x0800, Op::Address, true, true),
+    // Location and symbol definition
+    directive("ABS", NotAllowed, Op::None),
+    directive("ORG", NotAllowed, Op::Expression),
+    directive("EQU", Required, Op::Expression),
+    directive("END", NotAllowed, Op::Expression),
+    // Data
+    directive("DC", Optional, Op::Expression),
+    directive("DEC", Optional, Op::Decimal),
+    directive("XFLC", Optional, Op::Decimal),
+    directive("EBC", Optional, Op::Text),
+    directive("DMES", Optional, Op::Text),
+    directive("BSS", Optional, Op::Expression),
+    directive("BES", Optional, Op::Expression),
+    directive("DSA", Optional, Op::Symbols),
+    directive("DN", Optional, Op::Symbols),
+    // Subprograms and linkage
+    directive("ENT", NotAllowed, Op::Symbols),
+    directive("ISS", NotAllowed, Op::Symbols),
+    directive("ILS", NotAllowed, Op::Expression),
+    directive("LIBR", NotAllowed, Op::None),
+    directive("SPR", NotAllowed, Op::None),
+    directive("EPR", NotAllowed, Op::None),
+    directive("CALL", Optional, Op::Symbols),
+    directive("LIBF", Optional, Op::Symbols),
+    directive("LINK", Optional, Op::Symbols),
+    directive("EXIT", Optional, Op::None),
+    directive("DUMP", Optional, Op::Expression),
+    directive("PDMP", Optional, Op::Expression),
+    directive("FILE", NotAllowed, Op::Expression),
+    // Listing control
+    directive("LIST", NotAllowed, Op::Text),
+    directive("SPAC", NotAllowed, Op::Expression),
+    directive("EJCT", NotAllowed, Op::None),
+    directive("HDNG", NotAllowed, Op::Text),
+];
+
+/// Look up a mnemonic (uppercase, as punched)
+pub fn lookup(mnemonic: &str) -> Option<&'static OpcodeInfo> {
+    OPCODES.iter().find(|info| info.mnemonic == mnemonic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let ld = lookup("LD").unwrap();
+        assert_eq!(ld.kind, OpcodeKind::Instruction);
+        assert_eq!(ld.word >> 11, 0b11000);
+        assert!(ld.long && ld.indexed);
+        assert_eq!(lookup("EQU").unwrap().label, LabelRule::Required);
+        assert!(!lookup("WAIT").unwrap().long);
+        assert_eq!(lookup("ld"), None);
+        assert_eq!(lookup("ADD"), None);
+    }
+
+    #[test]
+    fn test_table_is_consistent() {
+        for (index, info) in OPCODES.iter().enumerate() {
+            assert!(
+                OPCODES[..index]
+                    .iter()
+                    .all(|other| other.mnemonic != info.mnemonic),
+                "{} is listed twice",
+                info.mnemonic
+            );
+            assert!(
+                info.mnemonic.len() <= 4,
+                "{} does not fit columns 27-30",
+                info.mnemonic
+            );
+            match info.kind {
+                // XIO (op code 00001) is the lowest instruction
+                OpcodeKind::Instruction => assert!(info.word >= 0x0800),
+                OpcodeKind::Directive => assert!(info.word == 0 && !info.long),
+            }
+        }
+    }
+}
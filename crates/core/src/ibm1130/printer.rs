@@ -0,0 +1,278 @@
+// IBM 1130 Printer Character Sets
+//
+// The printers on an 1130 could print far fewer characters than a keypunch
+// could punch. Anything outside a printer's set prints as a blank, so a
+// listing can silently lose characters that are on the cards.
+//
+// - 1132: 48 type wheel characters, the 1130 FORTRAN set
+// - 1403 AN chain: the commercial arrangement, with # @ % and the lozenge
+// - 1403 HN chain: the scientific arrangement, with = ' ( ) + in their place
+//
+// `deck_to_listing` prints a deck the way an 80-80 list program would: one
+// line per card image, optionally with column 1 as ASA carriage control:
+// - ` ` single space
+// - `0` double space
+// - `-` triple space
+// - `1` skip to a new page
+// - `+` overprint the previous line
+
+use crate::deck::Deck;
+use crate::punch_card::PunchCard;
+
+/// Letters and digits, common to every print set
+const ALPHANUMERIC: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// A printer's character set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrintChain {
+    /// 1132 printer
+    #[default]
+    Ibm1132,
+    /// 1403 printer, AN (commercial) chain
+    Ibm1403An,
+    /// 1403 printer, HN (FORTRAN) chain
+    Ibm1403Hn,
+}
+
+impl PrintChain {
+    /// The special characters, besides letters and digits
+    pub fn specials(self) -> &'static str {
+        match self {
+            PrintChain::Ibm1132 => "&.(+$*)-/,'=",
+            PrintChain::Ibm1403An => "&.⌑-$*/,%#@‡",
+            PrintChain::Ibm1403Hn => "+.)-$*/,('=‡",
+        }
+    }
+
+    /// Whether a character prints; blank always does
+    pub fn can_print(self, ch: char) -> bool {
+        ch == ' ' || ALPHANUMERIC.contains(ch) || self.specials().contains(ch)
+    }
+}
+
+/// Whether the 1132 printer can print a character
+pub fn is_printable_1132(ch: char) -> bool {
+    PrintChain::Ibm1132.can_print(ch)
+}
+
+/// List the columns whose characters would not print on an 1132
+///
+/// See `validate_printable_on`.
+pub fn validate_printable(card: &PunchCard) -> Vec<(usize, char)> {
+    validate_printable_on(card, PrintChain::Ibm1132)
+}
+
+/// List the columns (0-based) whose characters would not print on a chain
+///
+/// Columns are decoded as on an 029. A column with no character is listed
+/// with `char::REPLACEMENT_CHARACTER`.
+pub fn validate_printable_on(card: &PunchCard, chain: PrintChain) -> Vec<(usize, char)> {
+    card.columns()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, column)| {
+            let ch = column.to_char().unwrap_or(char::REPLACEMENT_CHARACTER);
+            (!chain.can_print(ch)).then_some((index, ch))
+        })
+        .collect()
+}
+
+/// Print positions on the 1132 and 1403
+pub const PRINT_WIDTH: usize = 132;
+
+/// How `deck_to_listing` prints a deck
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListingOptions {
+    /// Characters outside this set print as blanks
+    pub chain: PrintChain,
+    /// Start each line with the card's position in the deck
+    pub numbered: bool,
+    /// Read column 1 as ASA carriage control instead of printing it
+    pub carriage_control: bool,
+    /// Lines per page, including the header; 0 never skips to a new page
+    /// except on a `1` control character
+    pub lines_per_page: usize,
+    /// Title printed at the top of each page with the page number
+    pub header: Option<String>,
+}
+
+/// Print a deck as a listing, one line per card
+///
+/// Lines end with `\n`, a new page starts with a form feed, and an overprint
+/// line follows a carriage return, as the POSIX `asa` command writes them.
+/// Lines are cut at `PRINT_WIDTH` and have no trailing blanks.
+pub fn deck_to_listing(deck: &Deck, options: &ListingOptions) -> String {
+    let mut listing = String::new();
+    let mut page = 0;
+    let mut line = 0;
+    for (index, card) in deck.iter().enumerate() {
+        let mut text: String = card
+            .columns()
+            .iter()
+            .map(|column| match column.to_char() {
+                Some(ch) if options.chain.can_print(ch) => ch,
+                _ => ' ',
+            })
+            .collect();
+        let control = if options.carriage_control {
+            text.remove(0)
+        } else {
+            ' '
+        };
+        if options.numbered {
+            text.insert_str(0, &format!("{:>5}  ", index + 1));
+        }
+        let text: String = text.chars().take(PRINT_WIDTH).collect();
+        let text = text.trim_end();
+
+        if control == '+' && listing.ends_with('\n') {
+            listing.pop();
+            listing.push('\r');
+            listing.push_str(text);
+            listing.push('\n');
+            continue;
+        }
+        let spacing = match control {
+            '0' => 2,
+            '-' => 3,
+            _ => 1,
+        };
+        let full = options.lines_per_page > 0 && line + spacing > options.lines_per_page;
+        if page == 0 || control == '1' || full {
+            if page > 0 {
+                listing.push('\x0C');
+            }
+            page += 1;
+            line = 0;
+            if let Some(title) = &options.header {
+                let title: String = title.chars().take(PRINT_WIDTH - 9).collect();
+                listing.push_str(&format!(
+                    "{:<width$}PAGE {:>3}\n\n",
+                    title,
+                    page,
+                    width = PRINT_WIDTH - 8
+                ));
+                line = 2;
+            }
+        } else {
+            for _ in 1..spacing {
+                listing.push('\n');
+            }
+            line += spacing - 1;
+        }
+        listing.push_str(text);
+        listing.push('\n');
+        line += 1;
+    }
+    listing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hollerith::HollerithCode;
+
+    const SOURCE_DECK: &str = include_str!("../../tests/fixtures/ibm1130_source.txt");
+    const SOURCE_LISTING: &str = include_str!("../../tests/fixtures/ibm1130_source_listing.txt");
+    const REPORT_LISTING: &str = include_str!("../../tests/fixtures/ibm1130_report_listing.txt");
+
+    fn deck(text: &str) -> Deck {
+        text.lines().map(PunchCard::from_text).collect()
+    }
+
+    #[test]
+    fn test_print_sets_have_48_characters() {
+        for chain in [
+            PrintChain::Ibm1132,
+            PrintChain::Ibm1403An,
+            PrintChain::Ibm1403Hn,
+        ] {
+            let printable = (' '..='~')
+                .chain("⌑‡".chars())
+                .filter(|&ch| ch != ' ' && chain.can_print(ch))
+                .count();
+            assert_eq!(printable, 48, "{:?}", chain);
+        }
+    }
+
+    #[test]
+    fn test_is_printable_1132() {
+        for ch in "A Z 0 9 & . ( + $ * ) - / , ' =".chars() {
+            assert!(is_printable_1132(ch), "{:?}", ch);
+        }
+        for ch in "#@%<>;:!?\"_a".chars() {
+            assert!(!is_printable_1132(ch), "{:?}", ch);
+        }
+    }
+
+    #[test]
+    fn test_validate_printable() {
+        let card = PunchCard::from_text("      A = B + C  # LOOP @ 10%");
+        assert_eq!(
+            validate_printable(&card),
+            vec![(17, '#'), (24, '@'), (28, '%')]
+        );
+        assert_eq!(
+            validate_printable_on(&card, PrintChain::Ibm1403An),
+            vec![(8, '='), (12, '+')]
+        );
+        assert_eq!(
+            validate_printable_on(&card, PrintChain::Ibm1403Hn),
+            validate_printable(&card)
+        );
+
+        let mut odd = PunchCard::from_text("OK");
+        odd.set_column_hollerith(2, HollerithCode::new(vec![1, 2, 3]))
+            .unwrap();
+        assert_eq!(
+            validate_printable(&odd),
+            vec![(2, char::REPLACEMENT_CHARACTER)]
+        );
+        assert_eq!(
+            validate_printable(&PunchCard::from_text("A<B")),
+            vec![(1, '<')]
+        );
+    }
+
+    #[test]
+    fn test_source_listing() {
+        let options = ListingOptions {
+            numbered: true,
+            lines_per_page: 8,
+            header: Some("SUM A TABLE OF TEN WORDS".to_string()),
+            ..ListingOptions::default()
+        };
+        let listing = deck_to_listing(&deck(SOURCE_DECK), &options);
+        assert_eq!(listing, SOURCE_LISTING);
+        assert_eq!(listing.matches('\x0C').count(), 1);
+        assert!(
+            listing
+                .split(['\n', '\x0C'])
+                .all(|line| line.chars().count() <= PRINT_WIDTH)
+        );
+    }
+
+    #[test]
+    fn test_carriage_control_listing() {
+        let report = deck(concat!(
+            "1PAYROLL REPORT\n",
+            "0EMPLOYEE   HOURS   RATE\n",
+            "+--------\n",
+            " 00042      37.5    #22.50\n",
+            " 00057      40.0    #19.75\n",
+            "-TOTAL      77.5\n",
+            "1END OF REPORT\n",
+        ));
+        let options = ListingOptions {
+            carriage_control: true,
+            ..ListingOptions::default()
+        };
+        assert_eq!(deck_to_listing(&report, &options), REPORT_LISTING);
+
+        // Without carriage control, column 1 is printed like any other
+        let plain = deck_to_listing(&report, &ListingOptions::default());
+        assert!(plain.starts_with("1PAYROLL REPORT\n0EMPLOYEE"));
+        assert!(!plain.contains('\x0C'));
+        assert!(deck_to_listing(&Deck::new(), &options).is_empty());
+    }
+}
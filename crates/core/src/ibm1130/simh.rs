@@ -0,0 +1,292 @@
+// IBM 1130 Simulator Card Decks
+//
+// The simh IBM 1130 simulator (sim1130) reads binary card decks as the card
+// reader delivers them to memory: one 16-bit word per column, 80 words per
+// card, stored low byte first. The twelve rows fill the high bits of each
+// word, as the 1130 itself reads them:
+//
+//     row:  12  11   0   1   2   3   4   5   6   7   8   9   -   -   -   -
+//     bit: 15  14  13  12  11  10   9   8   7   6   5   4   3   2   1   0
+//
+// The four low bits are written as 0 and ignored on reading, as the
+// simulator ignores them. A deck file is the cards back to back with no
+// header, so decks written here attach straight to the simulator's card
+// reader (`attach -b cr deck.bin`) and decks it punches read back.
+//
+// The reader also takes ASCII text decks, one card per line. Lines are
+// punched as the 029 would punch them; the simulator's `!` directive lines
+// are not interpreted yet and read as ordinary cards.
+
+use super::classify_read_card;
+use crate::deck::Deck;
+use crate::punch_card::{PunchCard, UnsupportedCharPolicy};
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// Bytes per card in a simulator deck
+pub const SIMH_CARD_BYTES: usize = 160;
+
+/// The two deck file formats the simulator's card reader attaches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimhFormat {
+    /// 160-byte column-word records (`attach -b`)
+    Binary,
+    /// Text, one card per line
+    Ascii,
+}
+
+/// Errors from reading or writing a simulator deck
+#[derive(Debug)]
+pub enum SimhError {
+    /// The deck is not a whole number of cards (length in bytes)
+    Length(usize),
+    /// An ASCII deck line is longer than a card (0-based card, length)
+    LongLine { card: usize, len: usize },
+    /// An ASCII deck line cannot be punched (0-based card)
+    Unpunchable { card: usize, message: String },
+    /// A card has a column with no character, so it cannot be written as
+    /// text (0-based card and column)
+    NotText { card: usize, column: usize },
+    /// Reading the deck file failed
+    Io(io::Error),
+}
+
+impl fmt::Display for SimhError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimhError::Length(len) => write!(
+                f,
+                "Deck is {} bytes, which is not a whole number of {}-byte cards",
+                len, SIMH_CARD_BYTES
+            ),
+            SimhError::LongLine { card, len } => write!(
+                f,
+                "Card {}: line is {} characters; a card holds 80",
+                card + 1,
+                len
+            ),
+            SimhError::Unpunchable { card, message } => {
+                write!(f, "Card {}: {}", card + 1, message)
+            }
+            SimhError::NotText { card, column } => write!(
+                f,
+                "Card {}: column {} is not a character",
+                card + 1,
+                column + 1
+            ),
+            SimhError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SimhError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SimhError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SimhError {
+    fn from(e: io::Error) -> Self {
+        SimhError::Io(e)
+    }
+}
+
+/// Read a simulator binary deck
+///
+/// A card whose every column is a character reads as a text card, so
+/// source decks can be parsed as `SourceCard`s; any other card, such as an
+/// object card, reads as binary.
+pub fn read_deck(data: &[u8]) -> Result<Deck, SimhError> {
+    if !data.len().is_multiple_of(SIMH_CARD_BYTES) {
+        return Err(SimhError::Length(data.len()));
+    }
+    Ok(data
+        .chunks(SIMH_CARD_BYTES)
+        .map(|record| {
+            let mut columns = [0u16; 80];
+            for (column, pair) in columns.iter_mut().zip(record.chunks(2)) {
+                *column = u16::from_le_bytes([pair[0], pair[1]]) >> 4;
+            }
+            classify_read_card(PunchCard::from_u16_columns(&columns))
+        })
+        .collect())
+}
+
+/// Write a deck in the simulator's binary format
+pub fn write_deck(deck: &Deck) -> Vec<u8> {
+    deck.iter()
+        .flat_map(|card| card.to_u16_columns())
+        .flat_map(|column| (column << 4).to_le_bytes())
+        .collect()
+}
+
+/// Read an ASCII text deck, one card per line
+///
+/// Lowercase letters are punched as uppercase. Fails on a line longer than
+/// 80 characters or a character the 029 cannot punch.
+pub fn read_ascii_deck(text: &str) -> Result<Deck, SimhError> {
+    text.lines()
+        .enumerate()
+        .map(|(card, line)| {
+            let line = line.trim_end_matches('\r');
+            let len = line.chars().count();
+            if len > 80 {
+                return Err(SimhError::LongLine { card, len });
+            }
+            PunchCard::from_ascii_record(line, UnsupportedCharPolicy::Error)
+                .map_err(|message| SimhError::Unpunchable { card, message })
+        })
+        .collect()
+}
+
+/// Write a deck as ASCII text, one line per card with trailing blanks
+/// removed
+///
+/// Fails on a card with a column that is not a character, such as an
+/// object card; write those with `write_deck`.
+pub fn write_ascii_deck(deck: &Deck) -> Result<String, SimhError> {
+    let mut text = String::new();
+    for (card, punch_card) in deck.iter().enumerate() {
+        if let Some(column) = punch_card
+            .columns()
+            .iter()
+            .position(|column| column.to_char().is_none())
+        {
+            return Err(SimhError::NotText { card, column });
+        }
+        text.push_str(punch_card.to_text().trim_end());
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// Read a deck file in either simulator format
+pub fn read_deck_file(path: impl AsRef<Path>, format: SimhFormat) -> Result<Deck, SimhError> {
+    let data = std::fs::read(path)?;
+    match format {
+        SimhFormat::Binary => read_deck(&data),
+        SimhFormat::Ascii => {
+            let text = String::from_utf8(data)
+                .map_err(|e| SimhError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+            read_ascii_deck(&text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::CardFormat;
+    use crate::ibm1130::{SourceCard, disassemble_deck};
+    use crate::punch_card::CardType;
+
+    const SOURCE_DECK: &str = include_str!("../../tests/fixtures/ibm1130_source.txt");
+    const SUM_OBJECT_DECK: &[u8] =
+        include_bytes!("../../tests/fixtures/ibm1130_sum_object_synthetic.bin");
+
+    #[test]
+    fn test_source_deck_roundtrip() {
+        let punched: Deck = SOURCE_DECK.lines().map(PunchCard::from_text).collect();
+        let data = write_deck(&punched);
+        assert_eq!(data.len(), punched.len() * SIMH_CARD_BYTES);
+
+        let deck = read_deck(&data).unwrap();
+        let lines: Vec<String> = deck
+            .iter()
+            .map(|card| card.to_text().trim_end().to_string())
+            .collect();
+        assert_eq!(lines, SOURCE_DECK.lines().collect::<Vec<_>>());
+        assert!(SourceCard::parse(deck.get(2).unwrap()).is_ok());
+        assert_eq!(write_deck(&deck), data);
+    }
+
+    #[test]
+    fn test_object_deck_roundtrip() {
+        let object: Deck = SUM_OBJECT_DECK
+            .chunks(120)
+            .map(|data| PunchCard::parse(data, CardFormat::FullBinary120).unwrap())
+            .collect();
+        let deck = read_deck(&write_deck(&object)).unwrap();
+        assert!(deck.iter().all(|card| card.card_type() == CardType::Binary));
+        assert_eq!(
+            disassemble_deck(&deck).unwrap(),
+            disassemble_deck(&object).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_column_words() {
+        // A: rows 12 and 1; 0: row 0
+        let card = PunchCard::from_text("A0");
+        let data = write_deck(&Deck::from_cards(vec![card]));
+        assert_eq!(data.len(), SIMH_CARD_BYTES);
+        assert_eq!(&data[..4], &[0x00, 0x90, 0x00, 0x20]);
+        assert!(data[4..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_read_ignores_low_bits() {
+        let mut data = vec![0; SIMH_CARD_BYTES];
+        data[..4].copy_from_slice(&[0x0F, 0x90, 0x01, 0x20]);
+        let deck = read_deck(&data).unwrap();
+        assert_eq!(deck.get(0).unwrap().to_text().trim_end(), "A0");
+        assert!(matches!(read_deck(&[0; 100]), Err(SimhError::Length(100))));
+        assert!(read_deck(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ascii_deck_roundtrip() {
+        let deck = read_ascii_deck(SOURCE_DECK).unwrap();
+        assert_eq!(deck.len(), SOURCE_DECK.lines().count());
+        assert!(SourceCard::parse(deck.get(2).unwrap()).is_ok());
+        assert_eq!(write_ascii_deck(&deck).unwrap(), SOURCE_DECK);
+
+        let lower = read_ascii_deck("      ld   x\r\n").unwrap();
+        assert_eq!(lower.get(0).unwrap().to_text().trim_end(), "      LD   X");
+
+        let err = read_ascii_deck(&format!("A\n{}", "X".repeat(81))).unwrap_err();
+        assert!(matches!(err, SimhError::LongLine { card: 1, len: 81 }));
+        assert_eq!(
+            err.to_string(),
+            "Card 2: line is 81 characters; a card holds 80"
+        );
+        assert!(matches!(
+            read_ascii_deck("A~B"),
+            Err(SimhError::Unpunchable { card: 0, .. })
+        ));
+
+        let object: Deck = SUM_OBJECT_DECK
+            .chunks(120)
+            .map(|data| PunchCard::parse(data, CardFormat::FullBinary120).unwrap())
+            .collect();
+        assert!(matches!(
+            write_ascii_deck(&object),
+            Err(SimhError::NotText { card: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_deck_file() {
+        let dir = std::env::temp_dir().join(format!("simh-deck-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let punched = read_ascii_deck(SOURCE_DECK).unwrap();
+
+        let binary = dir.join("deck.bin");
+        std::fs::write(&binary, write_deck(&punched)).unwrap();
+        let deck = read_deck_file(&binary, SimhFormat::Binary).unwrap();
+        assert_eq!(write_deck(&deck), write_deck(&punched));
+
+        let ascii = dir.join("deck.txt");
+        std::fs::write(&ascii, SOURCE_DECK).unwrap();
+        let deck = read_deck_file(&ascii, SimhFormat::Ascii).unwrap();
+        assert_eq!(write_ascii_deck(&deck).unwrap(), SOURCE_DECK);
+
+        let missing = read_deck_file(dir.join("missing.bin"), SimhFormat::Binary);
+        assert!(matches!(missing, Err(SimhError::Io(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
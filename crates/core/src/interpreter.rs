@@ -0,0 +1,114 @@
+// Interpreter Module
+//
+// Simulates running a card through an IBM 557 interpreter, which reads the
+// holes and prints the decoded characters along the top edge. Punches are
+// never changed.
+
+use crate::punch_card::{CardType, PunchCard};
+use std::fmt;
+
+/// The outcome of interpreting a card
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterpretReport {
+    /// Number of columns that were printed
+    pub printed: usize,
+    /// Zero-based columns whose punches do not decode to a character; they
+    /// are left unprinted
+    pub uninterpretable: Vec<usize>,
+}
+
+impl InterpretReport {
+    /// Check if every column was printed
+    pub fn is_complete(&self) -> bool {
+        self.uninterpretable.is_empty()
+    }
+}
+
+impl fmt::Display for InterpretReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} columns printed, {} uninterpretable",
+            self.printed,
+            self.uninterpretable.len()
+        )
+    }
+}
+
+impl PunchCard {
+    /// Print each column's decoded character, keeping the card type
+    ///
+    /// This is `reprint_from_punches` with a report: blank columns print as
+    /// a space, and columns that do not decode are left unprinted and
+    /// listed.
+    pub fn interpret(&mut self) -> InterpretReport {
+        self.reprint_from_punches();
+        let uninterpretable: Vec<usize> = self
+            .columns()
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| column.printed_char.is_none())
+            .map(|(index, _)| index)
+            .collect();
+        InterpretReport {
+            printed: self.columns().len() - uninterpretable.len(),
+            uninterpretable,
+        }
+    }
+
+    /// Interpret the card (see `interpret`) and make it a text card
+    pub fn interpret_as_text(&mut self) -> InterpretReport {
+        let report = self.interpret();
+        self.set_card_type(CardType::Text);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hollerith::HollerithCode;
+
+    #[test]
+    fn test_interpret_binary_card() {
+        let text = PunchCard::from_text("HELLO 1130");
+        let mut card = PunchCard::from_binary(&text.to_binary());
+        assert_eq!(card.columns()[0].printed_char, None);
+
+        let report = card.interpret();
+        assert!(report.is_complete());
+        assert_eq!(report.printed, 80);
+        assert_eq!(card.card_type(), CardType::Binary);
+        assert_eq!(card.columns()[0].printed_char, Some('H'));
+        assert_eq!(card.columns()[5].printed_char, Some(' '));
+        assert!(card.punches_eq(&text));
+    }
+
+    #[test]
+    fn test_interpret_reports_undecodable_columns() {
+        let mut card = PunchCard::from_text("AB");
+        card.set_column_hollerith(1, HollerithCode::new(vec![12, 11, 0]))
+            .unwrap();
+        card.get_column_mut(1).unwrap().set_printed_char(Some('B'));
+        let before = card.clone();
+
+        let report = card.interpret_as_text();
+        assert_eq!(report.uninterpretable, vec![1]);
+        assert_eq!(report.printed, 79);
+        assert_eq!(report.to_string(), "79 columns printed, 1 uninterpretable");
+        assert!(card.punches_eq(&before));
+        // Stale printing on an undecodable column is removed
+        assert_eq!(card.columns()[1].printed_char, None);
+        assert_eq!(card.card_type(), CardType::Text);
+    }
+
+    #[test]
+    fn test_interpret_as_text_switches_binary_card() {
+        let mut card = crate::ibm1130::generate_example_object();
+        let before = card.clone();
+        let report = card.interpret_as_text();
+        assert_eq!(card.card_type(), CardType::Text);
+        assert_eq!(report.printed + report.uninterpretable.len(), 80);
+        assert!(card.punches_eq(&before));
+    }
+}
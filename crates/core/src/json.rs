@@ -0,0 +1,394 @@
+// JSON Interchange Module
+//
+// Stable, versioned JSON export/import for punch cards.
+//
+// Version 1 schema:
+//
+//   {
+//     "version": 1,
+//     "card_type": "text" | "binary",
+//     "width": 51,
+//     "variant": "port_a_punch",
+//     "columns": [ { "punches": [12, 1], "char": "A" }, ... ],
+//     "metadata": { "title": "...", "stock": "blue", "corner_cut": "upper_left",
+//                   "form": "fortran" }
+//   }
+//
+// - "width" is optional (written only for stub cards narrower than 80)
+// - "variant" is optional: "standard" (the default) or "port_a_punch"
+// - "columns" holds up to "width" entries; missing trailing columns load as blank
+// - "punches" lists punched rows (12, 11, 0-9), written top-to-bottom
+// - "char" is the printed character, or null for unprinted columns
+// - "metadata" is optional (written only when not default); missing fields
+//   take their defaults
+//
+// The schema is independent of the internal representation, so it stays
+// stable if HollerithCode or Column change shape.
+
+use crate::hollerith::{HollerithCode, is_valid_row};
+use crate::metadata::CardMetadata;
+use crate::punch_card::{CardType, CardVariant, CardWidth, Column, PunchCard};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The current JSON schema version
+pub const JSON_SCHEMA_VERSION: u64 = 1;
+
+/// Row order used when writing punches (top of card to bottom)
+const ROW_ORDER: [u8; 12] = [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+#[derive(Serialize)]
+struct CardV1 {
+    version: u64,
+    card_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    width: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variant: Option<&'static str>,
+    columns: Vec<ColumnV1>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<CardMetadata>,
+}
+
+#[derive(Serialize)]
+struct ColumnV1 {
+    punches: Vec<u8>,
+    char: Option<char>,
+}
+
+impl PunchCard {
+    /// Export the card as version 1 JSON
+    pub fn to_json_v1(&self) -> String {
+        let card = CardV1 {
+            version: JSON_SCHEMA_VERSION,
+            card_type: match self.card_type() {
+                CardType::Text => "text",
+                CardType::Binary => "binary",
+            },
+            width: (self.width() != CardWidth::STANDARD).then(|| self.width().columns()),
+            variant: match self.variant() {
+                CardVariant::Standard => None,
+                CardVariant::PortAPunch => Some("port_a_punch"),
+            },
+            columns: self
+                .columns()
+                .iter()
+                .map(|col| ColumnV1 {
+                    punches: ROW_ORDER
+                        .iter()
+                        .copied()
+                        .filter(|&row| col.punches.is_punched(row))
+                        .collect(),
+                    char: col.printed_char,
+                })
+                .collect(),
+            metadata: (!self.metadata().is_default()).then(|| self.metadata().clone()),
+        };
+        serde_json::to_string_pretty(&card).expect("card JSON serialization cannot fail")
+    }
+
+    /// Import a card from version 1 JSON
+    ///
+    /// Validates the version, card type, and every column, reporting the
+    /// location of the first problem found.
+    pub fn from_json_v1(json: &str) -> Result<PunchCard, String> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {}", e))?;
+        let obj = value.as_object().ok_or("Card JSON must be an object")?;
+
+        match obj.get("version") {
+            Some(Value::Number(n)) if n.as_u64() == Some(JSON_SCHEMA_VERSION) => {}
+            Some(Value::Number(n)) => {
+                return Err(format!(
+                    "Unsupported version {} (expected {})",
+                    n, JSON_SCHEMA_VERSION
+                ));
+            }
+            Some(_) => return Err("\"version\" must be a number".to_string()),
+            None => return Err("Missing \"version\" field".to_string()),
+        }
+
+        let card_type = match obj.get("card_type").and_then(Value::as_str) {
+            Some("text") => CardType::Text,
+            Some("binary") => CardType::Binary,
+            Some(other) => {
+                return Err(format!(
+                    "Unknown card_type \"{}\" (expected \"text\" or \"binary\")",
+                    other
+                ));
+            }
+            None => return Err("Missing or non-string \"card_type\" field".to_string()),
+        };
+
+        let width = match obj.get("width") {
+            None => CardWidth::STANDARD,
+            Some(value) => value
+                .as_u64()
+                .and_then(|w| CardWidth::new(usize::try_from(w).ok()?))
+                .ok_or_else(|| format!("\"width\" must be 1-80, got {}", value))?,
+        };
+
+        let variant = match obj.get("variant").map(|v| v.as_str()) {
+            None | Some(Some("standard")) => CardVariant::Standard,
+            Some(Some("port_a_punch")) => CardVariant::PortAPunch,
+            Some(other) => {
+                return Err(format!(
+                    "Unknown variant {} (expected \"standard\" or \"port_a_punch\")",
+                    other.map_or("(non-string)".to_string(), |v| format!("\"{}\"", v))
+                ));
+            }
+        };
+
+        let columns = obj
+            .get("columns")
+            .and_then(Value::as_array)
+            .ok_or("Missing or non-array \"columns\" field")?;
+        if columns.len() > width.columns() {
+            return Err(format!(
+                "Too many columns: {} (maximum {})",
+                columns.len(),
+                width.columns()
+            ));
+        }
+
+        let mut card = PunchCard::new(card_type).with_width(width);
+        // Columns are loaded as-is; disallowed punches show up in variant_warnings
+        card.set_variant(variant);
+        for (idx, entry) in columns.iter().enumerate() {
+            let column = parse_column(entry).map_err(|e| format!("columns[{}]: {}", idx, e))?;
+            *card.get_column_mut(idx).expect("index checked above") = column;
+        }
+
+        if let Some(metadata) = obj.get("metadata") {
+            let metadata =
+                CardMetadata::deserialize(metadata).map_err(|e| format!("metadata: {}", e))?;
+            card.set_metadata(metadata);
+        }
+        Ok(card)
+    }
+}
+
+fn parse_column(entry: &Value) -> Result<Column, String> {
+    let obj = entry.as_object().ok_or("column must be an object")?;
+
+    let punches = obj
+        .get("punches")
+        .and_then(Value::as_array)
+        .ok_or("missing or non-array \"punches\" field")?;
+    let mut rows = Vec::with_capacity(punches.len());
+    for (idx, punch) in punches.iter().enumerate() {
+        let row = punch
+            .as_u64()
+            .filter(|&r| r <= u64::from(u8::MAX) && is_valid_row(r as u8))
+            .ok_or_else(|| {
+                format!(
+                    "punches[{}]: {} is not a valid row (12, 11, 0-9)",
+                    idx, punch
+                )
+            })?;
+        rows.push(row as u8);
+    }
+
+    let printed_char = match obj.get("char") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(s)) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => {
+                    return Err(format!(
+                        "\"char\" must be a single character, got \"{}\"",
+                        s
+                    ));
+                }
+            }
+        }
+        Some(_) => return Err("\"char\" must be a string or null".to_string()),
+    };
+
+    Ok(Column {
+        punches: HollerithCode::new(rows),
+        printed_char,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE_V1: &str = include_str!("../tests/fixtures/card_v1.json");
+
+    #[test]
+    fn test_json_v1_roundtrip_text() {
+        let card = PunchCard::from_text("HELLO, WORLD. 1130");
+        let json = card.to_json_v1();
+        assert_eq!(PunchCard::from_json_v1(&json).unwrap(), card);
+    }
+
+    #[test]
+    fn test_json_v1_roundtrip_binary() {
+        let card = PunchCard::from_binary(&[0xA5; 108]);
+        let json = card.to_json_v1();
+        assert_eq!(PunchCard::from_json_v1(&json).unwrap(), card);
+    }
+
+    #[test]
+    fn test_json_v1_shape() {
+        let card = PunchCard::from_text("A");
+        let value: Value = serde_json::from_str(&card.to_json_v1()).unwrap();
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["card_type"], "text");
+        assert_eq!(value["columns"].as_array().unwrap().len(), 80);
+        assert_eq!(value["columns"][0]["punches"], serde_json::json!([12, 1]));
+        assert_eq!(value["columns"][0]["char"], "A");
+        assert_eq!(value["columns"][1]["punches"], serde_json::json!([]));
+        assert_eq!(value["columns"][1]["char"], Value::Null);
+    }
+
+    #[test]
+    fn test_json_v1_load_fixture() {
+        let card = PunchCard::from_json_v1(FIXTURE_V1).unwrap();
+        assert_eq!(card.card_type(), CardType::Text);
+        assert_eq!(card.to_text_trimmed(), "HELLO 1130");
+        assert_eq!(card.get_column(0).unwrap().printed_char, Some('H'));
+        assert_eq!(card.get_column(9).unwrap().punches.rows, vec![0]);
+        assert_eq!(card.punched_count(), 9);
+    }
+
+    #[test]
+    fn test_json_v1_short_column_list() {
+        let json = r#"{"version": 1, "card_type": "binary",
+            "columns": [{"punches": [9, 12], "char": null}]}"#;
+        let card = PunchCard::from_json_v1(json).unwrap();
+        assert_eq!(card.card_type(), CardType::Binary);
+        assert_eq!(card.get_column(0).unwrap().punches.rows, vec![9, 12]);
+        assert!(card.get_column(1).unwrap().is_blank());
+    }
+
+    #[test]
+    fn test_json_v1_rejects_bad_version() {
+        let json = r#"{"version": 2, "card_type": "text", "columns": []}"#;
+        let err = PunchCard::from_json_v1(json).unwrap_err();
+        assert!(err.contains("Unsupported version 2"), "{}", err);
+
+        let json = r#"{"card_type": "text", "columns": []}"#;
+        assert!(
+            PunchCard::from_json_v1(json)
+                .unwrap_err()
+                .contains("version")
+        );
+    }
+
+    #[test]
+    fn test_json_v1_rejects_bad_fields() {
+        let json = r#"{"version": 1, "card_type": "round", "columns": []}"#;
+        assert!(PunchCard::from_json_v1(json).unwrap_err().contains("round"));
+
+        let json = r#"{"version": 1, "card_type": "text",
+            "columns": [{"punches": []}, {"punches": [12, 13]}]}"#;
+        let err = PunchCard::from_json_v1(json).unwrap_err();
+        assert_eq!(
+            err,
+            "columns[1]: punches[1]: 13 is not a valid row (12, 11, 0-9)"
+        );
+
+        let json = r#"{"version": 1, "card_type": "text",
+            "columns": [{"punches": [1], "char": "AB"}]}"#;
+        assert!(
+            PunchCard::from_json_v1(json)
+                .unwrap_err()
+                .starts_with("columns[0]")
+        );
+
+        assert!(PunchCard::from_json_v1("not json").is_err());
+        assert!(PunchCard::from_json_v1("[]").is_err());
+    }
+
+    #[test]
+    fn test_json_v1_rejects_too_many_columns() {
+        let columns = vec![r#"{"punches": []}"#; 81].join(",");
+        let json = format!(
+            r#"{{"version": 1, "card_type": "text", "columns": [{}]}}"#,
+            columns
+        );
+        assert!(PunchCard::from_json_v1(&json).unwrap_err().contains("81"));
+    }
+
+    #[test]
+    fn test_json_v1_metadata_roundtrip() {
+        use crate::metadata::{CardStock, CornerCut, FormType};
+
+        let plain = PunchCard::from_text("A").to_json_v1();
+        assert!(!plain.contains("metadata"));
+
+        let mut card = PunchCard::from_text("      CALL EXIT");
+        card.set_metadata(CardMetadata {
+            title: Some("MAIN".to_string()),
+            stock: CardStock::Salmon,
+            corner_cut: CornerCut::UpperRight,
+            form: FormType::Fortran,
+        });
+        let json = card.to_json_v1();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["metadata"]["stock"], "salmon");
+        assert_eq!(value["metadata"]["corner_cut"], "upper_right");
+        assert_eq!(PunchCard::from_json_v1(&json).unwrap(), card);
+    }
+
+    #[test]
+    fn test_json_v1_partial_and_bad_metadata() {
+        use crate::metadata::CardStock;
+
+        let json = r#"{"version": 1, "card_type": "text", "columns": [],
+                       "metadata": {"stock": "blue"}}"#;
+        let card = PunchCard::from_json_v1(json).unwrap();
+        assert_eq!(card.metadata().stock, CardStock::Blue);
+        assert_eq!(card.metadata().title, None);
+
+        let bad = r#"{"version": 1, "card_type": "text", "columns": [],
+                      "metadata": {"stock": "plaid"}}"#;
+        assert!(
+            PunchCard::from_json_v1(bad)
+                .unwrap_err()
+                .starts_with("metadata: ")
+        );
+    }
+
+    #[test]
+    fn test_json_v1_width() {
+        assert!(!PunchCard::from_text("A").to_json_v1().contains("width"));
+
+        let card = PunchCard::from_text_with_width("STUB", 51).unwrap();
+        let json = card.to_json_v1();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["width"], 51);
+        assert_eq!(value["columns"].as_array().unwrap().len(), 51);
+        let loaded = PunchCard::from_json_v1(&json).unwrap();
+        assert_eq!(loaded, card);
+
+        let too_many = json.replace("\"width\": 51", "\"width\": 50");
+        assert_eq!(
+            PunchCard::from_json_v1(&too_many).unwrap_err(),
+            "Too many columns: 51 (maximum 50)"
+        );
+        let bad = json.replace("\"width\": 51", "\"width\": 81");
+        assert!(PunchCard::from_json_v1(&bad).unwrap_err().contains("1-80"));
+    }
+
+    #[test]
+    fn test_json_v1_variant() {
+        assert!(!PunchCard::from_text("A").to_json_v1().contains("variant"));
+
+        let card = PunchCard::from_text_with_variant("ABC", CardVariant::PortAPunch);
+        let json = card.to_json_v1();
+        assert!(json.contains("\"variant\": \"port_a_punch\""));
+        assert_eq!(PunchCard::from_json_v1(&json).unwrap(), card);
+
+        let bad = json.replace("port_a_punch", "mark_sense");
+        assert!(
+            PunchCard::from_json_v1(&bad)
+                .unwrap_err()
+                .contains("mark_sense")
+        );
+    }
+}
@@ -0,0 +1,549 @@
+// Keypunch Module
+//
+// Models an IBM 029 keypunch one keystroke at a time: the card in the punch
+// station, the column under the punch dies, and the keyboard shift. Each
+// keystroke reports what happened so a UI can animate it.
+//
+// Holes are never removed. Keying into a column that already has punches
+// adds holes to it, which is how operators overpunched by backspacing.
+//
+// With a program card on the drum (see `program_card`), the program sets the
+// shift for each column, and fields marked for automatic skip or duplication
+// are handled as soon as the card reaches them. Released cards move to the
+// read station, which is the source for automatic duplication.
+
+use crate::hollerith::{HollerithCode, char_to_hollerith, hollerith_to_char, is_valid_row};
+use crate::program_card::{FieldAction, ProgramCard};
+use crate::punch_card::{CardType, PunchCard};
+use std::fmt;
+
+/// Keyboard shift state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Shift {
+    /// Letters, digits, and special characters can all be keyed
+    #[default]
+    Alpha,
+    /// Only digits and special characters can be keyed; letter keys are
+    /// rejected
+    Numeric,
+}
+
+/// Why a keystroke did nothing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// The character has no punch pattern
+    UnsupportedChar(char),
+    /// The character cannot be keyed in the current shift
+    WrongShift(char),
+    /// A multi-punch row is not 12, 11, or 0-9
+    InvalidRow(u8),
+    /// The card has already passed its last column
+    EndOfCard,
+    /// Backspace at column 1
+    StartOfCard,
+    /// The column cannot be punched on this card variant
+    NotPunchable,
+    /// A skip target is behind the current column or past the card
+    InvalidSkip(usize),
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::UnsupportedChar(c) => write!(f, "Character {:?} has no punch pattern", c),
+            Rejection::WrongShift(c) => write!(f, "Character {:?} needs alpha shift", c),
+            Rejection::InvalidRow(row) => write!(f, "Invalid punch row {}", row),
+            Rejection::EndOfCard => write!(f, "End of card"),
+            Rejection::StartOfCard => write!(f, "Already at column 1"),
+            Rejection::NotPunchable => write!(f, "Column cannot be punched on this card"),
+            Rejection::InvalidSkip(column) => write!(f, "Cannot skip to column {}", column + 1),
+        }
+    }
+}
+
+/// What a keystroke did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyEvent {
+    /// Holes were punched in `column` (none for a space) and the card
+    /// advanced one column
+    Punched {
+        /// Zero-based column that was punched
+        column: usize,
+        /// Rows punched by this keystroke
+        rows: Vec<u8>,
+        /// What the column now prints, if the holes decode
+        printed: Option<char>,
+    },
+    /// The card moved without punching
+    Moved { from: usize, to: usize },
+    /// Nothing changed
+    Rejected { column: usize, reason: Rejection },
+}
+
+/// An 029 keypunch with a card in the punch station
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keypunch {
+    card: PunchCard,
+    column: usize,
+    shift: Shift,
+    program: Option<ProgramCard>,
+    read_station: Option<PunchCard>,
+    auto_events: Vec<KeyEvent>,
+}
+
+impl Default for Keypunch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Keypunch {
+    /// Create a keypunch with a blank card at column 1, in alpha shift
+    pub fn new() -> Self {
+        Keypunch {
+            card: PunchCard::new(CardType::Text),
+            column: 0,
+            shift: Shift::Alpha,
+            program: None,
+            read_station: None,
+            auto_events: Vec::new(),
+        }
+    }
+
+    /// Get the card in the punch station
+    pub fn card(&self) -> &PunchCard {
+        &self.card
+    }
+
+    /// Get the zero-based column under the punch (the card width once the
+    /// last column has been punched)
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Get the keyboard shift for the current column
+    ///
+    /// Under program control this is the program card's shift.
+    pub fn shift(&self) -> Shift {
+        match &self.program {
+            Some(program) => program.shift_at(self.column),
+            None => self.shift,
+        }
+    }
+
+    /// Set the keyboard shift used when no program card is mounted
+    pub fn set_shift(&mut self, shift: Shift) {
+        self.shift = shift;
+    }
+
+    /// Get the program card on the drum
+    pub fn program(&self) -> Option<&ProgramCard> {
+        self.program.as_ref()
+    }
+
+    /// Mount or remove a program card
+    ///
+    /// If the card is at the first column of an automatic field, that field
+    /// is handled straight away.
+    pub fn set_program(&mut self, program: Option<ProgramCard>) {
+        self.program = program;
+        self.run_program();
+    }
+
+    /// Get the last released card, which sits in the read station
+    pub fn read_station(&self) -> Option<&PunchCard> {
+        self.read_station.as_ref()
+    }
+
+    /// Take the events for skips and duplication done by the program card
+    /// since the last call
+    pub fn take_auto_events(&mut self) -> Vec<KeyEvent> {
+        std::mem::take(&mut self.auto_events)
+    }
+
+    /// Key a character: punch its code and advance one column
+    ///
+    /// Lowercase letters are keyed as uppercase.
+    pub fn key_char(&mut self, c: char) -> KeyEvent {
+        let c = c.to_ascii_uppercase();
+        let Some(code) = char_to_hollerith(c) else {
+            return self.reject(Rejection::UnsupportedChar(c));
+        };
+        if self.shift() == Shift::Numeric && c.is_ascii_alphabetic() {
+            return self.reject(Rejection::WrongShift(c));
+        }
+        self.keystroke(code)
+    }
+
+    /// Punch several rows (12, 11, 0-9) into one column, then advance
+    ///
+    /// Like holding the MULT PCH key while keying each row.
+    pub fn key_multi_punch(&mut self, rows: &[u8]) -> KeyEvent {
+        if let Some(&row) = rows.iter().find(|&&row| !is_valid_row(row)) {
+            return self.reject(Rejection::InvalidRow(row));
+        }
+        self.keystroke(HollerithCode::new(rows.to_vec()))
+    }
+
+    /// Move back one column without punching
+    pub fn backspace(&mut self) -> KeyEvent {
+        if self.column == 0 {
+            return self.reject(Rejection::StartOfCard);
+        }
+        self.move_to(self.column - 1)
+    }
+
+    /// Advance to a later column (zero-based) without punching
+    pub fn skip_to(&mut self, column: usize) -> KeyEvent {
+        if column < self.column || column >= self.card.width().columns() {
+            return self.reject(Rejection::InvalidSkip(column));
+        }
+        self.move_to(column)
+    }
+
+    /// Duplicate up to `n` columns from a card in the read station
+    ///
+    /// The holes of each column of `source` at the current position are
+    /// punched and printed, and the card advances. Stops at the end of the card or at
+    /// the first rejected column; the last event explains why.
+    pub fn dup(&mut self, source: &PunchCard, n: usize) -> Vec<KeyEvent> {
+        let mut events = Vec::new();
+        for _ in 0..n {
+            let code = source
+                .get_column(self.column)
+                .map(|col| col.punches.clone())
+                .unwrap_or_else(HollerithCode::empty);
+            let event = self.punch(code);
+            let rejected = matches!(event, KeyEvent::Rejected { .. });
+            events.push(event);
+            if rejected {
+                break;
+            }
+        }
+        self.run_program();
+        events
+    }
+
+    /// Eject the card and feed a blank one at column 1
+    ///
+    /// A copy of the ejected card moves to the read station. The shift state
+    /// is kept.
+    pub fn release(&mut self) -> PunchCard {
+        self.column = 0;
+        let card = std::mem::replace(&mut self.card, PunchCard::new(CardType::Text));
+        self.read_station = Some(card.clone());
+        self.run_program();
+        card
+    }
+
+    /// Punch a keyed code, then let the program card act on the new column
+    fn keystroke(&mut self, code: HollerithCode) -> KeyEvent {
+        let event = self.punch(code);
+        if matches!(event, KeyEvent::Punched { .. }) {
+            self.run_program();
+        }
+        event
+    }
+
+    /// Skip or duplicate each automatic field starting at the current column
+    fn run_program(&mut self) {
+        loop {
+            let Some(field) = self
+                .program
+                .as_ref()
+                .and_then(|program| program.field_at(self.column))
+                .filter(|field| field.columns.start == self.column)
+                .cloned()
+            else {
+                return;
+            };
+            match field.action {
+                FieldAction::Manual => return,
+                FieldAction::Skip => {
+                    let event = self.move_to(field.columns.end);
+                    self.auto_events.push(event);
+                }
+                FieldAction::Dup => {
+                    for index in field.columns {
+                        let code = self
+                            .read_station
+                            .as_ref()
+                            .and_then(|card| card.get_column(index))
+                            .map(|col| col.punches.clone())
+                            .unwrap_or_else(HollerithCode::empty);
+                        let event = self.punch(code);
+                        let rejected = matches!(event, KeyEvent::Rejected { .. });
+                        self.auto_events.push(event);
+                        if rejected {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Add holes to the current column, reprint it, and advance
+    fn punch(&mut self, code: HollerithCode) -> KeyEvent {
+        let column = self.column;
+        let Some(existing) = self.card.get_column(column) else {
+            return self.reject(Rejection::EndOfCard);
+        };
+        let mut rows = existing.punches.rows.clone();
+        rows.extend(&code.rows);
+        let combined = HollerithCode::new(rows);
+        let printed = hollerith_to_char(&combined);
+        if self.card.set_column_hollerith(column, combined).is_err() {
+            return self.reject(Rejection::NotPunchable);
+        }
+        if let Some(col) = self.card.get_column_mut(column) {
+            col.set_printed_char(printed);
+        }
+        self.column += 1;
+        KeyEvent::Punched {
+            column,
+            rows: code.rows,
+            printed,
+        }
+    }
+
+    fn move_to(&mut self, column: usize) -> KeyEvent {
+        let from = self.column;
+        self.column = column;
+        KeyEvent::Moved { from, to: column }
+    }
+
+    fn reject(&self, reason: Rejection) -> KeyEvent {
+        KeyEvent::Rejected {
+            column: self.column,
+            reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program_card::ProgramCard;
+    use crate::punch_card::CardVariant;
+
+    fn key_text(keypunch: &mut Keypunch, text: &str) {
+        for c in text.chars() {
+            keypunch.key_char(c);
+        }
+    }
+
+    #[test]
+    fn test_key_char_punches_and_advances() {
+        let mut keypunch = Keypunch::new();
+        assert_eq!(
+            keypunch.key_char('a'),
+            KeyEvent::Punched {
+                column: 0,
+                rows: vec![1, 12],
+                printed: Some('A'),
+            }
+        );
+        key_text(&mut keypunch, "BC 1");
+        assert_eq!(keypunch.column(), 5);
+        assert!(keypunch.card().punches_eq(&PunchCard::from_text("ABC 1")));
+        assert_eq!(keypunch.card().to_text_trimmed(), "ABC 1");
+    }
+
+    #[test]
+    fn test_rejected_keys_do_not_advance() {
+        let mut keypunch = Keypunch::new();
+        assert_eq!(
+            keypunch.key_char('~'),
+            KeyEvent::Rejected {
+                column: 0,
+                reason: Rejection::UnsupportedChar('~'),
+            }
+        );
+        keypunch.set_shift(Shift::Numeric);
+        assert_eq!(
+            keypunch.key_char('X'),
+            KeyEvent::Rejected {
+                column: 0,
+                reason: Rejection::WrongShift('X'),
+            }
+        );
+        assert!(matches!(keypunch.key_char('7'), KeyEvent::Punched { .. }));
+        assert!(matches!(keypunch.key_char('$'), KeyEvent::Punched { .. }));
+        assert_eq!(keypunch.column(), 2);
+    }
+
+    #[test]
+    fn test_multi_punch_and_overpunch() {
+        let mut keypunch = Keypunch::new();
+        assert_eq!(
+            keypunch.key_multi_punch(&[12, 3, 8]),
+            KeyEvent::Punched {
+                column: 0,
+                rows: vec![3, 8, 12],
+                printed: Some('.'),
+            }
+        );
+        assert_eq!(
+            keypunch.key_multi_punch(&[13]),
+            KeyEvent::Rejected {
+                column: 1,
+                reason: Rejection::InvalidRow(13),
+            }
+        );
+
+        // Backspace and key 11 over a 5 to get N (11-5)
+        keypunch.key_char('5');
+        assert_eq!(keypunch.backspace(), KeyEvent::Moved { from: 2, to: 1 });
+        keypunch.key_char('-');
+        assert_eq!(keypunch.card().to_text_trimmed(), ".N");
+    }
+
+    #[test]
+    fn test_backspace_and_skip() {
+        let mut keypunch = Keypunch::new();
+        assert_eq!(
+            keypunch.backspace(),
+            KeyEvent::Rejected {
+                column: 0,
+                reason: Rejection::StartOfCard,
+            }
+        );
+        assert_eq!(keypunch.skip_to(9), KeyEvent::Moved { from: 0, to: 9 });
+        assert!(matches!(
+            keypunch.skip_to(3),
+            KeyEvent::Rejected {
+                reason: Rejection::InvalidSkip(3),
+                ..
+            }
+        ));
+        assert!(matches!(
+            keypunch.skip_to(80),
+            KeyEvent::Rejected {
+                reason: Rejection::InvalidSkip(80),
+                ..
+            }
+        ));
+        keypunch.key_char('X');
+        assert_eq!(keypunch.card().get_column(9).unwrap().to_char(), Some('X'));
+        assert!(keypunch.card().get_column(0).unwrap().is_blank());
+    }
+
+    #[test]
+    fn test_end_of_card() {
+        let mut keypunch = Keypunch::new();
+        keypunch.skip_to(79);
+        assert!(matches!(
+            keypunch.key_char('Z'),
+            KeyEvent::Punched { column: 79, .. }
+        ));
+        assert_eq!(keypunch.column(), 80);
+        assert_eq!(
+            keypunch.key_char('Z'),
+            KeyEvent::Rejected {
+                column: 80,
+                reason: Rejection::EndOfCard,
+            }
+        );
+        assert_eq!(keypunch.backspace(), KeyEvent::Moved { from: 80, to: 79 });
+    }
+
+    #[test]
+    fn test_dup_copies_from_source() {
+        let source = PunchCard::from_text("PAYROLL 1234");
+        let mut keypunch = Keypunch::new();
+        let events = keypunch.dup(&source, 8);
+        assert_eq!(events.len(), 8);
+        key_text(&mut keypunch, "5678");
+        assert_eq!(keypunch.card().to_text_trimmed(), "PAYROLL 5678");
+
+        keypunch.skip_to(78);
+        let events = keypunch.dup(&source, 5);
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            events[2],
+            KeyEvent::Rejected {
+                reason: Rejection::EndOfCard,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_release_feeds_blank_card() {
+        let mut keypunch = Keypunch::new();
+        keypunch.set_shift(Shift::Numeric);
+        key_text(&mut keypunch, "42");
+        let card = keypunch.release();
+        assert_eq!(card.to_text_trimmed(), "42");
+        assert_eq!(keypunch.column(), 0);
+        assert_eq!(keypunch.card().punched_count(), 0);
+        assert_eq!(keypunch.shift(), Shift::Numeric);
+    }
+
+    #[test]
+    fn test_port_a_punch_column_rejected() {
+        let mut keypunch = Keypunch::new();
+        keypunch.card.set_variant(CardVariant::PortAPunch);
+        keypunch.key_char('A');
+        assert_eq!(
+            keypunch.key_char('B'),
+            KeyEvent::Rejected {
+                column: 1,
+                reason: Rejection::NotPunchable,
+            }
+        );
+        assert!(matches!(keypunch.key_char(' '), KeyEvent::Punched { .. }));
+    }
+
+    #[test]
+    fn test_program_card_skips_and_dups() {
+        // Columns 1-5 dup (alpha), 6-10 numeric, 11-20 skip, 21-40 alpha
+        let program = ProgramCard::builder()
+            .field(0..5, FieldAction::Dup, Shift::Alpha)
+            .field(5..10, FieldAction::Manual, Shift::Numeric)
+            .field(10..20, FieldAction::Skip, Shift::Numeric)
+            .field(20..40, FieldAction::Manual, Shift::Alpha)
+            .build()
+            .unwrap();
+
+        // The first card is keyed by hand, then the program is mounted
+        let mut keypunch = Keypunch::new();
+        key_text(&mut keypunch, "DEPT1");
+        keypunch.release();
+        keypunch.set_program(Some(program));
+
+        // Mounting at column 1 duplicates the department from the read station
+        assert_eq!(keypunch.column(), 5);
+        assert_eq!(keypunch.take_auto_events().len(), 5);
+        assert_eq!(keypunch.shift(), Shift::Numeric);
+        assert!(matches!(
+            keypunch.key_char('A'),
+            KeyEvent::Rejected {
+                reason: Rejection::WrongShift('A'),
+                ..
+            }
+        ));
+
+        // Finishing the numeric field skips columns 11-20
+        key_text(&mut keypunch, "1234");
+        assert_eq!(keypunch.column(), 9);
+        assert!(keypunch.take_auto_events().is_empty());
+        keypunch.key_char('5');
+        assert_eq!(keypunch.column(), 20);
+        assert_eq!(
+            keypunch.take_auto_events(),
+            vec![KeyEvent::Moved { from: 10, to: 20 }]
+        );
+
+        assert_eq!(keypunch.shift(), Shift::Alpha);
+        key_text(&mut keypunch, "SMITH");
+        let card = keypunch.release();
+        assert_eq!(card.to_text_trimmed(), "DEPT112345          SMITH");
+
+        // The next card duplicates from the one just released
+        assert_eq!(keypunch.column(), 5);
+        assert_eq!(keypunch.card().to_text_trimmed(), "DEPT1");
+        assert_eq!(keypunch.read_station(), Some(&card));
+    }
+}
@@ -3,10 +3,38 @@
 // This library provides the core functionality for simulating IBM punch cards,
 // including Hollerith encoding, punch card data structures, and IBM 1130 format support.
 
+#[cfg(any(test, feature = "proptest"))]
+pub mod arbitrary;
+pub mod bcd;
+pub mod bitpack;
+pub mod card96;
+pub mod consistency;
+pub mod csv;
+pub mod deck;
 pub mod ebcdic;
+pub mod field;
+pub mod format;
 pub mod hollerith;
 pub mod ibm1130;
+pub mod interpreter;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod keypunch;
+pub mod metadata;
+pub mod plausibility;
+pub mod program_card;
 pub mod punch_card;
+pub mod remrand;
+pub mod render;
+pub mod reproducer;
+pub mod search;
+pub mod share;
+pub mod sixbit;
+pub mod stats;
+pub mod translate;
+pub mod verifier;
+
+pub use consistency::{Inconsistency, consistency_check};
 
 #[cfg(test)]
 mod tests {
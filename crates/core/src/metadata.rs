@@ -0,0 +1,74 @@
+// Card Metadata Module
+//
+// Physical attributes of a card that are not punched into it: a title, the
+// stock color, which corner is cut, and the pre-printed form. Metadata
+// travels with the JSON format only; binary and character formats ignore it.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Descriptive information about a physical card
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct CardMetadata {
+    /// Optional title, e.g. the deck or program the card belongs to
+    pub title: Option<String>,
+    /// Color of the card stock
+    pub stock: CardStock,
+    /// Which corner of the card is cut
+    pub corner_cut: CornerCut,
+    /// Pre-printed form on the card face
+    pub form: FormType,
+}
+
+impl CardMetadata {
+    /// Check if every field is at its default
+    pub fn is_default(&self) -> bool {
+        *self == CardMetadata::default()
+    }
+}
+
+/// Color of the card stock
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CardStock {
+    /// Standard cream/manila stock
+    #[default]
+    Manila,
+    White,
+    Blue,
+    Green,
+    Yellow,
+    Pink,
+    Salmon,
+}
+
+/// Which corner of the card is cut
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CornerCut {
+    None,
+    /// The usual cut, used to spot cards facing the wrong way in a deck
+    #[default]
+    UpperLeft,
+    UpperRight,
+}
+
+/// Pre-printed form on the card face
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FormType {
+    /// IBM 5081 general purpose card (digits 0-9 in every column)
+    #[default]
+    GeneralPurpose5081,
+    /// FORTRAN statement card (statement number, continuation, sequence fields)
+    Fortran,
+    /// COBOL coding card (sequence, indicator, area A/B fields)
+    Cobol,
+    /// IBM 1130 assembler coding card
+    Assembler,
+}
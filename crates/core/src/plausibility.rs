@@ -0,0 +1,207 @@
+// Plausibility Module
+//
+// Heuristic scoring of how much loaded data looks like a real punched text
+// card, so loaders can warn before showing arbitrary bytes as a card.
+
+use crate::format::CardFormat;
+use crate::hollerith::PunchClass;
+use crate::punch_card::PunchCard;
+use std::fmt;
+
+/// Measurements describing how card-like a punch pattern is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlausibilityReport {
+    /// Fraction (0.0-1.0) of punched columns that decode to a character
+    ///
+    /// A card with no punches scores 1.0.
+    pub decodable_fraction: f64,
+    /// Average punches per column over all 80 columns
+    pub avg_punches_per_column: f64,
+    /// Number of columns with all 12 rows punched
+    pub fully_laced_columns: usize,
+    /// Whether the sequence area (columns 73-80) is blank
+    ///
+    /// `None` when the card was loaded from a binary format, where columns
+    /// 73-80 are either not stored or carry no sequence field.
+    pub sequence_area_blank: Option<bool>,
+}
+
+/// Limits a report must meet to be considered a text card
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlausibilityThresholds {
+    /// Minimum decodable fraction
+    pub min_decodable_fraction: f64,
+    /// Maximum average punches per column (029 characters use at most 3)
+    pub max_avg_punches_per_column: f64,
+    /// Whether fully laced columns are acceptable
+    pub allow_fully_laced: bool,
+    /// Whether columns 73-80 must be blank
+    pub require_blank_sequence_area: bool,
+}
+
+impl Default for PlausibilityThresholds {
+    fn default() -> Self {
+        PlausibilityThresholds {
+            min_decodable_fraction: 0.9,
+            max_avg_punches_per_column: 3.0,
+            allow_fully_laced: false,
+            require_blank_sequence_area: false,
+        }
+    }
+}
+
+impl PlausibilityReport {
+    /// Check the report against thresholds
+    pub fn looks_like_text(&self, thresholds: &PlausibilityThresholds) -> bool {
+        self.decodable_fraction >= thresholds.min_decodable_fraction
+            && self.avg_punches_per_column <= thresholds.max_avg_punches_per_column
+            && (thresholds.allow_fully_laced || self.fully_laced_columns == 0)
+            && (!thresholds.require_blank_sequence_area || self.sequence_area_blank != Some(false))
+    }
+}
+
+impl fmt::Display for PlausibilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.0}% decodable, {:.1} punches/column",
+            self.decodable_fraction * 100.0,
+            self.avg_punches_per_column
+        )?;
+        if self.fully_laced_columns > 0 {
+            write!(f, ", {} laced columns", self.fully_laced_columns)?;
+        }
+        if self.sequence_area_blank == Some(false) {
+            write!(f, ", columns 73-80 punched")?;
+        }
+        Ok(())
+    }
+}
+
+impl PunchCard {
+    /// Score how much the card's punches look like a text card
+    pub fn plausibility_report(&self) -> PlausibilityReport {
+        let columns = self.columns();
+        let punched: Vec<PunchClass> = self
+            .classify_columns()
+            .into_iter()
+            .filter(|class| *class != PunchClass::Blank)
+            .collect();
+        let decodable = punched.iter().filter(|class| class.is_character()).count();
+        let total_punches: usize = columns.iter().map(|col| col.punches.rows.len()).sum();
+
+        PlausibilityReport {
+            decodable_fraction: if punched.is_empty() {
+                1.0
+            } else {
+                decodable as f64 / punched.len() as f64
+            },
+            avg_punches_per_column: total_punches as f64 / columns.len() as f64,
+            fully_laced_columns: columns
+                .iter()
+                .filter(|col| col.punches.as_array().iter().all(|&p| p))
+                .count(),
+            sequence_area_blank: Some(
+                columns
+                    .iter()
+                    .skip(72)
+                    .all(|col| col.punches.rows.is_empty()),
+            ),
+        }
+    }
+
+    /// Score the card as loaded from `format`
+    ///
+    /// The sequence area check is skipped for binary formats.
+    pub fn plausibility_report_for(&self, format: CardFormat) -> PlausibilityReport {
+        let mut report = self.plausibility_report();
+        if format.is_binary() {
+            report.sequence_area_blank = None;
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_card_is_plausible() {
+        let report =
+            PunchCard::from_text("      LD   L  VALUE  LOAD THE VALUE").plausibility_report();
+        assert_eq!(report.decodable_fraction, 1.0);
+        assert_eq!(report.fully_laced_columns, 0);
+        assert_eq!(report.sequence_area_blank, Some(true));
+        assert!(report.looks_like_text(&PlausibilityThresholds::default()));
+    }
+
+    #[test]
+    fn test_random_binary_is_implausible() {
+        // Pseudo-random bytes, as from a file that isn't a card image
+        let mut seed = 0x1130u32;
+        let data: Vec<u8> = (0..108)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (seed >> 16) as u8
+            })
+            .collect();
+        let report = PunchCard::from_binary(&data).plausibility_report();
+        assert!(report.decodable_fraction < 0.5);
+        assert!(report.avg_punches_per_column > 3.0);
+        assert!(!report.looks_like_text(&PlausibilityThresholds::default()));
+        assert!(report.to_string().contains("% decodable"));
+    }
+
+    #[test]
+    fn test_laced_and_sequence_thresholds() {
+        let mut card = PunchCard::from_text("TEXT");
+        card.set_column_hollerith(
+            79,
+            crate::hollerith::HollerithCode::new(vec![12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]),
+        )
+        .unwrap();
+        let report = card.plausibility_report();
+        assert_eq!(report.fully_laced_columns, 1);
+        assert_eq!(report.sequence_area_blank, Some(false));
+
+        let lenient = PlausibilityThresholds {
+            min_decodable_fraction: 0.0,
+            allow_fully_laced: true,
+            ..PlausibilityThresholds::default()
+        };
+        assert!(report.looks_like_text(&lenient));
+        assert!(!report.looks_like_text(&PlausibilityThresholds {
+            require_blank_sequence_area: true,
+            ..lenient
+        }));
+        assert!(!report.looks_like_text(&PlausibilityThresholds::default()));
+    }
+
+    #[test]
+    fn test_blank_card_is_plausible() {
+        let report = PunchCard::from_text("").plausibility_report();
+        assert_eq!(report.decodable_fraction, 1.0);
+        assert_eq!(report.avg_punches_per_column, 0.0);
+        assert_eq!(report.to_string(), "100% decodable, 0.0 punches/column");
+    }
+
+    #[test]
+    fn test_sequence_area_not_applicable_for_binary() {
+        let card = PunchCard::from_binary(&[0u8; 108]);
+        let report = card.plausibility_report_for(CardFormat::Ibm1130Binary108);
+        assert_eq!(report.sequence_area_blank, None);
+        assert!(report.looks_like_text(&PlausibilityThresholds {
+            require_blank_sequence_area: true,
+            ..PlausibilityThresholds::default()
+        }));
+
+        let mut card = PunchCard::from_text("TEXT");
+        card.set_column_char(79, '1').unwrap();
+        let text = card.plausibility_report_for(CardFormat::AsciiRecord);
+        assert_eq!(text.sequence_area_blank, Some(false));
+        let binary = card.plausibility_report_for(CardFormat::FullBinary120);
+        assert_eq!(binary.sequence_area_blank, None);
+        assert!(!binary.to_string().contains("columns 73-80"));
+    }
+}
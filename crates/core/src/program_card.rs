@@ -0,0 +1,275 @@
+// Program Card Module
+//
+// The 029 keypunch program card, wrapped on the program drum, divides the
+// card into fields and tells the keypunch what to do in each one. Only the
+// program 1 rows are used:
+//
+// - 12: field definition, punched in every column of a field except the first
+// - 11: start automatic skip (first column of a field)
+// - 0: start automatic duplication (first column of a field)
+// - 1: alphabetic shift for this column (columns default to numeric shift)
+//
+// A column without a 12 punch starts a new field, so unprogrammed columns are
+// one-column manual fields.
+
+use crate::hollerith::HollerithCode;
+use crate::keypunch::Shift;
+use crate::punch_card::{CardType, PunchCard};
+use std::ops::Range;
+
+/// What the keypunch does on reaching the first column of a field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldAction {
+    /// The operator keys the field
+    #[default]
+    Manual,
+    /// The field is skipped
+    Skip,
+    /// The field is duplicated from the card in the read station
+    Dup,
+}
+
+/// A field defined by a program card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramField {
+    /// Zero-based columns of the field
+    pub columns: Range<usize>,
+    /// What happens on reaching the first column
+    pub action: FieldAction,
+}
+
+/// A parsed program card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramCard {
+    /// Fields in column order, covering all 80 columns
+    fields: Vec<ProgramField>,
+    /// Columns in alphabetic shift
+    alpha: [bool; 80],
+}
+
+impl ProgramCard {
+    /// Read a program card from its punches
+    ///
+    /// Fails if the first column of a field has both an 11 (skip) and a 0
+    /// (dup) punch.
+    pub fn from_card(card: &PunchCard) -> Result<Self, String> {
+        let mut fields: Vec<ProgramField> = Vec::new();
+        let mut alpha = [false; 80];
+        for (index, column) in card.columns_array().iter().enumerate() {
+            let punches = &column.punches;
+            alpha[index] = punches.is_punched(1);
+            if index > 0 && punches.is_punched(12) {
+                if let Some(field) = fields.last_mut() {
+                    field.columns.end = index + 1;
+                }
+                continue;
+            }
+            let action = match (punches.is_punched(11), punches.is_punched(0)) {
+                (true, true) => {
+                    return Err(format!(
+                        "Column {}: both auto-skip (11) and auto-dup (0) punched",
+                        index + 1
+                    ));
+                }
+                (true, false) => FieldAction::Skip,
+                (false, true) => FieldAction::Dup,
+                (false, false) => FieldAction::Manual,
+            };
+            fields.push(ProgramField {
+                columns: index..index + 1,
+                action,
+            });
+        }
+        Ok(ProgramCard { fields, alpha })
+    }
+
+    /// Start defining a program card field by field
+    pub fn builder() -> ProgramCardBuilder {
+        ProgramCardBuilder::default()
+    }
+
+    /// Punch the program card that defines these fields
+    pub fn to_card(&self) -> PunchCard {
+        let mut card = PunchCard::new(CardType::Binary);
+        for field in &self.fields {
+            for index in field.columns.clone() {
+                let mut rows = Vec::new();
+                if index == field.columns.start {
+                    match field.action {
+                        FieldAction::Manual => {}
+                        FieldAction::Skip => rows.push(11),
+                        FieldAction::Dup => rows.push(0),
+                    }
+                } else {
+                    rows.push(12);
+                }
+                if self.alpha[index] {
+                    rows.push(1);
+                }
+                card.set_column_hollerith(index, HollerithCode::new(rows))
+                    .expect("program fields lie within 80 columns");
+            }
+        }
+        card
+    }
+
+    /// Get all fields in column order
+    pub fn fields(&self) -> &[ProgramField] {
+        &self.fields
+    }
+
+    /// Get the field containing a column (0-based)
+    pub fn field_at(&self, index: usize) -> Option<&ProgramField> {
+        self.fields
+            .iter()
+            .find(|field| field.columns.contains(&index))
+    }
+
+    /// Get the keyboard shift for a column (0-based)
+    pub fn shift_at(&self, index: usize) -> Shift {
+        if self.alpha.get(index).copied().unwrap_or(false) {
+            Shift::Alpha
+        } else {
+            Shift::Numeric
+        }
+    }
+}
+
+/// Builds a `ProgramCard` from field definitions
+///
+/// Columns not covered by any field become one-column manual fields in
+/// numeric shift, as on a real program card.
+#[derive(Debug, Clone, Default)]
+pub struct ProgramCardBuilder {
+    fields: Vec<(Range<usize>, FieldAction, Shift)>,
+}
+
+impl ProgramCardBuilder {
+    /// Define a field over zero-based columns
+    pub fn field(mut self, columns: Range<usize>, action: FieldAction, shift: Shift) -> Self {
+        self.fields.push((columns, action, shift));
+        self
+    }
+
+    /// Build the program card
+    ///
+    /// Fails if a field is empty, extends past column 80, or overlaps
+    /// another field.
+    pub fn build(mut self) -> Result<ProgramCard, String> {
+        self.fields.sort_by_key(|(columns, _, _)| columns.start);
+        let mut fields = Vec::new();
+        let mut alpha = [false; 80];
+        let mut next = 0;
+        for (columns, action, shift) in self.fields {
+            if columns.is_empty() || columns.end > 80 {
+                return Err(format!(
+                    "Invalid field columns {}..{}",
+                    columns.start, columns.end
+                ));
+            }
+            if columns.start < next {
+                return Err(format!(
+                    "Field at columns {}..{} overlaps the previous field",
+                    columns.start, columns.end
+                ));
+            }
+            fields.extend((next..columns.start).map(|index| ProgramField {
+                columns: index..index + 1,
+                action: FieldAction::Manual,
+            }));
+            alpha[columns.clone()].fill(shift == Shift::Alpha);
+            next = columns.end;
+            fields.push(ProgramField { columns, action });
+        }
+        fields.extend((next..80).map(|index| ProgramField {
+            columns: index..index + 1,
+            action: FieldAction::Manual,
+        }));
+        Ok(ProgramCard { fields, alpha })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payroll_program() -> ProgramCard {
+        ProgramCard::builder()
+            .field(0..5, FieldAction::Dup, Shift::Alpha)
+            .field(5..10, FieldAction::Manual, Shift::Numeric)
+            .field(10..20, FieldAction::Skip, Shift::Numeric)
+            .field(20..40, FieldAction::Manual, Shift::Alpha)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_builder_fills_gaps() {
+        let program = payroll_program();
+        assert_eq!(program.fields().len(), 4 + 40);
+        assert_eq!(program.field_at(7).unwrap().columns, 5..10);
+        assert_eq!(program.field_at(12).unwrap().action, FieldAction::Skip);
+        assert_eq!(program.field_at(50).unwrap().columns, 50..51);
+        assert_eq!(program.shift_at(0), Shift::Alpha);
+        assert_eq!(program.shift_at(5), Shift::Numeric);
+        assert_eq!(program.shift_at(79), Shift::Numeric);
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let overlap = ProgramCard::builder()
+            .field(0..5, FieldAction::Manual, Shift::Alpha)
+            .field(4..8, FieldAction::Skip, Shift::Alpha)
+            .build();
+        assert!(overlap.is_err());
+        let past_end = ProgramCard::builder()
+            .field(70..81, FieldAction::Manual, Shift::Alpha)
+            .build();
+        assert!(past_end.is_err());
+        let empty = ProgramCard::builder()
+            .field(3..3, FieldAction::Manual, Shift::Alpha)
+            .build();
+        assert!(empty.is_err());
+    }
+
+    #[test]
+    fn test_control_punches() {
+        let card = payroll_program().to_card();
+        // Dup field start: 0 and 1 (alpha); continuation: 12 and 1
+        assert_eq!(card.get_column(0).unwrap().punches.rows, vec![0, 1]);
+        assert_eq!(card.get_column(1).unwrap().punches.rows, vec![1, 12]);
+        // Numeric manual field: start blank, then 12
+        assert!(card.get_column(5).unwrap().is_blank());
+        assert_eq!(card.get_column(6).unwrap().punches.rows, vec![12]);
+        // Skip field start
+        assert_eq!(card.get_column(10).unwrap().punches.rows, vec![11]);
+        assert!(card.get_column(79).unwrap().is_blank());
+    }
+
+    #[test]
+    fn test_card_roundtrip() {
+        let program = payroll_program();
+        assert_eq!(ProgramCard::from_card(&program.to_card()).unwrap(), program);
+
+        let blank = ProgramCard::from_card(&PunchCard::new(CardType::Binary)).unwrap();
+        assert_eq!(blank.fields().len(), 80);
+        assert!(
+            blank
+                .fields()
+                .iter()
+                .all(|f| f.action == FieldAction::Manual)
+        );
+    }
+
+    #[test]
+    fn test_skip_and_dup_conflict() {
+        let mut card = PunchCard::new(CardType::Binary);
+        card.set_column_hollerith(3, HollerithCode::new(vec![11, 0]))
+            .unwrap();
+        assert!(
+            ProgramCard::from_card(&card)
+                .unwrap_err()
+                .contains("Column 4")
+        );
+    }
+}
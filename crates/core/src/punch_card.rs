@@ -2,12 +2,30 @@
 //
 // Data structures and operations for IBM punch cards
 
-use crate::ebcdic::{ebcdic_to_hollerith, hollerith_to_ebcdic};
-use crate::hollerith::{HollerithCode, char_to_hollerith, hollerith_to_char};
-use serde::{Deserialize, Serialize};
+use crate::bcd::{BcdicExportError, bcdic_to_hollerith, hollerith_to_bcdic};
+use crate::bitpack;
+use crate::ebcdic::{
+    self, CodePage, ControlByte, ControlPolicy, EbcdicExportError, EbcdicLoadOptions,
+    ebcdic_to_char, ebcdic_to_hollerith, hollerith_to_ebcdic,
+};
+use crate::format::CardFormat;
+use crate::hollerith::{
+    BitOrder, CharacterSet, Charset, DecodeFailure, DecodeOptions, DecodeReport, HollerithCode,
+    PunchClass, SorterKey, SubstitutionMap, SubstitutionReport, char_to_hollerith,
+    decode_codes_with, encode_string_with_substitutions, hollerith_to_char,
+    hollerith_to_char_detailed, is_valid_row,
+};
+use crate::metadata::CardMetadata;
+#[cfg(feature = "serde")]
+use serde::de::Error as _;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
 
 /// Represents a single column on a punch card
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Column {
     /// The punch pattern for this column
     pub punches: HollerithCode,
@@ -34,6 +52,17 @@ impl Column {
         }
     }
 
+    /// Create a column from a character in the given character set
+    ///
+    /// Lowercase is kept if the set has it; otherwise this is `from_char`.
+    pub fn from_char_with(c: char, charset: &dyn CharacterSet) -> Self {
+        let keyed = charset.keyed_char(c);
+        Column {
+            punches: charset.encode(keyed).unwrap_or_else(HollerithCode::empty),
+            printed_char: Some(keyed),
+        }
+    }
+
     /// Create a column from a Hollerith code (binary mode, no printing)
     pub fn from_hollerith(code: HollerithCode) -> Self {
         Column {
@@ -42,15 +71,68 @@ impl Column {
         }
     }
 
+    /// Set or remove the printed character without changing the punches
+    ///
+    /// Real cards could be printed with something other than what was
+    /// punched, or not printed at all.
+    pub fn set_printed_char(&mut self, printed_char: Option<char>) {
+        self.printed_char = printed_char;
+    }
+
     /// Get the character representation of this column
     pub fn to_char(&self) -> Option<char> {
         hollerith_to_char(&self.punches)
     }
 
+    /// Get the character of this column, or why it has none
+    pub fn to_char_detailed(&self) -> Result<char, DecodeFailure> {
+        hollerith_to_char_detailed(&self.punches)
+    }
+
     /// Check if this column is blank (no punches)
     pub fn is_blank(&self) -> bool {
         self.punches.rows.is_empty()
     }
+
+    /// Check if two columns have the same holes, ignoring printed characters
+    pub fn punches_eq(&self, other: &Column) -> bool {
+        self.punches == other.punches
+    }
+
+    /// Punch a hole in the given row (12, 11, 0-9)
+    ///
+    /// Punching an already punched row has no effect. If the column has a
+    /// printed character it is re-derived from the new pattern, and cleared
+    /// if the pattern no longer maps to a character.
+    pub fn add_punch(&mut self, row: u8) -> Result<(), &'static str> {
+        if !is_valid_row(row) {
+            return Err("Row must be 12, 11, or 0-9");
+        }
+        let mut rows = self.punches.rows.clone();
+        rows.push(row);
+        self.punches = HollerithCode::new(rows);
+        self.refresh_printed_char();
+        Ok(())
+    }
+
+    /// Remove the hole in the given row (12, 11, 0-9), if punched
+    ///
+    /// The printed character is updated the same way as for `add_punch`.
+    pub fn remove_punch(&mut self, row: u8) -> Result<(), &'static str> {
+        if !is_valid_row(row) {
+            return Err("Row must be 12, 11, or 0-9");
+        }
+        self.punches.rows.retain(|&r| r != row);
+        self.refresh_printed_char();
+        Ok(())
+    }
+
+    /// Re-derive the printed character of a printing column
+    fn refresh_printed_char(&mut self) {
+        if self.printed_char.is_some() {
+            self.printed_char = self.to_char();
+        }
+    }
 }
 
 impl Default for Column {
@@ -60,7 +142,8 @@ impl Default for Column {
 }
 
 /// Type of punch card
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CardType {
     /// Text card with character printing (IBM 029 mode)
     Text,
@@ -68,22 +151,233 @@ pub enum CardType {
     Binary,
 }
 
-/// Represents a complete 80-column punch card
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Physical variant of the card stock, which can restrict where holes go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CardVariant {
+    /// Every column can be punched
+    #[default]
+    Standard,
+    /// Port-A-Punch: only the pre-scored odd-numbered columns (1, 3, ..., 79)
+    /// can be punched, by pushing the scored chads out with a stylus
+    PortAPunch,
+}
+
+impl CardVariant {
+    /// Human-readable name of the variant
+    pub fn name(self) -> &'static str {
+        match self {
+            CardVariant::Standard => "standard",
+            CardVariant::PortAPunch => "Port-A-Punch",
+        }
+    }
+
+    /// Check if a column (0-based) can be punched on this variant
+    pub fn allows_column(self, index: usize) -> bool {
+        match self {
+            CardVariant::Standard => true,
+            CardVariant::PortAPunch => index.is_multiple_of(2),
+        }
+    }
+}
+
+/// How to handle characters that have no punch pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum UnsupportedCharPolicy {
+    /// Leave the column blank (keypunch behavior for unknown keys)
+    Blank,
+    /// Fail with an error naming the column and character
+    Error,
+    /// Punch the given replacement character instead
+    Replace(ReplacementChar),
+}
+
+/// A character with a punch pattern, to punch in place of one without
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "char"))]
+pub struct ReplacementChar(char);
+
+impl ReplacementChar {
+    /// Check the character (uppercased) has a punch pattern
+    pub fn new(c: char) -> Option<Self> {
+        let c = c.to_ascii_uppercase();
+        char_to_hollerith(c).is_some().then_some(ReplacementChar(c))
+    }
+
+    /// Get the character
+    pub fn get(self) -> char {
+        self.0
+    }
+}
+
+impl TryFrom<char> for ReplacementChar {
+    type Error = String;
+
+    /// Check the character has a punch pattern, as `new` does
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        ReplacementChar::new(c)
+            .ok_or_else(|| format!("Replacement character {:?} has no punch pattern", c))
+    }
+}
+
+/// Number of columns on a card
+///
+/// Standard cards have 80 columns. Stub cards, such as the 51-column cards
+/// used for billing and transit, are the first columns of a standard card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u8"))]
+pub struct CardWidth(u8);
+
+impl CardWidth {
+    /// The standard 80-column card
+    pub const STANDARD: CardWidth = CardWidth(80);
+    /// The 51-column stub card
+    pub const STUB_51: CardWidth = CardWidth(51);
+
+    /// Create a width of 1-80 columns
+    pub fn new(columns: usize) -> Option<Self> {
+        (1..=80)
+            .contains(&columns)
+            .then_some(CardWidth(columns as u8))
+    }
+
+    /// Get the number of columns
+    pub fn columns(self) -> usize {
+        usize::from(self.0)
+    }
+}
+
+impl TryFrom<u8> for CardWidth {
+    type Error = String;
+
+    /// Check the width is 1-80 columns, as `new` does
+    fn try_from(columns: u8) -> Result<Self, Self::Error> {
+        CardWidth::new(usize::from(columns))
+            .ok_or_else(|| format!("Card width must be 1-80 columns, got {}", columns))
+    }
+}
+
+impl Default for CardWidth {
+    fn default() -> Self {
+        CardWidth::STANDARD
+    }
+}
+
+/// Represents a complete 80-column punch card (or a narrower stub card)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PunchCard {
-    /// The 80 columns of the card
-    columns: Vec<Column>,
+    /// The 80 column positions; stub cards leave those past `width` blank
+    columns: [Column; 80],
+    /// Number of columns in use (80, or fewer for stub cards)
+    width: CardWidth,
     /// The type of card (text or binary)
     card_type: CardType,
+    /// Physical attributes (stock, corner cut, form); not punched
+    metadata: CardMetadata,
+    /// Card variant restricting which columns can be punched
+    variant: CardVariant,
+}
+
+/// Serialized form of a card, borrowed for writing
+///
+/// Columns are written as a list of the card's width, the same shape used
+/// before cards switched to fixed-size storage.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct PunchCardRef<'a> {
+    columns: &'a [Column],
+    card_type: CardType,
+    metadata: &'a CardMetadata,
+    variant: CardVariant,
+}
+
+/// Serialized form of a card, owned for reading
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct PunchCardRepr {
+    columns: Vec<Column>,
+    card_type: CardType,
+    #[serde(default)]
+    metadata: CardMetadata,
+    #[serde(default)]
+    variant: CardVariant,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PunchCard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PunchCardRef {
+            columns: self.columns(),
+            card_type: self.card_type,
+            metadata: &self.metadata,
+            variant: self.variant,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PunchCard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = PunchCardRepr::deserialize(deserializer)?;
+        let width = CardWidth::new(repr.columns.len())
+            .ok_or_else(|| D::Error::invalid_length(repr.columns.len(), &"1 to 80 columns"))?;
+        let mut card = PunchCard::new(repr.card_type).with_width(width);
+        for (dest, column) in card.columns.iter_mut().zip(repr.columns) {
+            *dest = column;
+        }
+        card.metadata = repr.metadata;
+        card.variant = repr.variant;
+        Ok(card)
+    }
 }
 
 impl PunchCard {
     /// Create a new blank punch card
     pub fn new(card_type: CardType) -> Self {
         PunchCard {
-            columns: vec![Column::new(); 80],
+            columns: std::array::from_fn(|_| Column::new()),
+            width: CardWidth::STANDARD,
             card_type,
+            metadata: CardMetadata::default(),
+            variant: CardVariant::Standard,
+        }
+    }
+
+    /// Create a blank text card with fewer than 80 columns (e.g. 51)
+    ///
+    /// Fails if the width is outside 1-80.
+    pub fn new_with_width(width: usize) -> Result<Self, &'static str> {
+        let width = CardWidth::new(width).ok_or("Card width must be 1-80 columns")?;
+        Ok(PunchCard::new(CardType::Text).with_width(width))
+    }
+
+    /// Create a text card of the given width from a string
+    ///
+    /// Text beyond the card's last column is dropped, as with `from_text`.
+    pub fn from_text_with_width(text: &str, width: usize) -> Result<Self, &'static str> {
+        let mut card = PunchCard::new_with_width(width)?;
+        for (column, c) in card.columns_mut().iter_mut().zip(text.chars()) {
+            *column = Column::from_char(c);
+        }
+        Ok(card)
+    }
+
+    /// Create a text card of the given variant from a string
+    ///
+    /// Characters are punched into consecutive allowed columns, skipping
+    /// columns the variant cannot punch; text that does not fit is dropped.
+    pub fn from_text_with_variant(text: &str, variant: CardVariant) -> Self {
+        let mut card = PunchCard::new(CardType::Text);
+        card.variant = variant;
+        let allowed = card.allowed_columns();
+        for (index, c) in allowed.into_iter().zip(text.chars()) {
+            card.columns[index] = Column::from_char(c);
         }
+        card
     }
 
     /// Create a text card from a string (max 80 characters)
@@ -95,6 +389,90 @@ impl PunchCard {
         card
     }
 
+    /// Create a text card from a string punched in the given character set
+    ///
+    /// Like `from_text`, characters the set lacks are printed but not punched.
+    /// Lowercase is kept in sets that have it, such as `Charset::AnsiX326`.
+    pub fn from_text_with_charset(text: &str, charset: Charset) -> Self {
+        Self::from_text_with(text, &charset)
+    }
+
+    /// Create a text card from a string punched in any character set
+    ///
+    /// Characters are uppercased unless the set has them as given (see
+    /// `CharacterSet::keyed_char`); characters the set lacks are printed but
+    /// not punched.
+    pub fn from_text_with(text: &str, charset: &dyn CharacterSet) -> Self {
+        let mut card = PunchCard::new(CardType::Text);
+        for (i, c) in text.chars().take(80).enumerate() {
+            card.columns[i] = Column::from_char_with(c, charset);
+        }
+        card
+    }
+
+    /// Create a text card from a string, punching substitutes from `map`
+    /// (see `encode_string_with_substitutions`)
+    ///
+    /// Columns print the character that was punched. Returns the report of
+    /// substituted and unpunchable characters among the first 80.
+    pub fn from_text_with_substitutions(
+        text: &str,
+        map: &SubstitutionMap,
+    ) -> (Self, SubstitutionReport) {
+        let end = text.char_indices().nth(80).map_or(text.len(), |(i, _)| i);
+        let text = &text[..end];
+        let (codes, report) = encode_string_with_substitutions(text, map);
+        let mut card = PunchCard::new(CardType::Text);
+        for ((column, punches), c) in card.columns.iter_mut().zip(codes).zip(text.chars()) {
+            *column = Column {
+                punches,
+                printed_char: Some(map.get(c).unwrap_or(c).to_ascii_uppercase()),
+            };
+        }
+        (card, report)
+    }
+
+    /// Create a text card from an ASCII card-image record (one line of a deck file)
+    ///
+    /// The line is truncated to 80 columns (shorter lines leave the remaining
+    /// columns blank) and uppercased as the 029 did. Characters with no punch
+    /// pattern are handled according to `policy`.
+    pub fn from_ascii_record(line: &str, policy: UnsupportedCharPolicy) -> Result<Self, String> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let mut card = PunchCard::new(CardType::Text);
+        for (i, c) in line.chars().take(80).enumerate() {
+            let upper_c = c.to_ascii_uppercase();
+            card.columns[i] = if char_to_hollerith(upper_c).is_some() {
+                Column::from_char(upper_c)
+            } else {
+                match policy {
+                    UnsupportedCharPolicy::Blank => Column::from_char(' '),
+                    UnsupportedCharPolicy::Error => {
+                        return Err(format!(
+                            "Column {}: character {:?} has no punch pattern",
+                            i + 1,
+                            c
+                        ));
+                    }
+                    UnsupportedCharPolicy::Replace(r) => Column::from_char(r.get()),
+                }
+            };
+        }
+        Ok(card)
+    }
+
+    /// Convert the card to an ASCII card-image record
+    ///
+    /// Always returns exactly 80 characters. Columns that do not decode to
+    /// an ASCII character are written as `?`.
+    pub fn to_ascii_record(&self) -> String {
+        // Stub cards are padded with the blank columns past their width
+        self.columns
+            .iter()
+            .map(|col| col.to_char().filter(char::is_ascii).unwrap_or('?'))
+            .collect()
+    }
+
     /// Create a card from raw bytes
     ///
     /// Supports two formats:
@@ -109,19 +487,8 @@ impl PunchCard {
         if data.len() == 108 {
             // IBM 1130 binary format: 108 bytes = 864 bits for columns 1-72
             // Unpack 108 bytes into 864 bits (72 columns × 12 rows each)
-            let mut bit_idx = 0;
-            for col_idx in 0..72 {
-                let mut punch_array = [false; 12];
-                for punch in &mut punch_array {
-                    let byte_idx = bit_idx / 8;
-                    let bit_in_byte = bit_idx % 8;
-                    if byte_idx < data.len() {
-                        *punch = (data[byte_idx] & (1 << bit_in_byte)) != 0;
-                    }
-                    bit_idx += 1;
-                }
-                card.columns[col_idx] =
-                    Column::from_hollerith(HollerithCode::from_array(punch_array));
+            for (col_idx, column) in card.columns[..72].iter_mut().enumerate() {
+                *column = Column::from_hollerith(bitpack::unpack_column(data, col_idx));
             }
             // Columns 73-80 remain blank (default Column::new())
         } else {
@@ -146,45 +513,86 @@ impl PunchCard {
     ///
     /// Array layout: [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
     pub fn to_binary(&self) -> Vec<u8> {
-        let mut data = Vec::with_capacity(108);
+        let mut data = [0u8; 108];
+        self.to_binary_into(&mut data);
+        data.to_vec()
+    }
 
-        // Pack 72 columns × 12 rows = 864 bits into 108 bytes
-        let mut bit_buffer: Vec<bool> = Vec::with_capacity(864);
-        for i in 0..72 {
-            let punches = self.columns[i].punches.as_array();
-            for &is_punched in punches.iter() {
-                bit_buffer.push(is_punched);
-            }
-        }
+    /// Pack the card into IBM 1130 binary format in a caller-provided buffer
+    ///
+    /// Same layout as `to_binary` (see `bitpack`), without any heap
+    /// allocation.
+    pub fn to_binary_into(&self, out: &mut [u8; 108]) {
+        bitpack::pack_into(self.columns[..72].iter().map(|col| &col.punches), out);
+    }
 
-        // Convert bits to bytes (8 bits per byte)
-        for byte_idx in 0..108 {
-            let mut byte_val: u8 = 0;
-            for bit_in_byte in 0..8 {
-                let bit_idx = byte_idx * 8 + bit_in_byte;
-                if bit_idx < bit_buffer.len() && bit_buffer[bit_idx] {
-                    byte_val |= 1 << bit_in_byte;
-                }
-            }
-            data.push(byte_val);
-        }
+    /// Classify every column (see `HollerithCode::classify`)
+    pub fn classify_columns(&self) -> Vec<PunchClass> {
+        self.columns()
+            .iter()
+            .map(|col| col.punches.classify())
+            .collect()
+    }
+
+    /// Get the card sorter key of a column (0-based)
+    ///
+    /// Columns past the card's width read as blank.
+    pub fn column_sort_key(&self, col: usize) -> SorterKey {
+        self.columns().get(col).map_or_else(
+            || HollerithCode::empty().sorter_key(),
+            |c| c.punches.sorter_key(),
+        )
+    }
+
+    /// Get every column as a 12-bit integer, row 12 high (see
+    /// `BitOrder::Row12High`)
+    pub fn to_u16_columns(&self) -> [u16; 80] {
+        std::array::from_fn(|index| self.columns[index].punches.to_u16(BitOrder::Row12High))
+    }
 
-        data
+    /// Create a binary card from 12-bit column integers, row 12 high
+    ///
+    /// Bits 12-15 are ignored. Values past column 80 are ignored and
+    /// missing columns are blank.
+    pub fn from_u16_columns(columns: &[u16]) -> Self {
+        let mut card = PunchCard::new(CardType::Binary);
+        for (column, &value) in card.columns.iter_mut().zip(columns) {
+            *column = Column::from_hollerith(HollerithCode::from_u16(value, BitOrder::Row12High));
+        }
+        card
     }
 
     /// Convert the card to EBCDIC format (80 bytes = 1 byte per column)
     /// Standard format for IBM punch card data interchange
     ///
-    /// Each column's Hollerith punch pattern is converted to its EBCDIC character code
-    pub fn to_ebcdic(&self) -> Vec<u8> {
-        let mut data = Vec::with_capacity(80);
-
-        for column in &self.columns {
-            let ebcdic_byte = hollerith_to_ebcdic(&column.punches);
-            data.push(ebcdic_byte);
+    /// Each column's Hollerith punch pattern is converted to its EBCDIC character code.
+    /// Fails, listing the columns, if any pattern has no EBCDIC byte.
+    pub fn to_ebcdic(&self) -> Result<Vec<u8>, EbcdicExportError> {
+        let data = self.to_ebcdic_lossy();
+        let columns: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| hollerith_to_ebcdic(&column.punches).is_err())
+            .map(|(index, _)| index)
+            .collect();
+        if columns.is_empty() {
+            Ok(data)
+        } else {
+            Err(EbcdicExportError { columns })
         }
+    }
 
-        data
+    /// Convert the card to EBCDIC, writing unmapped patterns as blanks
+    ///
+    /// Like `to_ebcdic`, but punches with no EBCDIC byte are silently
+    /// written as 0x40.
+    pub fn to_ebcdic_lossy(&self) -> Vec<u8> {
+        // Stub cards are padded with the blank columns past their width
+        self.columns
+            .iter()
+            .map(|column| hollerith_to_ebcdic(&column.punches).unwrap_or(0x40))
+            .collect()
     }
 
     /// Create a card from EBCDIC format (80 bytes = 1 byte per column)
@@ -194,74 +602,376 @@ impl PunchCard {
         let mut card = PunchCard::new(CardType::Text);
 
         for (i, &ebcdic_byte) in data.iter().take(80).enumerate() {
-            let hollerith = ebcdic_to_hollerith(ebcdic_byte);
-            // Determine the printed character from the EBCDIC code
-            let printed_char = match ebcdic_byte {
-                0x40 => Some(' '),
-                0xF0..=0xF9 => Some((b'0' + (ebcdic_byte - 0xF0)) as char),
-                0xC1..=0xC9 => Some((b'A' + (ebcdic_byte - 0xC1)) as char),
-                0xD1..=0xD9 => Some((b'J' + (ebcdic_byte - 0xD1)) as char),
-                0xE2..=0xE9 => Some((b'S' + (ebcdic_byte - 0xE2)) as char),
-                _ => None,
+            // Print whatever the punches decode to, as an interpreter would
+            card.columns[i] = Column {
+                punches: ebcdic_to_hollerith(ebcdic_byte),
+                printed_char: ebcdic_to_char(ebcdic_byte),
+            };
+        }
+
+        card
+    }
+
+    /// Convert the card to EBCDIC in a code page
+    ///
+    /// Each column's 029 character is written as its byte in the code page.
+    /// Columns with no character, or whose character the page lacks, keep
+    /// their card code byte as in `to_ebcdic`, and fail the same way.
+    pub fn to_ebcdic_with(&self, code_page: CodePage) -> Result<Vec<u8>, EbcdicExportError> {
+        let mut data = Vec::with_capacity(self.columns.len());
+        let mut columns = Vec::new();
+        for (index, column) in self.columns.iter().enumerate() {
+            let byte = column
+                .to_char()
+                .and_then(|c| code_page.from_char(c))
+                .map_or_else(|| hollerith_to_ebcdic(&column.punches), Ok);
+            match byte {
+                Ok(byte) => data.push(byte),
+                Err(_) => columns.push(index),
+            }
+        }
+        if columns.is_empty() {
+            Ok(data)
+        } else {
+            Err(EbcdicExportError { columns })
+        }
+    }
+
+    /// Create a card from EBCDIC in a code page, with a policy for control
+    /// bytes
+    ///
+    /// Each byte's character is punched as on the 029. Characters the 029
+    /// cannot punch fall back to the byte's card code, as in `from_ebcdic`.
+    /// Fails only under `ControlPolicy::Error`.
+    pub fn from_ebcdic_with(data: &[u8], options: &EbcdicLoadOptions) -> Result<Self, ControlByte> {
+        let mut card = PunchCard::new(CardType::Text);
+        for (i, &byte) in data.iter().take(80).enumerate() {
+            let c = options.code_page.to_char(byte);
+            if c.is_none_or(char::is_control) {
+                match options.controls {
+                    ControlPolicy::Punch => {}
+                    ControlPolicy::Blank => continue,
+                    ControlPolicy::Error => return Err(ControlByte { column: i, byte }),
+                    ControlPolicy::RecordBoundary if ebcdic::is_line_end(byte) => break,
+                    ControlPolicy::RecordBoundary => continue,
+                }
+            }
+            card.columns[i] = match c.filter(|&c| char_to_hollerith(c).is_some()) {
+                Some(c) => Column::from_char(c),
+                None => Column {
+                    punches: ebcdic_to_hollerith(byte),
+                    printed_char: ebcdic_to_char(byte),
+                },
             };
+        }
+        Ok(card)
+    }
+
+    /// Convert the card to BCD interchange code (80 bytes, one 6-bit code per
+    /// column)
+    ///
+    /// Fails, listing every column, if any column's punches have no BCD
+    /// code.
+    pub fn to_bcdic(&self) -> Result<Vec<u8>, BcdicExportError> {
+        let mut data = Vec::with_capacity(self.columns.len());
+        let mut columns = Vec::new();
+        for (index, column) in self.columns.iter().enumerate() {
+            match hollerith_to_bcdic(&column.punches) {
+                Some(code) => data.push(code),
+                None => columns.push(index),
+            }
+        }
+        if columns.is_empty() {
+            Ok(data)
+        } else {
+            Err(BcdicExportError { columns })
+        }
+    }
+
+    /// Create a card from BCD interchange code (one 6-bit code per column)
+    pub fn from_bcdic(data: &[u8]) -> Self {
+        let mut card = PunchCard::new(CardType::Text);
+        for (i, &code) in data.iter().take(80).enumerate() {
+            let punches = bcdic_to_hollerith(code);
+            let printed_char = hollerith_to_char(&punches);
             card.columns[i] = Column {
-                punches: hollerith,
+                punches,
                 printed_char,
             };
         }
-
         card
     }
 
+    /// Create a card from raw bytes of an auto-detected format
+    ///
+    /// Uses the most likely result of `detect_format`. For a deck only the
+    /// first card is returned; for ASCII text the first line is punched.
+    /// Returns None if the format is not recognized.
+    pub fn from_detected(data: &[u8]) -> Option<Self> {
+        let detection = detect_format(data).into_iter().next()?;
+        Some(match detection.format {
+            CardFormat::AsciiRecord => {
+                let text = String::from_utf8_lossy(data);
+                let first_line = text.lines().next().unwrap_or("");
+                PunchCard::from_text(first_line.trim_end_matches('\r'))
+            }
+            format => PunchCard::parse(&data[..format.record_len()], format)
+                .expect("detected data fits its format"),
+        })
+    }
+
     /// Get the card type
     pub fn card_type(&self) -> CardType {
         self.card_type
     }
 
+    /// Change the card type without touching the columns
+    pub(crate) fn set_card_type(&mut self, card_type: CardType) {
+        self.card_type = card_type;
+    }
+
+    /// Get the card variant
+    pub fn variant(&self) -> CardVariant {
+        self.variant
+    }
+
+    /// Set the card variant
+    ///
+    /// Existing punches are kept even if the new variant disallows them;
+    /// use `variant_warnings` to find them.
+    pub fn set_variant(&mut self, variant: CardVariant) {
+        self.variant = variant;
+    }
+
+    /// Columns (0-based) that can be punched on this card's variant
+    pub fn allowed_columns(&self) -> Vec<usize> {
+        (0..self.width.columns())
+            .filter(|&index| self.variant.allows_column(index))
+            .collect()
+    }
+
+    /// Describe punched columns that the card's variant does not allow
+    ///
+    /// Cards loaded from files are not rejected for these; callers can show
+    /// the warnings instead.
+    pub fn variant_warnings(&self) -> Vec<String> {
+        self.columns()
+            .iter()
+            .enumerate()
+            .filter(|&(index, col)| !col.is_blank() && !self.variant.allows_column(index))
+            .map(|(index, _)| {
+                format!(
+                    "Column {}: punched, but {} cards cannot punch this column",
+                    index + 1,
+                    self.variant.name()
+                )
+            })
+            .collect()
+    }
+
+    /// Check a column index against the card width and variant
+    fn check_punchable(&self, index: usize) -> Result<(), &'static str> {
+        if index >= self.width.columns() {
+            return Err("Column index out of range");
+        }
+        if !self.variant.allows_column(index) {
+            return Err("Column cannot be punched on this card variant");
+        }
+        Ok(())
+    }
+
+    /// Get the number of columns on the card
+    pub fn width(&self) -> CardWidth {
+        self.width
+    }
+
+    /// Change the number of columns
+    ///
+    /// Narrowing drops the trailing columns; widening adds blank columns.
+    pub fn with_width(mut self, width: CardWidth) -> Self {
+        for column in &mut self.columns[width.columns()..] {
+            *column = Column::new();
+        }
+        self.width = width;
+        self
+    }
+
+    /// Copy of the card padded with blank columns to the standard 80
+    ///
+    /// Fixed-length record formats use this so stub cards serialize as
+    /// full-width records.
+    pub fn to_standard_width(&self) -> PunchCard {
+        self.clone().with_width(CardWidth::STANDARD)
+    }
+
+    /// Get the card's physical metadata
+    pub fn metadata(&self) -> &CardMetadata {
+        &self.metadata
+    }
+
+    /// Get a mutable reference to the card's physical metadata
+    pub fn metadata_mut(&mut self) -> &mut CardMetadata {
+        &mut self.metadata
+    }
+
+    /// Replace the card's physical metadata
+    pub fn set_metadata(&mut self, metadata: CardMetadata) {
+        self.metadata = metadata;
+    }
+
     /// Get a reference to a column
     pub fn get_column(&self, index: usize) -> Option<&Column> {
-        self.columns.get(index)
+        self.columns().get(index)
     }
 
     /// Get a mutable reference to a column
     pub fn get_column_mut(&mut self, index: usize) -> Option<&mut Column> {
-        self.columns.get_mut(index)
+        self.columns_mut().get_mut(index)
     }
 
     /// Set a column from a character (text mode)
+    ///
+    /// Fails for columns the card's variant cannot punch, unless the
+    /// character is a blank.
     pub fn set_column_char(&mut self, index: usize, c: char) -> Result<(), &'static str> {
-        if index >= 80 {
-            return Err("Column index out of range");
+        let column = Column::from_char(c);
+        if column.is_blank() {
+            self.get_column(index).ok_or("Column index out of range")?;
+        } else {
+            self.check_punchable(index)?;
         }
-        self.columns[index] = Column::from_char(c);
+        self.columns[index] = column;
         Ok(())
     }
 
     /// Set a column from a Hollerith code (binary mode)
+    ///
+    /// Fails for columns the card's variant cannot punch, unless the code
+    /// has no punches.
     pub fn set_column_hollerith(
         &mut self,
         index: usize,
         code: HollerithCode,
     ) -> Result<(), &'static str> {
-        if index >= 80 {
-            return Err("Column index out of range");
+        if code.rows.is_empty() {
+            self.get_column(index).ok_or("Column index out of range")?;
+        } else {
+            self.check_punchable(index)?;
         }
         self.columns[index] = Column::from_hollerith(code);
         Ok(())
     }
 
+    /// Toggle a single hole at the given column and row (12, 11, 0-9)
+    ///
+    /// On text cards the printed character is re-derived from the new punch
+    /// pattern, and cleared if the pattern no longer maps to a character.
+    /// Returns the new state of the hole (true = punched).
+    pub fn toggle_punch(&mut self, index: usize, row: u8) -> Result<bool, &'static str> {
+        self.check_punchable(index)?;
+        let card_type = self.card_type;
+        let column = &mut self.columns[index];
+        let punched = column.punches.is_punched(row);
+        if punched {
+            column.remove_punch(row)?;
+        } else {
+            column.add_punch(row)?;
+        }
+        if card_type == CardType::Text {
+            column.printed_char = if column.is_blank() {
+                None
+            } else {
+                column.to_char()
+            };
+        }
+        Ok(!punched)
+    }
+
+    /// Copy a run of columns (punches and printed characters) from another card
+    ///
+    /// Columns `src_range` of `source` are copied to this card starting at
+    /// `dest_start`. Fails without modifying the card if either range falls
+    /// outside its card, or a punched column would land on a column this
+    /// card's variant cannot punch.
+    pub fn copy_columns(
+        &mut self,
+        src_range: Range<usize>,
+        dest_start: usize,
+        source: &PunchCard,
+    ) -> Result<(), &'static str> {
+        check_copy_ranges(
+            &src_range,
+            source.width.columns(),
+            dest_start,
+            self.width.columns(),
+        )?;
+        let copied = &source.columns()[src_range];
+        self.check_copy_punchable(copied, dest_start)?;
+        self.columns[dest_start..dest_start + copied.len()].clone_from_slice(copied);
+        Ok(())
+    }
+
+    /// Copy a run of columns to another position on the same card
+    ///
+    /// Overlapping source and destination ranges are handled correctly, as
+    /// if the source columns were read before any were written.
+    pub fn copy_columns_within(
+        &mut self,
+        src_range: Range<usize>,
+        dest_start: usize,
+    ) -> Result<(), &'static str> {
+        let width = self.width.columns();
+        check_copy_ranges(&src_range, width, dest_start, width)?;
+        let copied = self.columns[src_range.clone()].to_vec();
+        self.check_copy_punchable(&copied, dest_start)?;
+        let dest_end = dest_start + src_range.len();
+        self.columns[dest_start..dest_end].clone_from_slice(&copied);
+        Ok(())
+    }
+
+    /// Check that every punched column of a copy lands on a column the
+    /// variant can punch (blank columns may go anywhere)
+    fn check_copy_punchable(
+        &self,
+        copied: &[Column],
+        dest_start: usize,
+    ) -> Result<(), &'static str> {
+        copied
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| !column.is_blank())
+            .try_for_each(|(offset, _)| self.check_punchable(dest_start + offset))
+    }
+
     /// Clear a column (make it blank)
     pub fn clear_column(&mut self, index: usize) -> Result<(), &'static str> {
-        if index >= 80 {
+        if index >= self.width.columns() {
             return Err("Column index out of range");
         }
         self.columns[index] = Column::new();
         Ok(())
     }
 
+    /// Re-derive every printed character from the punches
+    ///
+    /// This is what running the card through an interpreter did. Columns
+    /// whose punches decode to no character are left unprinted.
+    pub fn reprint_from_punches(&mut self) {
+        for column in self.columns_mut() {
+            column.printed_char = column.to_char();
+        }
+    }
+
+    /// Remove all printed characters, keeping the punches
+    pub fn clear_printing(&mut self) {
+        for column in self.columns_mut() {
+            column.printed_char = None;
+        }
+    }
+
     /// Clear the entire card
     pub fn clear(&mut self) {
-        for col in &mut self.columns {
+        for col in self.columns_mut() {
             *col = Column::new();
         }
     }
@@ -269,7 +979,63 @@ impl PunchCard {
     /// Convert the card to a text string
     /// Returns the text representation of all columns
     pub fn to_text(&self) -> String {
-        self.columns
+        self.to_text_replacing('?')
+    }
+
+    /// Decode the card in the given character set (see `decode_string_with`)
+    ///
+    /// Blank columns read as a space unless the set says otherwise.
+    pub fn to_text_with(
+        &self,
+        charset: &dyn CharacterSet,
+        options: &DecodeOptions,
+    ) -> Result<(String, DecodeReport), DecodeReport> {
+        decode_codes_with(
+            self.columns().iter().map(|col| &col.punches),
+            charset,
+            options,
+        )
+    }
+
+    /// List the columns `to_text` shows as '?', with the reason each did not
+    /// decode (zero-based column, failure)
+    pub fn decode_failures(&self) -> Vec<(usize, DecodeFailure)> {
+        self.columns()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, col)| col.to_char_detailed().err().map(|err| (index, err)))
+            .collect()
+    }
+
+    /// Convert the card to a text string with a chosen replacement for
+    /// columns that do not decode (see `decode_string_with`)
+    pub fn to_text_with_options(
+        &self,
+        options: &DecodeOptions,
+    ) -> Result<(String, DecodeReport), DecodeReport> {
+        self.to_text_with(&Charset::Ibm029, options)
+    }
+
+    /// Convert the card to a text string, using `replacement` for columns
+    /// whose punch pattern does not decode to a character
+    pub fn to_text_replacing(&self, replacement: char) -> String {
+        self.columns()
+            .iter()
+            .map(|col| col.to_char().unwrap_or(replacement))
+            .collect()
+    }
+
+    /// Convert the card to a text string with trailing blank columns removed
+    ///
+    /// Suitable for writing cards back out as lines of a source file.
+    /// A card whose last column is punched still yields all 80 characters.
+    pub fn to_text_trimmed(&self) -> String {
+        let used = self
+            .columns()
+            .iter()
+            .rposition(|col| !col.is_blank())
+            .map_or(0, |idx| idx + 1);
+        self.columns[..used]
             .iter()
             .map(|col| col.to_char().unwrap_or('?'))
             .collect()
@@ -280,10 +1046,109 @@ impl PunchCard {
         self.columns.iter().filter(|col| !col.is_blank()).count()
     }
 
-    /// Get all columns as a slice
+    /// Get all columns as a slice (shorter than 80 for stub cards)
     pub fn columns(&self) -> &[Column] {
+        &self.columns[..self.width.columns()]
+    }
+
+    /// Get all 80 column positions as a fixed-size array
+    ///
+    /// For stub cards the positions past the card's width are always blank.
+    pub fn columns_array(&self) -> &[Column; 80] {
         &self.columns
     }
+
+    /// Get the columns in use as a mutable slice
+    fn columns_mut(&mut self) -> &mut [Column] {
+        &mut self.columns[..self.width.columns()]
+    }
+
+    /// Check if two cards have the same holes in every column
+    ///
+    /// Unlike `==`, printed characters, the card type, and metadata are ignored, so a
+    /// text card equals its own binary round trip.
+    pub fn punches_eq(&self, other: &PunchCard) -> bool {
+        // Compare all 80 positions so a stub card equals its padded copy
+        self.columns
+            .iter()
+            .zip(&other.columns)
+            .all(|(a, b)| a.punches_eq(b))
+    }
+
+    /// Feed the punch patterns of all 80 columns into a hasher
+    ///
+    /// Consistent with `punches_eq`: cards with the same holes hash the same
+    /// regardless of printed characters, card type, or metadata. Use it to
+    /// implement `Hash` for a wrapper keyed on holes only.
+    pub fn punches_hash<H: Hasher>(&self, state: &mut H) {
+        for column in &self.columns {
+            column.punches.hash(state);
+        }
+    }
+
+    /// Compute a 16-bit checksum over the packed 108-byte binary image
+    ///
+    /// The image is summed as 54 big-endian 16-bit words with end-around
+    /// carry, the same style of sum an IBM 1130 loader uses to verify cards.
+    /// Columns 73-80 are not part of the binary image and do not contribute.
+    pub fn checksum(&self) -> u16 {
+        let mut sum: u32 = 0;
+        for pair in self.to_binary().chunks(2) {
+            sum += u32::from(u16::from_be_bytes([pair[0], pair[1]]));
+            sum = (sum & 0xFFFF) + (sum >> 16);
+        }
+        sum as u16
+    }
+
+    /// Compute a stable 64-bit hash of the punch patterns of all 80 columns
+    ///
+    /// Printed characters and the card type are ignored, so the same holes
+    /// hash identically however the card was created. The value is stable
+    /// across builds and platforms (FNV-1a), making it suitable for archives.
+    pub fn content_hash(&self) -> u64 {
+        let mut hash = fnv1a_start();
+        for column in self.columns() {
+            let mask = bitpack::code_to_bits(&column.punches);
+            hash = fnv1a(hash, &mask.to_be_bytes());
+        }
+        hash
+    }
+}
+
+/// Validate the source and destination of a column copy
+fn check_copy_ranges(
+    src_range: &Range<usize>,
+    src_width: usize,
+    dest_start: usize,
+    dest_width: usize,
+) -> Result<(), &'static str> {
+    if src_range.start > src_range.end || src_range.end > src_width {
+        return Err("Source column range out of range");
+    }
+    if dest_start
+        .checked_add(src_range.len())
+        .is_none_or(|dest_end| dest_end > dest_width)
+    {
+        return Err("Destination column range exceeds the card");
+    }
+    Ok(())
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Fold bytes into an FNV-1a hash state
+pub(crate) fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Initial state for an FNV-1a hash
+pub(crate) fn fnv1a_start() -> u64 {
+    FNV_OFFSET_BASIS
 }
 
 impl Default for PunchCard {
@@ -292,6 +1157,119 @@ impl Default for PunchCard {
     }
 }
 
+/// How sure `detect_format` is of a format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Confidence {
+    /// No other format fits the data
+    Certain,
+    /// Other formats fit the data too, so a UI should ask the user
+    Ambiguous,
+}
+
+/// A format the data passed to `detect_format` could be in
+///
+/// ASCII text is reported as `CardFormat::AsciiRecord` with one card per
+/// line, whatever the line length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Detection {
+    /// The card format
+    pub format: CardFormat,
+    /// Number of cards: records, or lines of text
+    pub cards: usize,
+    /// Whether other formats fit too
+    pub confidence: Confidence,
+}
+
+/// Detect the format of raw card data
+///
+/// Recognizes 108-byte IBM 1130 binary images, decks of them, 80-byte EBCDIC,
+/// BCD and legacy binary records, and plain ASCII text lines. EBCDIC text
+/// is all bytes 0x40 and up and BCD all bytes below 0x40, so the two never
+/// compete. An 80-byte record that could be read several ways gives every
+/// candidate, most likely first, each `Confidence::Ambiguous`. Returns an
+/// empty list for unrecognized data.
+pub fn detect_format(data: &[u8]) -> Vec<Detection> {
+    let certain = |format, cards| {
+        vec![Detection {
+            format,
+            cards,
+            confidence: Confidence::Certain,
+        }]
+    };
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let is_ascii_text = data
+        .iter()
+        .all(|&b| (0x20..=0x7E).contains(&b) || matches!(b, b'\n' | b'\r' | b'\t'));
+
+    if data.len() == 80 {
+        let mut candidates = Vec::new();
+        if data.iter().all(|&b| ebcdic::is_printable(b)) {
+            candidates.push(CardFormat::Ebcdic80);
+        }
+        if data.iter().all(|&b| is_card_code_bcd(b)) {
+            candidates.push(CardFormat::Bcdic80);
+        }
+        if is_ascii_text {
+            candidates.push(CardFormat::AsciiRecord);
+        }
+        candidates.push(CardFormat::LegacyByte80);
+        let ascii_cards = ascii_card_count(data);
+        let confidence = if candidates.len() == 1 {
+            Confidence::Certain
+        } else {
+            Confidence::Ambiguous
+        };
+        return candidates
+            .into_iter()
+            .map(|format| Detection {
+                format,
+                cards: if format == CardFormat::AsciiRecord {
+                    ascii_cards
+                } else {
+                    1
+                },
+                confidence,
+            })
+            .collect();
+    }
+
+    if is_ascii_text && data.contains(&b'\n') {
+        return certain(CardFormat::AsciiRecord, ascii_card_count(data));
+    }
+
+    if data.len().is_multiple_of(108) {
+        return certain(CardFormat::Ibm1130Binary108, data.len() / 108);
+    }
+
+    if is_ascii_text {
+        return certain(CardFormat::AsciiRecord, ascii_card_count(data));
+    }
+
+    Vec::new()
+}
+
+/// Count the cards in ASCII text: one per line, or one per 80 characters
+/// of back-to-back card images if there are no line ends
+fn ascii_card_count(data: &[u8]) -> usize {
+    if data.contains(&b'\n') {
+        String::from_utf8_lossy(data).lines().count()
+    } else {
+        data.len().div_ceil(80)
+    }
+}
+
+/// Check if a byte is a 6-bit BCD code with a card code
+///
+/// The A-zone-only code is left out, as it is punched blank.
+fn is_card_code_bcd(byte: u8) -> bool {
+    byte < 0o100 && byte != 0o20
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -326,6 +1304,101 @@ mod tests {
         assert_eq!(col.to_char(), Some('A'));
     }
 
+    #[test]
+    fn test_column_add_punch() {
+        let mut col = Column::new();
+        col.add_punch(1).unwrap();
+        col.add_punch(12).unwrap();
+        col.add_punch(1).unwrap();
+        assert_eq!(col.punches.rows, vec![1, 12]);
+        assert_eq!(col.to_char(), Some('A'));
+        assert_eq!(col.printed_char, None);
+    }
+
+    #[test]
+    fn test_column_add_punch_invalid_row() {
+        let mut col = Column::new();
+        assert!(col.add_punch(10).is_err());
+        assert!(col.add_punch(13).is_err());
+        assert!(col.is_blank());
+    }
+
+    #[test]
+    fn test_column_remove_punch_updates_printed_char() {
+        let mut col = Column::from_char('A');
+        col.remove_punch(12).unwrap();
+        assert_eq!(col.punches.rows, vec![1]);
+        assert_eq!(col.printed_char, Some('1'));
+
+        col.add_punch(11).unwrap();
+        col.add_punch(0).unwrap();
+        assert_eq!(col.printed_char, None);
+        assert!(col.remove_punch(10).is_err());
+    }
+
+    #[test]
+    fn test_toggle_punch() {
+        let mut card = PunchCard::new(CardType::Text);
+        assert!(card.toggle_punch(0, 12).unwrap());
+        assert_eq!(card.get_column(0).unwrap().printed_char, Some('&'));
+        assert!(card.toggle_punch(0, 1).unwrap());
+        assert_eq!(card.get_column(0).unwrap().printed_char, Some('A'));
+        assert!(!card.toggle_punch(0, 12).unwrap());
+        assert_eq!(card.get_column(0).unwrap().printed_char, Some('1'));
+        assert!(!card.toggle_punch(0, 1).unwrap());
+        assert!(card.get_column(0).unwrap().is_blank());
+        assert_eq!(card.get_column(0).unwrap().printed_char, None);
+    }
+
+    #[test]
+    fn test_toggle_punch_clears_undecodable_printing() {
+        let mut card = PunchCard::from_text("A");
+        card.toggle_punch(0, 11).unwrap();
+        assert_eq!(card.get_column(0).unwrap().printed_char, None);
+    }
+
+    #[test]
+    fn test_toggle_punch_binary_card_does_not_print() {
+        let mut card = PunchCard::new(CardType::Binary);
+        assert!(card.toggle_punch(5, 3).unwrap());
+        assert_eq!(card.get_column(5).unwrap().printed_char, None);
+        assert_eq!(card.get_column(5).unwrap().punches.rows, vec![3]);
+    }
+
+    #[test]
+    fn test_toggle_punch_errors() {
+        let mut card = PunchCard::new(CardType::Text);
+        assert!(card.toggle_punch(80, 1).is_err());
+        assert!(card.toggle_punch(0, 10).is_err());
+        assert_eq!(card.punched_count(), 0);
+    }
+
+    #[test]
+    fn test_column_punches_eq() {
+        let printed = Column::from_char('A');
+        let unprinted = Column::from_hollerith(HollerithCode::new(vec![1, 12]));
+        assert_ne!(printed, unprinted);
+        assert!(printed.punches_eq(&unprinted));
+        assert!(!printed.punches_eq(&Column::from_char('B')));
+    }
+
+    #[test]
+    fn test_punches_eq_ignores_printing_and_type() {
+        let text_card = PunchCard::from_text("A");
+        let binary_card = PunchCard::from_binary(&text_card.to_binary());
+        assert_ne!(text_card, binary_card);
+        assert!(text_card.punches_eq(&binary_card));
+        assert!(binary_card.punches_eq(&text_card));
+    }
+
+    #[test]
+    fn test_punches_eq_detects_differences() {
+        let a = PunchCard::from_text("HELLO");
+        let mut b = a.clone();
+        b.toggle_punch(79, 9).unwrap();
+        assert!(!a.punches_eq(&b));
+    }
+
     #[test]
     fn test_punch_card_new() {
         let card = PunchCard::new(CardType::Text);
@@ -414,25 +1487,7 @@ mod tests {
     #[test]
     fn test_clear_binary_card_completely() {
         // Create a binary card with data in all 72 columns
-        let mut binary_data = Vec::with_capacity(108);
-        let mut bit_buffer: Vec<bool> = Vec::with_capacity(864);
-        for _i in 0..72 {
-            let pattern = 0x0FFF; // All 12 bits set
-            for bit in 0..12 {
-                bit_buffer.push((pattern & (1 << bit)) != 0);
-            }
-        }
-        for byte_idx in 0..108 {
-            let mut byte_val: u8 = 0;
-            for bit_in_byte in 0..8 {
-                let bit_idx = byte_idx * 8 + bit_in_byte;
-                if bit_idx < bit_buffer.len() && bit_buffer[bit_idx] {
-                    byte_val |= 1 << bit_in_byte;
-                }
-            }
-            binary_data.push(byte_val);
-        }
-
+        let binary_data = bitpack::pack_columns(&vec![bitpack::code_from_bits(0x0FFF); 72]);
         let mut card = PunchCard::from_binary(&binary_data);
         assert_eq!(card.punched_count(), 72);
 
@@ -457,6 +1512,337 @@ mod tests {
         assert!(text.starts_with("HELLO WORLD"));
     }
 
+    #[test]
+    fn test_to_text_is_full_width() {
+        let card = PunchCard::from_text("HELLO");
+        assert_eq!(card.to_text().len(), 80);
+    }
+
+    #[test]
+    fn test_to_text_trimmed() {
+        let card = PunchCard::from_text("HELLO WORLD   ");
+        assert_eq!(card.to_text_trimmed(), "HELLO WORLD");
+
+        let blank = PunchCard::new(CardType::Text);
+        assert_eq!(blank.to_text_trimmed(), "");
+    }
+
+    #[test]
+    fn test_to_text_trimmed_last_column_punched() {
+        let mut card = PunchCard::from_text("A");
+        card.set_column_char(79, 'Z').unwrap();
+        let text = card.to_text_trimmed();
+        assert_eq!(text.len(), 80);
+        assert!(text.starts_with('A'));
+        assert!(text.ends_with('Z'));
+    }
+
+    #[test]
+    fn test_to_text_replacing() {
+        let mut card = PunchCard::from_text("AB");
+        card.set_column_hollerith(1, HollerithCode::new(vec![12, 11, 0]))
+            .unwrap();
+        assert!(card.to_text().starts_with("A?"));
+        assert!(card.to_text_replacing('#').starts_with("A#"));
+        assert_eq!(card.to_text_trimmed(), "A?");
+    }
+
+    #[test]
+    fn test_checksum_blank_card() {
+        let card = PunchCard::new(CardType::Binary);
+        assert_eq!(card.checksum(), 0);
+    }
+
+    #[test]
+    fn test_checksum_detects_change() {
+        let card = PunchCard::from_text("HELLO WORLD");
+        let mut changed = card.clone();
+        changed.set_column_char(3, 'P').unwrap();
+        assert_ne!(card.checksum(), changed.checksum());
+    }
+
+    #[test]
+    fn test_checksum_survives_binary_roundtrip() {
+        let card = PunchCard::from_text("CHECKSUM TEST 123");
+        let reloaded = PunchCard::from_binary(&card.to_binary());
+        assert_eq!(card.checksum(), reloaded.checksum());
+    }
+
+    #[test]
+    fn test_content_hash_ignores_printing_and_type() {
+        let text_card = PunchCard::from_text("HELLO");
+        let ebcdic_card = PunchCard::from_ebcdic(&[0xC8, 0xC5, 0xD3, 0xD3, 0xD6]);
+        assert_eq!(text_card.content_hash(), ebcdic_card.content_hash());
+
+        let binary_card = PunchCard::from_binary(&text_card.to_binary());
+        assert_eq!(text_card.content_hash(), binary_card.content_hash());
+    }
+
+    #[test]
+    fn test_content_hash_differs_for_different_punches() {
+        let a = PunchCard::from_text("HELLO");
+        let b = PunchCard::from_text("HELLP");
+        assert_ne!(a.content_hash(), b.content_hash());
+    }
+
+    /// The formats and confidence `detect_format` gives, in order
+    fn detected(data: &[u8]) -> Vec<(CardFormat, usize, Confidence)> {
+        detect_format(data)
+            .into_iter()
+            .map(|d| (d.format, d.cards, d.confidence))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_format_ibm1130_binary() {
+        let data = PunchCard::from_text("HELLO").to_binary();
+        assert_eq!(
+            detected(&data),
+            [(CardFormat::Ibm1130Binary108, 1, Confidence::Certain)]
+        );
+        let card = PunchCard::from_detected(&data).unwrap();
+        assert_eq!(card.card_type(), CardType::Binary);
+        assert_eq!(card.get_column(0).unwrap().to_char(), Some('H'));
+    }
+
+    #[test]
+    fn test_detect_format_binary_deck() {
+        let mut data = PunchCard::from_text("FIRST").to_binary();
+        data.extend(PunchCard::from_text("SECOND").to_binary());
+        data.extend(PunchCard::from_text("THIRD").to_binary());
+        assert_eq!(
+            detected(&data),
+            [(CardFormat::Ibm1130Binary108, 3, Confidence::Certain)]
+        );
+        let card = PunchCard::from_detected(&data).unwrap();
+        assert!(card.to_text().starts_with("FIRST"));
+    }
+
+    #[test]
+    fn test_detect_format_ebcdic() {
+        // Any 80-byte record is also a valid legacy binary image
+        let data = PunchCard::from_text("HELLO WORLD").to_ebcdic().unwrap();
+        assert_eq!(
+            detected(&data),
+            [
+                (CardFormat::Ebcdic80, 1, Confidence::Ambiguous),
+                (CardFormat::LegacyByte80, 1, Confidence::Ambiguous),
+            ]
+        );
+        let card = PunchCard::from_detected(&data).unwrap();
+        assert!(card.to_text().starts_with("HELLO WORLD"));
+    }
+
+    #[test]
+    fn test_detect_format_legacy_binary() {
+        let data = vec![0xFF; 80];
+        assert_eq!(
+            detected(&data),
+            [(CardFormat::LegacyByte80, 1, Confidence::Certain)]
+        );
+        let card = PunchCard::from_detected(&data).unwrap();
+        assert_eq!(card.card_type(), CardType::Binary);
+        // Bytes below 0x40 are also BCD codes
+        assert_eq!(
+            detected(&[0x07; 80]),
+            [
+                (CardFormat::Bcdic80, 1, Confidence::Ambiguous),
+                (CardFormat::LegacyByte80, 1, Confidence::Ambiguous),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_format_bcd() {
+        let data = PunchCard::from_text("PAYROLL 1401").to_bcdic().unwrap();
+        let candidates = detect_format(&data);
+        // 80-byte records are always ambiguous with legacy binary
+        assert!(candidates.len() > 1);
+        assert_eq!(candidates[0].format, CardFormat::Bcdic80);
+        assert_eq!(candidates[0].confidence, Confidence::Ambiguous);
+        assert!(!candidates.iter().any(|d| d.format == CardFormat::Ebcdic80));
+        let card = PunchCard::from_detected(&data).unwrap();
+        assert_eq!(card.to_text_trimmed(), "PAYROLL 1401");
+    }
+
+    #[test]
+    fn test_detect_format_ambiguous_80_bytes() {
+        // Eighty EBCDIC spaces are also eighty ASCII '@' characters
+        let data = vec![0x40; 80];
+        assert_eq!(
+            detected(&data),
+            [
+                (CardFormat::Ebcdic80, 1, Confidence::Ambiguous),
+                (CardFormat::AsciiRecord, 1, Confidence::Ambiguous),
+                (CardFormat::LegacyByte80, 1, Confidence::Ambiguous),
+            ]
+        );
+        let card = PunchCard::from_detected(&data).unwrap();
+        assert_eq!(card.punched_count(), 0);
+    }
+
+    #[test]
+    fn test_detect_format_ascii_text() {
+        let data = b"      LD   X\r\nLOOP  STO  Y\n";
+        assert_eq!(
+            detected(data),
+            [(CardFormat::AsciiRecord, 2, Confidence::Certain)]
+        );
+        let card = PunchCard::from_detected(data).unwrap();
+        assert_eq!(card.card_type(), CardType::Text);
+        assert_eq!(card.to_text_trimmed(), "      LD   X");
+    }
+
+    #[test]
+    fn test_detect_format_counts_ascii_records() {
+        // Four 19-character lines make 80 bytes
+        let data = "LINE ONE   00000001\n".repeat(4);
+        assert_eq!(data.len(), 80);
+        let candidates = detect_format(data.as_bytes());
+        let ascii = candidates
+            .iter()
+            .find(|d| d.format == CardFormat::AsciiRecord)
+            .unwrap();
+        assert_eq!(ascii.cards, 4);
+
+        // Card images back to back, with no line ends
+        let images = format!("{:80}{:80}", "FIRST", "SECOND");
+        assert_eq!(
+            detected(images.as_bytes()),
+            [(CardFormat::AsciiRecord, 2, Confidence::Certain)]
+        );
+        let card = PunchCard::from_detected(images.as_bytes()).unwrap();
+        assert_eq!(card.to_text_trimmed(), "FIRST");
+    }
+
+    #[test]
+    fn test_detect_format_unrecognized() {
+        assert!(detect_format(&[]).is_empty());
+        assert!(detect_format(&[0xFF, 0x00, 0x81]).is_empty());
+        assert!(PunchCard::from_detected(&[0xFF, 0x00, 0x81]).is_none());
+    }
+
+    #[test]
+    fn test_from_ascii_record() {
+        let card =
+            PunchCard::from_ascii_record("start dc   0", UnsupportedCharPolicy::Error).unwrap();
+        assert_eq!(card.card_type(), CardType::Text);
+        assert_eq!(card.to_text_trimmed(), "START DC   0");
+        assert_eq!(card.get_column(0).unwrap().printed_char, Some('S'));
+    }
+
+    #[test]
+    fn test_from_ascii_record_truncates_to_80() {
+        let line = format!("{}EXTRA", "X".repeat(80));
+        let card = PunchCard::from_ascii_record(&line, UnsupportedCharPolicy::Error).unwrap();
+        assert_eq!(card.to_ascii_record(), "X".repeat(80));
+    }
+
+    #[test]
+    fn test_from_ascii_record_policies() {
+        let blank = PunchCard::from_ascii_record("A[B", UnsupportedCharPolicy::Blank).unwrap();
+        assert_eq!(blank.to_text_trimmed(), "A B");
+
+        let paren = ReplacementChar::new('(').unwrap();
+        let replaced =
+            PunchCard::from_ascii_record("A[B", UnsupportedCharPolicy::Replace(paren)).unwrap();
+        assert_eq!(replaced.to_text_trimmed(), "A(B");
+        let lower = ReplacementChar::new('x').unwrap();
+        let replaced =
+            PunchCard::from_ascii_record("A[B", UnsupportedCharPolicy::Replace(lower)).unwrap();
+        assert_eq!(replaced.to_text_trimmed(), "AXB");
+
+        // The replacement itself must be punchable
+        assert_eq!(ReplacementChar::new('['), None);
+        assert_eq!(
+            ReplacementChar::try_from('~').unwrap_err(),
+            "Replacement character '~' has no punch pattern"
+        );
+
+        let err = PunchCard::from_ascii_record("A[B", UnsupportedCharPolicy::Error).unwrap_err();
+        assert!(err.contains("Column 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_to_ascii_record_is_80_columns() {
+        let card = PunchCard::from_text("HELLO");
+        let record = card.to_ascii_record();
+        assert_eq!(record.len(), 80);
+        assert_eq!(record.trim_end(), "HELLO");
+    }
+
+    #[test]
+    fn test_to_ascii_record_non_ascii() {
+        let card = PunchCard::from_text("A¬B");
+        assert!(card.to_ascii_record().starts_with("A?B"));
+    }
+
+    #[test]
+    fn test_copy_columns() {
+        let source = PunchCard::from_text("HEADER0001");
+        let mut card = PunchCard::from_text("          DATA");
+        card.copy_columns(0..6, 0, &source).unwrap();
+        assert_eq!(card.to_text_trimmed(), "HEADER    DATA");
+        assert_eq!(card.get_column(0).unwrap().printed_char, Some('H'));
+    }
+
+    #[test]
+    fn test_copy_columns_to_offset() {
+        let source = PunchCard::from_text("ABC");
+        let mut card = PunchCard::new(CardType::Text);
+        card.copy_columns(0..3, 77, &source).unwrap();
+        assert_eq!(&card.to_text()[77..], "ABC");
+    }
+
+    #[test]
+    fn test_copy_columns_errors() {
+        let source = PunchCard::from_text("ABC");
+        let mut card = PunchCard::from_text("XYZ");
+        assert!(card.copy_columns(0..3, 78, &source).is_err());
+        assert!(card.copy_columns(75..81, 0, &source).is_err());
+        assert!(card.copy_columns(0..3, usize::MAX, &source).is_err());
+        assert!(card.copy_columns_within(0..3, usize::MAX - 1).is_err());
+        assert_eq!(card.to_text_trimmed(), "XYZ");
+    }
+
+    #[test]
+    fn test_copy_columns_port_a_punch() {
+        let source = PunchCard::from_text("A B");
+        let mut card = PunchCard::new(CardType::Text);
+        card.set_variant(CardVariant::PortAPunch);
+        // Columns 1 and 3 are scored; the blank lands on column 2
+        card.copy_columns(0..3, 0, &source).unwrap();
+        assert_eq!(card.to_text_trimmed(), "A B");
+        // Shifted by one, A would land on unscored column 2
+        assert_eq!(
+            card.copy_columns(0..3, 1, &source),
+            Err("Column cannot be punched on this card variant")
+        );
+        assert_eq!(
+            card.copy_columns_within(0..1, 3),
+            Err("Column cannot be punched on this card variant")
+        );
+        assert_eq!(card.to_text_trimmed(), "A B");
+    }
+
+    #[test]
+    fn test_copy_columns_within_overlapping() {
+        let mut card = PunchCard::from_text("ABCDEF");
+        card.copy_columns_within(0..4, 2).unwrap();
+        assert_eq!(card.to_text_trimmed(), "ABABCD");
+
+        let mut card = PunchCard::from_text("ABCDEF");
+        card.copy_columns_within(2..6, 0).unwrap();
+        assert_eq!(card.to_text_trimmed(), "CDEFEF");
+    }
+
+    #[test]
+    fn test_copy_columns_within_errors() {
+        let mut card = PunchCard::from_text("ABCDEF");
+        assert!(card.copy_columns_within(0..10, 75).is_err());
+        assert_eq!(card.to_text_trimmed(), "ABCDEF");
+    }
+
     #[test]
     fn test_get_column_mut() {
         let mut card = PunchCard::new(CardType::Text);
@@ -511,28 +1897,10 @@ mod tests {
     fn test_binary_card_save_load_roundtrip() {
         // Test B: 72-column binary card round-trip
         // Create a binary card with only 72 columns of data (columns 73-80 blank)
-        let mut binary_data = Vec::with_capacity(108);
-
-        // Pack 72 columns × 12 bits each into 108 bytes
-        let mut bit_buffer: Vec<bool> = Vec::with_capacity(864);
-        for i in 0..72 {
-            let pattern = 0x0E49 | (i as u16);
-            for bit in 0..12 {
-                bit_buffer.push((pattern & (1 << bit)) != 0);
-            }
-        }
-
-        // Convert bits to bytes
-        for byte_idx in 0..108 {
-            let mut byte_val: u8 = 0;
-            for bit_in_byte in 0..8 {
-                let bit_idx = byte_idx * 8 + bit_in_byte;
-                if bit_idx < bit_buffer.len() && bit_buffer[bit_idx] {
-                    byte_val |= 1 << bit_in_byte;
-                }
-            }
-            binary_data.push(byte_val);
-        }
+        let codes: Vec<HollerithCode> = (0..72)
+            .map(|i| bitpack::code_from_bits(0x0E49 | i))
+            .collect();
+        let binary_data = bitpack::pack_columns(&codes);
 
         let original_card = PunchCard::from_binary(&binary_data);
 
@@ -546,30 +1914,642 @@ mod tests {
         // Verify the cards are identical
         assert_eq!(loaded_card.card_type(), CardType::Binary);
 
-        // Check column-by-column punch patterns match for all 80 columns
+        // Punch patterns match for all 80 columns
+        assert!(original_card.punches_eq(&loaded_card));
+
         // Columns 1-72 should have data, columns 73-80 should be blank
-        for i in 0..80 {
-            let orig_col = original_card.get_column(i).unwrap();
-            let loaded_col = loaded_card.get_column(i).unwrap();
-            assert_eq!(
-                orig_col.punches, loaded_col.punches,
-                "Column {} punch pattern mismatch",
+        for i in 72..80 {
+            assert!(
+                original_card.get_column(i).unwrap().is_blank(),
+                "Column {} should be blank in original",
                 i
             );
+            assert!(
+                loaded_card.get_column(i).unwrap().is_blank(),
+                "Column {} should be blank after load",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_metadata_not_in_binary() {
+        use crate::metadata::{CardStock, FormType};
+
+        let mut card = PunchCard::from_text("HELLO");
+        card.metadata_mut().stock = CardStock::Blue;
+        card.metadata_mut().form = FormType::Cobol;
+        let plain = PunchCard::from_text("HELLO");
+        assert_eq!(card.to_binary(), plain.to_binary());
+        assert_eq!(card.content_hash(), plain.content_hash());
+        assert!(card.punches_eq(&plain));
+        assert_ne!(card, plain);
+
+        let loaded = PunchCard::from_binary(&card.to_binary());
+        assert!(loaded.metadata().is_default());
+    }
+
+    #[test]
+    fn test_stub_card_width() {
+        let card = PunchCard::new_with_width(51).unwrap();
+        assert_eq!(card.width(), CardWidth::STUB_51);
+        assert_eq!(card.columns().len(), 51);
+        assert_eq!(PunchCard::new(CardType::Text).width(), CardWidth::STANDARD);
+        assert!(PunchCard::new_with_width(0).is_err());
+        assert!(PunchCard::new_with_width(81).is_err());
+    }
+
+    #[test]
+    fn test_stub_card_bounds() {
+        let mut card = PunchCard::from_text_with_width(&"X".repeat(60), 51).unwrap();
+        assert_eq!(card.to_text(), "X".repeat(51));
+        assert!(card.set_column_char(50, 'A').is_ok());
+        assert!(card.set_column_char(51, 'A').is_err());
+        assert!(card.toggle_punch(51, 1).is_err());
+        assert!(card.clear_column(51).is_err());
+        assert!(card.copy_columns_within(40..51, 0).is_ok());
+        assert!(card.copy_columns_within(45..55, 0).is_err());
+
+        let full = PunchCard::from_text(&"Y".repeat(80));
+        assert!(card.copy_columns(70..80, 0, &full).is_ok());
+        assert!(card.copy_columns(0..10, 45, &full).is_err());
+    }
+
+    #[test]
+    fn test_stub_card_binary_roundtrip() {
+        let card = PunchCard::from_text_with_width("STUB 51", 51).unwrap();
+        let data = card.to_binary();
+        assert_eq!(data.len(), 108);
+        let loaded = PunchCard::from_binary(&data);
+        assert_eq!(loaded.width(), CardWidth::STANDARD);
+        assert!(loaded.with_width(CardWidth::STUB_51).punches_eq(&card));
+        assert_eq!(card.to_ebcdic().unwrap().len(), 80);
+    }
+
+    #[test]
+    fn test_with_width() {
+        let card = PunchCard::from_text(&"Z".repeat(80)).with_width(CardWidth::STUB_51);
+        assert_eq!(card.to_text(), "Z".repeat(51));
+        let padded = card.to_standard_width();
+        assert_eq!(padded.width(), CardWidth::STANDARD);
+        assert_eq!(padded.to_text_trimmed(), "Z".repeat(51));
+    }
 
-            // Verify columns 73-80 are blank
-            if i >= 72 {
-                assert!(
-                    orig_col.is_blank(),
-                    "Column {} should be blank in original",
-                    i
-                );
-                assert!(
-                    loaded_col.is_blank(),
-                    "Column {} should be blank after load",
-                    i
-                );
+    #[test]
+    fn test_port_a_punch_allowed_columns() {
+        let mut card = PunchCard::new(CardType::Text);
+        assert_eq!(card.allowed_columns().len(), 80);
+        card.set_variant(CardVariant::PortAPunch);
+        let allowed = card.allowed_columns();
+        assert_eq!(allowed.len(), 40);
+        assert_eq!(&allowed[..3], &[0, 2, 4]);
+        assert_eq!(allowed.last(), Some(&78));
+    }
+
+    #[test]
+    fn test_port_a_punch_rejects_disallowed_columns() {
+        let mut card = PunchCard::new(CardType::Text);
+        card.set_variant(CardVariant::PortAPunch);
+        assert!(card.set_column_char(0, 'A').is_ok());
+        assert!(card.set_column_char(1, 'A').is_err());
+        assert!(card.set_column_char(1, ' ').is_ok());
+        assert!(card.toggle_punch(1, 12).is_err());
+        assert!(card.toggle_punch(2, 12).unwrap());
+        assert!(
+            card.set_column_hollerith(3, HollerithCode::new(vec![5]))
+                .is_err()
+        );
+        assert!(card.variant_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_port_a_punch_from_text_skips_columns() {
+        let card = PunchCard::from_text_with_variant("HELLO", CardVariant::PortAPunch);
+        assert_eq!(card.to_text_trimmed(), "H E L L O");
+        let long = PunchCard::from_text_with_variant(&"X".repeat(50), CardVariant::PortAPunch);
+        assert_eq!(long.punched_count(), 40);
+    }
+
+    #[test]
+    fn test_port_a_punch_binary_load_warns() {
+        let mut card = PunchCard::from_binary(&PunchCard::from_text("AB").to_binary());
+        card.set_variant(CardVariant::PortAPunch);
+        assert_eq!(
+            card.variant_warnings(),
+            vec!["Column 2: punched, but Port-A-Punch cards cannot punch this column"]
+        );
+    }
+
+    #[test]
+    fn test_set_printed_char() {
+        let mut card = PunchCard::from_text("A");
+        let column = card.get_column_mut(0).unwrap();
+        column.set_printed_char(Some('X'));
+        assert_eq!(column.printed_char, Some('X'));
+        assert_eq!(column.to_char(), Some('A'));
+        column.set_printed_char(None);
+        assert_eq!(column.printed_char, None);
+    }
+
+    #[test]
+    fn test_reprint_and_clear_printing() {
+        let mut card = PunchCard::from_binary(&PunchCard::from_text("HI, 5").to_binary());
+        card.set_column_hollerith(10, HollerithCode::new(vec![12, 11, 0]))
+            .unwrap();
+        assert!(card.columns().iter().all(|col| col.printed_char.is_none()));
+
+        card.reprint_from_punches();
+        assert_eq!(card.get_column(2).unwrap().printed_char, Some(','));
+        assert_eq!(card.get_column(4).unwrap().printed_char, Some('5'));
+        assert_eq!(card.get_column(10).unwrap().printed_char, None);
+        assert!(card.to_text().starts_with("HI, 5"));
+
+        let before = card.content_hash();
+        card.clear_printing();
+        assert!(card.columns().iter().all(|col| col.printed_char.is_none()));
+        assert_eq!(card.content_hash(), before);
+    }
+
+    #[test]
+    fn test_from_ebcdic_prints_every_decodable_column() {
+        let card = PunchCard::from_ebcdic(&PunchCard::from_text("A&-/").to_ebcdic().unwrap());
+        let printed: String = card.columns()[..4]
+            .iter()
+            .map(|col| col.printed_char.unwrap())
+            .collect();
+        assert_eq!(printed, "A&-/");
+
+        // A source line full of 8-punch specials survives EBCDIC intact
+        let line = "      X = (A+B)*C/D.  IF (X .GT. 0) GO TO 10 ; $1,000 @ 5% 'OK' \"Q?\"";
+        let ebcdic = PunchCard::from_text(line).to_ebcdic().unwrap();
+        assert_eq!(&ebcdic[8..11], &[0x7E, 0x40, 0x4D]);
+        let card = PunchCard::from_ebcdic(&ebcdic);
+        assert_eq!(card.to_text().trim_end(), line);
+        assert!(card.columns().iter().all(|col| col.printed_char.is_some()));
+    }
+
+    #[test]
+    fn test_from_ebcdic_prints_specials() {
+        let specials = "&-/¢.<(+|!$*);¬,%_>?:#@'=\"";
+        let record: Vec<u8> = specials
+            .chars()
+            .map(|c| crate::ebcdic::char_to_ebcdic(c).unwrap())
+            .collect();
+        assert_eq!(&record[..4], &[0x50, 0x60, 0x61, 0x4A]);
+        let card = PunchCard::from_ebcdic(&record);
+        for (column, c) in card.columns().iter().zip(specials.chars()) {
+            assert_eq!(column.punches, char_to_hollerith(c).unwrap(), "{:?}", c);
+            assert_eq!(column.printed_char, Some(c));
+        }
+        assert!(
+            card.columns()[specials.chars().count()..]
+                .iter()
+                .all(Column::is_blank)
+        );
+    }
+
+    #[test]
+    fn test_to_ebcdic_reports_unmapped_columns() {
+        let mut card = PunchCard::from_text("OBJ");
+        card.set_column_hollerith(1, HollerithCode::new(vec![12, 11, 0, 1, 2]))
+            .unwrap();
+        card.set_column_hollerith(79, HollerithCode::from_array([true; 12]))
+            .unwrap();
+        let err = card.to_ebcdic().unwrap_err();
+        assert_eq!(err.columns, vec![1, 79]);
+        assert_eq!(err.to_string(), "No EBCDIC byte for column(s) 2, 80");
+        assert_eq!(card.to_ebcdic_with(CodePage::Cp037).unwrap_err(), err);
+
+        let lossy = card.to_ebcdic_lossy();
+        assert_eq!(&lossy[..3], &[0xD6, 0x40, 0xD1]);
+        assert_eq!(lossy[79], 0x40);
+    }
+
+    #[test]
+    fn test_lowercase_ebcdic_roundtrip() {
+        let alphabet = "abcdefghijklmnopqrstuvwxyz";
+        let record: Vec<u8> = alphabet
+            .chars()
+            .map(|c| crate::ebcdic::char_to_ebcdic(c).unwrap())
+            .collect();
+        let card = PunchCard::from_ebcdic(&record);
+        for (column, c) in card.columns().iter().zip(alphabet.chars()) {
+            let code = crate::hollerith::char_to_hollerith_with(Charset::Ibm029Extended, c);
+            assert_eq!(Some(&column.punches), code.as_ref(), "{:?}", c);
+            assert_eq!(column.printed_char, Some(c));
+        }
+        assert_eq!(card.columns()[0].punches.rows, vec![0, 1, 12]);
+        assert_eq!(&card.to_ebcdic().unwrap()[..26], &record[..]);
+        assert_eq!(
+            &card.to_ebcdic_with(CodePage::Cp037).unwrap()[..26],
+            &record[..]
+        );
+
+        let binary = PunchCard::from_binary(&card.to_binary());
+        assert!(binary.punches_eq(&card));
+        assert_eq!(&binary.to_ebcdic().unwrap()[..26], &record[..]);
+        let (text, _) = binary
+            .to_text_with(&Charset::Ibm029Extended, &DecodeOptions::default())
+            .unwrap();
+        assert_eq!(text.trim_end(), alphabet);
+    }
+
+    #[test]
+    fn test_ebcdic_control_policies() {
+        let data = [0xC1, 0x05, 0xC2, 0x15, 0xC3];
+        let load = |controls| {
+            let options = EbcdicLoadOptions {
+                controls,
+                ..EbcdicLoadOptions::default()
+            };
+            PunchCard::from_ebcdic_with(&data, &options)
+        };
+
+        let punched = load(ControlPolicy::Punch).unwrap();
+        assert!(punched.punches_eq(&PunchCard::from_ebcdic(&data)));
+        assert_eq!(punched.columns()[1].punches.rows, vec![5, 9, 12]);
+        assert_eq!(punched.columns()[1].printed_char, None);
+
+        let blanked = load(ControlPolicy::Blank).unwrap();
+        assert_eq!(blanked.to_text_trimmed(), "A B C");
+        assert!(blanked.columns()[1].is_blank());
+
+        let err = load(ControlPolicy::Error).unwrap_err();
+        assert_eq!(
+            err,
+            ControlByte {
+                column: 1,
+                byte: 0x05
             }
+        );
+        assert_eq!(err.to_string(), "Column 2: control byte 0x05");
+
+        let line = load(ControlPolicy::RecordBoundary).unwrap();
+        assert_eq!(line.to_text_trimmed(), "A B");
+    }
+
+    #[test]
+    fn test_ebcdic_code_pages() {
+        let card = PunchCard::from_text("A|B!C¬");
+        let card_code = card.to_ebcdic().unwrap();
+        assert_eq!(card.to_ebcdic_with(CodePage::CardCode).unwrap(), card_code);
+        assert_eq!(card.to_ebcdic_with(CodePage::Cp037).unwrap(), card_code);
+        assert_eq!(
+            &card.to_ebcdic_with(CodePage::Cp500).unwrap()[..6],
+            &[0xC1, 0xBB, 0xC2, 0x4F, 0xC3, 0xBA]
+        );
+        assert_eq!(
+            &card.to_ebcdic_with(CodePage::Cp1047).unwrap()[..6],
+            &[0xC1, 0x4F, 0xC2, 0x5A, 0xC3, 0xB0]
+        );
+        for page in [CodePage::Cp037, CodePage::Cp500, CodePage::Cp1047] {
+            let options = EbcdicLoadOptions {
+                code_page: page,
+                ..EbcdicLoadOptions::default()
+            };
+            let loaded =
+                PunchCard::from_ebcdic_with(&card.to_ebcdic_with(page).unwrap(), &options).unwrap();
+            assert!(loaded.punches_eq(&card), "{:?}", page);
         }
+
+        // CP500 0x4A is '[', which the 029 cannot punch, so it keeps its
+        // card code (12-8-2, the 029's ¢)
+        let options = EbcdicLoadOptions {
+            code_page: CodePage::Cp500,
+            ..EbcdicLoadOptions::default()
+        };
+        let loaded = PunchCard::from_ebcdic_with(&[0x4A, 0x4F], &options).unwrap();
+        assert_eq!(loaded.columns()[0].punches, ebcdic_to_hollerith(0x4A));
+        assert_eq!(loaded.columns()[1].printed_char, Some('!'));
+        assert_eq!(loaded.columns()[1].punches, char_to_hollerith('!').unwrap());
+    }
+
+    #[test]
+    fn test_decode_failures() {
+        let mut card = PunchCard::from_text("ABC");
+        card.set_column_hollerith(1, HollerithCode::new(vec![12, 11, 0]))
+            .unwrap();
+        card.set_column_hollerith(2, HollerithCode::new(vec![12, 0, 1]))
+            .unwrap();
+        assert_eq!(&card.to_text()[..3], "A??");
+        assert_eq!(
+            card.decode_failures(),
+            vec![
+                (1, DecodeFailure::IllegalZoneCombination),
+                (2, DecodeFailure::NotInCharacterSet)
+            ]
+        );
+        assert!(PunchCard::from_text("OK").decode_failures().is_empty());
+
+        let (text, report) = card
+            .to_text_with_options(&DecodeOptions::default())
+            .unwrap();
+        assert_eq!(text, card.to_text());
+        assert_eq!(report.failures, card.decode_failures());
+        let strict = DecodeOptions {
+            replacement: None,
+            ..DecodeOptions::default()
+        };
+        assert!(card.to_text_with_options(&strict).is_err());
+    }
+
+    #[test]
+    fn test_u16_columns() {
+        let card = PunchCard::from_text("A9");
+        let columns = card.to_u16_columns();
+        assert_eq!(&columns[..3], &[0x0900, 0x0001, 0]);
+        let loaded = PunchCard::from_u16_columns(&columns[..2]);
+        assert_eq!(loaded.card_type(), CardType::Binary);
+        assert!(loaded.punches_eq(&card));
+        assert!(PunchCard::from_u16_columns(&[0xF000]).columns()[0].is_blank());
+    }
+
+    #[test]
+    fn test_bcdic_roundtrip() {
+        let card = PunchCard::from_text("PAY 1401 $12.50, NET-3 / A&Z");
+        let bcdic = card.to_bcdic().unwrap();
+        assert_eq!(bcdic.len(), 80);
+        assert!(bcdic.iter().all(|&code| code < 64));
+        assert_eq!(&bcdic[..3], &[0o47, 0o61, 0o30]);
+        let loaded = PunchCard::from_bcdic(&bcdic);
+        assert!(loaded.punches_eq(&card));
+        assert_eq!(loaded.to_text(), card.to_text());
+    }
+
+    #[test]
+    fn test_to_bcdic_reports_unmappable_columns() {
+        let mut card = PunchCard::from_text("A B C");
+        card.set_column_hollerith(1, HollerithCode::new(vec![12, 0, 1]))
+            .unwrap();
+        card.set_column_hollerith(79, HollerithCode::from_array([true; 12]))
+            .unwrap();
+        let err = card.to_bcdic().unwrap_err();
+        assert_eq!(err.columns, vec![1, 79]);
+        assert_eq!(err.to_string(), "No BCD code for column(s) 2, 80");
+    }
+
+    #[test]
+    fn test_columns_array() {
+        let card = PunchCard::from_text("HI");
+        let array: &[Column; 80] = card.columns_array();
+        assert_eq!(array[1].to_char(), Some('I'));
+
+        let stub = PunchCard::from_text_with_width(&"X".repeat(80), 51).unwrap();
+        assert_eq!(stub.columns().len(), 51);
+        assert!(stub.columns_array()[51..].iter().all(Column::is_blank));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_shape_is_unchanged() {
+        let card = PunchCard::from_text("AB");
+        let value = serde_json::to_value(&card).unwrap();
+        assert_eq!(value["columns"].as_array().unwrap().len(), 80);
+        assert_eq!(
+            value["columns"][0]["punches"]["rows"],
+            serde_json::json!([1, 12])
+        );
+        assert_eq!(value["card_type"], "Text");
+        let back: PunchCard = serde_json::from_value(value).unwrap();
+        assert_eq!(back, card);
+
+        let stub = PunchCard::from_text_with_width("AB", 51).unwrap();
+        let value = serde_json::to_value(&stub).unwrap();
+        assert_eq!(value["columns"].as_array().unwrap().len(), 51);
+        assert_eq!(serde_json::from_value::<PunchCard>(value).unwrap(), stub);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_reads_cards_without_newer_fields() {
+        // Cards serialized before metadata and variants existed
+        let blank = serde_json::json!({"punches": {"rows": []}, "printed_char": null});
+        let mut columns = vec![blank; 80];
+        columns[0] = serde_json::json!({"punches": {"rows": [1, 12]}, "printed_char": "A"});
+        let value = serde_json::json!({"columns": columns, "card_type": "Text"});
+        let card: PunchCard = serde_json::from_value(value).unwrap();
+        assert_eq!(card.to_text_trimmed(), "A");
+        assert_eq!(card.variant(), CardVariant::Standard);
+        assert!(card.metadata().is_default());
+
+        let too_many = serde_json::json!({"columns": vec![serde_json::json!({"punches": {"rows": []}, "printed_char": null}); 81], "card_type": "Text"});
+        assert!(serde_json::from_value::<PunchCard>(too_many).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_card_width_serde_is_checked() {
+        assert_eq!(serde_json::to_value(CardWidth::STUB_51).unwrap(), 51);
+        assert_eq!(
+            serde_json::from_value::<CardWidth>(serde_json::json!(51)).unwrap(),
+            CardWidth::STUB_51
+        );
+        assert!(serde_json::from_value::<CardWidth>(serde_json::json!(0)).is_err());
+        assert!(serde_json::from_value::<CardWidth>(serde_json::json!(81)).is_err());
+        assert_eq!(CardWidth::try_from(80), Ok(CardWidth::STANDARD));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_replacement_policy_serde_is_checked() {
+        let policy = UnsupportedCharPolicy::Replace(ReplacementChar::new('(').unwrap());
+        let value = serde_json::to_value(policy).unwrap();
+        assert_eq!(value, serde_json::json!({"Replace": "("}));
+        assert_eq!(
+            serde_json::from_value::<UnsupportedCharPolicy>(value).unwrap(),
+            policy
+        );
+        let bad = serde_json::json!({"Replace": "~"});
+        assert!(serde_json::from_value::<UnsupportedCharPolicy>(bad).is_err());
+    }
+
+    #[test]
+    fn test_to_binary_into_matches_to_binary() {
+        let card = PunchCard::from_text("HELLO, WORLD. 1130 ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+        let mut buf = [0xFFu8; 108];
+        card.to_binary_into(&mut buf);
+        assert_eq!(buf.as_slice(), card.to_binary().as_slice());
+        assert!(PunchCard::from_binary(&buf).punches_eq(&card));
+    }
+
+    #[test]
+    fn test_hash_set_dedups_cards() {
+        use std::collections::HashSet;
+
+        let from_text = PunchCard::from_text("HI");
+        let mut built = PunchCard::new(CardType::Text);
+        built.set_column_char(0, 'H').unwrap();
+        built.set_column_char(1, 'I').unwrap();
+        let mut cards = HashSet::new();
+        assert!(cards.insert(from_text.clone()));
+        assert!(!cards.insert(built));
+        assert!(cards.insert(PunchCard::from_text("HO")));
+        assert_eq!(cards.len(), 2);
+
+        // Row order does not matter: H = 12-8
+        let codes: HashSet<HollerithCode> = [
+            HollerithCode::new(vec![12, 8]),
+            HollerithCode::new(vec![8, 12, 8]),
+            HollerithCode::from_array(from_text.columns()[0].punches.as_array()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(codes.len(), 1);
+    }
+
+    #[test]
+    fn test_punches_hash_matches_punches_eq() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn punches_hash(card: &PunchCard) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            card.punches_hash(&mut hasher);
+            hasher.finish()
+        }
+
+        // A binary round trip drops the printed characters and card type
+        let text = PunchCard::from_text("HELLO");
+        let binary = PunchCard::from_binary(&text.to_binary());
+        assert_ne!(text, binary);
+        assert!(text.punches_eq(&binary));
+        assert_eq!(punches_hash(&text), punches_hash(&binary));
+        assert_ne!(
+            punches_hash(&text),
+            punches_hash(&PunchCard::from_text("HELP"))
+        );
+    }
+
+    #[test]
+    fn test_from_text_with_substitutions() {
+        let map = SubstitutionMap::ascii_approximation();
+        let (card, report) = PunchCard::from_text_with_substitutions("x = a[i] ~ 1", &map);
+        assert!(card.punches_eq(&PunchCard::from_text("X = A(I) - 1")));
+        assert_eq!(card.to_text().trim_end(), "X = A(I) - 1");
+        assert_eq!(card.columns()[5].printed_char, Some('('));
+        assert_eq!(report.to_string(), "3 characters were approximated");
+
+        // Only the first 80 characters are punched or reported
+        let long = format!("{:80}[", "");
+        let (_, report) = PunchCard::from_text_with_substitutions(&long, &map);
+        assert!(report.substitutions.is_empty());
+    }
+    #[test]
+    fn test_from_text_with_charset() {
+        use crate::hollerith::hollerith_to_char_with;
+
+        let text = "PRICE<$5.00>@";
+        let card_029 = PunchCard::from_text_with_charset(text, Charset::Ibm029);
+        let card_026 = PunchCard::from_text_with_charset(text, Charset::Ibm026Commercial);
+        assert!(card_029.punches_eq(&PunchCard::from_text(text)));
+
+        // The 026 commercial set has no < or >, so those columns stay blank
+        let differing: Vec<usize> = (0..80)
+            .filter(|&i| !card_029.columns()[i].punches_eq(&card_026.columns()[i]))
+            .collect();
+        assert_eq!(differing, vec![5, 11]);
+        assert!(card_026.columns()[5].is_blank());
+        assert_eq!(card_026.columns()[5].printed_char, Some('<'));
+
+        // The 029's < holes (12-4-8) read as a lozenge on the 026
+        let lt = &card_029.columns()[5].punches;
+        assert_eq!(
+            hollerith_to_char_with(Charset::Ibm026Commercial, lt),
+            Some('⌑')
+        );
+    }
+
+    /// Decode a card in a character set with the default options
+    fn text_in(card: &PunchCard, charset: &dyn CharacterSet) -> String {
+        card.to_text_with(charset, &DecodeOptions::default())
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_from_text_with_ansi_keeps_case() {
+        let text = "Hello, [World]! {x^2}";
+        let card = PunchCard::from_text_with(text, &Charset::AnsiX326);
+        assert_eq!(card.columns()[1].printed_char, Some('e'));
+        assert_eq!(card.columns()[1].punches.rows, vec![0, 5, 12]);
+        assert!(text_in(&card, &Charset::AnsiX326).starts_with(text));
+        // The 029 has no lowercase, so it still uppercases
+        let upper = PunchCard::from_text_with_charset("Hello", Charset::Ibm029);
+        assert!(upper.to_text().starts_with("HELLO"));
+    }
+
+    #[test]
+    fn test_to_text_with_case_policy() {
+        use crate::hollerith::CasePolicy;
+
+        let set = Charset::Ibm029Extended;
+        let card = PunchCard::from_text_with("Mixed Case", &set);
+        let before = card.clone();
+        let decode = |case| {
+            let options = DecodeOptions {
+                case,
+                ..DecodeOptions::default()
+            };
+            let (text, report) = card.to_text_with(&set, &options).unwrap();
+            assert!(report.is_clean());
+            text.trim_end().to_string()
+        };
+        assert_eq!(decode(CasePolicy::Preserve), "Mixed Case");
+        assert_eq!(decode(CasePolicy::Upper), "MIXED CASE");
+        assert_eq!(decode(CasePolicy::Lower), "mixed case");
+        assert!(card.punches_eq(&before));
+        assert_eq!(card.columns()[1].printed_char, Some('i'));
+
+        // In the 029 the lowercase patterns do not decode at all
+        let (text, report) = card
+            .to_text_with_options(&DecodeOptions::default())
+            .unwrap();
+        assert!(text.starts_with("M???? C???"));
+        assert_eq!(report.failures.len(), 7);
+    }
+
+    #[test]
+    fn test_lowercase_pipeline_roundtrip() {
+        let set = Charset::Ibm029Extended;
+        assert_eq!(Column::from_char_with('q', &set).printed_char, Some('q'));
+        assert_eq!(
+            Column::from_char_with('q', &Charset::Ibm029).printed_char,
+            Some('Q')
+        );
+
+        let text = "Mixed Case deck of 42 cards";
+        let card = PunchCard::from_text_with(text, &set);
+        let ebcdic = card.to_ebcdic().unwrap();
+        assert_eq!(&ebcdic[..2], &[0xD4, 0x89]);
+        let loaded = PunchCard::from_ebcdic(&ebcdic);
+        assert!(loaded.punches_eq(&card));
+        assert!(text_in(&loaded, &set).starts_with(text));
+    }
+
+    #[test]
+    fn test_record_and_group_marks_roundtrip() {
+        let set = Charset::Ibm029Extended;
+        let card = PunchCard::from_text_with("REC1‡REC2‡⧧", &set);
+        let from_ebcdic = PunchCard::from_ebcdic(&card.to_ebcdic().unwrap());
+        let from_binary = PunchCard::from_binary(&card.to_binary());
+        for loaded in [from_ebcdic, from_binary] {
+            assert!(loaded.punches_eq(&card));
+            assert!(text_in(&loaded, &set).starts_with("REC1‡REC2‡⧧ "));
+        }
+    }
+
+    #[test]
+    fn test_from_text_with_custom_set() {
+        use crate::hollerith::TableCharacterSet;
+
+        let table: &[(char, &[u8])] = &[('A', &[12, 1]), ('b', &[12, 0, 2]), ('€', &[11, 0, 8])];
+        let set = TableCharacterSet::new(table).unwrap();
+        let card = PunchCard::from_text_with("Ab€z", &set);
+        assert_eq!(card.columns()[1].printed_char, Some('b'));
+        assert_eq!(card.columns()[2].punches.rows, vec![0, 8, 11]);
+        assert!(card.columns()[3].is_blank());
+        assert_eq!(card.columns()[3].printed_char, Some('Z'));
+        let decoded: String = text_in(&card, &set).chars().take(4).collect();
+        assert_eq!(decoded, "Ab€ ");
+        assert!(text_in(&card, &Charset::Ibm029).starts_with("A?? "));
     }
 }
@@ -0,0 +1,210 @@
+// Remington Rand 90-Column Card Module
+//
+// The Remington Rand (later UNIVAC) card has the same size as the IBM card
+// but uses small round holes in two tiers of 45 columns. Each column has six
+// punch positions, labelled 0, 1, 3, 5, 7, 9.
+//
+// Digits follow the published chart: the odd digits and 0 are single
+// punches, and each even digit is the odd digit below it plus the 9 punch
+// (2 = 1-9, 4 = 3-9, 6 = 5-9, 8 = 7-9). Letters and special characters are
+// not supported yet: their codes have not been transcribed from the chart.
+//
+// Binary format: 68 bytes, packed as in `sixbit` (position 0 of column 1 is
+// the high bit of byte 0); the final four bits are zero.
+
+use crate::punch_card::PunchCard;
+use crate::sixbit::{CodeTable, SixBitColumns};
+
+/// Number of columns on a 90-column card
+pub const REMRAND_COLUMNS: usize = 90;
+/// Number of columns in each of the two tiers
+pub const REMRAND_TIER_COLUMNS: usize = 45;
+/// Size of the packed binary format in bytes
+pub const REMRAND_BINARY_LEN: usize = SixBitColumns::<REMRAND_COLUMNS>::BINARY_LEN;
+
+/// Punch positions in bit order (position 0 is the high bit)
+pub const REMRAND_POSITIONS: [u8; 6] = [0, 1, 3, 5, 7, 9];
+
+/// Character to punch code table (blank is the empty code 0)
+pub(crate) const CODE_TABLE: CodeTable = CodeTable(&[
+    ('0', 0b100000), // 0
+    ('1', 0b010000), // 1
+    ('2', 0b010001), // 1-9
+    ('3', 0b001000), // 3
+    ('4', 0b001001), // 3-9
+    ('5', 0b000100), // 5
+    ('6', 0b000101), // 5-9
+    ('7', 0b000010), // 7
+    ('8', 0b000011), // 7-9
+    ('9', 0b000001), // 9
+]);
+
+/// Convert a character to its 90-column punch code
+///
+/// Returns None for characters with no code.
+pub fn char_to_remrand(c: char) -> Option<u8> {
+    CODE_TABLE.encode(c)
+}
+
+/// Convert a 90-column punch code to its character
+///
+/// Returns None for unassigned codes and values wider than 6 bits.
+pub fn remrand_to_char(code: u8) -> Option<char> {
+    CODE_TABLE.decode(code)
+}
+
+/// A Remington Rand 90-column card
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemRandCard {
+    /// Punch code for each column (low 6 bits, position 0 highest)
+    columns: SixBitColumns<REMRAND_COLUMNS>,
+}
+
+impl RemRandCard {
+    /// Create a blank card
+    pub fn new() -> Self {
+        RemRandCard::default()
+    }
+
+    /// Create a card from text (max 90 characters)
+    ///
+    /// Fails on text that is too long or contains characters with no code.
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        SixBitColumns::from_text(CODE_TABLE, text).map(|columns| RemRandCard { columns })
+    }
+
+    /// Decode the card as text, using '?' for unassigned codes
+    ///
+    /// Trailing blanks are kept, so the result is always 90 characters.
+    pub fn to_text(&self) -> String {
+        self.columns.to_text(CODE_TABLE)
+    }
+
+    /// Get the punch code for a column (0-based)
+    pub fn get(&self, index: usize) -> Option<u8> {
+        self.columns.get(index)
+    }
+
+    /// Set the punch code for a column (0-based)
+    pub fn set(&mut self, index: usize, code: u8) -> Result<(), &'static str> {
+        self.columns.set(index, code)
+    }
+
+    /// Get the punched positions (0, 1, 3, 5, 7, 9) of a column (0-based)
+    pub fn punched_positions(&self, index: usize) -> Option<Vec<u8>> {
+        let code = self.get(index)?;
+        Some(
+            REMRAND_POSITIONS
+                .iter()
+                .enumerate()
+                .filter(|&(bit, _)| code & (0b100000 >> bit) != 0)
+                .map(|(_, &pos)| pos)
+                .collect(),
+        )
+    }
+
+    /// Get all column codes
+    pub fn columns(&self) -> &[u8] {
+        self.columns.codes()
+    }
+
+    /// Get the columns of one tier (0 = upper, 1 = lower)
+    pub fn tier(&self, tier: usize) -> Option<&[u8]> {
+        self.columns.tier(tier, REMRAND_TIER_COLUMNS)
+    }
+
+    /// Pack the card into the 68-byte binary format
+    pub fn to_binary(&self) -> Vec<u8> {
+        self.columns.to_binary()
+    }
+
+    /// Unpack a card from the 68-byte binary format
+    ///
+    /// The four padding bits at the end must be zero.
+    pub fn from_binary(data: &[u8]) -> Result<Self, String> {
+        SixBitColumns::from_binary(data).map(|columns| RemRandCard { columns })
+    }
+
+    /// Convert to an 80-column card for display (lossy)
+    ///
+    /// Each column's character is punched in 029 code; columns 81-90 are
+    /// dropped and unassigned codes become '?'.
+    pub fn to_punch_card(&self) -> PunchCard {
+        PunchCard::from_text(&self.to_text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full_charset() -> String {
+        CODE_TABLE.chars()
+    }
+
+    #[test]
+    fn test_digit_codes() {
+        let card = RemRandCard::from_text("0123456789").unwrap();
+        let positions: Vec<Vec<u8>> = (0..10)
+            .map(|i| card.punched_positions(i).unwrap())
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                vec![0],
+                vec![1],
+                vec![1, 9],
+                vec![3],
+                vec![3, 9],
+                vec![5],
+                vec![5, 9],
+                vec![7],
+                vec![7, 9],
+                vec![9],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_roundtrip_full_charset() {
+        let text = full_charset();
+        let card = RemRandCard::from_text(&text).unwrap();
+        assert_eq!(card.to_text().trim_end(), text.trim_end());
+        assert_eq!(card.to_text().chars().count(), REMRAND_COLUMNS);
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let card = RemRandCard::from_text(&full_charset().repeat(9)[..90]).unwrap();
+        let data = card.to_binary();
+        assert_eq!(data.len(), 68);
+        assert_eq!(RemRandCard::from_binary(&data).unwrap(), card);
+        assert!(RemRandCard::from_binary(&data[..67]).is_err());
+
+        let mut padded = data.clone();
+        padded[67] |= 0x01;
+        assert!(RemRandCard::from_binary(&padded).is_err());
+    }
+
+    #[test]
+    fn test_validation_and_tiers() {
+        assert!(RemRandCard::from_text(&"1".repeat(91)).is_err());
+        assert_eq!(
+            RemRandCard::from_text("1A").unwrap_err(),
+            "Column 2: character 'A' has no punch code"
+        );
+
+        let mut card = RemRandCard::new();
+        assert!(card.set(90, 1).is_err());
+        assert!(card.set(0, 0x40).is_err());
+        card.set(45, char_to_remrand('8').unwrap()).unwrap();
+        assert_eq!(card.tier(1).unwrap()[0], char_to_remrand('8').unwrap());
+        assert!(card.tier(2).is_none());
+    }
+
+    #[test]
+    fn test_to_punch_card() {
+        let card = RemRandCard::from_text("1108 90").unwrap();
+        assert_eq!(card.to_punch_card().to_text_trimmed(), "1108 90");
+    }
+}
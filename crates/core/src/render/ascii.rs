@@ -0,0 +1,186 @@
+// ASCII Art Rendering
+//
+// Renders a punch card as plain text for terminals and logs:
+//
+//       HELLO
+//       ----+----1----+----2
+//   12  O.O......
+//   11  ......
+//    0  ......
+//   ... (rows 1-9)
+
+use crate::punch_card::PunchCard;
+use std::ops::RangeInclusive;
+
+/// Row labels in card order, matching `HollerithCode::as_array`
+const ROW_LABELS: [&str; 12] = ["12", "11", "0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+/// Character drawn for an unpunched position
+const HOLE_CHAR: char = '.';
+
+/// Options for `to_ascii_art`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsciiArtOptions {
+    /// Character drawn for a punched hole
+    pub punch_char: char,
+    /// Prefix each punch row with its row label (12, 11, 0-9)
+    pub show_row_labels: bool,
+    /// Draw a ruler line marking every 5th and 10th column
+    pub show_column_ruler: bool,
+    /// The 1-based columns to render, e.g. `1..=40` for the left half
+    pub columns: RangeInclusive<usize>,
+}
+
+impl Default for AsciiArtOptions {
+    fn default() -> Self {
+        AsciiArtOptions {
+            punch_char: 'O',
+            show_row_labels: true,
+            show_column_ruler: true,
+            columns: 1..=80,
+        }
+    }
+}
+
+/// Render a card as multi-line ASCII art
+///
+/// Output is the printed character row, an optional column ruler, and the
+/// 12 punch rows, each line ending in a newline. Columns outside the card are
+/// ignored, so a window can be rendered to fit a narrow terminal.
+pub fn render(card: &PunchCard, options: &AsciiArtOptions) -> String {
+    let first = (*options.columns.start()).max(1);
+    let last = (*options.columns.end()).min(card.columns().len());
+    let columns = if first <= last {
+        &card.columns()[first - 1..last]
+    } else {
+        &[]
+    };
+    let prefix = if options.show_row_labels { "    " } else { "" };
+    let mut out = String::new();
+
+    out.push_str(prefix);
+    out.extend(columns.iter().map(|col| col.printed_char.unwrap_or(' ')));
+    out.push('\n');
+
+    if options.show_column_ruler {
+        out.push_str(prefix);
+        out.extend((first..first + columns.len()).map(|col| {
+            if col % 10 == 0 {
+                char::from(b'0' + (col / 10 % 10) as u8)
+            } else if col % 5 == 0 {
+                '+'
+            } else {
+                '-'
+            }
+        }));
+        out.push('\n');
+    }
+
+    for (row_idx, label) in ROW_LABELS.iter().enumerate() {
+        if options.show_row_labels {
+            out.push_str(&format!("{:>2}  ", label));
+        }
+        out.extend(columns.iter().map(|col| {
+            if col.punches.as_array()[row_idx] {
+                options.punch_char
+            } else {
+                HOLE_CHAR
+            }
+        }));
+        out.push('\n');
+    }
+
+    out
+}
+
+impl PunchCard {
+    /// Render the card as multi-line ASCII art (see `render::ascii::render`)
+    pub fn to_ascii_art(&self, options: &AsciiArtOptions) -> String {
+        render(self, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::punch_card::CardType;
+
+    const GOLDEN_FULL: &str = include_str!("../../tests/fixtures/ascii_art_full.txt");
+    const GOLDEN_RIGHT_HALF: &str = include_str!("../../tests/fixtures/ascii_art_right_half.txt");
+
+    fn example_card() -> PunchCard {
+        let mut card = PunchCard::from_text("HELLO, WORLD 0123456789 &-/.$*");
+        card.set_column_char(79, 'Z').unwrap();
+        card
+    }
+
+    #[test]
+    fn test_ascii_art_golden_full() {
+        let art = example_card().to_ascii_art(&AsciiArtOptions::default());
+        assert_eq!(art, GOLDEN_FULL);
+    }
+
+    #[test]
+    fn test_ascii_art_golden_right_half() {
+        let options = AsciiArtOptions {
+            punch_char: '#',
+            columns: 41..=80,
+            ..AsciiArtOptions::default()
+        };
+        let art = example_card().to_ascii_art(&options);
+        assert_eq!(art, GOLDEN_RIGHT_HALF);
+    }
+
+    #[test]
+    fn test_ascii_art_line_count_and_width() {
+        let art = example_card().to_ascii_art(&AsciiArtOptions::default());
+        let lines: Vec<&str> = art.lines().collect();
+        assert_eq!(lines.len(), 14);
+        assert!(lines.iter().all(|line| line.chars().count() == 84));
+    }
+
+    #[test]
+    fn test_ascii_art_without_labels_or_ruler() {
+        let options = AsciiArtOptions {
+            punch_char: 'X',
+            show_row_labels: false,
+            show_column_ruler: false,
+            columns: 1..=3,
+        };
+        let art = PunchCard::from_text("A1").to_ascii_art(&options);
+        let expected = "A1 \n\
+                        X..\n\
+                        ...\n\
+                        ...\n\
+                        XX.\n\
+                        ...\n\
+                        ...\n\
+                        ...\n\
+                        ...\n\
+                        ...\n\
+                        ...\n\
+                        ...\n\
+                        ...\n";
+        assert_eq!(art, expected);
+    }
+
+    #[test]
+    fn test_ascii_art_binary_card_has_blank_print_row() {
+        let card = PunchCard::from_binary(&PunchCard::from_text("AB").to_binary());
+        let art = card.to_ascii_art(&AsciiArtOptions::default());
+        assert_eq!(art.lines().next().unwrap().trim(), "");
+    }
+
+    #[test]
+    fn test_ascii_art_clamps_columns() {
+        let options = AsciiArtOptions {
+            columns: 0..=200,
+            ..AsciiArtOptions::default()
+        };
+        let card = PunchCard::new(CardType::Text);
+        assert_eq!(
+            card.to_ascii_art(&options),
+            card.to_ascii_art(&AsciiArtOptions::default())
+        );
+    }
+}
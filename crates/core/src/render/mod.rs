@@ -0,0 +1,53 @@
+// Render Module
+//
+// Produces visual representations of punch cards outside the web UI
+
+pub mod ascii;
+#[cfg(feature = "png")]
+pub mod png;
+pub mod svg;
+
+/// Card aspect ratio: 7⅜" × 3¼"
+pub const CARD_ASPECT_RATIO: f64 = 2.269;
+
+/// Layout of the card derived from its width
+pub(crate) struct Geometry {
+    pub card_width: f64,
+    pub card_height: f64,
+    pub left_margin: f64,
+    pub top_margin: f64,
+    pub col_width: f64,
+    pub row_height: f64,
+    /// Scale of fixed-size text and offsets relative to an 800-unit card
+    pub scale: f64,
+}
+
+impl Geometry {
+    pub fn new(card_width: f64) -> Self {
+        let card_height = card_width / CARD_ASPECT_RATIO;
+        // Reduced margins for better visual appearance (smaller than ANSI spec)
+        let left_margin = card_width * 0.025;
+        let right_margin = card_width * 0.025;
+        let top_margin = card_height * 0.045;
+        let bottom_margin = card_height * 0.045;
+        Geometry {
+            card_width,
+            card_height,
+            left_margin,
+            top_margin,
+            col_width: (card_width - left_margin - right_margin) / 80.0,
+            row_height: (card_height - top_margin - bottom_margin) / 12.0,
+            scale: card_width / 800.0,
+        }
+    }
+
+    /// X coordinate of the center of a column
+    pub fn col_center(&self, col: usize) -> f64 {
+        self.left_margin + col as f64 * self.col_width + self.col_width / 2.0
+    }
+
+    /// Y coordinate of the center of a row (index in card order 12, 11, 0-9)
+    pub fn row_center(&self, row_idx: usize) -> f64 {
+        self.top_margin + row_idx as f64 * self.row_height + self.row_height / 2.0
+    }
+}
@@ -0,0 +1,421 @@
+// PNG Rendering (requires the `png` feature)
+//
+// Rasterizes a punch card by drawing primitives directly, using the same
+// geometry as the SVG renderer: stock with a transparent corner cut, guide
+// holes, pre-printed digits, column numbers, printed characters (from a
+// built-in 5×7 bitmap font), and solid rectangular punches.
+
+use super::Geometry;
+use crate::punch_card::{CardType, PunchCard};
+
+/// Smallest supported image width (one pixel per column plus margins)
+pub const MIN_WIDTH_PX: u32 = 100;
+/// Largest supported image width
+pub const MAX_WIDTH_PX: u32 = 16_000;
+
+type Rgba = [u8; 4];
+
+const CARD_COLOR: Rgba = [0xf4, 0xe8, 0xd0, 0xff];
+const BORDER_COLOR: Rgba = [0x99, 0x99, 0x99, 0xff];
+const PUNCH_COLOR: Rgba = [0x00, 0x00, 0x00, 0xff];
+const TEXT_COLOR: Rgba = [0x00, 0x00, 0x00, 0xff];
+const GUIDE_COLOR: Rgba = [0xcc, 0xcc, 0xcc, 0xff];
+const DIGIT_COLOR: Rgba = [0xbb, 0xbb, 0xbb, 0xff];
+const COLUMN_NUMBER_COLOR: Rgba = [0x55, 0x55, 0x55, 0xff];
+const TRANSPARENT: Rgba = [0, 0, 0, 0];
+
+/// Render a card as a PNG image `width_px` pixels wide
+///
+/// The height follows the 7⅜" × 3¼" card aspect ratio. The corner cut is
+/// transparent. Fails if the width is outside MIN_WIDTH_PX..=MAX_WIDTH_PX.
+pub fn card_to_png(card: &PunchCard, width_px: u32) -> Result<Vec<u8>, String> {
+    if !(MIN_WIDTH_PX..=MAX_WIDTH_PX).contains(&width_px) {
+        return Err(format!(
+            "Image width must be {}-{} pixels, got {}",
+            MIN_WIDTH_PX, MAX_WIDTH_PX, width_px
+        ));
+    }
+
+    let g = Geometry::new(f64::from(width_px));
+    let mut canvas = Canvas::new(width_px as usize, g.card_height.round() as usize);
+    draw_card(&mut canvas, card, &g);
+    canvas.encode()
+}
+
+impl PunchCard {
+    /// Render the card as a PNG image (see `render::png::card_to_png`)
+    pub fn to_png(&self, width_px: u32) -> Result<Vec<u8>, String> {
+        card_to_png(self, width_px)
+    }
+}
+
+fn draw_card(canvas: &mut Canvas, card: &PunchCard, g: &Geometry) {
+    // Stock with the upper-left corner cut away
+    let border = (g.scale).max(1.0);
+    for y in 0..canvas.height {
+        for x in 0..canvas.width {
+            let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+            let corner = px / g.left_margin + py / g.top_margin;
+            if corner < 1.0 {
+                continue;
+            }
+            // Distance to the diagonal cut, scaled to pixels
+            let diagonal = (corner - 1.0) / (1.0 / g.left_margin).hypot(1.0 / g.top_margin);
+            let edge = px
+                .min(py)
+                .min(g.card_width - px)
+                .min(g.card_height - py)
+                .min(diagonal);
+            canvas.set(
+                x,
+                y,
+                if edge < border {
+                    BORDER_COLOR
+                } else {
+                    CARD_COLOR
+                },
+            );
+        }
+    }
+
+    // Column numbers between rows 0 and 1, and between row 9 and the bottom edge
+    for baseline in [
+        g.top_margin + 3.0 * g.row_height,
+        g.top_margin + 12.0 * g.row_height,
+    ] {
+        for col in 0..80 {
+            let label = (col + 1).to_string();
+            canvas.draw_text(
+                &label,
+                g.col_center(col),
+                baseline,
+                4.5 * g.scale,
+                COLUMN_NUMBER_COLOR,
+            );
+        }
+    }
+
+    // Printed characters (text cards only)
+    if card.card_type() == CardType::Text {
+        let baseline = g.top_margin - 5.0 * g.scale;
+        for (col, column) in card.columns().iter().enumerate() {
+            if let Some(ch) = column.printed_char.filter(|&ch| ch != ' ') {
+                canvas.draw_glyph(ch, g.col_center(col), baseline, 9.0 * g.scale, TEXT_COLOR);
+            }
+        }
+    }
+
+    // Guide holes
+    let guide_rx = g.col_width * 0.5 / 2.0;
+    let guide_ry = g.row_height * 0.6 / 2.0;
+    for col in 0..80 {
+        for row_idx in 0..12 {
+            canvas.stroke_ellipse(
+                g.col_center(col),
+                g.row_center(row_idx),
+                guide_rx,
+                guide_ry,
+                GUIDE_COLOR,
+            );
+        }
+    }
+
+    // Pre-printed digits 0-9 (rows 0-9 are at indices 2-11)
+    for col in 0..80 {
+        for digit in 0..10u8 {
+            canvas.draw_glyph(
+                char::from(b'0' + digit),
+                g.col_center(col),
+                g.row_center(usize::from(digit) + 2) + 3.0 * g.scale,
+                7.0 * g.scale,
+                DIGIT_COLOR,
+            );
+        }
+    }
+
+    // Punches
+    let punch_width = g.col_width * 0.6;
+    let punch_height = g.row_height * 0.7;
+    for (col, column) in card.columns().iter().enumerate() {
+        for (row_idx, punched) in column.punches.as_array().into_iter().enumerate() {
+            if punched {
+                let (cx, cy) = (g.col_center(col), g.row_center(row_idx));
+                canvas.fill_rect(
+                    cx - punch_width / 2.0,
+                    cy - punch_height / 2.0,
+                    cx + punch_width / 2.0,
+                    cy + punch_height / 2.0,
+                    PUNCH_COLOR,
+                );
+            }
+        }
+    }
+}
+
+/// An RGBA pixel buffer
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgba>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![TRANSPARENT; width * height],
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, color: Rgba) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    /// Fill the pixels whose centers lie inside the rectangle
+    fn fill_rect(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, color: Rgba) {
+        let (xa, xb) = (pixel_span(x0, x1), pixel_span(y0, y1));
+        for y in xb.0..xb.1 {
+            for x in xa.0..xa.1 {
+                self.set(x, y, color);
+            }
+        }
+    }
+
+    /// Draw a one-pixel ellipse outline
+    fn stroke_ellipse(&mut self, cx: f64, cy: f64, rx: f64, ry: f64, color: Rgba) {
+        let (xs, ys) = (
+            pixel_span(cx - rx - 1.0, cx + rx + 1.0),
+            pixel_span(cy - ry - 1.0, cy + ry + 1.0),
+        );
+        let half_width = 0.5 / rx.min(ry);
+        for y in ys.0..ys.1 {
+            for x in xs.0..xs.1 {
+                let dx = (x as f64 + 0.5 - cx) / rx;
+                let dy = (y as f64 + 0.5 - cy) / ry;
+                if (dx.hypot(dy) - 1.0).abs() <= half_width {
+                    self.set(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Draw a character from the bitmap font, centered on `cx`, sitting on `baseline`
+    fn draw_glyph(&mut self, ch: char, cx: f64, baseline: f64, height: f64, color: Rgba) {
+        let dot = height / 7.0;
+        let left = cx - 2.5 * dot;
+        let top = baseline - height;
+        for (row, bits) in glyph(ch).iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0b10000 >> col) != 0 {
+                    let x = left + f64::from(col) * dot;
+                    let y = top + row as f64 * dot;
+                    self.fill_rect(x, y, x + dot, y + dot, color);
+                }
+            }
+        }
+    }
+
+    /// Draw a string centered on `cx`
+    fn draw_text(&mut self, text: &str, cx: f64, baseline: f64, height: f64, color: Rgba) {
+        let advance = height / 7.0 * 6.0;
+        let count = text.chars().count() as f64;
+        let first = cx - advance * (count - 1.0) / 2.0;
+        for (i, ch) in text.chars().enumerate() {
+            self.draw_glyph(ch, first + i as f64 * advance, baseline, height, color);
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, String> {
+        let mut out = Vec::new();
+        let mut encoder = ::png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+        encoder.set_color(::png::ColorType::Rgba);
+        encoder.set_depth(::png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer
+            .write_image_data(self.pixels.as_flattened())
+            .map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())?;
+        Ok(out)
+    }
+}
+
+/// Pixel indices whose centers lie in [start, end)
+fn pixel_span(start: f64, end: f64) -> (usize, usize) {
+    let first = (start - 0.5).ceil().max(0.0) as usize;
+    let last = (end - 0.5).ceil().max(0.0) as usize;
+    (first, last.max(first))
+}
+
+/// 5×7 bitmap glyph, one byte per row, bit 4 is the leftmost dot
+fn glyph(ch: char) -> [u8; 7] {
+    match ch.to_ascii_uppercase() {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '&' => [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '¢' => [0x04, 0x0E, 0x15, 0x14, 0x15, 0x0E, 0x04],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        '<' => [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '|' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '$' => [0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04],
+        '*' => [0x00, 0x04, 0x15, 0x0E, 0x15, 0x04, 0x00],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        ';' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x04, 0x08],
+        '¬' => [0x00, 0x00, 0x1F, 0x01, 0x01, 0x00, 0x00],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '>' => [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08],
+        '?' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '@' => [0x0E, 0x11, 0x01, 0x0D, 0x15, 0x15, 0x0E],
+        '\'' => [0x0C, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+        '=' => [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00],
+        '"' => [0x0A, 0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00],
+        ' ' => [0x00; 7],
+        // Unknown glyphs draw as an open box
+        _ => [0x1F, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1F],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode a PNG into (width, height, RGBA bytes)
+    fn decode(data: &[u8]) -> (u32, u32, Vec<u8>) {
+        let decoder = ::png::Decoder::new(data);
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!(info.color_type, ::png::ColorType::Rgba);
+        buf.truncate(info.buffer_size());
+        (info.width, info.height, buf)
+    }
+
+    fn pixel(buf: &[u8], width: u32, x: f64, y: f64) -> [u8; 4] {
+        let idx = (y as usize * width as usize + x as usize) * 4;
+        [buf[idx], buf[idx + 1], buf[idx + 2], buf[idx + 3]]
+    }
+
+    #[test]
+    fn test_png_dimensions_match_aspect_ratio() {
+        let data = card_to_png(&PunchCard::from_text("HELLO"), 1200).unwrap();
+        let (width, height, _) = decode(&data);
+        assert_eq!(width, 1200);
+        assert_eq!(height, 529);
+        let ratio = f64::from(width) / f64::from(height);
+        assert!((ratio - 7.375 / 3.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_png_punches_are_solid() {
+        let card = PunchCard::from_text("A");
+        let data = card.to_png(1200).unwrap();
+        let (width, _, buf) = decode(&data);
+        let g = Geometry::new(1200.0);
+        // 'A' punches rows 12 (index 0) and 1 (index 3) in column 1
+        assert_eq!(
+            pixel(&buf, width, g.col_center(0), g.row_center(0)),
+            PUNCH_COLOR
+        );
+        assert_eq!(
+            pixel(&buf, width, g.col_center(0), g.row_center(3)),
+            PUNCH_COLOR
+        );
+        assert_ne!(
+            pixel(&buf, width, g.col_center(1), g.row_center(0)),
+            PUNCH_COLOR
+        );
+    }
+
+    #[test]
+    fn test_png_corner_cut_is_transparent() {
+        let data = PunchCard::from_text("A").to_png(1200).unwrap();
+        let (width, height, buf) = decode(&data);
+        assert_eq!(pixel(&buf, width, 1.0, 1.0)[3], 0);
+        assert_eq!(pixel(&buf, width, f64::from(width) - 2.0, 1.0)[3], 0xff);
+        assert_eq!(pixel(&buf, width, 1.0, f64::from(height) - 2.0)[3], 0xff);
+    }
+
+    /// Count text-colored pixels in the printed character row of column 0
+    fn printed_row_ink(card: &PunchCard) -> usize {
+        let data = card.to_png(1200).unwrap();
+        let (width, _, buf) = decode(&data);
+        let g = Geometry::new(1200.0);
+        let mut ink = 0;
+        for y in 0..g.top_margin as usize {
+            for x in (g.left_margin as usize)..((g.left_margin + g.col_width) as usize) {
+                if pixel(&buf, width, x as f64, y as f64) == TEXT_COLOR {
+                    ink += 1;
+                }
+            }
+        }
+        ink
+    }
+
+    #[test]
+    fn test_png_printed_characters_legible() {
+        // At 1200px each font dot is over a pixel, so a letter has solid strokes
+        assert!(printed_row_ink(&PunchCard::from_text("W")) > 30);
+        let binary = PunchCard::from_binary(&PunchCard::from_text("W").to_binary());
+        assert_eq!(printed_row_ink(&binary), 0);
+    }
+
+    #[test]
+    fn test_png_rejects_bad_width() {
+        let card = PunchCard::from_text("A");
+        assert!(card_to_png(&card, 0).is_err());
+        assert!(card_to_png(&card, MIN_WIDTH_PX - 1).is_err());
+        assert!(card_to_png(&card, MAX_WIDTH_PX + 1).is_err());
+        assert!(card_to_png(&card, MIN_WIDTH_PX).is_ok());
+    }
+
+    #[test]
+    fn test_png_signature() {
+        let data = PunchCard::from_text("A").to_png(400).unwrap();
+        assert_eq!(&data[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
@@ -0,0 +1,270 @@
+// SVG Rendering
+//
+// Renders a punch card as a standalone SVG document with the same geometry as
+// the web view: corner cut, column number rows, printed characters, guide
+// holes, pre-printed digits, and rectangular punches.
+
+use super::Geometry;
+use crate::punch_card::{CardType, PunchCard};
+use std::fmt::Write;
+
+/// Options for `card_to_svg`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Width of the card in SVG user units (height follows the aspect ratio)
+    pub width: f64,
+    /// Card stock color
+    pub card_color: String,
+    /// Card outline color
+    pub border_color: String,
+    /// Punched hole color
+    pub punch_color: String,
+    /// Printed character color
+    pub text_color: String,
+    /// Guide hole outline color
+    pub guide_color: String,
+    /// Pre-printed digit color
+    pub digit_color: String,
+    /// Column number color
+    pub column_number_color: String,
+    /// Zero-based column to highlight (current keypunch position)
+    pub highlight_column: Option<usize>,
+    /// Highlight color
+    pub highlight_color: String,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            width: 800.0,
+            card_color: "#f4e8d0".to_string(),
+            border_color: "#999".to_string(),
+            punch_color: "#000".to_string(),
+            text_color: "#000".to_string(),
+            guide_color: "#ccc".to_string(),
+            digit_color: "#bbb".to_string(),
+            column_number_color: "#555".to_string(),
+            highlight_column: None,
+            highlight_color: "#4a90e2".to_string(),
+        }
+    }
+}
+
+/// Render a card as an SVG document
+pub fn card_to_svg(card: &PunchCard, options: &SvgOptions) -> String {
+    let g = Geometry::new(options.width);
+    let mut svg = String::new();
+
+    let _ = write!(
+        svg,
+        r#"<svg class="punch-card" viewBox="0 0 {} {}" xmlns="http://www.w3.org/2000/svg">"#,
+        g.card_width, g.card_height
+    );
+
+    // Card background as polygon with corner cut (upper left)
+    let _ = write!(
+        svg,
+        r#"<polygon points="{},{} {},{} {},{} {},{} {},{}" fill="{}" stroke="{}" stroke-width="2"></polygon>"#,
+        g.left_margin,
+        0,
+        g.card_width,
+        0,
+        g.card_width,
+        g.card_height,
+        0,
+        g.card_height,
+        0,
+        g.top_margin,
+        options.card_color,
+        options.border_color
+    );
+
+    // Column numbers between rows 0 and 1, and between row 9 and the bottom edge
+    for y in [
+        g.top_margin + 3.0 * g.row_height,
+        g.top_margin + 12.0 * g.row_height,
+    ] {
+        for col in 0..80 {
+            let _ = write!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="middle" font-size="{}" fill="{}" font-family="monospace" font-weight="bold">{}</text>"#,
+                g.col_center(col),
+                y,
+                6.0 * g.scale,
+                options.column_number_color,
+                col + 1
+            );
+        }
+    }
+
+    // Printed characters (text cards only)
+    if card.card_type() == CardType::Text {
+        let text_y = g.top_margin - 5.0 * g.scale;
+        for (col, column) in card.columns().iter().enumerate() {
+            if let Some(ch) = column.printed_char {
+                let _ = write!(
+                    svg,
+                    r#"<text x="{}" y="{}" text-anchor="middle" font-size="{}" font-family="Courier New, monospace" fill="{}">{}</text>"#,
+                    g.col_center(col),
+                    text_y,
+                    12.0 * g.scale,
+                    options.text_color,
+                    escape_xml(ch)
+                );
+            }
+        }
+    }
+
+    // Column highlight (current position)
+    if let Some(col) = options.highlight_column.filter(|&col| col < 80) {
+        let _ = write!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="0.2"></rect>"#,
+            g.left_margin + col as f64 * g.col_width,
+            g.top_margin,
+            g.col_width,
+            g.card_height - g.top_margin,
+            options.highlight_color
+        );
+    }
+
+    // Guide holes (all possible punch positions)
+    let guide_rx = g.col_width * 0.5 / 2.0;
+    let guide_ry = g.row_height * 0.6 / 2.0;
+    for col in 0..80 {
+        for row_idx in 0..12 {
+            let _ = write!(
+                svg,
+                r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}" fill="none" stroke="{}" stroke-width="0.5"></ellipse>"#,
+                g.col_center(col),
+                g.row_center(row_idx),
+                guide_rx,
+                guide_ry,
+                options.guide_color
+            );
+        }
+    }
+
+    // Pre-printed digits 0-9 in each column (rows 0-9 are at indices 2-11)
+    for col in 0..80 {
+        for digit in 0..10 {
+            let _ = write!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="middle" font-size="{}" fill="{}" font-family="'Courier New', monospace" font-weight="bold">{}</text>"#,
+                g.col_center(col),
+                g.row_center(digit + 2) + 3.0 * g.scale,
+                10.0 * g.scale,
+                options.digit_color,
+                digit
+            );
+        }
+    }
+
+    // Punches (solid rectangles over the guide holes, taller than wide)
+    let punch_width = g.col_width * 0.6;
+    let punch_height = g.row_height * 0.7;
+    for (col, column) in card.columns().iter().enumerate() {
+        for (row_idx, punched) in column.punches.as_array().into_iter().enumerate() {
+            if punched {
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" rx="1"></rect>"#,
+                    g.col_center(col) - punch_width / 2.0,
+                    g.row_center(row_idx) - punch_height / 2.0,
+                    punch_width,
+                    punch_height,
+                    options.punch_color
+                );
+            }
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Escape a character for use in XML text content
+fn escape_xml(ch: char) -> String {
+    match ch {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '"' => "&quot;".to_string(),
+        '\'' => "&apos;".to_string(),
+        _ => ch.to_string(),
+    }
+}
+
+impl PunchCard {
+    /// Render the card as an SVG document (see `render::svg::card_to_svg`)
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        card_to_svg(self, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_document_structure() {
+        let svg = card_to_svg(&PunchCard::from_text("A"), &SvgOptions::default());
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(r#"viewBox="0 0 800 352.5782282944028""#));
+        assert_eq!(svg.matches("<polygon").count(), 1);
+        assert_eq!(svg.matches("<ellipse").count(), 960);
+    }
+
+    #[test]
+    fn test_svg_punch_count() {
+        let card = PunchCard::from_text("HELLO");
+        let svg = card_to_svg(&card, &SvgOptions::default());
+        assert_eq!(svg.matches(r#"rx="1""#).count(), 10);
+    }
+
+    #[test]
+    fn test_svg_printed_characters_escaped() {
+        let card = PunchCard::from_text("A&<");
+        let svg = card_to_svg(&card, &SvgOptions::default());
+        assert!(svg.contains(">A</text>"));
+        assert!(svg.contains(">&amp;</text>"));
+        assert!(svg.contains(">&lt;</text>"));
+    }
+
+    #[test]
+    fn test_svg_binary_card_has_no_printing() {
+        let card = PunchCard::from_binary(&PunchCard::from_text("A").to_binary());
+        let svg = card_to_svg(&card, &SvgOptions::default());
+        assert!(!svg.contains("Courier New, monospace"));
+    }
+
+    #[test]
+    fn test_svg_highlight() {
+        let card = PunchCard::from_text("A");
+        assert!(!card.to_svg(&SvgOptions::default()).contains("fill-opacity"));
+        let options = SvgOptions {
+            highlight_column: Some(3),
+            ..SvgOptions::default()
+        };
+        assert_eq!(card.to_svg(&options).matches("fill-opacity").count(), 1);
+        let options = SvgOptions {
+            highlight_column: Some(80),
+            ..SvgOptions::default()
+        };
+        assert!(!card.to_svg(&options).contains("fill-opacity"));
+    }
+
+    #[test]
+    fn test_svg_width_and_colors() {
+        let options = SvgOptions {
+            width: 1600.0,
+            card_color: "#add8e6".to_string(),
+            ..SvgOptions::default()
+        };
+        let svg = PunchCard::from_text("A").to_svg(&options);
+        assert!(svg.contains(r#"viewBox="0 0 1600 "#));
+        assert!(svg.contains(r##"fill="#add8e6""##));
+        assert!(svg.contains(r#"font-size="24""#));
+    }
+}
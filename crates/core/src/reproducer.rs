@@ -0,0 +1,294 @@
+// Reproducer Module
+//
+// Simulates the IBM 514/519 reproducing punch: each card is read and a new
+// card is punched through a plugboard that wires every destination column to
+// a source column, to nothing, or to a constant (gang punching). The
+// reproducer only punches, so new cards are unprinted; use `interpret` to
+// print them.
+
+use crate::deck::Deck;
+use crate::hollerith::{HollerithCode, char_to_hollerith, hollerith_to_char, is_valid_row};
+use crate::punch_card::{CardWidth, PunchCard};
+use std::fmt;
+use std::ops::Range;
+
+/// What a destination column is wired to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Wire {
+    /// Copy the punches of a source column (0-based)
+    Column(usize),
+    /// Punch nothing
+    Blank,
+    /// Punch the same pattern on every card
+    Constant(HollerithCode),
+}
+
+/// Errors from wiring a plugboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlugboardError {
+    /// A destination column was wired more than once
+    DuplicateDestination(usize),
+    /// A destination or source column is past column 80
+    ColumnOutOfRange(usize),
+    /// A constant character has no punch pattern
+    Unencodable(char),
+    /// A constant wired to a destination column is not a character's
+    /// punch pattern
+    InvalidConstant { column: usize, rows: Vec<u8> },
+}
+
+impl fmt::Display for PlugboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlugboardError::DuplicateDestination(column) => {
+                write!(f, "Column {} is wired more than once", column + 1)
+            }
+            PlugboardError::ColumnOutOfRange(column) => {
+                write!(f, "Column {} is out of range", column + 1)
+            }
+            PlugboardError::Unencodable(ch) => {
+                write!(f, "Character {:?} has no punch pattern", ch)
+            }
+            PlugboardError::InvalidConstant { column, rows } => {
+                write!(
+                    f,
+                    "Column {}: constant punches {:?} are not a character",
+                    column + 1,
+                    rows
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlugboardError {}
+
+/// Wiring from destination columns to sources
+///
+/// Unwired destination columns are left blank.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plugboard {
+    wires: [Option<Wire>; 80],
+    width: CardWidth,
+}
+
+impl Plugboard {
+    /// Wire a plugboard for 80-column cards from `(destination, wire)` pairs
+    ///
+    /// Fails if a destination is wired twice, any column is past 80, or a
+    /// constant is not a character's punch pattern.
+    pub fn new(wiring: impl IntoIterator<Item = (usize, Wire)>) -> Result<Self, PlugboardError> {
+        Plugboard::new_with_width(wiring, CardWidth::STANDARD)
+    }
+
+    /// Wire a plugboard that punches cards of the given width
+    ///
+    /// As `new`, but destinations must also be within the width. Sources
+    /// may be any of the 80 columns.
+    pub fn new_with_width(
+        wiring: impl IntoIterator<Item = (usize, Wire)>,
+        width: CardWidth,
+    ) -> Result<Self, PlugboardError> {
+        let mut wires: [Option<Wire>; 80] = std::array::from_fn(|_| None);
+        for (dest, wire) in wiring {
+            match &wire {
+                Wire::Column(source) if *source >= 80 => {
+                    return Err(PlugboardError::ColumnOutOfRange(*source));
+                }
+                Wire::Constant(code)
+                    if !code.rows.iter().all(|&row| is_valid_row(row))
+                        || hollerith_to_char(code).is_none() =>
+                {
+                    return Err(PlugboardError::InvalidConstant {
+                        column: dest,
+                        rows: code.rows.clone(),
+                    });
+                }
+                _ => {}
+            }
+            let slot = wires[..width.columns()]
+                .get_mut(dest)
+                .ok_or(PlugboardError::ColumnOutOfRange(dest))?;
+            if slot.is_some() {
+                return Err(PlugboardError::DuplicateDestination(dest));
+            }
+            *slot = Some(wire);
+        }
+        Ok(Plugboard { wires, width })
+    }
+
+    /// Wire every column to itself, for a straight copy
+    pub fn straight() -> Self {
+        Plugboard {
+            wires: std::array::from_fn(|index| Some(Wire::Column(index))),
+            width: CardWidth::STANDARD,
+        }
+    }
+
+    /// Get the wire for a destination column, if any
+    pub fn wire(&self, dest: usize) -> Option<&Wire> {
+        self.wires.get(dest)?.as_ref()
+    }
+
+    /// Get the width of the cards the board punches
+    pub fn width(&self) -> CardWidth {
+        self.width
+    }
+}
+
+/// Wires copying the `source` columns to columns starting at `dest_start`
+pub fn move_field(source: Range<usize>, dest_start: usize) -> impl Iterator<Item = (usize, Wire)> {
+    source
+        .enumerate()
+        .map(move |(offset, column)| (dest_start + offset, Wire::Column(column)))
+}
+
+/// Wires gang punching `text` into columns starting at `dest_start`
+///
+/// Fails if a character has no punch pattern.
+pub fn constant_field(text: &str, dest_start: usize) -> Result<Vec<(usize, Wire)>, PlugboardError> {
+    text.chars()
+        .enumerate()
+        .map(|(offset, ch)| {
+            char_to_hollerith(ch.to_ascii_uppercase())
+                .map(|code| (dest_start + offset, Wire::Constant(code)))
+                .ok_or(PlugboardError::Unencodable(ch))
+        })
+        .collect()
+}
+
+/// Punch a new card from `card` through the plugboard
+///
+/// The new card keeps the source card type, is the board's width and has
+/// no printing. Source columns past a stub card's width read as blank.
+pub fn reproduce(card: &PunchCard, board: &Plugboard) -> PunchCard {
+    let mut copy = PunchCard::new(card.card_type()).with_width(board.width);
+    for (dest, wire) in board.wires.iter().enumerate() {
+        let code = match wire {
+            Some(Wire::Column(source)) => card
+                .get_column(*source)
+                .map(|col| col.punches.clone())
+                .unwrap_or_else(HollerithCode::empty),
+            Some(Wire::Constant(code)) => code.clone(),
+            Some(Wire::Blank) | None => continue,
+        };
+        copy.set_column_hollerith(dest, code)
+            .expect("plugboard destinations are within its width");
+    }
+    copy
+}
+
+impl Deck {
+    /// Reproduce every card through the plugboard (see `reproduce`)
+    pub fn reproduce(&self, board: &Plugboard) -> Deck {
+        self.iter().map(|card| reproduce(card, board)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_straight_copy() {
+        let card = PunchCard::from_text("HELLO, WORLD");
+        let copy = reproduce(&card, &Plugboard::straight());
+        assert!(copy.punches_eq(&card));
+        assert_eq!(copy.columns()[0].printed_char, None);
+        assert_eq!(copy.card_type(), card.card_type());
+    }
+
+    #[test]
+    fn test_invalid_wiring_rejected() {
+        let twice = Plugboard::new([(5, Wire::Column(0)), (5, Wire::Blank)]);
+        assert_eq!(twice, Err(PlugboardError::DuplicateDestination(5)));
+        let overlap = Plugboard::new(move_field(0..6, 74).chain(move_field(10..12, 79)));
+        assert_eq!(overlap, Err(PlugboardError::DuplicateDestination(79)));
+        assert_eq!(
+            Plugboard::new(move_field(0..6, 75)),
+            Err(PlugboardError::ColumnOutOfRange(80))
+        );
+        assert_eq!(
+            Plugboard::new([(0, Wire::Column(80))]),
+            Err(PlugboardError::ColumnOutOfRange(80))
+        );
+        assert_eq!(
+            constant_field("A~", 0),
+            Err(PlugboardError::Unencodable('~'))
+        );
+
+        // Constants must be characters, within the card
+        let bad = HollerithCode {
+            rows: vec![1, 2, 3],
+        };
+        let err = Plugboard::new([(3, Wire::Constant(bad))]).unwrap_err();
+        assert_eq!(
+            err,
+            PlugboardError::InvalidConstant {
+                column: 3,
+                rows: vec![1, 2, 3]
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Column 4: constant punches [1, 2, 3] are not a character"
+        );
+        let row13 = HollerithCode { rows: vec![13] };
+        assert!(Plugboard::new([(0, Wire::Constant(row13))]).is_err());
+        let stub = CardWidth::STUB_51;
+        assert_eq!(
+            Plugboard::new_with_width(constant_field("PAID", 50).unwrap(), stub),
+            Err(PlugboardError::ColumnOutOfRange(51))
+        );
+    }
+
+    #[test]
+    fn test_reproduce_deck_with_moved_and_constant_fields() {
+        // Move the date from columns 1-6 to 75-80, keep the name in 7-20,
+        // gang punch "PAID" into 21-24 and leave everything else blank
+        let wiring = move_field(0..6, 74)
+            .chain(move_field(6..20, 6))
+            .chain(constant_field("PAID", 20).unwrap())
+            .chain([(30, Wire::Blank)]);
+        let board = Plugboard::new(wiring).unwrap();
+        assert_eq!(board.wire(74), Some(&Wire::Column(0)));
+        assert_eq!(board.wire(0), None);
+
+        let deck = Deck::from_cards(vec![
+            PunchCard::from_text("010124JONES         SECRET"),
+            PunchCard::from_text("020224SMITH         SECRET"),
+        ]);
+        let mut copies = deck.reproduce(&board);
+        assert_eq!(copies.len(), 2);
+        for copy in copies.iter_mut() {
+            copy.interpret();
+        }
+        let texts: Vec<String> = copies.iter().map(PunchCard::to_text).collect();
+        assert_eq!(
+            texts[0].trim_end(),
+            format!("{:6}JONES         PAID{:50}010124", "", "")
+        );
+        assert_eq!(
+            texts[1].trim_end(),
+            format!("{:6}SMITH         PAID{:50}020224", "", "")
+        );
+    }
+
+    #[test]
+    fn test_stub_source_columns_read_blank() {
+        let stub = PunchCard::from_text_with_width("ABC", 51).unwrap();
+        let board = Plugboard::new(move_field(49..52, 0)).unwrap();
+        let copy = reproduce(&stub, &board);
+        assert!(copy.columns()[..3].iter().all(|col| col.is_blank()));
+
+        // A stub-width board punches stub cards
+        let board = Plugboard::new_with_width(move_field(0..3, 48), CardWidth::STUB_51).unwrap();
+        let copy = reproduce(&PunchCard::from_text("ABC"), &board);
+        assert_eq!(copy.width(), CardWidth::STUB_51);
+        assert!(
+            copy.punches_eq(
+                &PunchCard::from_text_with_width(&format!("{:48}ABC", ""), 51).unwrap()
+            )
+        );
+    }
+}
@@ -0,0 +1,100 @@
+// Search Module
+//
+// Text search over the decoded columns of cards and decks. Cards only hold
+// uppercase characters, so matching is case-insensitive; columns that do not
+// decode to a character never match.
+
+use crate::deck::Deck;
+use crate::punch_card::PunchCard;
+
+impl PunchCard {
+    /// Find every starting column (0-based) where the decoded text matches
+    ///
+    /// Matches may overlap. An empty needle, or one longer than the card,
+    /// finds nothing.
+    pub fn find(&self, needle: &str) -> Vec<usize> {
+        let needle: Vec<char> = needle.chars().map(|c| c.to_ascii_uppercase()).collect();
+        let decoded: Vec<Option<char>> = self.columns().iter().map(|col| col.to_char()).collect();
+        if needle.is_empty() || needle.len() > decoded.len() {
+            return Vec::new();
+        }
+        decoded
+            .windows(needle.len())
+            .enumerate()
+            .filter(|(_, window)| window.iter().zip(&needle).all(|(col, c)| *col == Some(*c)))
+            .map(|(start, _)| start)
+            .collect()
+    }
+}
+
+impl Deck {
+    /// Find every match in the deck as (card index, starting column) pairs
+    ///
+    /// Matches do not span cards.
+    pub fn find(&self, needle: &str) -> Vec<(usize, usize)> {
+        self.iter()
+            .enumerate()
+            .flat_map(|(card_index, card)| {
+                card.find(needle)
+                    .into_iter()
+                    .map(move |column| (card_index, column))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hollerith::HollerithCode;
+
+    #[test]
+    fn test_find_case_insensitive_and_overlapping() {
+        let card = PunchCard::from_text("LD   L  VALUE  AAA");
+        assert_eq!(card.find("value"), vec![8]);
+        assert_eq!(card.find("L"), vec![0, 5, 10]);
+        assert_eq!(card.find("AA"), vec![15, 16]);
+        assert!(card.find("STO").is_empty());
+    }
+
+    #[test]
+    fn test_find_spans_blank_padding() {
+        let card = PunchCard::from_text("END");
+        assert_eq!(card.find("D  "), vec![2]);
+        assert_eq!(card.find(&" ".repeat(77)), vec![3]);
+        assert_eq!(card.find(&format!("END{}", " ".repeat(77))), vec![0]);
+    }
+
+    #[test]
+    fn test_find_edge_cases() {
+        let card = PunchCard::from_text("A");
+        assert!(card.find("").is_empty());
+        assert!(card.find(&"A".repeat(81)).is_empty());
+        assert!(card.find(&" ".repeat(81)).is_empty());
+    }
+
+    #[test]
+    fn test_find_skips_undecodable_columns() {
+        let mut card = PunchCard::from_text("A?B");
+        card.set_column_hollerith(1, HollerithCode::new(vec![12, 11, 0]))
+            .unwrap();
+        assert!(card.find("A?B").is_empty());
+        assert!(card.find("?").is_empty());
+        assert_eq!(card.find("B"), vec![2]);
+    }
+
+    #[test]
+    fn test_deck_find() {
+        let deck: Deck = [
+            "      LD   L  VALUE",
+            "      STO  L  RESULT",
+            "VALUE DC   5",
+        ]
+        .iter()
+        .map(|t| PunchCard::from_text(t))
+        .collect();
+        assert_eq!(deck.find("VALUE"), vec![(0, 14), (2, 0)]);
+        assert_eq!(deck.find("sto"), vec![(1, 6)]);
+        assert!(deck.find(&"X".repeat(100)).is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+// Share String Module
+//
+// Compact, URL-safe text encoding of a single card for links and chat.
+//
+// Layout before encoding (121 bytes):
+//
+//   byte 0     header: high nibble = version (1), bit 0 = card type (1 = binary)
+//   bytes 1..  the full 80-column punch matrix in FullBinary120 packing
+//
+// The bytes are encoded as unpadded base64url (RFC 4648 §5), giving a
+// 162-character string. Printed characters are not stored; text cards are
+// re-printed from their punches on decode.
+
+use crate::format::CardFormat;
+use crate::hollerith::hollerith_to_char;
+use crate::punch_card::{CardType, PunchCard};
+
+/// The current share string version
+pub const SHARE_VERSION: u8 = 1;
+
+/// Length of a share string in characters
+pub const SHARE_STRING_LEN: usize = 162;
+
+const PAYLOAD_LEN: usize = 121;
+const BINARY_FLAG: u8 = 0x01;
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+impl PunchCard {
+    /// Encode the card's punches and type as a base64url share string
+    pub fn to_share_string(&self) -> String {
+        let mut payload = Vec::with_capacity(PAYLOAD_LEN);
+        let type_bit = match self.card_type() {
+            CardType::Text => 0,
+            CardType::Binary => BINARY_FLAG,
+        };
+        payload.push(SHARE_VERSION << 4 | type_bit);
+        payload.extend(
+            self.serialize(CardFormat::FullBinary120)
+                .expect("every card is representable in full binary"),
+        );
+        encode_base64url(&payload)
+    }
+
+    /// Decode a card from a share string
+    ///
+    /// Surrounding whitespace is ignored. Fails on a wrong length, characters
+    /// outside the base64url alphabet, or an unknown version or header bit.
+    pub fn from_share_string(share: &str) -> Result<PunchCard, String> {
+        let share = share.trim();
+        if share.len() != SHARE_STRING_LEN {
+            return Err(format!(
+                "Share string must be {} characters, got {}",
+                SHARE_STRING_LEN,
+                share.len()
+            ));
+        }
+        let payload = decode_base64url(share)?;
+
+        let header = payload[0];
+        if header >> 4 != SHARE_VERSION {
+            return Err(format!(
+                "Unsupported share string version {} (expected {})",
+                header >> 4,
+                SHARE_VERSION
+            ));
+        }
+        if header & 0x0e != 0 {
+            return Err(format!("Invalid share string header 0x{:02x}", header));
+        }
+        let card_type = if header & BINARY_FLAG != 0 {
+            CardType::Binary
+        } else {
+            CardType::Text
+        };
+
+        let punched = PunchCard::parse(&payload[1..], CardFormat::FullBinary120)
+            .map_err(|e| e.to_string())?;
+        let mut card = PunchCard::new(card_type);
+        for (idx, column) in punched.columns().iter().enumerate() {
+            let dest = card
+                .get_column_mut(idx)
+                .expect("both cards have 80 columns");
+            dest.punches = column.punches.clone();
+            if card_type == CardType::Text && !column.punches.rows.is_empty() {
+                dest.printed_char = hollerith_to_char(&column.punches);
+            }
+        }
+        Ok(card)
+    }
+}
+
+fn encode_base64url(data: &[u8]) -> String {
+    let mut out = String::with_capacity((data.len() * 4).div_ceil(3));
+    for chunk in data.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | u32::from(b) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(char::from(ALPHABET[(bits >> (18 - 6 * i)) as usize & 0x3f]));
+        }
+    }
+    out
+}
+
+fn decode_base64url(text: &str) -> Result<Vec<u8>, String> {
+    let mut sextets = Vec::with_capacity(text.len());
+    for (idx, ch) in text.chars().enumerate() {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| char::from(a) == ch)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid character {:?} at position {} in share string",
+                    ch,
+                    idx + 1
+                )
+            })?;
+        sextets.push(value as u32);
+    }
+    if sextets.len() % 4 == 1 {
+        return Err("Truncated share string".to_string());
+    }
+
+    let mut out = Vec::with_capacity(sextets.len() * 3 / 4);
+    for chunk in sextets.chunks(4) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &s)| acc | s << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            out.push((bits >> (16 - 8 * i)) as u8);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_roundtrip_text() {
+        let card = PunchCard::from_text("HELLO, WORLD. 1130");
+        let share = card.to_share_string();
+        assert_eq!(share.len(), SHARE_STRING_LEN);
+        assert!(share.len() < 200);
+        assert!(
+            share
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        );
+        let loaded = PunchCard::from_share_string(&share).unwrap();
+        assert_eq!(loaded.card_type(), CardType::Text);
+        assert!(loaded.punches_eq(&card));
+        assert_eq!(loaded.to_text_trimmed(), "HELLO, WORLD. 1130");
+    }
+
+    #[test]
+    fn test_share_roundtrip_binary() {
+        let card = PunchCard::from_binary(&[0xC3; 108]);
+        let loaded = PunchCard::from_share_string(&card.to_share_string()).unwrap();
+        assert_eq!(loaded, card);
+    }
+
+    #[test]
+    fn test_base64url_known_values() {
+        assert_eq!(encode_base64url(b"Man"), "TWFu");
+        assert_eq!(encode_base64url(b"Ma"), "TWE");
+        assert_eq!(encode_base64url(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode_base64url("-_8").unwrap(), vec![0xfb, 0xff]);
+    }
+
+    #[test]
+    fn test_share_rejects_corruption() {
+        let share = PunchCard::from_text("A").to_share_string();
+
+        let short = &share[..SHARE_STRING_LEN - 1];
+        assert!(PunchCard::from_share_string(short).is_err());
+
+        let mut bad_char = share.clone();
+        bad_char.replace_range(10..11, "+");
+        assert_eq!(
+            PunchCard::from_share_string(&bad_char).unwrap_err(),
+            "Invalid character '+' at position 11 in share string"
+        );
+
+        let mut bad_version = share.clone();
+        bad_version.replace_range(0..1, "A");
+        assert!(
+            PunchCard::from_share_string(&bad_version)
+                .unwrap_err()
+                .starts_with("Unsupported share string version")
+        );
+
+        let multibyte = "é".repeat(SHARE_STRING_LEN / 2);
+        assert!(PunchCard::from_share_string(&multibyte).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+// Six-Bit Column Module
+//
+// The round-hole cards (System/3 96-column and Remington Rand 90-column)
+// hold one 6-bit punch code per column, looked up in a character code
+// table. This module holds what they share: the table lookups, the text
+// conversions and the binary packing.
+//
+// Binary format: each column's 6 bits packed MSB-first in column order (the
+// high bit of column 1's code is the high bit of byte 0). Bits after the
+// last column are zero.
+
+/// Number of bytes needed to pack `n_cols` 6-bit columns
+pub const fn packed_len(n_cols: usize) -> usize {
+    (n_cols * 6).div_ceil(8)
+}
+
+/// A character to punch code table
+///
+/// Blank is the empty code 0 and is not listed. Lowercase letters are
+/// looked up as uppercase.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeTable(pub &'static [(char, u8)]);
+
+impl CodeTable {
+    /// Get the punch code for a character, if the table has it
+    pub fn encode(&self, c: char) -> Option<u8> {
+        let c = c.to_ascii_uppercase();
+        if c == ' ' {
+            return Some(0);
+        }
+        self.0
+            .iter()
+            .find(|&&(ch, _)| ch == c)
+            .map(|&(_, code)| code)
+    }
+
+    /// Get the character for a punch code, if the table assigns one
+    pub fn decode(&self, code: u8) -> Option<char> {
+        if code == 0 {
+            return Some(' ');
+        }
+        self.0.iter().find(|&&(_, c)| c == code).map(|&(ch, _)| ch)
+    }
+
+    /// Blank followed by every character in the table
+    pub fn chars(&self) -> String {
+        std::iter::once(' ')
+            .chain(self.0.iter().map(|&(c, _)| c))
+            .collect()
+    }
+}
+
+/// The punch codes of an `N`-column card
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SixBitColumns<const N: usize> {
+    codes: [u8; N],
+}
+
+impl<const N: usize> Default for SixBitColumns<N> {
+    fn default() -> Self {
+        SixBitColumns { codes: [0; N] }
+    }
+}
+
+impl<const N: usize> SixBitColumns<N> {
+    /// Size of the packed binary format in bytes
+    pub const BINARY_LEN: usize = packed_len(N);
+
+    /// Punch text (max `N` characters) using a code table
+    ///
+    /// Fails on text that is too long or contains characters with no code.
+    pub fn from_text(table: CodeTable, text: &str) -> Result<Self, String> {
+        let len = text.chars().count();
+        if len > N {
+            return Err(format!(
+                "Text is {} characters; a {}-column card holds {}",
+                len, N, N
+            ));
+        }
+        let mut columns = SixBitColumns::default();
+        for (i, c) in text.chars().enumerate() {
+            columns.codes[i] = table
+                .encode(c)
+                .ok_or_else(|| format!("Column {}: character {:?} has no punch code", i + 1, c))?;
+        }
+        Ok(columns)
+    }
+
+    /// Decode as text, using '?' for unassigned codes
+    ///
+    /// Trailing blanks are kept, so the result is always `N` characters.
+    pub fn to_text(&self, table: CodeTable) -> String {
+        self.codes
+            .iter()
+            .map(|&code| table.decode(code).unwrap_or('?'))
+            .collect()
+    }
+
+    /// Get the punch code for a column (0-based)
+    pub fn get(&self, index: usize) -> Option<u8> {
+        self.codes.get(index).copied()
+    }
+
+    /// Set the punch code for a column (0-based)
+    pub fn set(&mut self, index: usize, code: u8) -> Result<(), &'static str> {
+        if code > 0b111111 {
+            return Err("Punch code must fit in 6 bits");
+        }
+        let column = self
+            .codes
+            .get_mut(index)
+            .ok_or("Column index out of bounds")?;
+        *column = code;
+        Ok(())
+    }
+
+    /// Get all column codes
+    pub fn codes(&self) -> &[u8] {
+        &self.codes
+    }
+
+    /// Get the `width` columns of one tier (0 = top)
+    pub fn tier(&self, tier: usize, width: usize) -> Option<&[u8]> {
+        let start = tier.checked_mul(width)?;
+        self.codes.get(start..start.checked_add(width)?)
+    }
+
+    /// Pack the codes into `BINARY_LEN` bytes
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut data = vec![0u8; Self::BINARY_LEN];
+        for (col, &code) in self.codes.iter().enumerate() {
+            for bit in 0..6 {
+                if code & (0b100000 >> bit) != 0 {
+                    let idx = col * 6 + bit;
+                    data[idx / 8] |= 0x80 >> (idx % 8);
+                }
+            }
+        }
+        data
+    }
+
+    /// Unpack codes from `BINARY_LEN` bytes
+    ///
+    /// Any padding bits after the last column must be zero.
+    pub fn from_binary(data: &[u8]) -> Result<Self, String> {
+        if data.len() != Self::BINARY_LEN {
+            return Err(format!(
+                "{}-column binary data must be {} bytes, got {}",
+                N,
+                Self::BINARY_LEN,
+                data.len()
+            ));
+        }
+        let padding = Self::BINARY_LEN * 8 - N * 6;
+        if data[Self::BINARY_LEN - 1] & ((1u16 << padding) - 1) as u8 != 0 {
+            return Err(format!(
+                "{}-column binary data has non-zero padding bits",
+                N
+            ));
+        }
+        let mut columns = SixBitColumns::default();
+        for (col, code) in columns.codes.iter_mut().enumerate() {
+            for bit in 0..6 {
+                let idx = col * 6 + bit;
+                if data[idx / 8] & (0x80 >> (idx % 8)) != 0 {
+                    *code |= 0b100000 >> bit;
+                }
+            }
+        }
+        Ok(columns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card96, remrand};
+
+    #[test]
+    fn test_code_tables_are_bijective() {
+        for table in [card96::CODE_TABLE, remrand::CODE_TABLE] {
+            for (i, &(c, code)) in table.0.iter().enumerate() {
+                assert!(code != 0 && code <= 0b111111);
+                assert_eq!(table.encode(c), Some(code));
+                assert_eq!(table.decode(code), Some(c));
+                assert!(
+                    table.0[i + 1..]
+                        .iter()
+                        .all(|&(c2, code2)| c2 != c && code2 != code)
+                );
+            }
+            assert_eq!(table.decode(0x40), None);
+        }
+    }
+
+    #[test]
+    fn test_binary_bit_order_and_padding() {
+        let mut columns = SixBitColumns::<3>::default();
+        columns.set(0, 0b100000).unwrap();
+        columns.set(2, 0b000001).unwrap();
+        let data = columns.to_binary();
+        assert_eq!(data, vec![0x80, 0x00, 0x40]);
+        assert_eq!(SixBitColumns::<3>::from_binary(&data).unwrap(), columns);
+        assert_eq!(
+            SixBitColumns::<3>::from_binary(&[0x00, 0x00, 0x01]).unwrap_err(),
+            "3-column binary data has non-zero padding bits"
+        );
+        assert!(SixBitColumns::<3>::from_binary(&data[..2]).is_err());
+
+        // Four columns fill three bytes exactly
+        assert_eq!(SixBitColumns::<4>::BINARY_LEN, 3);
+        assert!(SixBitColumns::<4>::from_binary(&[0xFF; 3]).is_ok());
+    }
+
+    #[test]
+    fn test_tiers() {
+        let columns = SixBitColumns::<6>::default();
+        assert_eq!(columns.tier(2, 2).map(<[u8]>::len), Some(2));
+        assert!(columns.tier(3, 2).is_none());
+        assert!(columns.tier(usize::MAX, 2).is_none());
+    }
+}
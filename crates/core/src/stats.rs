@@ -0,0 +1,280 @@
+// Statistics Module
+//
+// Punch counts and histograms for cards and decks
+
+use crate::deck::Deck;
+use crate::punch_card::PunchCard;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Default lace threshold: columns with more punches than this are laced
+///
+/// A fully laced column has all 12 rows punched, which weakens the card.
+pub const DEFAULT_LACE_THRESHOLD: usize = 11;
+
+/// Aggregate punch statistics for a card (or a deck, summed)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CardStats {
+    /// Total number of holes
+    pub total_punches: usize,
+    /// Holes per row, in card order [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+    pub row_punches: [usize; 12],
+    /// Number of columns with no punches
+    pub blank_columns: usize,
+    /// Most punches found in any single column
+    pub max_column_punches: usize,
+    /// Number of columns with more punches than the lace threshold
+    pub laced_columns: usize,
+    /// How often each character appears in punched, decodable columns
+    pub char_frequency: BTreeMap<char, usize>,
+}
+
+/// Statistics for a whole deck
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeckStats {
+    /// Number of cards in the deck
+    pub card_count: usize,
+    /// Statistics summed over all cards
+    pub totals: CardStats,
+    /// Average number of holes per card (0 for an empty deck)
+    pub average_punches_per_card: f64,
+}
+
+impl DeckStats {
+    /// Compute statistics for a deck
+    pub fn from_deck(deck: &Deck) -> Self {
+        let totals = deck.stats();
+        let average_punches_per_card = if deck.is_empty() {
+            0.0
+        } else {
+            totals.total_punches as f64 / deck.len() as f64
+        };
+        DeckStats {
+            card_count: deck.len(),
+            totals,
+            average_punches_per_card,
+        }
+    }
+}
+
+impl CardStats {
+    /// Compute statistics for a card using the default lace threshold
+    pub fn from_card(card: &PunchCard) -> Self {
+        card.stats()
+    }
+
+    /// Check if any column exceeded the lace threshold
+    pub fn has_lace(&self) -> bool {
+        self.laced_columns > 0
+    }
+
+    /// Add another set of statistics into this one
+    fn accumulate(&mut self, other: &CardStats) {
+        self.total_punches += other.total_punches;
+        for (sum, count) in self.row_punches.iter_mut().zip(other.row_punches) {
+            *sum += count;
+        }
+        self.blank_columns += other.blank_columns;
+        self.max_column_punches = self.max_column_punches.max(other.max_column_punches);
+        self.laced_columns += other.laced_columns;
+        for (&c, &count) in &other.char_frequency {
+            *self.char_frequency.entry(c).or_insert(0) += count;
+        }
+    }
+}
+
+impl PunchCard {
+    /// Count punches per row across the card's columns
+    ///
+    /// Rows are in card order [12, 11, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9].
+    pub fn row_histogram(&self) -> [usize; 12] {
+        let mut histogram = [0; 12];
+        for column in self.columns() {
+            for (count, punched) in histogram.iter_mut().zip(column.punches.as_array()) {
+                *count += usize::from(punched);
+            }
+        }
+        histogram
+    }
+
+    /// Count punches in each column, one count per column of the card's
+    /// width (so a stub card has fewer than 80)
+    pub fn column_punch_counts(&self) -> Vec<u8> {
+        self.columns()
+            .iter()
+            .map(|column| column.punches.as_array().iter().filter(|&&p| p).count() as u8)
+            .collect()
+    }
+
+    /// Compute punch statistics using the default lace threshold
+    pub fn stats(&self) -> CardStats {
+        self.stats_with_lace_threshold(DEFAULT_LACE_THRESHOLD)
+    }
+
+    /// Compute punch statistics, counting columns with more than
+    /// `lace_threshold` punches as laced
+    pub fn stats_with_lace_threshold(&self, lace_threshold: usize) -> CardStats {
+        let counts = self.column_punch_counts();
+        CardStats {
+            total_punches: counts.iter().map(|&c| usize::from(c)).sum(),
+            row_punches: self.row_histogram(),
+            blank_columns: counts.iter().filter(|&&c| c == 0).count(),
+            max_column_punches: counts.iter().map(|&c| usize::from(c)).max().unwrap_or(0),
+            laced_columns: counts
+                .iter()
+                .filter(|&&c| usize::from(c) > lace_threshold)
+                .count(),
+            char_frequency: self
+                .columns()
+                .iter()
+                .filter(|col| !col.is_blank())
+                .filter_map(|col| col.to_char())
+                .fold(BTreeMap::new(), |mut freq, c| {
+                    *freq.entry(c).or_insert(0) += 1;
+                    freq
+                }),
+        }
+    }
+}
+
+impl Deck {
+    /// Sum punch statistics over all cards (maximum for `max_column_punches`)
+    pub fn stats(&self) -> CardStats {
+        let mut total = CardStats::default();
+        for card in self {
+            total.accumulate(&card.stats());
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hollerith::HollerithCode;
+    use crate::punch_card::CardType;
+
+    #[test]
+    fn test_row_histogram() {
+        // A = 12-1, J = 11-1, S = 0-2, 1 = 1
+        let card = PunchCard::from_text("AJS1");
+        assert_eq!(card.row_histogram(), [1, 1, 1, 3, 1, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_column_punch_counts() {
+        let card = PunchCard::from_text("A.1 ");
+        let counts = card.column_punch_counts();
+        assert_eq!(&counts[0..4], &[2, 3, 1, 0]);
+        assert!(counts[4..].iter().all(|&c| c == 0));
+        assert_eq!(counts.len(), 80);
+    }
+
+    #[test]
+    fn test_stub_card_stats() {
+        // Columns 52-80 do not exist on a 51-column stub card
+        let stub = PunchCard::from_text_with_width("HELLO", 51).unwrap();
+        assert_eq!(stub.column_punch_counts().len(), 51);
+        let stats = stub.stats();
+        assert_eq!(stats.total_punches, 10);
+        assert_eq!(stats.blank_columns, 46);
+        assert_eq!(
+            Deck::from_cards(vec![stub.clone(), stub])
+                .stats()
+                .blank_columns,
+            92
+        );
+    }
+
+    #[test]
+    fn test_card_stats() {
+        let card = PunchCard::from_text("HELLO");
+        let stats = card.stats();
+        assert_eq!(stats.total_punches, 10);
+        assert_eq!(stats.blank_columns, 75);
+        assert_eq!(stats.max_column_punches, 2);
+        assert!(!stats.has_lace());
+    }
+
+    #[test]
+    fn test_card_stats_blank() {
+        let stats = PunchCard::new(CardType::Binary).stats();
+        assert_eq!(stats.total_punches, 0);
+        assert_eq!(stats.blank_columns, 80);
+        assert_eq!(stats.max_column_punches, 0);
+    }
+
+    #[test]
+    fn test_card_stats_lace() {
+        let mut card = PunchCard::new(CardType::Binary);
+        card.set_column_hollerith(0, HollerithCode::from_array([true; 12]))
+            .unwrap();
+        card.set_column_hollerith(1, HollerithCode::new(vec![12, 11, 0, 1, 2, 3, 4]))
+            .unwrap();
+        let stats = card.stats();
+        assert_eq!(stats.max_column_punches, 12);
+        assert_eq!(stats.laced_columns, 1);
+        assert!(stats.has_lace());
+        assert_eq!(card.stats_with_lace_threshold(6).laced_columns, 2);
+    }
+
+    #[test]
+    fn test_deck_stats() {
+        let deck = Deck::from_cards(vec![PunchCard::from_text("AB"), PunchCard::from_text(".")]);
+        let stats = deck.stats();
+        assert_eq!(stats.total_punches, 7);
+        assert_eq!(stats.blank_columns, 78 + 79);
+        assert_eq!(stats.max_column_punches, 3);
+        assert_eq!(stats.row_punches[0], 3);
+        assert_eq!(Deck::new().stats(), CardStats::default());
+    }
+
+    #[test]
+    fn test_char_frequency() {
+        // Ten letters of two holes each (e.g. H = 12-8, L = 11-3)
+        let stats = CardStats::from_card(&PunchCard::from_text("HELLO WORLD"));
+        assert_eq!(stats.total_punches, 20);
+        assert_eq!(stats.char_frequency[&'L'], 3);
+        assert_eq!(stats.char_frequency[&'O'], 2);
+        assert_eq!(stats.char_frequency.get(&' '), None);
+        assert_eq!(stats.char_frequency.values().sum::<usize>(), 10);
+
+        let mut undecodable = PunchCard::new(CardType::Binary);
+        undecodable
+            .set_column_hollerith(0, HollerithCode::new(vec![12, 11, 0]))
+            .unwrap();
+        assert!(undecodable.stats().char_frequency.is_empty());
+    }
+
+    #[test]
+    fn test_deck_stats_from_deck() {
+        // "AB" = 12-1, 12-2 (4 holes); "9." = 9, 12-3-8 (4 holes)
+        let deck = Deck::from_cards(vec![PunchCard::from_text("AB"), PunchCard::from_text("9.")]);
+        let stats = DeckStats::from_deck(&deck);
+        assert_eq!(stats.card_count, 2);
+        assert_eq!(stats.totals.total_punches, 8);
+        assert_eq!(stats.totals.row_punches[0], 3);
+        assert_eq!(stats.totals.blank_columns, 156);
+        assert_eq!(stats.average_punches_per_card, 4.0);
+        assert_eq!(stats.totals.char_frequency.len(), 4);
+        assert_eq!(
+            DeckStats::from_deck(&Deck::new()).average_punches_per_card,
+            0.0
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_stats_serialize() {
+        let stats = DeckStats::from_deck(&Deck::from_cards(vec![PunchCard::from_text("AA")]));
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["card_count"], 1);
+        assert_eq!(json["totals"]["total_punches"], 4);
+        assert_eq!(json["totals"]["char_frequency"]["A"], 2);
+        let back: DeckStats = serde_json::from_value(json).unwrap();
+        assert_eq!(back, stats);
+    }
+}
@@ -0,0 +1,175 @@
+// Translate Module
+//
+// Character-level rewriting of cards and decks: each column is decoded, the
+// character is mapped, and the result is re-punched. Used for sanitizing
+// decks (changing printer-chain characters, blanking confidential fields).
+
+use crate::deck::Deck;
+use crate::hollerith::char_to_hollerith;
+use crate::punch_card::{CardType, Column, PunchCard};
+use std::ops::Range;
+
+/// What to do with columns whose punches do not decode to a character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndecodablePolicy {
+    /// Leave the column untouched
+    Keep,
+    /// Clear the column
+    Blank,
+}
+
+impl PunchCard {
+    /// Map every decodable column through `f` and re-punch the result
+    ///
+    /// Columns where `f` returns None, or a character with no punch pattern,
+    /// are left untouched, as are columns that do not decode.
+    pub fn translate<F: Fn(char) -> Option<char>>(&mut self, f: F) {
+        let all = 0..self.columns().len();
+        self.translate_columns(all, UndecodablePolicy::Keep, f)
+            .expect("full range is within the card");
+    }
+
+    /// Map the columns in `cols` through `f` and re-punch the result
+    ///
+    /// Text cards get the new character printed; binary cards stay unprinted.
+    /// Fails without modifying the card if the range falls outside it.
+    pub fn translate_columns<F: Fn(char) -> Option<char>>(
+        &mut self,
+        cols: Range<usize>,
+        undecodable: UndecodablePolicy,
+        f: F,
+    ) -> Result<(), &'static str> {
+        if cols.start > cols.end || cols.end > self.columns().len() {
+            return Err("Column range out of range");
+        }
+        let printing = self.card_type() == CardType::Text;
+        for index in cols {
+            let column = self.get_column_mut(index).expect("range checked above");
+            let Some(decoded) = column.to_char() else {
+                if undecodable == UndecodablePolicy::Blank {
+                    *column = Column::new();
+                }
+                continue;
+            };
+            let Some(mapped) = f(decoded).map(|c| c.to_ascii_uppercase()) else {
+                continue;
+            };
+            if let Some(punches) = char_to_hollerith(mapped) {
+                column.punches = punches;
+                column.printed_char = printing.then_some(mapped);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Deck {
+    /// Apply `PunchCard::translate` to every card
+    pub fn translate<F: Fn(char) -> Option<char>>(&mut self, f: F) {
+        for card in self.iter_mut() {
+            card.translate(&f);
+        }
+    }
+
+    /// Apply `PunchCard::translate_columns` to every card
+    ///
+    /// Fails without modifying the deck if the range falls outside any card.
+    pub fn translate_columns<F: Fn(char) -> Option<char>>(
+        &mut self,
+        cols: Range<usize>,
+        undecodable: UndecodablePolicy,
+        f: F,
+    ) -> Result<(), &'static str> {
+        if self
+            .iter()
+            .any(|card| cols.start > cols.end || cols.end > card.columns().len())
+        {
+            return Err("Column range out of range");
+        }
+        for card in self.iter_mut() {
+            card.translate_columns(cols.clone(), undecodable, &f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hollerith::HollerithCode;
+
+    #[test]
+    fn test_translate_updates_punches_and_printing() {
+        let mut card = PunchCard::from_text("A|B|C");
+        card.translate(|c| if c == '|' { Some('!') } else { None });
+        assert_eq!(card.to_text_trimmed(), "A!B!C");
+        let column = card.get_column(1).unwrap();
+        assert_eq!(column.punches, char_to_hollerith('!').unwrap());
+        assert_eq!(column.printed_char, Some('!'));
+        assert_eq!(card.get_column(0).unwrap().printed_char, Some('A'));
+    }
+
+    #[test]
+    fn test_translate_columns_blanks_field() {
+        let mut card = PunchCard::from_text(&"X".repeat(40));
+        card.translate_columns(19..30, UndecodablePolicy::Keep, |_| Some(' '))
+            .unwrap();
+        let text = card.to_text_trimmed();
+        assert_eq!(&text[..19], "X".repeat(19));
+        assert_eq!(&text[19..30], " ".repeat(11));
+        assert_eq!(&text[30..], "X".repeat(10));
+        assert!(card.get_column(19).unwrap().is_blank());
+        assert!(
+            card.translate_columns(70..81, UndecodablePolicy::Keep, Some)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_translate_undecodable_policy() {
+        let laced = HollerithCode::new(vec![12, 11, 0, 1]);
+        let mut card = PunchCard::from_text("AB");
+        card.set_column_hollerith(1, laced.clone()).unwrap();
+
+        let mut kept = card.clone();
+        kept.translate(Some);
+        assert_eq!(kept.get_column(1).unwrap().punches, laced);
+
+        card.translate_columns(0..80, UndecodablePolicy::Blank, Some)
+            .unwrap();
+        assert!(card.get_column(1).unwrap().is_blank());
+        assert_eq!(card.get_column(0).unwrap().to_char(), Some('A'));
+    }
+
+    #[test]
+    fn test_translate_ignores_unencodable_results() {
+        let mut card = PunchCard::from_text("ABC");
+        card.translate(|c| if c == 'B' { Some('~') } else { Some(c) });
+        assert_eq!(card.to_text_trimmed(), "ABC");
+        // Lowercase results are punched as uppercase, like the keypunch
+        card.translate(|c| Some(c.to_ascii_lowercase()));
+        assert_eq!(card.to_text_trimmed(), "ABC");
+    }
+
+    #[test]
+    fn test_translate_binary_card_stays_unprinted() {
+        let mut card = PunchCard::from_binary(&PunchCard::from_text("A").to_binary());
+        card.translate(|c| if c == 'A' { Some('Z') } else { None });
+        let column = card.get_column(0).unwrap();
+        assert_eq!(column.to_char(), Some('Z'));
+        assert_eq!(column.printed_char, None);
+    }
+
+    #[test]
+    fn test_deck_translate() {
+        let mut deck: Deck = ["SECRET 123", "PUBLIC 456"]
+            .iter()
+            .map(|t| PunchCard::from_text(t))
+            .collect();
+        deck.translate_columns(7..10, UndecodablePolicy::Keep, |_| Some('*'))
+            .unwrap();
+        deck.translate(|c| if c == 'E' { Some('3') } else { None });
+        let texts: Vec<String> = deck.iter().map(|c| c.to_text_trimmed()).collect();
+        assert_eq!(texts, vec!["S3CR3T ***", "PUBLIC ***"]);
+    }
+}
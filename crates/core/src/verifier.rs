@@ -0,0 +1,157 @@
+// Verifier Module
+//
+// Simulates the IBM 059/129 card verifier: the operator re-keys the source data
+// and every column whose punches differ from the re-keyed character is flagged.
+
+use crate::punch_card::PunchCard;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A column whose punches did not match the re-keyed text
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VerifyMismatch {
+    /// Zero-based column index
+    pub column: usize,
+    /// The character that was re-keyed for this column (blank past the text)
+    pub expected: char,
+    /// The character the punches decode to, or None if undecodable
+    pub actual: Option<char>,
+}
+
+/// The outcome of verifying a card against expected text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VerifyResult {
+    /// All mismatched columns in column order
+    pub mismatches: Vec<VerifyMismatch>,
+}
+
+impl VerifyResult {
+    /// Check if every column matched
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Get the indices of the mismatched columns
+    pub fn mismatched_columns(&self) -> Vec<usize> {
+        self.mismatches.iter().map(|m| m.column).collect()
+    }
+}
+
+impl PunchCard {
+    /// Verify the card's punches against expected text
+    ///
+    /// The text is encoded with the same rules as `from_text` (uppercased,
+    /// truncated to 80 columns), and columns beyond the end of the text are
+    /// verified as blank. Printed characters are ignored; only holes count.
+    pub fn verify_against_text(&self, text: &str) -> VerifyResult {
+        let expected_card = PunchCard::from_text(text);
+        let mut expected_chars = text.chars().map(|c| c.to_ascii_uppercase());
+
+        let mismatches = self
+            .columns()
+            .iter()
+            .zip(expected_card.columns())
+            .enumerate()
+            .filter_map(|(column, (actual, expected))| {
+                let expected_char = expected_chars.next().unwrap_or(' ');
+                if actual.punches == expected.punches {
+                    None
+                } else {
+                    Some(VerifyMismatch {
+                        column,
+                        expected: expected_char,
+                        actual: actual.to_char(),
+                    })
+                }
+            })
+            .collect();
+
+        VerifyResult { mismatches }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hollerith::HollerithCode;
+
+    #[test]
+    fn test_verify_matching_card() {
+        let card = PunchCard::from_text("HELLO WORLD");
+        let result = card.verify_against_text("HELLO WORLD");
+        assert!(result.passed());
+    }
+
+    #[test]
+    fn test_verify_is_case_insensitive() {
+        let card = PunchCard::from_text("HELLO");
+        assert!(card.verify_against_text("hello").passed());
+    }
+
+    #[test]
+    fn test_verify_reports_mismatches() {
+        let card = PunchCard::from_text("HELLO WORLD");
+        let result = card.verify_against_text("HELLO WORD");
+        assert!(!result.passed());
+        assert_eq!(result.mismatched_columns(), vec![9, 10]);
+        assert_eq!(
+            result.mismatches[0],
+            VerifyMismatch {
+                column: 9,
+                expected: 'D',
+                actual: Some('L'),
+            }
+        );
+        assert_eq!(
+            result.mismatches[1],
+            VerifyMismatch {
+                column: 10,
+                expected: ' ',
+                actual: Some('D'),
+            }
+        );
+    }
+
+    #[test]
+    fn test_verify_remaining_columns_blank() {
+        let mut card = PunchCard::from_text("ABC");
+        card.set_column_char(79, 'Z').unwrap();
+        let result = card.verify_against_text("ABC");
+        assert_eq!(result.mismatched_columns(), vec![79]);
+        assert_eq!(result.mismatches[0].expected, ' ');
+    }
+
+    #[test]
+    fn test_verify_binary_card_ignores_printing() {
+        let card = PunchCard::from_binary(&PunchCard::from_text("DATA").to_binary());
+        assert!(card.verify_against_text("DATA").passed());
+    }
+
+    #[test]
+    fn test_verify_undecodable_column() {
+        let mut card = PunchCard::from_text("AB");
+        card.set_column_hollerith(1, HollerithCode::new(vec![12, 11, 0]))
+            .unwrap();
+        let result = card.verify_against_text("AB");
+        assert_eq!(
+            result.mismatches,
+            vec![VerifyMismatch {
+                column: 1,
+                expected: 'B',
+                actual: None,
+            }]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_verify_result_serializes() {
+        let card = PunchCard::from_text("AB");
+        let result = card.verify_against_text("AC");
+        let json = serde_json::to_string(&result).unwrap();
+        let back: VerifyResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, result);
+    }
+}
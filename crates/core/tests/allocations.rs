@@ -0,0 +1,61 @@
+// Allocation Tests
+//
+// Counts heap allocations on deck-level paths: cards store their columns
+// inline, so cloning a deck allocates only for the card list and punched
+// columns, and binary output packs each card without allocating.
+
+use punch_card_core::deck::Deck;
+use punch_card_core::punch_card::{CardType, PunchCard};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations_during<T>(f: impl FnOnce() -> T) -> (usize, T) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    (ALLOCATIONS.load(Ordering::Relaxed) - before, result)
+}
+
+// The allocation counter is global, so all measurements run in one test
+// rather than in parallel test threads.
+#[test]
+fn test_deck_allocations() {
+    let deck: Deck = (0..1000)
+        .map(|_| PunchCard::new(CardType::Binary))
+        .collect();
+    let (count, copy) = allocations_during(|| deck.clone());
+    // One allocation for the card list; a Vec<Column> per card would add 1,000
+    assert_eq!(count, 1);
+    assert_eq!(copy.len(), 1000);
+
+    let deck: Deck = (0..1000).map(|_| PunchCard::from_text("HELLO")).collect();
+    let (count, _copy) = allocations_during(|| deck.clone());
+    assert_eq!(count, 1 + 1000 * 5);
+
+    // 10,000 cards into a preallocated buffer: no per-card allocation
+    let deck: Deck = (0..10_000)
+        .map(|i| PunchCard::from_text(&format!("CARD {:05}", i)))
+        .collect();
+    let mut out = Vec::with_capacity(deck.len() * 108);
+    let (count, result) = allocations_during(|| deck.write_binary(&mut out));
+    result.unwrap();
+    assert_eq!(count, 0);
+    assert_eq!(out.len(), 1_080_000);
+}
@@ -1,7 +1,11 @@
 // Main App Component
 
+use punch_card_core::format::{CardFormat, ColumnLoss};
 use punch_card_core::ibm1130;
-use punch_card_core::punch_card::{CardType, PunchCard as CorePunchCard};
+use punch_card_core::plausibility::PlausibilityThresholds;
+use punch_card_core::punch_card::{
+    CardType, Confidence, PunchCard as CorePunchCard, detect_format,
+};
 use wasm_bindgen::JsCast;
 use yew::prelude::*;
 
@@ -39,25 +43,20 @@ pub fn app() -> Html {
         })
     };
 
-    let on_load_source_example = {
+    let on_load_example = {
         let text_value = text_value.clone();
         let card = card.clone();
-        Callback::from(move |_| {
-            // Load text example
-            let example_text = "START DC   0             IBM 1130 EXAMPLE".to_string();
-            text_value.set(example_text.clone());
-            card.set(CorePunchCard::from_text(&example_text));
-        })
-    };
-
-    let on_load_object_example = {
-        let text_value = text_value.clone();
-        let card = card.clone();
-        Callback::from(move |_| {
-            // Load binary example - set card first, then clear text
-            let object_card = ibm1130::generate_example_object();
-            card.set(object_card);
-            text_value.set(String::new());
+        Callback::from(move |example: ibm1130::ExampleCard| {
+            let example_card = example.card();
+            if example_card.card_type() == CardType::Text {
+                // Text examples show in the manual input as well
+                text_value.set(example_card.to_text().trim_end().to_string());
+                card.set(example_card);
+            } else {
+                // Binary example - set card first, then clear text
+                card.set(example_card);
+                text_value.set(String::new());
+            }
         })
     };
 
@@ -103,6 +102,19 @@ pub fn app() -> Html {
         })
     };
 
+    let on_interpret = {
+        let card = card.clone();
+        Callback::from(move |_| {
+            // Print the decoded characters onto the loaded card
+            let mut interpreted = (*card).clone();
+            let report = interpreted.interpret();
+            if !report.is_complete() {
+                web_sys::console::warn_1(&format!("Interpreted card: {}", report).into());
+            }
+            card.set(interpreted);
+        })
+    };
+
     let on_file_change = {
         let text_value = text_value.clone();
         let card = card.clone();
@@ -125,12 +137,44 @@ pub fn app() -> Html {
                         let mut bytes = vec![0u8; array.length() as usize];
                         array.copy_to(&mut bytes);
 
-                        if bytes.len() == 108 || bytes.len() == 80 {
-                            // Load as binary format (108 bytes = IBM 1130 format, or 80 bytes = legacy)
-                            // from_binary() handles both 108-byte and 80-byte formats
-                            let new_card = CorePunchCard::from_binary(&bytes);
+                        // Detect the format (1130 binary, EBCDIC, legacy, deck, or text)
+                        if let Some(new_card) = CorePunchCard::from_detected(&bytes) {
+                            let candidates = detect_format(&bytes);
+                            if candidates[0].confidence == Confidence::Ambiguous {
+                                let others: Vec<String> = candidates[1..]
+                                    .iter()
+                                    .map(|detection| detection.format.to_string())
+                                    .collect();
+                                let message = format!(
+                                    "Loading as {}; the file could also be {}",
+                                    candidates[0].format,
+                                    others.join(", ")
+                                );
+                                web_sys::console::log_1(&message.into());
+                            }
+                            let report = new_card.plausibility_report_for(candidates[0].format);
+                            if !report.looks_like_text(&PlausibilityThresholds::default()) {
+                                let message = if new_card.card_type() == CardType::Text {
+                                    format!("This file may not be a text card ({})", report)
+                                } else {
+                                    format!(
+                                        "This file does not look like a text card ({}); interpreting as binary",
+                                        report
+                                    )
+                                };
+                                web_sys::console::warn_1(&message.into());
+                            }
+                            // Text cards keep the input box in sync so typing continues them
+                            if new_card.card_type() == CardType::Text {
+                                text_value.set(new_card.to_text_trimmed());
+                            } else {
+                                text_value.set(String::new());
+                            }
                             card.set(new_card);
-                            text_value.set(String::new());
+                        } else {
+                            web_sys::console::warn_1(
+                                &format!("Unrecognized card file ({} bytes)", bytes.len()).into(),
+                            );
                         }
                     }
                 });
@@ -170,6 +214,22 @@ pub fn app() -> Html {
         },
     ];
 
+    // Warn before saving if the 1130 format would drop punched columns
+    let save_warning = {
+        let audit = card.audit_export(CardFormat::Ibm1130Binary108);
+        let columns: Vec<String> = audit
+            .columns(ColumnLoss::Dropped)
+            .iter()
+            .map(|column| (column + 1).to_string())
+            .collect();
+        (!columns.is_empty()).then(|| {
+            format!(
+                "Warning: punches in column(s) {} will not be saved",
+                columns.join(", ")
+            )
+        })
+    };
+
     html! {
         <div class="app">
             <header>
@@ -207,17 +267,20 @@ pub fn app() -> Html {
                         <TabPanel id="examples" active_tab={(*active_tab).clone()}>
                             <p>{ "Load example IBM 1130 punch cards:" }</p>
                             <div class="example-buttons">
-                                <button onclick={on_load_source_example}>
-                                    { "Assembler Source Card" }
-                                </button>
-                                <button onclick={on_load_object_example}>
-                                    { "Object Deck Card (Binary)" }
-                                </button>
+                                { for ibm1130::examples().into_iter().map(|example| {
+                                    let on_load_example = on_load_example.clone();
+                                    html! {
+                                        <button onclick={Callback::from(move |_| on_load_example.emit(example))}>
+                                            { example.title }
+                                        </button>
+                                    }
+                                }) }
                             </div>
                             <div style="margin-top: 20px;">
                                 <h3>{ "About Examples" }</h3>
-                                <p><strong>{ "Assembler Source:" }</strong>{ " IBM 1130 assembler instruction with label, opcode, and operands" }</p>
-                                <p><strong>{ "Object Deck:" }</strong>{ " Binary compiled code with authentic 4:3 punch pattern" }</p>
+                                { for ibm1130::examples().into_iter().map(|example| html! {
+                                    <p><strong>{ format!("{}:", example.title) }</strong>{ format!(" {}", example.description) }</p>
+                                }) }
                             </div>
                         </TabPanel>
 
@@ -228,23 +291,28 @@ pub fn app() -> Html {
                                 <div style="flex: 0 0 40%; padding: 15px; border: 1px solid #ccc; border-radius: 5px; background: #f9f9f9;">
                                     <h3 style="margin-top: 0;">{ "Save Card" }</h3>
                                     <p style="font-size: 0.9em;">{ "Download the current punch card as a 108-byte binary file (IBM 1130 format: 72 columns × 12 rows, columns 73-80 not saved):" }</p>
+                                    if let Some(warning) = save_warning {
+                                        <p class="export-warning">{ warning }</p>
+                                    }
                                     <button onclick={on_save}>{ "Download Card (.bin)" }</button>
                                 </div>
 
                                 // Load section (2/5 width = 40%)
                                 <div style="flex: 0 0 40%; padding: 15px; border: 1px solid #ccc; border-radius: 5px; background: #f9f9f9;">
                                     <h3 style="margin-top: 0;">{ "Load Card" }</h3>
-                                    <p style="font-size: 0.9em;">{ "Upload a binary file to load as a punch card (108 bytes IBM 1130 format, or legacy 80-byte format):" }</p>
+                                    <p style="font-size: 0.9em;">{ "Upload a card file to load as a punch card (108-byte IBM 1130 binary, 80-byte EBCDIC or legacy binary, or a text line):" }</p>
                                     <div class="file-upload-container">
                                         <input
                                             type="file"
-                                            accept=".bin,.dat,.card"
+                                            accept=".bin,.dat,.card,.txt"
                                             onchange={on_file_change}
                                         />
                                     </div>
                                     <p style="margin-top: 10px; font-size: 0.85em; color: #666;">
                                         <strong>{ "Note:" }</strong>{ " Loaded binary cards will not display printed characters at the top of the card, only the punch hole patterns." }
                                     </p>
+                                    <p style="font-size: 0.9em;">{ "Print the characters the holes decode to, as an interpreter would:" }</p>
+                                    <button onclick={on_interpret}>{ "Interpret Card" }</button>
                                 </div>
 
                                 // Clear section (1/5 width = 20%)
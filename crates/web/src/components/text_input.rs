@@ -1,5 +1,6 @@
 // TextInput Component
 
+use punch_card_core::hollerith::encode_string_lossy_with_report;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
@@ -31,6 +32,28 @@ pub fn text_input(props: &TextInputProps) -> Html {
         })
     };
 
+    // Underline characters that will be left blank on the card
+    let (_, issues) = encode_string_lossy_with_report(&props.value);
+    let issue_preview = if issues.is_empty() {
+        html! {}
+    } else {
+        let chars = props.value.chars().enumerate().map(|(index, ch)| {
+            if issues.iter().any(|issue| issue.char_index == index) {
+                html! { <span class="unencodable" title="Cannot be punched">{ ch }</span> }
+            } else {
+                html! { <span>{ ch }</span> }
+            }
+        });
+        html! {
+            <div class="input-issues">
+                <div class="input-preview">{ for chars }</div>
+                <span>
+                    { format!("{} character(s) cannot be punched and will be left blank", issues.len()) }
+                </span>
+            </div>
+        }
+    };
+
     html! {
         <div class="text-input-container">
             <label for="card-input">
@@ -48,6 +71,7 @@ pub fn text_input(props: &TextInputProps) -> Html {
             <div class="input-info">
                 <span>{ format!("Characters: {} / {}", props.value.len(), props.max_length) }</span>
             </div>
+            { issue_preview }
         </div>
     }
 }